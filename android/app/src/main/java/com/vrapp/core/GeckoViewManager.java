@@ -2,8 +2,11 @@ package com.vrapp.core;
 
 import android.content.Context;
 import android.graphics.PixelFormat;
+import android.hardware.HardwareBuffer;
+import android.hardware.SyncFence;
 import android.media.Image;
 import android.media.ImageReader;
+import android.os.Build;
 import android.os.Handler;
 import android.os.HandlerThread;
 import android.os.Looper;
@@ -31,12 +34,24 @@ import java.nio.ByteBuffer;
  * (An earlier design shared one display and re-acquired it on every switch — that
  * tears down/rebuilds WebRender's compositor mid-frame and segfaults the Gecko
  * thread with "webrender error 3". Per-tab displays avoid all of that.)
+ *
+ * Floating browser windows get tabs of their own, outside the screen's tab list:
+ * popOut() moves the active tab into one, openWindow() opens a new one. They stay
+ * active and push frames alongside the screen's, tagged with their window number
+ * (0 = the screen).
+ *
+ * Frames go to Rust as the HardwareBuffer the page was rendered into when the
+ * renderer can import it (API 29+ readers are made GPU-sampleable for that), and
+ * as an RGBA copy otherwise. A buffer is lent under a token: its Image stays
+ * acquired, so Gecko can't draw into it, until Rust's GPU copy from it has
+ * completed and releaseFrame() hands the token back.
  */
 public class GeckoViewManager {
     private static final String TAG      = "VRAppJava";
     private static final int    WEB_W    = 1920;
     private static final int    WEB_H    = 1080;
     private static final int    MAX_TABS = 4;   // each tab = its own compositor (heavy)
+    private static final int    READER_IMAGES = 4; // lent to the GPU copy + room to acquire
     private static final String HOME_URL = "https://www.google.com";
 
     private final Context context;
@@ -56,10 +71,25 @@ public class GeckoViewManager {
         boolean loaded = false;
         boolean inFullscreen = false;
         int     w = WEB_W, h = WEB_H;
+        int     window = 0;          // floating window it's shown in, 0 = the screen
+        boolean starved = false;     // a frame waits for a lent buffer to come back
+    }
+
+    /** A frame's image, lent to Rust as a hardware buffer until releaseFrame(). */
+    private static final class Lent {
+        final Tab tab;
+        final ImageReader reader;
+        final Image image;
+        Lent(Tab tab, ImageReader reader, Image image) { this.tab = tab; this.reader = reader; this.image = image; }
     }
 
+    private final java.util.Map<Long, Lent> lent = new java.util.concurrent.ConcurrentHashMap<>();
+    private final java.util.concurrent.atomic.AtomicLong nextToken = new java.util.concurrent.atomic.AtomicLong(1);
+
     private final java.util.List<Tab> tabs = new java.util.ArrayList<>();
+    private final java.util.Map<Integer, Tab> windows = new java.util.concurrent.ConcurrentHashMap<>();
     private int activeTab = 0;
+    private final boolean gpuFrames = Build.VERSION.SDK_INT >= 29;
 
     private byte[] frameBuf;
     private volatile boolean running = false;
@@ -93,9 +123,9 @@ public class GeckoViewManager {
 
         java.util.List<String> saved = loadTabState();
         if (saved.isEmpty()) {
-            createTab(HOME_URL);
+            tabs.add(createTab(HOME_URL));
         } else {
-            for (String u : saved) createTab(u);
+            for (String u : saved) tabs.add(createTab(u));
         }
         activeTab = Math.max(0, Math.min(loadActiveIndex(), tabs.size() - 1));
         activateTab(activeTab);
@@ -111,13 +141,22 @@ public class GeckoViewManager {
         return url.trim();
     }
 
-    /** Build a tab with its own session + display pipeline (no load yet). */
+    /** A capture reader (RGBX_8888 = Gecko compositor format), GPU-sampleable where possible. */
+    private ImageReader newReader(int w, int h) {
+        if (gpuFrames) {
+            return ImageReader.newInstance(w, h, PixelFormat.RGBX_8888, READER_IMAGES,
+                HardwareBuffer.USAGE_GPU_SAMPLED_IMAGE | HardwareBuffer.USAGE_CPU_READ_OFTEN);
+        }
+        return ImageReader.newInstance(w, h, PixelFormat.RGBX_8888, READER_IMAGES);
+    }
+
+    /** Build a tab with its own session + display pipeline (no load yet, not listed). */
     private Tab createTab(String url) {
         final Tab tab = new Tab();
         tab.url = sanitize(url);
 
-        // Own capture pipeline (RGBX_8888 = Gecko compositor format).
-        tab.reader = ImageReader.newInstance(WEB_W, WEB_H, PixelFormat.RGBX_8888, 3);
+        // Own capture pipeline
+        tab.reader = newReader(WEB_W, WEB_H);
         tab.reader.setOnImageAvailableListener(r -> onImageAvailable(r, tab), readerHandler);
 
         tab.session = new GeckoSession();
@@ -130,6 +169,9 @@ public class GeckoViewManager {
         tab.session.setProgressDelegate(new GeckoSession.ProgressDelegate() {
             @Override public void onPageStart(GeckoSession sess, String locUrl) {
                 if (locUrl != null) tab.url = locUrl;
+                if (locUrl != null && tab.window != 0 && context instanceof MainActivity) {
+                    ((MainActivity) context).onWebWindowUrl(tab.window, locUrl);
+                }
             }
         });
         tab.session.open(runtime);
@@ -140,7 +182,6 @@ public class GeckoViewManager {
             new GeckoDisplay.SurfaceInfo.Builder(tab.reader.getSurface())
                 .size(WEB_W, WEB_H).build());
 
-        tabs.add(tab);
         return tab;
     }
 
@@ -169,7 +210,7 @@ public class GeckoViewManager {
             if (tabs.size() >= MAX_TABS) { Log.i(TAG, "newTab: at MAX_TABS"); return; }
             Tab old = active();
             if (old != null && old.inFullscreen) old.session.exitFullScreen();
-            createTab(url);
+            tabs.add(createTab(url));
             activateTab(tabs.size() - 1);
             Log.i(TAG, "Opened Gecko tab " + activeTab + " (" + tabs.size() + " total)");
         });
@@ -200,10 +241,59 @@ public class GeckoViewManager {
     private void destroyTab(Tab t) {
         try { if (t.session != null && t.display != null) t.session.releaseDisplay(t.display); } catch (Exception e) {}
         try { if (t.session != null) t.session.close(); } catch (Exception e) {}
+        forgetLent(t.reader);
         if (t.reader != null) { try { t.reader.close(); } catch (Exception e) {} }
         t.display = null; t.session = null; t.reader = null;
     }
 
+    // ── Floating windows ───────────────────────────────────────────────────────
+
+    /** Move the active tab into floating window `window`; the screen goes on with another. */
+    public void popOut(int window) {
+        mainHandler.post(() -> {
+            Tab t = active();
+            if (t == null) { openWindow(window, HOME_URL); return; }
+            if (t.inFullscreen) t.session.exitFullScreen();
+            tabs.remove(activeTab);
+            t.window = window;
+            windows.put(window, t);
+            if (tabs.isEmpty()) tabs.add(createTab(HOME_URL));
+            activeTab = Math.min(activeTab, tabs.size() - 1);
+            activateTab(activeTab);
+            t.session.setActive(true);
+            if (t.url != null && context instanceof MainActivity) {
+                ((MainActivity) context).onWebWindowUrl(window, t.url);
+            }
+            Log.i(TAG, "Gecko tab popped out to window " + window + "; " + tabs.size() + " on screen");
+        });
+    }
+
+    /** Open `url` in a tab of its own for floating window `window`. */
+    public void openWindow(int window, String url) {
+        mainHandler.post(() -> {
+            Tab t = createTab(url);
+            t.window = window;
+            windows.put(window, t);
+            t.session.setActive(true);
+            t.session.loadUri(t.url);
+            t.loaded = true;
+            Log.i(TAG, "Opened Gecko window " + window + " -> " + t.url);
+        });
+    }
+
+    public void closeWindow(int window) {
+        mainHandler.post(() -> {
+            Tab t = windows.remove(window);
+            if (t != null) destroyTab(t);
+            Log.i(TAG, "Closed Gecko window " + window);
+        });
+    }
+
+    /** The tab showing in `window` (0 = the screen's active tab). */
+    private Tab target(int window) {
+        return window == 0 ? active() : windows.get(window);
+    }
+
     public int getTabCount()  { return tabs.size(); }
     public int getActiveTab() { return activeTab; }
 
@@ -243,10 +333,35 @@ public class GeckoViewManager {
     private void onImageAvailable(ImageReader reader, Tab tab) {
         Image image = null;
         try {
-            image = reader.acquireLatestImage();
+            try {
+                image = reader.acquireLatestImage();
+            } catch (IllegalStateException e) {
+                // Every buffer is lent out: pick up again when one comes back
+                tab.starved = true;
+                return;
+            }
             if (image == null) return;
-            // Only the active tab of the selected engine pushes frames.
-            if (!running || !active || tab != active()) return;
+            // A window's tab always pushes frames; of the screen's, only the active
+            // tab of the selected engine.
+            if (!running || (tab.window == 0 && (!active || tab != active()))) return;
+            if (!(context instanceof MainActivity)) return;
+            MainActivity activity = (MainActivity) context;
+
+            if (gpuFrames) {
+                HardwareBuffer hb = image.getHardwareBuffer();
+                if (hb != null) {
+                    awaitFence(image);
+                    long token = nextToken.getAndIncrement();
+                    lent.put(token, new Lent(tab, reader, image));
+                    boolean taken = activity.onWebHardwareBuffer(tab.window, hb, token);
+                    hb.close();
+                    if (taken) {
+                        image = null;
+                        return;
+                    }
+                    lent.remove(token);
+                }
+            }
 
             Image.Plane plane = image.getPlanes()[0];
             ByteBuffer buf = plane.getBuffer();
@@ -266,9 +381,7 @@ public class GeckoViewManager {
                 }
             }
 
-            if (context instanceof MainActivity) {
-                ((MainActivity) context).onWebFrame(w, h, frameBuf);
-            }
+            activity.onWebFrame(tab.window, w, h, frameBuf);
         } catch (Exception e) {
             Log.e(TAG, "Gecko onImageAvailable error: " + e.getMessage());
         } finally {
@@ -276,6 +389,42 @@ public class GeckoViewManager {
         }
     }
 
+    /** Wait until Gecko's rendering into the image is done, where the image has a fence (API 33+). */
+    private static void awaitFence(Image image) {
+        if (Build.VERSION.SDK_INT < 33) return;
+        try (SyncFence fence = image.getFence()) {
+            if (fence.isValid()) fence.awaitForever();
+        } catch (Exception e) {
+            Log.w(TAG, "Gecko frame fence: " + e);
+        }
+    }
+
+    /**
+     * Rust's GPU copy from the buffer lent under `token` has completed (or it
+     * dropped the frame unseen): close the image so the reader can reuse it.
+     */
+    public void releaseFrame(long token) {
+        Lent l = lent.remove(token);
+        if (l == null) return;
+        readerHandler.post(() -> {
+            try { l.image.close(); } catch (Exception e) {}
+            if (l.tab.starved && l.tab.reader == l.reader) {
+                l.tab.starved = false;
+                onImageAvailable(l.reader, l.tab);
+            }
+        });
+    }
+
+    /**
+     * A reader is closing, and its images with it. Nothing draws into its buffers
+     * again, and Rust keeps its own references to those it has, so their tokens
+     * are just dropped.
+     */
+    private void forgetLent(ImageReader reader) {
+        if (reader == null) return;
+        lent.values().removeIf(l -> l.reader == reader);
+    }
+
     /** Toggle whether this engine pushes frames to the renderer. */
     public void setActive(boolean a) { this.active = a; }
 
@@ -312,18 +461,19 @@ public class GeckoViewManager {
     }
 
     private boolean dragging = false;
+    private Tab    dragTab;   // the tab the drag is in (the screen's or a window's)
     private float dragX, dragY;
     private long   dragDownT;
     private final Runnable endDrag = new Runnable() {
         @Override public void run() {
-            if (activeSession() == null || !dragging) return;
-            sendTouch(android.view.MotionEvent.ACTION_UP, dragX, dragY);
+            if (dragTab == null || !dragging) return;
+            sendTouch(dragTab, android.view.MotionEvent.ACTION_UP, dragX, dragY);
             dragging = false;
         }
     };
 
-    private void sendTouch(int action, float x, float y) {
-        GeckoSession s = activeSession();
+    private void sendTouch(Tab t, int action, float x, float y) {
+        GeckoSession s = t.session;
         if (s == null) return;
         android.view.MotionEvent e = android.view.MotionEvent.obtain(
             dragDownT, android.os.SystemClock.uptimeMillis(), action, x, y, 0);
@@ -332,34 +482,39 @@ public class GeckoViewManager {
         e.recycle();
     }
 
-    public void scroll(float dx, float dy, float cx, float cy) {
+    public void scroll(int window, float dx, float dy, float cx, float cy) {
         mainHandler.post(() -> {
-            Tab t = active();
+            Tab t = target(window);
             if (t == null) return;
+            if (dragging && dragTab != t) {
+                sendTouch(dragTab, android.view.MotionEvent.ACTION_UP, dragX, dragY);
+                dragging = false;
+            }
             if (!dragging) {
+                dragTab = t;
                 dragX = cx * t.w; dragY = cy * t.h;
                 dragDownT = android.os.SystemClock.uptimeMillis();
-                sendTouch(android.view.MotionEvent.ACTION_DOWN, dragX, dragY);
+                sendTouch(t, android.view.MotionEvent.ACTION_DOWN, dragX, dragY);
                 dragging = true;
             }
             dragX -= dx; dragY -= dy;
             if (dragX < 4 || dragX > t.w - 4 || dragY < 4 || dragY > t.h - 4) {
-                sendTouch(android.view.MotionEvent.ACTION_UP,
+                sendTouch(t, android.view.MotionEvent.ACTION_UP,
                     Math.max(4, Math.min(t.w - 4, dragX)),
                     Math.max(4, Math.min(t.h - 4, dragY)));
                 dragging = false;
             } else {
-                sendTouch(android.view.MotionEvent.ACTION_MOVE, dragX, dragY);
+                sendTouch(t, android.view.MotionEvent.ACTION_MOVE, dragX, dragY);
             }
             mainHandler.removeCallbacks(endDrag);
             mainHandler.postDelayed(endDrag, 130);
         });
     }
 
-    public void tap(float xNorm, float yNorm) {
+    public void tap(int window, float xNorm, float yNorm) {
         mainHandler.post(() -> {
-            Tab t = active();
-            if (t == null) return;
+            Tab t = target(window);
+            if (t == null || t.session == null) return;
             long ts = android.os.SystemClock.uptimeMillis();
             float px = xNorm * t.w, py = yNorm * t.h;
             android.view.MotionEvent d = android.view.MotionEvent.obtain(
@@ -394,12 +549,13 @@ public class GeckoViewManager {
             if (t == null || t.display == null) return;
             try {
                 ImageReader old = t.reader;
-                t.reader = ImageReader.newInstance(w, h, PixelFormat.RGBX_8888, 3);
+                t.reader = newReader(w, h);
                 final Tab ft = t;
                 t.reader.setOnImageAvailableListener(r -> onImageAvailable(r, ft), readerHandler);
                 t.display.surfaceChanged(
                     new GeckoDisplay.SurfaceInfo.Builder(t.reader.getSurface())
                         .size(w, h).build());
+                forgetLent(old);
                 if (old != null) old.close();
                 t.w = w; t.h = h;
                 Log.i(TAG, "Gecko (tab " + activeTab + ") resized to " + w + "x" + h);
@@ -428,6 +584,8 @@ public class GeckoViewManager {
         mainHandler.post(() -> {
             for (Tab t : tabs) destroyTab(t);
             tabs.clear();
+            for (Tab t : windows.values()) destroyTab(t);
+            windows.clear();
         });
         if (readerThread != null) { readerThread.quitSafely(); readerThread = null; }
     }
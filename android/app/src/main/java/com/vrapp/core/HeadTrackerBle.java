@@ -0,0 +1,221 @@
+package com.vrapp.core;
+
+import android.bluetooth.BluetoothAdapter;
+import android.bluetooth.BluetoothDevice;
+import android.bluetooth.BluetoothGatt;
+import android.bluetooth.BluetoothGattCallback;
+import android.bluetooth.BluetoothGattCharacteristic;
+import android.bluetooth.BluetoothGattDescriptor;
+import android.bluetooth.BluetoothGattService;
+import android.bluetooth.BluetoothManager;
+import android.bluetooth.BluetoothProfile;
+import android.bluetooth.le.BluetoothLeScanner;
+import android.bluetooth.le.ScanCallback;
+import android.bluetooth.le.ScanFilter;
+import android.bluetooth.le.ScanResult;
+import android.bluetooth.le.ScanSettings;
+import android.content.Context;
+import android.os.ParcelUuid;
+import android.util.Log;
+
+import java.nio.ByteBuffer;
+import java.nio.ByteOrder;
+import java.util.Arrays;
+import java.util.List;
+import java.util.UUID;
+
+/**
+ * External Bluetooth LE head tracker.
+ *
+ * Scans for a tracker, subscribes to its orientation characteristic and hands
+ * every quaternion to Rust (sensors.rs picks it up when the "External" tracking
+ * source is selected). Two kinds of tracker are understood:
+ *
+ *   - Nordic Thingy:52 motion service: quaternion as 4 × int32 Q30 (w, x, y, z).
+ *   - A generic profile for DIY trackers (ESP32 etc.): service GENERIC_SERVICE,
+ *     notify characteristic GENERIC_QUATERNION carrying 4 × float32 (x, y, z, w).
+ *
+ * Both little-endian, device → world, with the tracker's axes x right, y up and
+ * z toward the wearer when worn. Reconnects (by scanning again) if the link drops.
+ */
+public class HeadTrackerBle {
+    private static final String TAG = "VRAppJava";
+
+    private static final UUID THINGY_SERVICE     = UUID.fromString("ef680400-9b35-4933-9b10-52ffa9740042");
+    private static final UUID THINGY_QUATERNION  = UUID.fromString("ef680404-9b35-4933-9b10-52ffa9740042");
+    private static final UUID GENERIC_SERVICE    = UUID.fromString("b5a70000-3f0b-4d5e-9c66-5a0e5e1f7a10");
+    private static final UUID GENERIC_QUATERNION = UUID.fromString("b5a70001-3f0b-4d5e-9c66-5a0e5e1f7a10");
+    private static final UUID CLIENT_CONFIG      = UUID.fromString("00002902-0000-1000-8000-00805f9b34fb");
+    private static final float Q30 = 1.0f / (1 << 30);
+
+    private final Context context;
+    private BluetoothLeScanner scanner;
+    private BluetoothGatt gatt;
+    private volatile boolean running = false;
+
+    /** One orientation sample (device → world). */
+    static native void onSample(float x, float y, float z, float w);
+
+    /** Human-readable connection state for the diagnostics panel. */
+    static native void onState(String state);
+
+    public HeadTrackerBle(Context context) {
+        this.context = context;
+    }
+
+    public void start() {
+        if (this.running) {
+            return;
+        }
+        BluetoothManager manager = (BluetoothManager) this.context.getSystemService(Context.BLUETOOTH_SERVICE);
+        BluetoothAdapter adapter = manager != null ? manager.getAdapter() : null;
+        if (adapter == null || !adapter.isEnabled()) {
+            onState("Bluetooth is off");
+            return;
+        }
+        this.scanner = adapter.getBluetoothLeScanner();
+        this.running = true;
+        scan();
+    }
+
+    public void stop() {
+        this.running = false;
+        try {
+            if (this.scanner != null) {
+                this.scanner.stopScan(this.scanCallback);
+            }
+            if (this.gatt != null) {
+                this.gatt.close();
+                this.gatt = null;
+            }
+        } catch (SecurityException e) {
+            Log.w(TAG, "HeadTracker stop: " + e);
+        }
+        onState("Off");
+    }
+
+    private void scan() {
+        List<ScanFilter> filters = Arrays.asList(
+                new ScanFilter.Builder().setServiceUuid(new ParcelUuid(THINGY_SERVICE)).build(),
+                new ScanFilter.Builder().setServiceUuid(new ParcelUuid(GENERIC_SERVICE)).build());
+        ScanSettings settings = new ScanSettings.Builder().setScanMode(ScanSettings.SCAN_MODE_LOW_LATENCY).build();
+        try {
+            this.scanner.startScan(filters, settings, this.scanCallback);
+            onState("Scanning…");
+        } catch (SecurityException e) {
+            Log.w(TAG, "HeadTracker scan: " + e);
+            this.running = false;
+            onState("No Bluetooth permission");
+        }
+    }
+
+    private static String name(BluetoothDevice device) {
+        try {
+            String name = device.getName();
+            return name != null ? name : device.getAddress();
+        } catch (SecurityException e) {
+            return device.getAddress();
+        }
+    }
+
+    private final ScanCallback scanCallback = new ScanCallback() {
+        @Override
+        public void onScanResult(int callbackType, ScanResult result) {
+            if (!HeadTrackerBle.this.running || HeadTrackerBle.this.gatt != null) {
+                return;
+            }
+            BluetoothDevice device = result.getDevice();
+            try {
+                HeadTrackerBle.this.scanner.stopScan(this);
+                HeadTrackerBle.this.gatt = device.connectGatt(HeadTrackerBle.this.context, false,
+                        HeadTrackerBle.this.gattCallback, BluetoothDevice.TRANSPORT_LE);
+                onState("Connecting to " + name(device));
+            } catch (SecurityException e) {
+                Log.w(TAG, "HeadTracker connect: " + e);
+                onState("No Bluetooth permission");
+            }
+        }
+
+        @Override
+        public void onScanFailed(int errorCode) {
+            Log.w(TAG, "HeadTracker scan failed: " + errorCode);
+            onState("Scan failed (" + errorCode + ")");
+        }
+    };
+
+    private final BluetoothGattCallback gattCallback = new BluetoothGattCallback() {
+        @Override
+        public void onConnectionStateChange(BluetoothGatt g, int status, int newState) {
+            try {
+                if (newState == BluetoothProfile.STATE_CONNECTED) {
+                    g.requestConnectionPriority(BluetoothGatt.CONNECTION_PRIORITY_HIGH);
+                    g.discoverServices();
+                } else if (newState == BluetoothProfile.STATE_DISCONNECTED) {
+                    g.close();
+                    HeadTrackerBle.this.gatt = null;
+                    if (HeadTrackerBle.this.running) {
+                        Log.i(TAG, "HeadTracker disconnected (" + status + "), scanning again");
+                        HeadTrackerBle.this.scan();
+                    }
+                }
+            } catch (SecurityException e) {
+                Log.w(TAG, "HeadTracker: " + e);
+            }
+        }
+
+        @Override
+        public void onServicesDiscovered(BluetoothGatt g, int status) {
+            BluetoothGattCharacteristic characteristic = find(g, THINGY_SERVICE, THINGY_QUATERNION);
+            if (characteristic == null) {
+                characteristic = find(g, GENERIC_SERVICE, GENERIC_QUATERNION);
+            }
+            if (characteristic == null) {
+                onState(name(g.getDevice()) + " has no orientation data");
+                return;
+            }
+            try {
+                g.setCharacteristicNotification(characteristic, true);
+                BluetoothGattDescriptor config = characteristic.getDescriptor(CLIENT_CONFIG);
+                if (config != null) {
+                    config.setValue(BluetoothGattDescriptor.ENABLE_NOTIFICATION_VALUE);
+                    g.writeDescriptor(config);
+                }
+                onState("Connected to " + name(g.getDevice()));
+            } catch (SecurityException e) {
+                Log.w(TAG, "HeadTracker subscribe: " + e);
+            }
+        }
+
+        // API 33+ calls this one instead of the deprecated overload below
+        @Override
+        public void onCharacteristicChanged(BluetoothGatt g, BluetoothGattCharacteristic characteristic, byte[] value) {
+            HeadTrackerBle.parse(characteristic.getUuid(), value);
+        }
+
+        @Override
+        public void onCharacteristicChanged(BluetoothGatt g, BluetoothGattCharacteristic characteristic) {
+            HeadTrackerBle.parse(characteristic.getUuid(), characteristic.getValue());
+        }
+    };
+
+    private static BluetoothGattCharacteristic find(BluetoothGatt g, UUID service, UUID characteristic) {
+        BluetoothGattService s = g.getService(service);
+        return s != null ? s.getCharacteristic(characteristic) : null;
+    }
+
+    static void parse(UUID uuid, byte[] value) {
+        if (value == null || value.length < 16) {
+            return;
+        }
+        ByteBuffer b = ByteBuffer.wrap(value).order(ByteOrder.LITTLE_ENDIAN);
+        if (THINGY_QUATERNION.equals(uuid)) {
+            float w = b.getInt() * Q30;
+            float x = b.getInt() * Q30;
+            float y = b.getInt() * Q30;
+            float z = b.getInt() * Q30;
+            onSample(x, y, z, w);
+        } else if (GENERIC_QUATERNION.equals(uuid)) {
+            onSample(b.getFloat(), b.getFloat(), b.getFloat(), b.getFloat());
+        }
+    }
+}
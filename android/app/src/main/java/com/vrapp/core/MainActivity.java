@@ -1,17 +1,34 @@
 package com.vrapp.core;
 
 import android.app.NativeActivity;
+import android.content.ContentResolver;
+import android.content.ContentValues;
 import android.content.Intent;
 import android.content.res.Configuration;
 import android.graphics.Bitmap;
+import android.graphics.BitmapFactory;
 import android.graphics.Canvas;
+import android.graphics.Matrix;
 import android.graphics.Paint;
 import android.graphics.Rect;
+import android.hardware.BatteryState;
+import android.hardware.HardwareBuffer;
+import android.hardware.Sensor;
+import android.hardware.SensorEvent;
+import android.hardware.SensorEventListener;
+import android.hardware.SensorManager;
+import android.hardware.display.DisplayManager;
+import android.hardware.input.InputManager;
 import android.media.AudioManager;
 import android.media.MediaMetadataRetriever;
 import android.media.MediaPlayer;
 import android.media.ThumbnailUtils;
+import android.net.ConnectivityManager;
+import android.net.Network;
+import android.net.NetworkCapabilities;
 import android.net.Uri;
+import android.os.BatteryManager;
+import android.os.Build;
 import android.os.Bundle;
 import android.os.Environment;
 import android.os.Handler;
@@ -19,8 +36,14 @@ import android.os.IBinder;
 import android.os.Looper;
 import android.os.ParcelFileDescriptor;
 import android.os.PowerManager;
+import android.os.storage.StorageManager;
+import android.os.storage.StorageVolume;
+import android.os.VibrationEffect;
+import android.os.Vibrator;
+import android.provider.MediaStore;
 import android.speech.RecognitionListener;
 import android.speech.SpeechRecognizer;
+import android.text.format.DateFormat;
 import android.util.Log;
 import android.util.Size;
 import android.view.Display;
@@ -36,24 +59,73 @@ import java.io.FileInputStream;
 import java.io.FileNotFoundException;
 import java.io.FileOutputStream;
 import java.io.IOException;
+import java.io.OutputStream;
 import java.nio.ByteBuffer;
 import java.util.ArrayList;
+import java.util.Arrays;
 import java.util.Collections;
+import java.util.Date;
 import java.util.HashSet;
 import java.util.Set;
 import java.util.concurrent.ExecutorService;
 import java.util.concurrent.Executors;
 import java.util.concurrent.ThreadFactory;
+import java.util.concurrent.atomic.AtomicInteger;
 
 /* JADX INFO: loaded from: classes.dex */
 public class MainActivity extends NativeActivity {
     private static final int MAX_WIDTH = 854;
     private static final int PICK_VIDEO_REQUEST = 1001;
+    private static final int CAMERA_PERMISSION_REQUEST = 1002;
+    private static final int BLUETOOTH_PERMISSION_REQUEST = 1003;
     private static final String TAG = "VRAppJava";
     private byte[] frameBuffer;
     private Thread frameThread;
     private GamepadOverlay gamepadOverlay;
     private GeckoViewManager geckoViewManager;
+    private HeadTrackerBle headTracker;
+    private MediaSessionBridge mediaSession;
+    private int gamepadDeviceId = -1;
+    private final Set<Integer> describedGamepads = new HashSet();
+    private final float[] gamepadAxes = new float[24];
+    private final InputManager.InputDeviceListener inputDeviceListener = new InputManager.InputDeviceListener() { // from class: com.vrapp.core.MainActivity.12
+        @Override // android.hardware.input.InputManager.InputDeviceListener
+        public void onInputDeviceAdded(int i) {
+            InputDevice device = InputDevice.getDevice(i);
+            if (device == null) {
+                return;
+            }
+            int sources = device.getSources();
+            if ((sources & 1025) == 1025 || (sources & InputDeviceCompat.SOURCE_JOYSTICK) == 16777232) {
+                Log.i(TAG, "Controller connected: " + i + " (" + device.getName() + ")");
+                MainActivity.this.describeGamepad(i);
+                MainActivity.this.onGamepadAdded(i);
+            }
+        }
+
+        @Override // android.hardware.input.InputManager.InputDeviceListener
+        public void onInputDeviceChanged(int i) {
+        }
+
+        @Override // android.hardware.input.InputManager.InputDeviceListener
+        public void onInputDeviceRemoved(int i) {
+            Log.i(TAG, "Input device removed: " + i);
+            MainActivity.this.describedGamepads.remove(Integer.valueOf(i));
+            MainActivity.this.onGamepadRemoved(i);
+        }
+    };
+    private SensorManager controllerSensors;
+    private final SensorEventListener controllerGyroListener = new SensorEventListener() { // from class: com.vrapp.core.MainActivity.10
+        @Override // android.hardware.SensorEventListener
+        public void onSensorChanged(SensorEvent sensorEvent) {
+            MainActivity.this.onControllerGyro(sensorEvent.values[0], sensorEvent.values[1], sensorEvent.values[2], sensorEvent.timestamp);
+        }
+
+        @Override // android.hardware.SensorEventListener
+        public void onAccuracyChanged(Sensor sensor, int i) {
+        }
+    };
+    private boolean headTrackerWanted = false;
     private MediaPlayer mediaPlayer;
     private int[] pixelBuffer;
     private MediaMetadataRetriever retriever;
@@ -69,6 +141,7 @@ public class MainActivity extends NativeActivity {
     private boolean webViewReady = false;
     private boolean geckoReady = false;
     private int activeEngine = 0;
+    private final AtomicInteger nextWebWindow = new AtomicInteger(1);
     private float displayHz = 60.0f;
     private boolean overlayAdded = false;
     private float lastHatX = 0.0f;
@@ -82,14 +155,42 @@ public class MainActivity extends NativeActivity {
         }
     });
     private final Set<String> thumbInFlight = Collections.synchronizedSet(new HashSet());
+    /** Thumbnail cache size cap; checked every {@code THUMB_TRIM_EVERY} writes. */
+    private static final long THUMB_CACHE_MAX_BYTES = 32L * 1024 * 1024;
+    private static final int THUMB_TRIM_EVERY = 32;
+    private final AtomicInteger thumbWrites = new AtomicInteger(0);
+    private final DisplayManager.DisplayListener displayListener = new DisplayManager.DisplayListener() { // from class: com.vrapp.core.MainActivity.1
+        @Override // android.hardware.display.DisplayManager.DisplayListener
+        public void onDisplayAdded(int i) {
+        }
+
+        @Override // android.hardware.display.DisplayManager.DisplayListener
+        public void onDisplayRemoved(int i) {
+        }
+
+        @Override // android.hardware.display.DisplayManager.DisplayListener
+        public void onDisplayChanged(int i) {
+            if (i == 0) {
+                MainActivity.this.reportDisplayRotation();
+            }
+        }
+    };
 
     public native void onDisplayRotation(int i);
 
-    public native void onDpadAxis(float f, float f2);
+    public native void onControllerGyro(float f, float f2, float f3, long j);
+
+    public native void onDpadAxis(int i, float f, float f2);
 
-    public native void onGamepadAxis(float f, float f2, float f3, float f4, float f5, float f6);
+    public native void onGamepadAxis(int i, float[] fArr);
 
-    public native void onGamepadButton(int i, boolean z);
+    public native void onGamepadButton(int i, int i2, boolean z);
+
+    public native void onGamepadAdded(int i);
+
+    public native void onGamepadInfo(int i, String str, int i2, int i3);
+
+    public native void onGamepadRemoved(int i);
 
     public native void onThumbnail(String str, int i, int i2, byte[] bArr);
 
@@ -101,7 +202,11 @@ public class MainActivity extends NativeActivity {
 
     public native void onVoiceResult(String str);
 
-    public native void onWebFrame(int i, int i2, byte[] bArr);
+    public native void onWebFrame(int i, int i2, int i3, byte[] bArr);
+
+    public native boolean onWebHardwareBuffer(int i, HardwareBuffer hardwareBuffer, long j);
+
+    public native void onWebWindowUrl(int i, String str);
 
     public void webViewIgNavigate(boolean z) {
     }
@@ -146,11 +251,81 @@ public class MainActivity extends NativeActivity {
         } catch (Exception e) {
             Log.w(TAG, "Refresh-rate selection failed: " + e.getMessage());
         }
+        try {
+            ((DisplayManager) getSystemService("display")).registerDisplayListener(this.displayListener, new Handler(Looper.getMainLooper()));
+        } catch (Exception e2) {
+            Log.w(TAG, "registerDisplayListener failed: " + e2);
+        }
+        ((InputManager) getSystemService("input")).registerInputDeviceListener(this.inputDeviceListener, new Handler(Looper.getMainLooper()));
         requestAllFilesAccess();
         restoreWebData();
+        this.mediaSession = new MediaSessionBridge(this);
         this.activeEngine = 1;
     }
 
+    public void requestCameraPermission() {
+        if (checkSelfPermission("android.permission.CAMERA") == 0) {
+            return;
+        }
+        Log.i(TAG, "Requesting CAMERA (positional tracking)");
+        new Handler(Looper.getMainLooper()).post(new Runnable() { // from class: com.vrapp.core.MainActivity.8
+            @Override // java.lang.Runnable
+            public void run() {
+                MainActivity.this.requestPermissions(new String[]{"android.permission.CAMERA"}, MainActivity.CAMERA_PERMISSION_REQUEST);
+            }
+        });
+    }
+
+    public void setHeadTrackerEnabled(final boolean z) {
+        new Handler(Looper.getMainLooper()).post(new Runnable() { // from class: com.vrapp.core.MainActivity.9
+            @Override // java.lang.Runnable
+            public void run() {
+                MainActivity.this.applyHeadTracker(z);
+            }
+        });
+    }
+
+    /* JADX INFO: Access modifiers changed from: private */
+    public void applyHeadTracker(boolean z) {
+        this.headTrackerWanted = z;
+        if (!z) {
+            HeadTrackerBle headTrackerBle = this.headTracker;
+            if (headTrackerBle != null) {
+                headTrackerBle.stop();
+            }
+            return;
+        }
+        if (Build.VERSION.SDK_INT >= 31 && (checkSelfPermission("android.permission.BLUETOOTH_SCAN") != 0 || checkSelfPermission("android.permission.BLUETOOTH_CONNECT") != 0)) {
+            Log.i(TAG, "Requesting BLUETOOTH_SCAN / BLUETOOTH_CONNECT (head tracker)");
+            requestPermissions(new String[]{"android.permission.BLUETOOTH_SCAN", "android.permission.BLUETOOTH_CONNECT"}, BLUETOOTH_PERMISSION_REQUEST);
+            return;
+        }
+        if (Build.VERSION.SDK_INT < 31 && checkSelfPermission("android.permission.ACCESS_FINE_LOCATION") != 0) {
+            Log.i(TAG, "Requesting ACCESS_FINE_LOCATION (BLE scan, head tracker)");
+            requestPermissions(new String[]{"android.permission.ACCESS_FINE_LOCATION"}, BLUETOOTH_PERMISSION_REQUEST);
+            return;
+        }
+        if (this.headTracker == null) {
+            this.headTracker = new HeadTrackerBle(this);
+        }
+        this.headTracker.start();
+    }
+
+    @Override // android.app.Activity
+    public void onRequestPermissionsResult(int i, String[] strArr, int[] iArr) {
+        super.onRequestPermissionsResult(i, strArr, iArr);
+        if (i != BLUETOOTH_PERMISSION_REQUEST || !this.headTrackerWanted) {
+            return;
+        }
+        for (int i2 : iArr) {
+            if (i2 != 0) {
+                Log.w(TAG, "Head tracker: Bluetooth permission denied");
+                return;
+            }
+        }
+        applyHeadTracker(true);
+    }
+
     private void requestAllFilesAccess() {
         try {
             if (Environment.isExternalStorageManager()) {
@@ -196,6 +371,84 @@ public class MainActivity extends NativeActivity {
         }
     }
 
+    public void setControllerMotionEnabled(final boolean z) {
+        new Handler(Looper.getMainLooper()).post(new Runnable() { // from class: com.vrapp.core.MainActivity.11
+            @Override // java.lang.Runnable
+            public void run() {
+                MainActivity.this.applyControllerMotion(z);
+            }
+        });
+    }
+
+    /* JADX INFO: Access modifiers changed from: private */
+    public void applyControllerMotion(boolean z) {
+        SensorManager sensorManager = this.controllerSensors;
+        if (sensorManager != null) {
+            sensorManager.unregisterListener(this.controllerGyroListener);
+            this.controllerSensors = null;
+        }
+        if (!z || Build.VERSION.SDK_INT < 31) {
+            return;
+        }
+        InputDevice device = InputDevice.getDevice(this.gamepadDeviceId);
+        if (device == null) {
+            return;
+        }
+        SensorManager sensorManager2 = device.getSensorManager();
+        Sensor defaultSensor = sensorManager2.getDefaultSensor(4);
+        if (defaultSensor == null) {
+            Log.i(TAG, "Controller has no gyroscope (motion aim unavailable)");
+            return;
+        }
+        sensorManager2.registerListener(this.controllerGyroListener, defaultSensor, 1);
+        this.controllerSensors = sensorManager2;
+    }
+
+    public void rumble(int i, int i2) {
+        InputDevice device = InputDevice.getDevice(this.gamepadDeviceId);
+        if (device == null) {
+            return;
+        }
+        Vibrator vibrator = Build.VERSION.SDK_INT >= 31 ? device.getVibratorManager().getDefaultVibrator() : device.getVibrator();
+        if (vibrator == null || !vibrator.hasVibrator()) {
+            return;
+        }
+        vibrator.vibrate(VibrationEffect.createOneShot(i, vibrator.hasAmplitudeControl() ? i2 : -1));
+    }
+
+    /* JADX INFO: Access modifiers changed from: private */
+    public void describeGamepad(int i) {
+        InputDevice device;
+        if (this.describedGamepads.contains(Integer.valueOf(i)) || (device = InputDevice.getDevice(i)) == null) {
+            return;
+        }
+        this.describedGamepads.add(Integer.valueOf(i));
+        onGamepadInfo(i, device.getName(), device.getVendorId(), device.getProductId());
+    }
+
+    public void setMediaState(boolean z, long j, long j2, String str) {
+        MediaSessionBridge mediaSessionBridge = this.mediaSession;
+        if (mediaSessionBridge != null) {
+            mediaSessionBridge.setState(z, j, j2, str);
+        }
+    }
+
+    public float controllerBattery(int i) {
+        InputDevice device = InputDevice.getDevice(i);
+        if (device == null || Build.VERSION.SDK_INT < 31) {
+            return -1.0f;
+        }
+        BatteryState batteryState = device.getBatteryState();
+        if (!batteryState.isPresent()) {
+            return -1.0f;
+        }
+        float capacity = batteryState.getCapacity();
+        if (Float.isNaN(capacity)) {
+            return -1.0f;
+        }
+        return capacity;
+    }
+
     public void volumeUp() {
         AudioManager audioManager = (AudioManager) getSystemService("audio");
         int streamVolume = audioManager.getStreamVolume(3);
@@ -224,6 +477,83 @@ public class MainActivity extends NativeActivity {
         return ((AudioManager) getSystemService("audio")).getStreamMaxVolume(3);
     }
 
+    /** Window brightness override (0..1); negative follows the system setting. */
+    public void setScreenBrightness(final float brightness) {
+        runOnUiThread(new Runnable() {
+            @Override
+            public void run() {
+                Window window = getWindow();
+                WindowManager.LayoutParams attributes = window.getAttributes();
+                attributes.screenBrightness = brightness < 0.0f ? WindowManager.LayoutParams.BRIGHTNESS_OVERRIDE_NONE : brightness;
+                window.setAttributes(attributes);
+            }
+        });
+    }
+
+    /**
+     * Phone status for the VR HUD as "time\tpercent\tcharging\twifi": the time in the
+     * user's 12/24-hour format, battery percent (-1 if unknown), and Wi-Fi as the signal
+     * in dBm, "on" when connected but the signal isn't reported (before API 29), or "off".
+     */
+    public String getDeviceStatus() {
+        String time = DateFormat.getTimeFormat(this).format(new Date());
+        BatteryManager batteryManager = (BatteryManager) getSystemService("batterymanager");
+        int percent = batteryManager.getIntProperty(BatteryManager.BATTERY_PROPERTY_CAPACITY);
+        if (percent <= 0 || percent > 100) {
+            percent = -1;
+        }
+        boolean charging = batteryManager.isCharging();
+        String wifi = "off";
+        ConnectivityManager connectivityManager = (ConnectivityManager) getSystemService("connectivity");
+        Network network = connectivityManager.getActiveNetwork();
+        NetworkCapabilities capabilities = network == null ? null : connectivityManager.getNetworkCapabilities(network);
+        if (capabilities != null && capabilities.hasTransport(NetworkCapabilities.TRANSPORT_WIFI)) {
+            int rssi = Build.VERSION.SDK_INT >= 29 ? capabilities.getSignalStrength() : Integer.MIN_VALUE;
+            wifi = rssi == Integer.MIN_VALUE ? "on" : Integer.toString(rssi);
+        }
+        return time + "\t" + percent + "\t" + charging + "\t" + wifi;
+    }
+
+    /**
+     * Mounted storage volumes for the Media Center, one "path\tdescription\tremovable"
+     * line each. The path is only public from API 30; before that it's the hidden getPath().
+     */
+    public String getStorageVolumes() {
+        StorageManager storageManager = (StorageManager) getSystemService("storage");
+        StringBuilder out = new StringBuilder();
+        for (StorageVolume volume : storageManager.getStorageVolumes()) {
+            String state = volume.getState();
+            if (!Environment.MEDIA_MOUNTED.equals(state) && !Environment.MEDIA_MOUNTED_READ_ONLY.equals(state)) {
+                continue;
+            }
+            File dir = null;
+            if (Build.VERSION.SDK_INT >= 30) {
+                dir = volume.getDirectory();
+            } else {
+                try {
+                    dir = new File((String) StorageVolume.class.getMethod("getPath").invoke(volume));
+                } catch (Exception e) {
+                    Log.w(TAG, "StorageVolume.getPath: " + e);
+                }
+            }
+            if (dir == null) {
+                continue;
+            }
+            out.append(dir.getAbsolutePath()).append('\t')
+               .append(volume.getDescription(this)).append('\t')
+               .append(volume.isRemovable()).append('\n');
+        }
+        return out.toString();
+    }
+
+    /** From the dock's volume slider; the level shows in the VR OSD, not the system UI. */
+    public void setVolume(int level) {
+        AudioManager audioManager = (AudioManager) getSystemService("audio");
+        int streamMaxVolume = audioManager.getStreamMaxVolume(3);
+        audioManager.setStreamVolume(3, Math.max(0, Math.min(level, streamMaxVolume)), 0);
+        Log.i(TAG, "Volume Set: " + level + "/" + streamMaxVolume);
+    }
+
     public void launchVideoPicker() {
         Log.i(TAG, "Launching Video Picker (Google Photos)...");
         Intent intent = new Intent("android.intent.action.GET_CONTENT");
@@ -545,7 +875,8 @@ public class MainActivity extends NativeActivity {
         reportDisplayRotation();
     }
 
-    private void reportDisplayRotation() {
+    /* JADX INFO: Access modifiers changed from: private */
+    public void reportDisplayRotation() {
         try {
             int rotation = getWindowManager().getDefaultDisplay().getRotation();
             Log.i(TAG, "Display rotation = " + rotation);
@@ -615,9 +946,9 @@ public class MainActivity extends NativeActivity {
         }
     }
 
-    public void webViewScroll(float f, float f2, float f3, float f4) {
+    public void webViewScroll(int i, float f, float f2, float f3, float f4) {
         if (this.geckoReady) {
-            this.geckoViewManager.scroll(f, f2, f3, f4);
+            this.geckoViewManager.scroll(i, f, f2, f3, f4);
         }
     }
 
@@ -627,9 +958,9 @@ public class MainActivity extends NativeActivity {
         }
     }
 
-    public void webViewTap(float f, float f2) {
+    public void webViewTap(int i, float f, float f2) {
         if (this.geckoReady) {
-            this.geckoViewManager.tap(f, f2);
+            this.geckoViewManager.tap(i, f, f2);
         }
     }
 
@@ -670,6 +1001,50 @@ public class MainActivity extends NativeActivity {
         }
     }
 
+    /** Move the screen's active tab into a new floating window; its number (Rust's webview::pop_out). */
+    public int webViewPopOut() {
+        final int andIncrement = this.nextWebWindow.getAndIncrement();
+        new Handler(Looper.getMainLooper()).post(new Runnable() {
+            @Override // java.lang.Runnable
+            public final void run() {
+                MainActivity.this.ensureGeckoInit();
+                if (MainActivity.this.geckoReady) {
+                    MainActivity.this.geckoViewManager.popOut(andIncrement);
+                }
+            }
+        });
+        return andIncrement;
+    }
+
+    /** Open a page in a new floating window's own tab; its number (Rust's webview::open_window). */
+    public int webViewOpenWindow(final String str) {
+        final int andIncrement = this.nextWebWindow.getAndIncrement();
+        new Handler(Looper.getMainLooper()).post(new Runnable() {
+            @Override // java.lang.Runnable
+            public final void run() {
+                MainActivity.this.ensureGeckoInit();
+                if (MainActivity.this.geckoReady) {
+                    MainActivity.this.geckoViewManager.openWindow(andIncrement, str);
+                }
+            }
+        });
+        return andIncrement;
+    }
+
+    public void webViewCloseWindow(int i) {
+        if (this.geckoReady) {
+            this.geckoViewManager.closeWindow(i);
+        }
+    }
+
+    /** Rust is done with a frame's hardware buffer (webview::release_frames); the reader may reuse it. */
+    public void webViewReleaseFrame(long j) {
+        GeckoViewManager geckoViewManager = this.geckoViewManager;
+        if (geckoViewManager != null) {
+            geckoViewManager.releaseFrame(j);
+        }
+    }
+
     public void setBrowserActive(boolean z) {
         GeckoViewManager geckoViewManager = this.geckoViewManager;
         if (geckoViewManager != null) {
@@ -817,6 +1192,21 @@ public class MainActivity extends NativeActivity {
 
     @Override // android.app.NativeActivity, android.app.Activity
     protected void onDestroy() {
+        HeadTrackerBle headTrackerBle = this.headTracker;
+        if (headTrackerBle != null) {
+            headTrackerBle.stop();
+        }
+        MediaSessionBridge mediaSessionBridge = this.mediaSession;
+        if (mediaSessionBridge != null) {
+            mediaSessionBridge.release();
+            this.mediaSession = null;
+        }
+        try {
+            ((DisplayManager) getSystemService("display")).unregisterDisplayListener(this.displayListener);
+        } catch (Exception e) {
+            Log.w(TAG, "unregisterDisplayListener: " + e);
+        }
+        ((InputManager) getSystemService("input")).unregisterInputDeviceListener(this.inputDeviceListener);
         backupWebData();
         stopVideo();
         if (this.overlayAdded && this.gamepadOverlay != null) {
@@ -960,27 +1350,174 @@ public class MainActivity extends NativeActivity {
         return thread;
     }
 
-    public void requestThumbnail(final String str, final int i, final int i2) {
+    public void requestThumbnail(final String str, final int i, final int i2, final String str2) {
         if (str == null || !this.thumbInFlight.add(str)) {
             return;
         }
         this.thumbPool.execute(new Runnable() { // from class: com.vrapp.core.MainActivity$$ExternalSyntheticLambda0
             @Override // java.lang.Runnable
             public final void run() {
-                MainActivity.this.lambda$requestThumbnail$4(str, i, i2);
+                MainActivity.this.lambda$requestThumbnail$4(str, i, i2, str2);
             }
         });
     }
 
+    /** Hand a poster to Rust as RGBA8 (recycles the bitmap). */
+    private void deliverThumbnail(String str, Bitmap bitmap) {
+        if (bitmap.getConfig() != Bitmap.Config.ARGB_8888) {
+            Bitmap bitmapCopy = bitmap.copy(Bitmap.Config.ARGB_8888, false);
+            bitmap.recycle();
+            bitmap = bitmapCopy;
+        }
+        int width = bitmap.getWidth();
+        int height = bitmap.getHeight();
+        ByteBuffer byteBufferAllocate = ByteBuffer.allocate(width * height * 4);
+        bitmap.copyPixelsToBuffer(byteBufferAllocate);
+        bitmap.recycle();
+        onThumbnail(str, width, height, byteBufferAllocate.array());
+    }
+
+    /** Write a poster to the thumbnail cache as JPEG (via a temp file, so a crash never leaves half a file). */
+    private void saveThumbnail(Bitmap bitmap, String str) {
+        File file = new File(str);
+        File tmp = new File(str + ".tmp");
+        File parent = file.getParentFile();
+        if (parent != null) {
+            parent.mkdirs();
+        }
+        try (FileOutputStream out = new FileOutputStream(tmp)) {
+            bitmap.compress(Bitmap.CompressFormat.JPEG, 85, out);
+        } catch (IOException e) {
+            Log.w(TAG, "thumbnail cache write failed for " + str + ": " + e.getMessage());
+            tmp.delete();
+            return;
+        }
+        if (!tmp.renameTo(file)) {
+            tmp.delete();
+            return;
+        }
+        if (parent != null && this.thumbWrites.incrementAndGet() % THUMB_TRIM_EVERY == 0) {
+            trimThumbnailCache(parent);
+        }
+    }
+
+    /** Delete the least recently shown posters (oldest mtime; cache hits touch it) until the cache fits its cap. */
+    private synchronized void trimThumbnailCache(File dir) {
+        File[] files = dir.listFiles();
+        if (files == null) {
+            return;
+        }
+        long total = 0;
+        for (File f : files) {
+            total += f.length();
+        }
+        if (total <= THUMB_CACHE_MAX_BYTES) {
+            return;
+        }
+        Arrays.sort(files, (a, b) -> Long.compare(a.lastModified(), b.lastModified()));
+        int removed = 0;
+        for (File f : files) {
+            if (total <= THUMB_CACHE_MAX_BYTES) {
+                break;
+            }
+            long size = f.length();
+            if (f.delete()) {
+                total -= size;
+                removed++;
+            }
+        }
+        Log.i(TAG, "thumbnail cache trimmed: " + removed + " files removed");
+    }
+
+    /** Save an RGBA8 video frame from Rust, turned upright by {@code i3} degrees, to Pictures/VR Snapshots. */
+    public void saveSnapshot(final byte[] bArr, final int i, final int i2, final int i3, final String str) {
+        this.thumbPool.execute(new Runnable() {
+            @Override // java.lang.Runnable
+            public final void run() {
+                MainActivity.this.writeSnapshot(bArr, i, i2, i3, str);
+            }
+        });
+    }
+
+    /* JADX INFO: Access modifiers changed from: private */
+    public void writeSnapshot(byte[] bArr, int i, int i2, int i3, String str) {
+        Bitmap bitmap = Bitmap.createBitmap(i, i2, Bitmap.Config.ARGB_8888);
+        bitmap.copyPixelsFromBuffer(ByteBuffer.wrap(bArr));
+        if (i3 != 0) {
+            Matrix matrix = new Matrix();
+            matrix.postRotate(i3);
+            Bitmap rotated = Bitmap.createBitmap(bitmap, 0, 0, i, i2, matrix, true);
+            bitmap.recycle();
+            bitmap = rotated;
+        }
+        String fileName = str + ".jpg";
+        ContentValues contentValues = new ContentValues();
+        contentValues.put("_display_name", fileName);
+        contentValues.put("mime_type", "image/jpeg");
+        if (Build.VERSION.SDK_INT >= 29) {
+            contentValues.put("relative_path", Environment.DIRECTORY_PICTURES + "/VR Snapshots");
+            contentValues.put("is_pending", (Integer) 1);
+        } else {
+            File dir = new File(Environment.getExternalStoragePublicDirectory(Environment.DIRECTORY_PICTURES), "VR Snapshots");
+            dir.mkdirs();
+            contentValues.put("_data", new File(dir, fileName).getAbsolutePath());
+        }
+        ContentResolver contentResolver = getContentResolver();
+        Uri uri = contentResolver.insert(MediaStore.Images.Media.EXTERNAL_CONTENT_URI, contentValues);
+        if (uri == null) {
+            Log.w(TAG, "snapshot: MediaStore insert failed for " + fileName);
+            bitmap.recycle();
+            return;
+        }
+        try (OutputStream out = contentResolver.openOutputStream(uri)) {
+            if (out == null) {
+                throw new IOException("no output stream");
+            }
+            bitmap.compress(Bitmap.CompressFormat.JPEG, 95, out);
+        } catch (IOException e) {
+            Log.w(TAG, "snapshot write failed for " + fileName + ": " + e.getMessage());
+            contentResolver.delete(uri, null, null);
+            bitmap.recycle();
+            return;
+        }
+        bitmap.recycle();
+        if (Build.VERSION.SDK_INT >= 29) {
+            contentValues.clear();
+            contentValues.put("is_pending", (Integer) 0);
+            contentResolver.update(uri, contentValues, null, null);
+        }
+        Log.i(TAG, "snapshot saved: " + fileName);
+    }
+
     /* JADX INFO: Access modifiers changed from: private */
-    public /* synthetic */ void lambda$requestThumbnail$4(String str, int i, int i2) {
+    public /* synthetic */ void lambda$requestThumbnail$4(String str, int i, int i2, String str2) {
+        // Poster cached as JPEG on an earlier visit (str2 = cache file chosen by Rust)
+        if (str2 != null) {
+            Bitmap cached = BitmapFactory.decodeFile(str2);
+            if (cached != null) {
+                // Mark it recently used, so trimming drops other posters first
+                new File(str2).setLastModified(System.currentTimeMillis());
+                deliverThumbnail(str, cached);
+                this.thumbInFlight.remove(str);
+                return;
+            }
+        }
         MediaMetadataRetriever mediaMetadataRetriever = new MediaMetadataRetriever();
         try {
             try {
                 mediaMetadataRetriever.setDataSource(str);
-                Bitmap scaledFrameAtTime = mediaMetadataRetriever.getScaledFrameAtTime(1000000L, 2, i, i2);
+                // A frame ~10% in is more representative than the (often black) opening
+                long frameUs = 1000000L;
+                String durationMs = mediaMetadataRetriever.extractMetadata(MediaMetadataRetriever.METADATA_KEY_DURATION);
+                if (durationMs != null) {
+                    try {
+                        frameUs = Long.parseLong(durationMs) * 100L;
+                    } catch (NumberFormatException unused) {
+                    }
+                }
+                Bitmap scaledFrameAtTime = mediaMetadataRetriever.getScaledFrameAtTime(frameUs, 2, i, i2);
                 if (scaledFrameAtTime == null) {
-                    scaledFrameAtTime = mediaMetadataRetriever.getScaledFrameAtTime(1000000L, 3, i, i2);
+                    scaledFrameAtTime = mediaMetadataRetriever.getScaledFrameAtTime(frameUs, 3, i, i2);
                 }
                 if (scaledFrameAtTime == null) {
                     scaledFrameAtTime = mediaMetadataRetriever.getScaledFrameAtTime(0L, 2, i, i2);
@@ -1001,17 +1538,10 @@ public class MainActivity extends NativeActivity {
                     }
                 }
                 if (scaledFrameAtTime != null) {
-                    if (scaledFrameAtTime.getConfig() != Bitmap.Config.ARGB_8888) {
-                        Bitmap bitmapCopy = scaledFrameAtTime.copy(Bitmap.Config.ARGB_8888, false);
-                        scaledFrameAtTime.recycle();
-                        scaledFrameAtTime = bitmapCopy;
+                    if (str2 != null) {
+                        saveThumbnail(scaledFrameAtTime, str2);
                     }
-                    int width = scaledFrameAtTime.getWidth();
-                    int height = scaledFrameAtTime.getHeight();
-                    ByteBuffer byteBufferAllocate = ByteBuffer.allocate(width * height * 4);
-                    scaledFrameAtTime.copyPixelsToBuffer(byteBufferAllocate);
-                    scaledFrameAtTime.recycle();
-                    onThumbnail(str, width, height, byteBufferAllocate.array());
+                    deliverThumbnail(str, scaledFrameAtTime);
                 } else {
                     onThumbnail(str, 0, 0, new byte[0]);
                 }
@@ -1040,17 +1570,10 @@ public class MainActivity extends NativeActivity {
         if ((source & 1025) == 1025 || (source & InputDeviceCompat.SOURCE_JOYSTICK) == 16777232) {
             int keyCode = keyEvent.getKeyCode();
             boolean z = keyEvent.getAction() == 0;
+            this.gamepadDeviceId = keyEvent.getDeviceId();
+            describeGamepad(this.gamepadDeviceId);
             Log.i(TAG, "GAMEPAD KEY: code=" + keyCode + " scan=" + keyEvent.getScanCode() + " pressed=" + z);
-            if (z) {
-                if (keyCode == 21) {
-                    volumeDown();
-                    Log.i(TAG, "D-pad LEFT KeyEvent: Volume Down");
-                } else if (keyCode == 22) {
-                    volumeUp();
-                    Log.i(TAG, "D-pad RIGHT KeyEvent: Volume Up");
-                }
-            }
-            onGamepadButton(keyCode, z);
+            onGamepadButton(keyEvent.getDeviceId(), keyCode, z);
             return true;
         }
         return super.dispatchKeyEvent(keyEvent);
@@ -1061,30 +1584,22 @@ public class MainActivity extends NativeActivity {
         Log.i(TAG, "dispatchGenericMotionEvent CALLED! source=" + motionEvent.getSource());
         int source = motionEvent.getSource();
         if (((source & InputDeviceCompat.SOURCE_JOYSTICK) == 16777232 || (source & 1025) == 1025) && motionEvent.getAction() == 2) {
-            float axisValue = motionEvent.getAxisValue(0);
-            float axisValue2 = motionEvent.getAxisValue(1);
-            float axisValue3 = motionEvent.getAxisValue(11);
-            float axisValue4 = motionEvent.getAxisValue(14);
-            float axisValue5 = motionEvent.getAxisValue(17);
-            float axisValue6 = motionEvent.getAxisValue(18);
-            if (axisValue5 == 0.0f) {
-                axisValue5 = motionEvent.getAxisValue(23);
-            }
-            if (axisValue6 == 0.0f) {
-                axisValue6 = motionEvent.getAxisValue(22);
+            int deviceId = motionEvent.getDeviceId();
+            boolean deviceChanged = deviceId != this.gamepadDeviceId;
+            this.gamepadDeviceId = deviceId;
+            describeGamepad(deviceId);
+            for (int i = 0; i < this.gamepadAxes.length; i++) {
+                this.gamepadAxes[i] = motionEvent.getAxisValue(i);
             }
-            float f = axisValue6;
             float axisValue7 = motionEvent.getAxisValue(15);
             float axisValue8 = motionEvent.getAxisValue(16);
             float f2 = this.lastHatX;
-            boolean z = (axisValue7 == f2 && axisValue8 == this.lastHatY) ? false : true;
+            boolean z = (axisValue7 == f2 && axisValue8 == this.lastHatY && !deviceChanged) ? false : true;
             if (axisValue7 != f2) {
                 if (axisValue7 < -0.5f) {
-                    volumeDown();
-                    Log.i(TAG, "D-pad LEFT: Volume Down");
+                    Log.i(TAG, "D-pad LEFT");
                 } else if (axisValue7 > 0.5f) {
-                    volumeUp();
-                    Log.i(TAG, "D-pad RIGHT: Volume Up");
+                    Log.i(TAG, "D-pad RIGHT");
                 }
                 this.lastHatX = axisValue7;
             }
@@ -1097,10 +1612,10 @@ public class MainActivity extends NativeActivity {
                 this.lastHatY = axisValue8;
             }
             if (z) {
-                onDpadAxis(axisValue7, axisValue8);
+                onDpadAxis(deviceId, axisValue7, axisValue8);
                 Log.i(TAG, "HAT axis sent to Rust: x=" + axisValue7 + " y=" + axisValue8);
             }
-            onGamepadAxis(axisValue, axisValue2, axisValue3, axisValue4, axisValue5, f);
+            onGamepadAxis(deviceId, this.gamepadAxes);
             return true;
         }
         return super.dispatchGenericMotionEvent(motionEvent);
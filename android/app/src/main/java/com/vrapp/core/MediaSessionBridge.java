@@ -0,0 +1,115 @@
+package com.vrapp.core;
+
+import android.content.Context;
+import android.media.MediaMetadata;
+import android.media.session.MediaSession;
+import android.media.session.PlaybackState;
+import android.os.Handler;
+import android.os.Looper;
+import android.os.SystemClock;
+
+/**
+ * Media session for the NDK player.
+ *
+ * Makes the app the target of media buttons (Bluetooth headphones, watches, the
+ * lock screen) and forwards them to Rust (media_session.rs), which applies them
+ * in the render loop. Rust reports the playback state back through
+ * {@link #setState} so the buttons toggle the right way and the lock screen
+ * shows the title and position.
+ */
+public class MediaSessionBridge {
+    // Command codes, as matched in media_session.rs
+    private static final int PLAY = 0;
+    private static final int PAUSE = 1;
+    private static final int STOP = 2;
+    private static final int NEXT = 3;
+    private static final int PREVIOUS = 4;
+    private static final int SEEK_TO = 5;
+    private static final int FAST_FORWARD = 6;
+    private static final int REWIND = 7;
+
+    private static final long ACTIONS = PlaybackState.ACTION_PLAY | PlaybackState.ACTION_PAUSE
+            | PlaybackState.ACTION_PLAY_PAUSE | PlaybackState.ACTION_STOP
+            | PlaybackState.ACTION_SKIP_TO_NEXT | PlaybackState.ACTION_SKIP_TO_PREVIOUS
+            | PlaybackState.ACTION_SEEK_TO | PlaybackState.ACTION_FAST_FORWARD | PlaybackState.ACTION_REWIND;
+
+    private final MediaSession session;
+    private final Handler handler = new Handler(Looper.getMainLooper());
+
+    /** A transport command; {@code arg} is the position in ms for SEEK_TO. */
+    static native void onMediaCommand(int command, long arg);
+
+    public MediaSessionBridge(Context context) {
+        this.session = new MediaSession(context, "vr_core");
+        this.session.setCallback(new MediaSession.Callback() {
+            @Override
+            public void onPlay() {
+                onMediaCommand(PLAY, 0);
+            }
+
+            @Override
+            public void onPause() {
+                onMediaCommand(PAUSE, 0);
+            }
+
+            @Override
+            public void onStop() {
+                onMediaCommand(STOP, 0);
+            }
+
+            @Override
+            public void onSkipToNext() {
+                onMediaCommand(NEXT, 0);
+            }
+
+            @Override
+            public void onSkipToPrevious() {
+                onMediaCommand(PREVIOUS, 0);
+            }
+
+            @Override
+            public void onSeekTo(long pos) {
+                onMediaCommand(SEEK_TO, pos);
+            }
+
+            @Override
+            public void onFastForward() {
+                onMediaCommand(FAST_FORWARD, 0);
+            }
+
+            @Override
+            public void onRewind() {
+                onMediaCommand(REWIND, 0);
+            }
+        }, this.handler);
+        this.session.setPlaybackState(new PlaybackState.Builder()
+                .setActions(ACTIONS)
+                .setState(PlaybackState.STATE_NONE, 0, 0.0f)
+                .build());
+        this.session.setActive(true);
+    }
+
+    /** Called (via MainActivity) from the render thread whenever the state changes. */
+    public void setState(final boolean playing, final long positionMs, final long durationMs, final String title) {
+        this.handler.post(new Runnable() {
+            @Override
+            public void run() {
+                int state = title.isEmpty() ? PlaybackState.STATE_STOPPED
+                        : playing ? PlaybackState.STATE_PLAYING : PlaybackState.STATE_PAUSED;
+                MediaSessionBridge.this.session.setPlaybackState(new PlaybackState.Builder()
+                        .setActions(ACTIONS)
+                        .setState(state, positionMs, playing ? 1.0f : 0.0f, SystemClock.elapsedRealtime())
+                        .build());
+                MediaSessionBridge.this.session.setMetadata(new MediaMetadata.Builder()
+                        .putString(MediaMetadata.METADATA_KEY_TITLE, title)
+                        .putLong(MediaMetadata.METADATA_KEY_DURATION, durationMs)
+                        .build());
+            }
+        });
+    }
+
+    public void release() {
+        this.session.setActive(false);
+        this.session.release();
+    }
+}
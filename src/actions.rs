@@ -0,0 +1,314 @@
+//! Semantic input actions
+//!
+//! Every input source — dock buttons and the seek bar, the hardware keyboard and
+//! mouse, touch taps, media buttons (media_session.rs) and the controller once
+//! lib.rs has decided what a button means in the current context — says what it
+//! wants as an `Action`. They queue here and the render loop drains the queue
+//! once per frame, so play / pause, seeks and the rest are carried out in one
+//! place whatever asked for them.
+//!
+//! Menu actions (`Navigate`, `Select`, `Confirm`, `Back`, `ToggleMenu`) mean
+//! different things in each panel; lib.rs folds them into the frame's
+//! `GamepadActions` with `fold_menu` so the panels read them as controller input.
+//!
+//! `CHORDS` binds two-button controller shortcuts straight to actions, and
+//! `HINTS` lists what the controller does in each `Context` for the hint bar.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::gamepad::{Button, GamepadActions};
+use crate::i18n::{tr, trf};
+
+/// Skip size for the seek buttons, keys and media fast-forward / rewind.
+pub const SEEK_STEP_US: i64 = 10_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    PlayPause,
+    Play,
+    Pause,
+    /// Stop and unload the current video
+    Stop,
+    /// Relative, µs
+    SeekBy(i64),
+    /// Absolute, µs
+    SeekTo(i64),
+    NextTrack,
+    PreviousTrack,
+    ToggleVr,
+    ExitVr,
+    Recenter,
+    Snapshot,
+    /// Change the content scale by this much
+    Zoom(f32),
+    // Menu actions, interpreted by the open panel
+    Navigate(Direction),
+    /// Activate the highlighted item (✕)
+    Select,
+    /// The panel's secondary action (□)
+    Confirm,
+    Back,
+    ToggleMenu,
+}
+
+static QUEUE: Mutex<Vec<Action>> = Mutex::new(Vec::new());
+
+/// Queue an action for the next frame.
+pub fn push(action: Action) {
+    if let Ok(mut queue) = QUEUE.lock() {
+        queue.push(action);
+    }
+}
+
+/// Everything queued since the last call, oldest first.
+pub fn drain() -> Vec<Action> {
+    QUEUE.lock().map(|mut q| std::mem::take(&mut *q)).unwrap_or_default()
+}
+
+/// A two-button controller shortcut: fires when both are down.
+pub struct Chord {
+    pub buttons: [Button; 2],
+    pub action: Action,
+    /// For the hint bar and the Input settings
+    pub name: &'static str,
+}
+
+/// Controller shortcuts. A chord's buttons keep their own bindings, but those
+/// wait for the button to be released (a tap) and are dropped when the press
+/// became part of a chord.
+pub const CHORDS: &[Chord] = &[
+    Chord { buttons: [Button::L1, Button::R1], action: Action::Recenter, name: "Recenter" },
+    Chord { buttons: [Button::Create, Button::Options], action: Action::Snapshot, name: "Save frame" },
+];
+
+/// Held together for `UNLOCK_HOLD`, the only thing the controller does while
+/// the controls are locked.
+pub const UNLOCK: [Button; 2] = [Button::L1, Button::R1];
+pub const UNLOCK_HOLD: Duration = Duration::from_secs(2);
+
+/// Buttons that act while held (trim modifier, analog zoom, D-pad repeat), so
+/// their press can't wait to see whether it becomes a chord.
+const HELD: [Button; 7] = [
+    Button::Square, Button::L2, Button::R2,
+    Button::DpadUp, Button::DpadDown, Button::DpadLeft, Button::DpadRight,
+];
+
+/// Whether `button` is part of a chord, so its single press fires on release.
+pub fn chorded(button: Button) -> bool {
+    CHORDS.iter().any(|c| c.buttons.contains(&button))
+}
+
+/// Chords that can't work alongside the other bindings: a button paired with
+/// itself, the same pair bound twice, or a button whose binding acts while held.
+pub fn conflicts() -> Vec<String> {
+    let mut found = Vec::new();
+    for (i, chord) in CHORDS.iter().enumerate() {
+        let [a, b] = chord.buttons;
+        if a == b {
+            found.push(format!("{}: {:?} twice", chord.name, a));
+        }
+        if let Some(other) = CHORDS[..i].iter().find(|c| c.buttons.contains(&a) && c.buttons.contains(&b)) {
+            found.push(format!("{}: {:?} + {:?} is already {}", chord.name, a, b, other.name));
+        }
+        for button in chord.buttons.iter().filter(|b| HELD.contains(b)) {
+            found.push(format!("{}: {:?} acts while held", chord.name, button));
+        }
+    }
+    found
+}
+
+/// Which part of the app the controller is driving, in the order lib.rs checks them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Context {
+    Playback,
+    Dock,
+    Settings,
+    MediaCenter,
+    Queue,
+    ResumePrompt,
+    UrlDialog,
+    /// The virtual keyboard typing the Media Center filter
+    Search,
+    /// The Media Center in multi-select mode
+    MediaSelect,
+    /// The hold-□ menu for a Media Center entry
+    EntryMenu,
+    /// The virtual keyboard typing a new name for a Media Center entry
+    Rename,
+    /// The virtual keyboard typing a name for the lens values
+    PresetName,
+    /// The first-run walkthrough
+    Tutorial,
+    /// The card saying why playback failed
+    PlaybackError,
+    /// Touch and the controller locked
+    Locked,
+}
+
+/// How a hint's control is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    /// Any of these
+    Press(&'static [Button]),
+    Hold(&'static [Button]),
+    /// All of these held together
+    HoldAll(&'static [Button]),
+    LeftStick,
+    RightStick,
+}
+
+/// One entry of the hint bar.
+pub struct Hint {
+    pub context: Context,
+    pub input: Input,
+    pub label: &'static str,
+}
+
+const DPAD: &[Button] = &[Button::DpadUp, Button::DpadDown, Button::DpadLeft, Button::DpadRight];
+
+/// The controller bindings lib.rs applies in each context, most used first.
+pub const HINTS: &[Hint] = &[
+    Hint { context: Context::Playback, input: Input::Press(&[Button::Cross]), label: "Play / pause" },
+    Hint { context: Context::Playback, input: Input::Press(&[Button::L1, Button::R1]), label: "Seek" },
+    Hint { context: Context::Playback, input: Input::Hold(&[Button::L1, Button::R1]), label: "Prev / next" },
+    Hint { context: Context::Playback, input: Input::Press(&[Button::Triangle]), label: "Menu" },
+    Hint { context: Context::Playback, input: Input::Press(&[Button::Create]), label: "Media center" },
+    Hint { context: Context::Playback, input: Input::Press(&[Button::DpadUp, Button::DpadDown]), label: "Chapters" },
+    Hint { context: Context::Playback, input: Input::Press(&[Button::DpadLeft, Button::DpadRight]), label: "3D layout" },
+    Hint { context: Context::Playback, input: Input::Press(&[Button::L2, Button::R2]), label: "Zoom" },
+    Hint { context: Context::Playback, input: Input::RightStick, label: "Look" },
+    Hint { context: Context::Playback, input: Input::Press(&[Button::R3]), label: "VR / 2D" },
+    Hint { context: Context::Playback, input: Input::Hold(&[Button::Cross]), label: "Stop" },
+    Hint { context: Context::Dock, input: Input::Press(&[Button::DpadLeft, Button::DpadRight]), label: "Move" },
+    Hint { context: Context::Dock, input: Input::Press(&[Button::DpadUp, Button::DpadDown]), label: "Seek bar" },
+    Hint { context: Context::Dock, input: Input::Press(&[Button::Cross, Button::Square]), label: "Open" },
+    Hint { context: Context::Dock, input: Input::Press(&[Button::Triangle, Button::Circle]), label: "Close" },
+    Hint { context: Context::Settings, input: Input::Press(DPAD), label: "Move" },
+    Hint { context: Context::Settings, input: Input::Press(&[Button::Cross]), label: "Change" },
+    Hint { context: Context::Settings, input: Input::Press(&[Button::L1, Button::R1]), label: "Section" },
+    Hint { context: Context::Settings, input: Input::LeftStick, label: "Point" },
+    Hint { context: Context::Settings, input: Input::Press(&[Button::Circle]), label: "Back" },
+    Hint { context: Context::Settings, input: Input::Press(&[Button::Triangle]), label: "Close" },
+    Hint { context: Context::MediaCenter, input: Input::Press(DPAD), label: "Browse" },
+    Hint { context: Context::MediaCenter, input: Input::Press(&[Button::Cross]), label: "Open" },
+    Hint { context: Context::MediaCenter, input: Input::Press(&[Button::DpadUp]), label: "Folder path" },
+    // □ toggles the details pane, so queueing moved to an R1 tap and play beside to holding R1
+    Hint { context: Context::MediaCenter, input: Input::Press(&[Button::Square]), label: "Details" },
+    Hint { context: Context::MediaCenter, input: Input::Press(&[Button::R1]), label: "Add to queue" },
+    Hint { context: Context::MediaCenter, input: Input::Hold(&[Button::Square]), label: "Favorite / rename / delete" },
+    Hint { context: Context::MediaCenter, input: Input::LeftStick, label: "Browse / tabs" },
+    Hint { context: Context::MediaCenter, input: Input::Hold(&[Button::R1]), label: "Play beside" },
+    Hint { context: Context::MediaCenter, input: Input::Press(&[Button::L1]), label: "Search" },
+    Hint { context: Context::MediaCenter, input: Input::Press(&[Button::Create]), label: "Grid / coverflow" },
+    Hint { context: Context::MediaCenter, input: Input::Press(&[Button::L2, Button::R2]), label: "Filter letter" },
+    Hint { context: Context::MediaCenter, input: Input::Hold(&[Button::Create]), label: "Select several" },
+    Hint { context: Context::MediaCenter, input: Input::Press(&[Button::Options]), label: "URL" },
+    Hint { context: Context::MediaCenter, input: Input::Press(&[Button::Circle]), label: "Clear / up a folder" },
+    Hint { context: Context::MediaCenter, input: Input::Press(&[Button::Triangle]), label: "Close" },
+    Hint { context: Context::MediaSelect, input: Input::Press(DPAD), label: "Browse" },
+    Hint { context: Context::MediaSelect, input: Input::Press(&[Button::Square]), label: "Select" },
+    Hint { context: Context::MediaSelect, input: Input::Hold(&[Button::Square]), label: "Select all" },
+    Hint { context: Context::MediaSelect, input: Input::Press(&[Button::Cross]), label: "Play all" },
+    Hint { context: Context::MediaSelect, input: Input::Press(&[Button::R1]), label: "Queue all" },
+    Hint { context: Context::MediaSelect, input: Input::Press(&[Button::Circle]), label: "Done" },
+    Hint { context: Context::EntryMenu, input: Input::Press(&[Button::DpadUp, Button::DpadDown]), label: "Move" },
+    Hint { context: Context::EntryMenu, input: Input::Press(&[Button::Cross]), label: "Choose" },
+    Hint { context: Context::EntryMenu, input: Input::Press(&[Button::Circle, Button::Triangle]), label: "Cancel" },
+    Hint { context: Context::Queue, input: Input::Press(&[Button::DpadUp, Button::DpadDown]), label: "Select" },
+    Hint { context: Context::Queue, input: Input::Press(&[Button::Cross]), label: "Play" },
+    Hint { context: Context::Queue, input: Input::Press(&[Button::Square]), label: "Remove" },
+    Hint { context: Context::Queue, input: Input::Hold(&[Button::L1, Button::R1]), label: "Prev / next" },
+    Hint { context: Context::Queue, input: Input::Press(&[Button::Circle, Button::Triangle]), label: "Close" },
+    Hint { context: Context::ResumePrompt, input: Input::Press(&[Button::Cross]), label: "Resume" },
+    Hint { context: Context::ResumePrompt, input: Input::Press(&[Button::Circle]), label: "Start over" },
+    Hint { context: Context::UrlDialog, input: Input::Press(DPAD), label: "Move" },
+    Hint { context: Context::UrlDialog, input: Input::Press(&[Button::Cross]), label: "Type" },
+    Hint { context: Context::UrlDialog, input: Input::Press(&[Button::Square]), label: "Delete" },
+    Hint { context: Context::UrlDialog, input: Input::Press(&[Button::Triangle]), label: "Shift" },
+    Hint { context: Context::UrlDialog, input: Input::Press(&[Button::L1, Button::R1]), label: "Recent" },
+    Hint { context: Context::UrlDialog, input: Input::Press(&[Button::Options]), label: "Play" },
+    Hint { context: Context::UrlDialog, input: Input::Press(&[Button::Circle]), label: "Cancel" },
+    Hint { context: Context::Search, input: Input::Press(DPAD), label: "Move" },
+    Hint { context: Context::Search, input: Input::Press(&[Button::Cross]), label: "Type" },
+    Hint { context: Context::Search, input: Input::Press(&[Button::Square]), label: "Delete" },
+    Hint { context: Context::Search, input: Input::Press(&[Button::Triangle]), label: "Shift" },
+    Hint { context: Context::Search, input: Input::Press(&[Button::Options, Button::Circle]), label: "Done" },
+    Hint { context: Context::Rename, input: Input::Press(DPAD), label: "Move" },
+    Hint { context: Context::Rename, input: Input::Press(&[Button::Cross]), label: "Type" },
+    Hint { context: Context::Rename, input: Input::Press(&[Button::Square]), label: "Delete" },
+    Hint { context: Context::Rename, input: Input::Press(&[Button::Triangle]), label: "Shift" },
+    Hint { context: Context::Rename, input: Input::Press(&[Button::Options]), label: "Rename" },
+    Hint { context: Context::Rename, input: Input::Press(&[Button::Circle]), label: "Cancel" },
+    Hint { context: Context::PresetName, input: Input::Press(DPAD), label: "Move" },
+    Hint { context: Context::PresetName, input: Input::Press(&[Button::Cross]), label: "Type" },
+    Hint { context: Context::PresetName, input: Input::Press(&[Button::Square]), label: "Delete" },
+    Hint { context: Context::PresetName, input: Input::Press(&[Button::Triangle]), label: "Shift" },
+    Hint { context: Context::PresetName, input: Input::Press(&[Button::Options]), label: "Save" },
+    Hint { context: Context::PresetName, input: Input::Press(&[Button::Circle]), label: "Cancel" },
+    Hint { context: Context::Tutorial, input: Input::Press(&[Button::Cross, Button::DpadRight]), label: "Next" },
+    Hint { context: Context::Tutorial, input: Input::Press(&[Button::Circle, Button::DpadLeft]), label: "Back" },
+    Hint { context: Context::Tutorial, input: Input::Press(&[Button::Triangle]), label: "Skip" },
+    Hint { context: Context::PlaybackError, input: Input::Press(&[Button::Cross]), label: "Try again" },
+    Hint { context: Context::PlaybackError, input: Input::Press(&[Button::Circle, Button::Triangle]), label: "Close" },
+    Hint { context: Context::Locked, input: Input::HoldAll(&UNLOCK), label: "Unlock" },
+];
+
+/// The hint bar's entries for `context` as (control, what it does), with the
+/// controls named for the connected pad and its remaps and the labels
+/// translated. Playback adds the chords.
+pub fn hints(context: Context) -> Vec<(String, &'static str)> {
+    let glyphs = |buttons: &[Button]| {
+        if buttons.iter().all(|b| DPAD.contains(b)) {
+            let arrows: String = buttons.iter().map(|&b| match b {
+                Button::DpadUp => '▲',
+                Button::DpadDown => '▼',
+                Button::DpadLeft => '◀',
+                _ => '▶',
+            }).collect();
+            return if buttons.len() == DPAD.len() { "D-pad".to_string() } else { arrows };
+        }
+        buttons.iter().map(|&b| crate::gamepad::glyph(b)).collect::<Vec<_>>().join("/")
+    };
+    let mut hints: Vec<(String, &'static str)> = HINTS.iter()
+        .filter(|h| h.context == context)
+        .map(|h| (match h.input {
+            Input::Press(buttons) => glyphs(buttons),
+            Input::Hold(buttons) => trf("hold {}", &[&glyphs(buttons)]),
+            Input::HoldAll(buttons) => trf("hold {}", &[&buttons.iter().map(|&b| crate::gamepad::glyph(b)).collect::<Vec<_>>().join(" + ")]),
+            Input::LeftStick => tr("Left stick").to_string(),
+            Input::RightStick => tr("Right stick").to_string(),
+        }, tr(h.label)))
+        .collect();
+    if context == Context::Playback {
+        hints.extend(CHORDS.iter().map(|c| (format!("{} + {}", glyphs(&c.buttons[..1]), glyphs(&c.buttons[1..])), tr(c.name))));
+    }
+    hints
+}
+
+/// Move the menu actions out of `actions` into `gp`, leaving the rest.
+pub fn fold_menu(actions: &mut Vec<Action>, gp: &mut GamepadActions) {
+    actions.retain(|action| {
+        match action {
+            Action::Navigate(Direction::Up) => gp.nav_up = true,
+            Action::Navigate(Direction::Down) => gp.nav_down = true,
+            Action::Navigate(Direction::Left) => gp.nav_left = true,
+            Action::Navigate(Direction::Right) => gp.nav_right = true,
+            Action::Select => gp.play_pause = true,
+            // A key press is over by now, so it's a tap too
+            Action::Confirm => { gp.confirm = true; gp.confirm_tap = true; }
+            Action::Back => gp.back = true,
+            Action::ToggleMenu => gp.toggle_ui = true,
+            _ => return true,
+        }
+        false
+    });
+}
@@ -0,0 +1,339 @@
+//! ARCore positional tracking
+//!
+//! Optional 6DOF: ARCore's motion tracking (through its NDK C API) supplies the
+//! head *position*, so leaning toward the screen moves the viewpoint. Rotation
+//! stays with the IMU (`sensors.rs`) — it runs at sensor rate rather than ARCore's
+//! 30 fps camera rate and keeps its smoothing / trim settings.
+//!
+//! `libarcore_sdk_c.so` ships in the ARCore AAR and is loaded at run time, so
+//! phones without Google Play Services for AR simply report it unavailable.
+//! ARCore wants a GL ES context and a camera texture to update into; the tracking
+//! thread makes a 1×1 pbuffer context of its own for that (nothing is drawn).
+
+use android_activity::AndroidApp;
+use glam::{Quat, Vec3};
+use jni::objects::JObject;
+use jni::sys::jobject;
+use log::{error, info, warn};
+use std::ffi::{c_void, CStr};
+use std::ptr;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+/// How often to retry a session that couldn't be created (no permission yet, …).
+const RETRY: Duration = Duration::from_secs(1);
+
+// ── ARCore C API (subset) ─────────────────────────────────────────────────────
+
+type ArStatus = i32;
+const AR_SUCCESS: ArStatus = 0;
+const AR_ERROR_CAMERA_PERMISSION_NOT_GRANTED: ArStatus = -9;
+const AR_UPDATE_MODE_BLOCKING: i32 = 0;
+const AR_PLANE_FINDING_MODE_DISABLED: i32 = 0;
+const AR_LIGHT_ESTIMATION_MODE_DISABLED: i32 = 0;
+const AR_TRACKING_STATE_TRACKING: i32 = 0;
+
+/// Declares `Api`, one field per entry point, and `Api::resolve`, which looks
+/// them all up by name (`None` if any is missing).
+macro_rules! api {
+    ($($field:ident = $name:literal: fn($($arg:ty),*) $(-> $ret:ty)?,)*) => {
+        /// Entry points resolved from `libarcore_sdk_c.so`.
+        struct Api {
+            $($field: unsafe extern "C" fn($($arg),*) $(-> $ret)?,)*
+        }
+
+        impl Api {
+            unsafe fn resolve(sym: impl Fn(&CStr) -> *mut c_void) -> Option<Api> {
+                Some(Api { $($field: {
+                    let f = sym($name);
+                    if f.is_null() { return None; }
+                    std::mem::transmute::<*mut c_void, unsafe extern "C" fn($($arg),*) $(-> $ret)?>(f)
+                }),* })
+            }
+        }
+    };
+}
+
+api! {
+    session_create = c"ArSession_create":                       fn(*mut c_void, *mut c_void, *mut *mut c_void) -> ArStatus,
+    session_configure = c"ArSession_configure":                 fn(*mut c_void, *const c_void) -> ArStatus,
+    session_resume = c"ArSession_resume":                       fn(*mut c_void) -> ArStatus,
+    session_pause = c"ArSession_pause":                         fn(*mut c_void) -> ArStatus,
+    session_destroy = c"ArSession_destroy":                     fn(*mut c_void),
+    session_update = c"ArSession_update":                       fn(*mut c_void, *mut c_void) -> ArStatus,
+    session_set_texture = c"ArSession_setCameraTextureName":    fn(*mut c_void, u32),
+    session_set_geometry = c"ArSession_setDisplayGeometry":     fn(*mut c_void, i32, i32, i32),
+    config_create = c"ArConfig_create":                         fn(*const c_void, *mut *mut c_void),
+    config_destroy = c"ArConfig_destroy":                       fn(*mut c_void),
+    config_update_mode = c"ArConfig_setUpdateMode":             fn(*const c_void, *mut c_void, i32),
+    config_plane_finding = c"ArConfig_setPlaneFindingMode":     fn(*const c_void, *mut c_void, i32),
+    config_light_estimate = c"ArConfig_setLightEstimationMode": fn(*const c_void, *mut c_void, i32),
+    frame_create = c"ArFrame_create":                           fn(*const c_void, *mut *mut c_void),
+    frame_destroy = c"ArFrame_destroy":                         fn(*mut c_void),
+    frame_acquire_camera = c"ArFrame_acquireCamera":            fn(*const c_void, *const c_void, *mut *mut c_void),
+    camera_release = c"ArCamera_release":                       fn(*mut c_void),
+    camera_tracking_state = c"ArCamera_getTrackingState":       fn(*const c_void, *const c_void, *mut i32),
+    camera_display_pose = c"ArCamera_getDisplayOrientedPose":   fn(*const c_void, *const c_void, *mut c_void),
+    pose_create = c"ArPose_create":                             fn(*const c_void, *const f32, *mut *mut c_void),
+    pose_destroy = c"ArPose_destroy":                           fn(*mut c_void),
+    pose_raw = c"ArPose_getPoseRaw":                            fn(*const c_void, *const c_void, *mut f32),
+}
+
+static API: OnceLock<Option<Api>> = OnceLock::new();
+
+impl Api {
+    fn get() -> Option<&'static Api> {
+        API.get_or_init(|| unsafe { Self::load() }).as_ref()
+    }
+
+    unsafe fn load() -> Option<Api> {
+        let lib = libc::dlopen(c"libarcore_sdk_c.so".as_ptr(), libc::RTLD_NOW);
+        if lib.is_null() {
+            warn!("ARCore: libarcore_sdk_c.so not found");
+            return None;
+        }
+        Self::resolve(|name| {
+            let f = libc::dlsym(lib, name.as_ptr());
+            if f.is_null() {
+                error!("ARCore: missing {:?}", name);
+            }
+            f
+        })
+    }
+}
+
+// ── EGL / GLES (just enough for a camera texture) ─────────────────────────────
+
+const EGL_NONE: i32 = 0x3038;
+const EGL_RENDERABLE_TYPE: i32 = 0x3040;
+const EGL_OPENGL_ES2_BIT: i32 = 0x0004;
+const EGL_SURFACE_TYPE: i32 = 0x3033;
+const EGL_PBUFFER_BIT: i32 = 0x0001;
+const EGL_WIDTH: i32 = 0x3057;
+const EGL_HEIGHT: i32 = 0x3056;
+const EGL_CONTEXT_CLIENT_VERSION: i32 = 0x3098;
+const GL_TEXTURE_EXTERNAL_OES: u32 = 0x8D65;
+
+#[link(name = "EGL")]
+extern "C" {
+    fn eglGetDisplay(display_id: *mut c_void) -> *mut c_void;
+    fn eglInitialize(display: *mut c_void, major: *mut i32, minor: *mut i32) -> u32;
+    fn eglChooseConfig(display: *mut c_void, attribs: *const i32, configs: *mut *mut c_void, size: i32, count: *mut i32) -> u32;
+    fn eglCreateContext(display: *mut c_void, config: *mut c_void, share: *mut c_void, attribs: *const i32) -> *mut c_void;
+    fn eglCreatePbufferSurface(display: *mut c_void, config: *mut c_void, attribs: *const i32) -> *mut c_void;
+    fn eglMakeCurrent(display: *mut c_void, draw: *mut c_void, read: *mut c_void, context: *mut c_void) -> u32;
+    fn eglDestroySurface(display: *mut c_void, surface: *mut c_void) -> u32;
+    fn eglDestroyContext(display: *mut c_void, context: *mut c_void) -> u32;
+}
+
+#[link(name = "GLESv2")]
+extern "C" {
+    fn glGenTextures(n: i32, textures: *mut u32);
+    fn glBindTexture(target: u32, texture: u32);
+    fn glDeleteTextures(n: i32, textures: *const u32);
+}
+
+/// A current 1×1 pbuffer GL ES context with one external texture.
+struct GlContext {
+    display: *mut c_void,
+    surface: *mut c_void,
+    context: *mut c_void,
+    texture: u32,
+}
+
+impl GlContext {
+    unsafe fn new() -> Option<Self> {
+        let display = eglGetDisplay(ptr::null_mut());
+        if display.is_null() || eglInitialize(display, ptr::null_mut(), ptr::null_mut()) == 0 {
+            return None;
+        }
+        let attribs = [EGL_RENDERABLE_TYPE, EGL_OPENGL_ES2_BIT, EGL_SURFACE_TYPE, EGL_PBUFFER_BIT, EGL_NONE];
+        let mut config = ptr::null_mut();
+        let mut count = 0;
+        if eglChooseConfig(display, attribs.as_ptr(), &mut config, 1, &mut count) == 0 || count < 1 {
+            return None;
+        }
+        let context = eglCreateContext(display, config, ptr::null_mut(), [EGL_CONTEXT_CLIENT_VERSION, 2, EGL_NONE].as_ptr());
+        let surface = eglCreatePbufferSurface(display, config, [EGL_WIDTH, 1, EGL_HEIGHT, 1, EGL_NONE].as_ptr());
+        if context.is_null() || surface.is_null() || eglMakeCurrent(display, surface, surface, context) == 0 {
+            return None;
+        }
+        let mut texture = 0;
+        glGenTextures(1, &mut texture);
+        glBindTexture(GL_TEXTURE_EXTERNAL_OES, texture);
+        Some(Self { display, surface, context, texture })
+    }
+}
+
+impl Drop for GlContext {
+    fn drop(&mut self) {
+        unsafe {
+            glDeleteTextures(1, &self.texture);
+            eglMakeCurrent(self.display, ptr::null_mut(), ptr::null_mut(), ptr::null_mut());
+            eglDestroySurface(self.display, self.surface);
+            eglDestroyContext(self.display, self.context);
+        }
+    }
+}
+
+// ── Tracker ───────────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct Shared {
+    running: bool,
+    /// Latest camera pose in ARCore's world (rotation, position in metres).
+    pose: Option<(Quat, Vec3)>,
+    /// Pose at the last recenter; positions are reported relative to it.
+    reference: Option<(Quat, Vec3)>,
+}
+
+/// Runs an ARCore session on its own thread while it exists.
+pub struct ArTracker {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl ArTracker {
+    /// Start tracking (asks for the camera permission if it isn't granted yet).
+    /// `None` if ARCore isn't installed / bundled.
+    pub fn start(app: &AndroidApp) -> Option<Self> {
+        Api::get()?;
+        request_camera_permission(app);
+        let shared = Arc::new(Mutex::new(Shared { running: true, ..Default::default() }));
+        let thread_shared = shared.clone();
+        let app = app.clone();
+        thread::spawn(move || tracking_loop(app, thread_shared));
+        Some(Self { shared })
+    }
+
+    /// Head position since the last recenter, in the view frame at that moment
+    /// (x right, y up, z toward the viewer). Zero while not tracking.
+    pub fn position(&self) -> Vec3 {
+        let Ok(mut s) = self.shared.lock() else { return Vec3::ZERO };
+        let Some(pose) = s.pose else { return Vec3::ZERO };
+        let (ref_rotation, ref_position) = *s.reference.get_or_insert(pose);
+        ref_rotation.inverse() * (pose.1 - ref_position)
+    }
+
+    /// Make the current position the origin (with the view's recenter).
+    pub fn recenter(&self) {
+        if let Ok(mut s) = self.shared.lock() {
+            s.reference = s.pose;
+        }
+    }
+}
+
+impl Drop for ArTracker {
+    fn drop(&mut self) {
+        if let Ok(mut s) = self.shared.lock() {
+            s.running = false;
+        }
+    }
+}
+
+fn request_camera_permission(app: &AndroidApp) {
+    let vm = unsafe { jni::JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM).unwrap() };
+    let Ok(mut env) = vm.attach_current_thread() else { return };
+    let activity = unsafe { JObject::from_raw(app.activity_as_ptr() as jobject) };
+    if let Err(e) = env.call_method(&activity, "requestCameraPermission", "()V", &[]) {
+        error!("ARCore: requestCameraPermission failed: {:?}", e);
+    }
+}
+
+fn running(shared: &Mutex<Shared>) -> bool {
+    shared.lock().map(|s| s.running).unwrap_or(false)
+}
+
+fn tracking_loop(app: AndroidApp, shared: Arc<Mutex<Shared>>) {
+    let Some(api) = Api::get() else { return };
+    let vm = unsafe { jni::JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM).unwrap() };
+    let env = match vm.attach_current_thread() {
+        Ok(e) => e,
+        Err(e) => { error!("ARCore: attach failed: {:?}", e); return; }
+    };
+    let Some(gl) = (unsafe { GlContext::new() }) else {
+        error!("ARCore: couldn't create a GL context");
+        return;
+    };
+
+    unsafe {
+        // The session needs the camera permission; keep trying until it's granted
+        let mut session = ptr::null_mut();
+        while running(&shared) {
+            let status = (api.session_create)(env.get_raw() as *mut c_void, app.activity_as_ptr(), &mut session);
+            if status == AR_SUCCESS {
+                break;
+            }
+            if status != AR_ERROR_CAMERA_PERMISSION_NOT_GRANTED {
+                error!("ARCore: unavailable ({})", status);
+                return;
+            }
+            session = ptr::null_mut();
+            thread::sleep(RETRY);
+        }
+        if session.is_null() {
+            return;
+        }
+
+        let mut config = ptr::null_mut();
+        (api.config_create)(session, &mut config);
+        (api.config_update_mode)(session, config, AR_UPDATE_MODE_BLOCKING);
+        (api.config_plane_finding)(session, config, AR_PLANE_FINDING_MODE_DISABLED);
+        (api.config_light_estimate)(session, config, AR_LIGHT_ESTIMATION_MODE_DISABLED);
+        let status = (api.session_configure)(session, config);
+        (api.config_destroy)(config);
+        if status != AR_SUCCESS || (api.session_resume)(session) != AR_SUCCESS {
+            error!("ARCore: couldn't start the session ({})", status);
+            (api.session_destroy)(session);
+            return;
+        }
+        (api.session_set_texture)(session, gl.texture);
+        info!("ARCore: tracking started");
+
+        let mut frame = ptr::null_mut();
+        (api.frame_create)(session, &mut frame);
+        let mut pose = ptr::null_mut();
+        (api.pose_create)(session, ptr::null(), &mut pose);
+        let mut rotation = -1;
+        let mut was_tracking = false;
+
+        while running(&shared) {
+            // Only the rotation matters for the display-oriented pose; the size is nominal
+            let display_rotation = crate::sensors::display_rotation();
+            if display_rotation != rotation {
+                (api.session_set_geometry)(session, display_rotation, 1920, 1080);
+                rotation = display_rotation;
+            }
+            if (api.session_update)(session, frame) != AR_SUCCESS {
+                thread::sleep(Duration::from_millis(10));
+                continue;
+            }
+            let mut camera = ptr::null_mut();
+            (api.frame_acquire_camera)(session, frame, &mut camera);
+            let mut state = -1;
+            (api.camera_tracking_state)(session, camera, &mut state);
+            let tracking = state == AR_TRACKING_STATE_TRACKING;
+            if tracking {
+                (api.camera_display_pose)(session, camera, pose);
+                let mut raw = [0.0f32; 7];
+                (api.pose_raw)(session, pose, raw.as_mut_ptr());
+                let q = Quat::from_xyzw(raw[0], raw[1], raw[2], raw[3]).normalize();
+                if let Ok(mut s) = shared.lock() {
+                    s.pose = Some((q, Vec3::new(raw[4], raw[5], raw[6])));
+                }
+            }
+            (api.camera_release)(camera);
+            if tracking != was_tracking {
+                info!("ARCore: {}", if tracking { "tracking" } else { "tracking lost" });
+                was_tracking = tracking;
+            }
+        }
+
+        (api.pose_destroy)(pose);
+        (api.frame_destroy)(frame);
+        (api.session_pause)(session);
+        (api.session_destroy)(session);
+        info!("ARCore: tracking stopped");
+    }
+    drop(gl);
+}
@@ -0,0 +1,287 @@
+//! Chapter markers
+//!
+//! Reads the chapter list of a local file: Nero chapters (`moov/udta/chpl`) in
+//! MP4 / M4V / MOV, and the first edition's chapter atoms in Matroska / WebM.
+//! Only the few boxes / elements on the way there are read, so this is cheap
+//! enough to do when a file starts. Drives next / previous chapter and the tick
+//! marks on the dock's seek bar.
+
+use log::info;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Going back within this long of a chapter's start goes to the previous one.
+const RESTART_GRACE_US: i64 = 3_000_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    pub start_us: i64,
+    pub title:    String,
+}
+
+/// Chapters of `path`, sorted by start (empty if it has none, isn't a local
+/// file or can't be read).
+pub fn read(path: &str) -> Vec<Chapter> {
+    if !path.starts_with('/') {
+        return Vec::new();
+    }
+    let Ok(mut file) = File::open(path) else { return Vec::new() };
+    let mut magic = [0u8; 4];
+    if file.read_exact(&mut magic).is_err() {
+        return Vec::new();
+    }
+    let mut chapters = if magic == EBML_MAGIC {
+        read_mkv(&mut file)
+    } else {
+        read_mp4(&mut file)
+    }
+    .unwrap_or_default();
+    chapters.sort_by_key(|c| c.start_us);
+    if !chapters.is_empty() {
+        info!("Chapters: {} in {}", chapters.len(), path);
+    }
+    chapters
+}
+
+/// Index of the chapter playing at `position_us`.
+pub fn index_at(chapters: &[Chapter], position_us: i64) -> Option<usize> {
+    chapters.iter().rposition(|c| c.start_us <= position_us)
+}
+
+/// Start of the chapter after the one at `position_us`.
+pub fn next_start(chapters: &[Chapter], position_us: i64) -> Option<i64> {
+    chapters.iter().map(|c| c.start_us).find(|&s| s > position_us)
+}
+
+/// Start of the current chapter, or of the previous one when `position_us` is
+/// just past the current one's start (like a CD player's ⏮).
+pub fn previous_start(chapters: &[Chapter], position_us: i64) -> Option<i64> {
+    let i = index_at(chapters, position_us)?;
+    if position_us - chapters[i].start_us < RESTART_GRACE_US && i > 0 {
+        Some(chapters[i - 1].start_us)
+    } else {
+        Some(chapters[i].start_us)
+    }
+}
+
+// ── MP4 ───────────────────────────────────────────────────────────────────────
+
+/// Body range of the first `name` box between `start` and `end`.
+fn find_box(file: &mut File, start: u64, end: u64, name: &[u8; 4]) -> io::Result<Option<(u64, u64)>> {
+    let mut pos = start;
+    while pos.checked_add(8).is_some_and(|header_end| header_end <= end) {
+        file.seek(SeekFrom::Start(pos))?;
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header)?;
+        let mut size = u32::from_be_bytes(header[..4].try_into().unwrap()) as u64;
+        let mut body = pos + 8;
+        if size == 1 {
+            let mut large = [0u8; 8];
+            file.read_exact(&mut large)?;
+            size = u64::from_be_bytes(large);
+            body += 8;
+        } else if size == 0 {
+            size = end - pos; // runs to the end of the parent
+        }
+        if size < body - pos {
+            return Ok(None); // corrupt
+        }
+        // A 64-bit size can run past u64: treat it as corrupt
+        let Some(box_end) = pos.checked_add(size) else {
+            return Ok(None);
+        };
+        if &header[4..] == name {
+            return Ok(Some((body, box_end.min(end))));
+        }
+        pos = box_end;
+    }
+    Ok(None)
+}
+
+fn read_mp4(file: &mut File) -> io::Result<Vec<Chapter>> {
+    let len = file.metadata()?.len();
+    let mut range = (0, len);
+    for name in [b"moov", b"udta", b"chpl"] {
+        match find_box(file, range.0, range.1, name)? {
+            Some(r) => range = r,
+            None => return Ok(Vec::new()),
+        }
+    }
+    let Some(chpl_len) = range.1.checked_sub(range.0) else { return Ok(Vec::new()) };
+    let mut chpl = vec![0u8; chpl_len.min(1 << 20) as usize];
+    file.seek(SeekFrom::Start(range.0))?;
+    file.read_exact(&mut chpl)?;
+
+    // version, flags[3], (version 1: 4 reserved bytes), count, then per chapter
+    // start (u64, 100 ns units), title length (u8), title
+    let mut at = if chpl.first() == Some(&1) { 8 } else { 4 };
+    let count = *chpl.get(at).unwrap_or(&0);
+    at += 1;
+    let mut chapters = Vec::new();
+    for _ in 0..count {
+        let Some(start) = chpl.get(at..at + 8) else { break };
+        let start = u64::from_be_bytes(start.try_into().unwrap());
+        let Some(&title_len) = chpl.get(at + 8) else { break };
+        let Some(title) = chpl.get(at + 9..at + 9 + title_len as usize) else { break };
+        chapters.push(Chapter {
+            start_us: (start / 10) as i64,
+            title:    String::from_utf8_lossy(title).into_owned(),
+        });
+        at += 9 + title_len as usize;
+    }
+    Ok(chapters)
+}
+
+// ── Matroska ──────────────────────────────────────────────────────────────────
+
+const EBML_MAGIC: [u8; 4] = [0x1A, 0x45, 0xDF, 0xA3];
+const ID_SEGMENT: u32 = 0x1853_8067;
+const ID_SEEK_HEAD: u32 = 0x114D_9B74;
+const ID_SEEK: u32 = 0x4DBB;
+const ID_SEEK_ID: u32 = 0x53AB;
+const ID_SEEK_POSITION: u32 = 0x53AC;
+const ID_CLUSTER: u32 = 0x1F43_B675;
+const ID_CHAPTERS: u32 = 0x1043_A770;
+const ID_EDITION: u32 = 0x45B9;
+const ID_ATOM: u32 = 0xB6;
+const ID_TIME_START: u32 = 0x91;
+const ID_FLAG_HIDDEN: u32 = 0x98;
+const ID_DISPLAY: u32 = 0x80;
+const ID_STRING: u32 = 0x85;
+
+/// An EBML variable-length integer: (value, length). IDs keep their length
+/// marker; sizes drop it, and an all-ones size ("unknown") comes back as `None`.
+fn read_vint(file: &mut File, keep_marker: bool) -> io::Result<(Option<u64>, u64)> {
+    let mut first = [0u8; 1];
+    file.read_exact(&mut first)?;
+    let len = first[0].leading_zeros() as usize + 1;
+    if len > 8 {
+        return Err(io::ErrorKind::InvalidData.into());
+    }
+    let mask = (0xFFu16 >> len) as u8;
+    let mut value = if keep_marker { first[0] } else { first[0] & mask } as u64;
+    let mut all_ones = value == mask as u64;
+    let mut rest = [0u8; 7];
+    file.read_exact(&mut rest[..len - 1])?;
+    for &b in &rest[..len - 1] {
+        value = (value << 8) | b as u64;
+        all_ones &= b == 0xFF;
+    }
+    Ok((if all_ones && !keep_marker { None } else { Some(value) }, len as u64))
+}
+
+/// Header at `pos`: (id, body start, body end; `end` if the size is unknown).
+fn element(file: &mut File, pos: u64, end: u64) -> io::Result<(u32, u64, u64)> {
+    file.seek(SeekFrom::Start(pos))?;
+    let (id, id_len) = read_vint(file, true)?;
+    let (size, size_len) = read_vint(file, false)?;
+    let body = pos + id_len + size_len;
+    // A size running past u64 is cut at `end` like an unknown one
+    let body_end = size.and_then(|s| body.checked_add(s)).map_or(end, |e| e.min(end));
+    Ok((id.unwrap_or(0) as u32, body, body_end))
+}
+
+/// Children of `[start, end)` as (id, body start, body end).
+fn children(file: &mut File, start: u64, end: u64) -> io::Result<Vec<(u32, u64, u64)>> {
+    let mut out = Vec::new();
+    let mut pos = start;
+    while pos < end {
+        let child = element(file, pos, end)?;
+        out.push(child);
+        pos = child.2;
+    }
+    Ok(out)
+}
+
+fn read_bytes(file: &mut File, start: u64, end: u64) -> io::Result<Vec<u8>> {
+    let Some(len) = end.checked_sub(start) else { return Ok(Vec::new()) };
+    let mut buf = vec![0u8; len.min(4096) as usize];
+    file.seek(SeekFrom::Start(start))?;
+    file.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_uint(file: &mut File, start: u64, end: u64) -> io::Result<u64> {
+    Ok(read_bytes(file, start, end)?.iter().fold(0, |v, &b| (v << 8) | b as u64))
+}
+
+fn read_mkv(file: &mut File) -> io::Result<Vec<Chapter>> {
+    let len = file.metadata()?.len();
+    // Skip the EBML header to the segment
+    let (_, _, header_end) = element(file, 0, len)?;
+    let (id, segment, segment_end) = element(file, header_end, len)?;
+    if id != ID_SEGMENT {
+        return Ok(Vec::new());
+    }
+
+    // Walk the segment's top level up to the first cluster; chapters usually sit
+    // before it, else the seek head says where they are.
+    let mut chapters_at = None;
+    let mut pos = segment;
+    while pos < segment_end {
+        let (id, body, end) = element(file, pos, segment_end)?;
+        match id {
+            ID_CHAPTERS => return read_chapters(file, body, end),
+            ID_SEEK_HEAD => {
+                for (seek_id, seek, seek_end) in children(file, body, end)? {
+                    if seek_id != ID_SEEK {
+                        continue;
+                    }
+                    let mut target = (0, 0);
+                    for (field, start, field_end) in children(file, seek, seek_end)? {
+                        match field {
+                            ID_SEEK_ID => target.0 = read_uint(file, start, field_end)? as u32,
+                            ID_SEEK_POSITION => target.1 = read_uint(file, start, field_end)?,
+                            _ => {}
+                        }
+                    }
+                    if target.0 == ID_CHAPTERS {
+                        chapters_at = segment.checked_add(target.1);
+                    }
+                }
+            }
+            ID_CLUSTER => break,
+            _ => {}
+        }
+        pos = end;
+    }
+    match chapters_at {
+        Some(at) => {
+            let (id, body, end) = element(file, at, segment_end)?;
+            if id == ID_CHAPTERS { read_chapters(file, body, end) } else { Ok(Vec::new()) }
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+/// The first edition's visible top-level atoms.
+fn read_chapters(file: &mut File, start: u64, end: u64) -> io::Result<Vec<Chapter>> {
+    let Some((_, edition, edition_end)) = children(file, start, end)?
+        .into_iter().find(|c| c.0 == ID_EDITION) else { return Ok(Vec::new()) };
+    let mut chapters = Vec::new();
+    for (id, atom, atom_end) in children(file, edition, edition_end)? {
+        if id != ID_ATOM {
+            continue;
+        }
+        let mut start_ns = None;
+        let mut hidden = false;
+        let mut title = String::new();
+        for (field, body, field_end) in children(file, atom, atom_end)? {
+            match field {
+                ID_TIME_START => start_ns = Some(read_uint(file, body, field_end)?),
+                ID_FLAG_HIDDEN => hidden = read_uint(file, body, field_end)? != 0,
+                ID_DISPLAY if title.is_empty() => {
+                    if let Some((_, s, s_end)) = children(file, body, field_end)?
+                        .into_iter().find(|c| c.0 == ID_STRING) {
+                        title = String::from_utf8_lossy(&read_bytes(file, s, s_end)?).into_owned();
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let (Some(ns), false) = (start_ns, hidden) {
+            chapters.push(Chapter { start_us: (ns / 1000) as i64, title });
+        }
+    }
+    Ok(chapters)
+}
@@ -0,0 +1,312 @@
+//! Decoder capability probing
+//!
+//! The NDK has no `MediaCodecList`, so the decoder list is read once at startup
+//! through the Java `android.media.MediaCodecList` API: supported mimes, profiles,
+//! max resolution, hardware vs software and secure / tunneled playback support.
+//! The decode thread checks a file's format against it so the UI can warn
+//! ("This device can't hardware-decode AV1 4K") before playback stutters or fails.
+//!
+//! Per mime type, the user can also force a specific decoder (e.g. the software
+//! `c2.android.hevc.decoder` where the hardware one returns corrupt raw buffers).
+//! That choice is saved with the settings (config.rs).
+
+use android_activity::AndroidApp;
+use jni::objects::{JObject, JObjectArray, JString, JValue};
+use jni::JNIEnv;
+use log::{error, info};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// One decoder × mime type it supports.
+#[derive(Debug, Clone)]
+pub struct CodecInfo {
+    pub name:       String,
+    pub mime:       String,
+    pub hardware:   bool,
+    /// `MediaCodecInfo.CodecProfileLevel` profile constants.
+    pub profiles:   Vec<i32>,
+    /// Largest supported width / height (0 for non-video codecs).
+    pub max_width:  i32,
+    pub max_height: i32,
+    pub secure:     bool,
+    pub tunneled:   bool,
+    /// Supports the `low-latency` format key (Android 11+).
+    pub low_latency: bool,
+}
+
+impl CodecInfo {
+    /// Whether a `width`×`height` frame fits, in either orientation.
+    pub fn fits(&self, width: i32, height: i32) -> bool {
+        let (long, short) = (width.max(height), width.min(height));
+        long <= self.max_width.max(self.max_height) && short <= self.max_width.min(self.max_height)
+    }
+
+    /// Whether the codec lists `profile` (codecs that list none are assumed to cope).
+    pub fn has_profile(&self, profile: i32) -> bool {
+        self.profiles.is_empty() || self.profiles.contains(&profile)
+    }
+}
+
+/// How well the device can decode a format.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Support {
+    Hardware,
+    Software,
+    Unsupported,
+}
+
+static DECODERS: OnceLock<Vec<CodecInfo>> = OnceLock::new();
+
+/// Forced decoder name per mime.
+static PREFERRED: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+/// Query the platform decoder list (call once at startup, on a JNI thread).
+pub fn probe(app: &AndroidApp) {
+    if DECODERS.get().is_some() {
+        return;
+    }
+    let vm = unsafe { jni::JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM).unwrap() };
+    let mut env = match vm.attach_current_thread() {
+        Ok(e) => e,
+        Err(e) => { error!("codecs: attach failed: {:?}", e); return; }
+    };
+    let decoders = match list_decoders(&mut env) {
+        Ok(d) => d,
+        Err(e) => {
+            if env.exception_check().unwrap_or(false) {
+                let _ = env.exception_clear();
+            }
+            error!("codecs: probing failed: {:?}", e);
+            Vec::new()
+        }
+    };
+    for c in decoders.iter().filter(|c| c.mime.starts_with("video/")) {
+        info!("codecs: {} {} {} max {}x{}{}{}{}", c.name, c.mime,
+            if c.hardware { "hw" } else { "sw" }, c.max_width, c.max_height,
+            if c.secure { " secure" } else { "" }, if c.tunneled { " tunneled" } else { "" },
+            if c.low_latency { " low-latency" } else { "" });
+    }
+    let _ = DECODERS.set(decoders);
+}
+
+/// Every decoder found by `probe` (empty if it hasn't run or failed).
+pub fn decoders() -> &'static [CodecInfo] {
+    DECODERS.get().map(Vec::as_slice).unwrap_or(&[])
+}
+
+/// Decoders for `mime`, in the platform's preference order.
+pub fn decoders_for(mime: &str) -> impl Iterator<Item = &'static CodecInfo> + '_ {
+    decoders().iter().filter(move |c| c.mime.eq_ignore_ascii_case(mime))
+}
+
+/// Put back the forced decoders saved with the settings.
+pub fn restore_preferences(prefs: HashMap<String, String>) {
+    for (mime, name) in &prefs {
+        info!("codecs: {} forced to {}", mime, name);
+    }
+    *PREFERRED.lock().unwrap() = Some(prefs);
+}
+
+/// The forced decoders as (mime, name), sorted by mime, for the settings file.
+pub fn preferences() -> Vec<(String, String)> {
+    let mut prefs: Vec<_> = PREFERRED.lock().ok()
+        .and_then(|p| p.clone())
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    prefs.sort();
+    prefs
+}
+
+/// The decoder the user forced for `mime`, if any.
+pub fn preferred(mime: &str) -> Option<String> {
+    PREFERRED.lock().ok()?.as_ref()?.get(mime).cloned()
+}
+
+/// Force `name` for `mime` (`None` = back to automatic). Applies to the next
+/// file opened; the settings file picks it up on its next check.
+pub fn set_preferred(mime: &str, name: Option<&str>) {
+    let Ok(mut prefs) = PREFERRED.lock() else { return };
+    let prefs = prefs.get_or_insert_with(HashMap::new);
+    match name {
+        Some(n) => { prefs.insert(mime.to_string(), n.to_string()); }
+        None => { prefs.remove(mime); }
+    }
+}
+
+/// Video mime types with at least one decoder, in a stable order.
+pub fn video_mimes() -> Vec<&'static str> {
+    let mut mimes: Vec<&str> = decoders().iter()
+        .filter(|c| c.mime.starts_with("video/"))
+        .map(|c| c.mime.as_str())
+        .collect();
+    mimes.sort_unstable();
+    mimes.dedup();
+    mimes
+}
+
+/// Best available decoding for `mime` (and `profile`, if the container says) at
+/// `width`×`height`.
+pub fn support(mime: &str, profile: Option<i32>, width: i32, height: i32) -> Support {
+    let fitting = || decoders_for(mime)
+        .filter(|c| c.fits(width, height) && profile.is_none_or(|p| c.has_profile(p)));
+    if fitting().any(|c| c.hardware) {
+        Support::Hardware
+    } else if fitting().next().is_some() {
+        Support::Software
+    } else {
+        Support::Unsupported
+    }
+}
+
+/// A message for the user if this format won't decode (well) on this device;
+/// `None` when it's hardware-decodable or the decoder list is unknown.
+pub fn warning(mime: &str, profile: Option<i32>, width: i32, height: i32) -> Option<String> {
+    if decoders().is_empty() {
+        return None;
+    }
+    let what = format!("{} {}", format_label(mime), resolution_label(width, height));
+    match support(mime, profile, width, height) {
+        Support::Hardware => None,
+        Support::Software => Some(crate::i18n::trf(
+            "This device can't hardware-decode {}; software decoding may stutter", &[&what])),
+        Support::Unsupported => Some(crate::i18n::trf("This device can't decode {}", &[&what])),
+    }
+}
+
+/// "AV1", "HEVC", … for a video mime type.
+pub fn format_label(mime: &str) -> &str {
+    match mime {
+        "video/avc" => "H.264",
+        "video/hevc" => "HEVC",
+        "video/av01" => "AV1",
+        "video/x-vnd.on2.vp9" => "VP9",
+        "video/x-vnd.on2.vp8" => "VP8",
+        "video/mp4v-es" => "MPEG-4",
+        "video/3gpp" => "H.263",
+        "video/mpeg2" => "MPEG-2",
+        "video/dolby-vision" => "Dolby Vision",
+        m => m.strip_prefix("video/").unwrap_or(m),
+    }
+}
+
+/// "8K" / "4K" / "1440p" / "1080p" / "720p", else "W×H".
+pub fn resolution_label(width: i32, height: i32) -> String {
+    let (long, short) = (width.max(height), width.min(height));
+    match (long, short) {
+        (l, _) if l >= 7680 => "8K".into(),
+        (l, _) if l >= 3840 => "4K".into(),
+        (_, 1440) => "1440p".into(),
+        (_, 1080) => "1080p".into(),
+        (_, 720) => "720p".into(),
+        _ => format!("{}×{}", width, height),
+    }
+}
+
+// ── JNI ───────────────────────────────────────────────────────────────────────
+
+/// `MediaCodecList.REGULAR_CODECS`
+const REGULAR_CODECS: i32 = 0;
+
+fn list_decoders(env: &mut JNIEnv) -> Result<Vec<CodecInfo>, jni::errors::Error> {
+    let sdk = env.get_static_field("android/os/Build$VERSION", "SDK_INT", "I")?.i()?;
+    let list = env.new_object("android/media/MediaCodecList", "(I)V", &[JValue::Int(REGULAR_CODECS)])?;
+    let infos: JObjectArray = env
+        .call_method(&list, "getCodecInfos", "()[Landroid/media/MediaCodecInfo;", &[])?
+        .l()?
+        .into();
+    let mut out = Vec::new();
+    for i in 0..env.get_array_length(&infos)? {
+        // Hundreds of local refs otherwise; free them per codec
+        env.with_local_frame(32, |env| -> Result<(), jni::errors::Error> {
+            let info = env.get_object_array_element(&infos, i)?;
+            if env.call_method(&info, "isEncoder", "()Z", &[])?.z()? {
+                return Ok(());
+            }
+            let name = get_string(env, &info, "getName")?;
+            let hardware = if sdk >= 29 {
+                env.call_method(&info, "isHardwareAccelerated", "()Z", &[])?.z()?
+            } else {
+                !(name.starts_with("OMX.google.") || name.starts_with("c2.android.") || name.contains(".sw."))
+            };
+            let types: JObjectArray = env
+                .call_method(&info, "getSupportedTypes", "()[Ljava/lang/String;", &[])?
+                .l()?
+                .into();
+            for t in 0..env.get_array_length(&types)? {
+                let jmime: JString = env.get_object_array_element(&types, t)?.into();
+                let mime: String = env.get_string(&jmime)?.into();
+                // Some vendor codecs throw for types they list; skip those
+                match describe(env, &info, &jmime) {
+                    Ok((profiles, max_width, max_height, [secure, tunneled, low_latency])) => out.push(CodecInfo {
+                        name: name.clone(), mime, hardware, profiles, max_width, max_height, secure, tunneled,
+                        low_latency,
+                    }),
+                    Err(jni::errors::Error::JavaException) => { let _ = env.exception_clear(); }
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok(())
+        })?;
+    }
+    info!("codecs: {} decoder entries", out.len());
+    Ok(out)
+}
+
+/// Profiles, max width/height and secure / tunneled / low-latency support for one
+/// codec + mime.
+fn describe(
+    env: &mut JNIEnv,
+    info: &JObject,
+    mime: &JString,
+) -> Result<(Vec<i32>, i32, i32, [bool; 3]), jni::errors::Error> {
+    let caps = env.call_method(info, "getCapabilitiesForType",
+        "(Ljava/lang/String;)Landroid/media/MediaCodecInfo$CodecCapabilities;",
+        &[JValue::Object(mime)])?.l()?;
+
+    let levels: JObjectArray = env
+        .get_field(&caps, "profileLevels", "[Landroid/media/MediaCodecInfo$CodecProfileLevel;")?
+        .l()?
+        .into();
+    let mut profiles = Vec::new();
+    for i in 0..env.get_array_length(&levels)? {
+        let level = env.get_object_array_element(&levels, i)?;
+        let profile = env.get_field(&level, "profile", "I")?.i()?;
+        if !profiles.contains(&profile) {
+            profiles.push(profile);
+        }
+        env.delete_local_ref(level)?;
+    }
+
+    let (mut max_width, mut max_height) = (0, 0);
+    let video = env.call_method(&caps, "getVideoCapabilities",
+        "()Landroid/media/MediaCodecInfo$VideoCapabilities;", &[])?.l()?;
+    if !video.is_null() {
+        max_width = range_upper(env, &video, "getSupportedWidths")?;
+        max_height = range_upper(env, &video, "getSupportedHeights")?;
+    }
+
+    let secure = feature(env, &caps, "secure-playback")?;
+    let tunneled = feature(env, &caps, "tunneled-playback")?;
+    // Unknown feature names just report false before Android 11
+    let low_latency = feature(env, &caps, "low-latency")?;
+    Ok((profiles, max_width, max_height, [secure, tunneled, low_latency]))
+}
+
+fn get_string(env: &mut JNIEnv, obj: &JObject, method: &str) -> Result<String, jni::errors::Error> {
+    let s: JString = env.call_method(obj, method, "()Ljava/lang/String;", &[])?.l()?.into();
+    let s: String = env.get_string(&s)?.into();
+    Ok(s)
+}
+
+/// `Range<Integer>.getUpper()` of a VideoCapabilities getter.
+fn range_upper(env: &mut JNIEnv, video: &JObject, method: &str) -> Result<i32, jni::errors::Error> {
+    let range = env.call_method(video, method, "()Landroid/util/Range;", &[])?.l()?;
+    let upper = env.call_method(&range, "getUpper", "()Ljava/lang/Comparable;", &[])?.l()?;
+    env.call_method(&upper, "intValue", "()I", &[])?.i()
+}
+
+fn feature(env: &mut JNIEnv, caps: &JObject, name: &str) -> Result<bool, jni::errors::Error> {
+    let jname = env.new_string(name)?;
+    env.call_method(caps, "isFeatureSupported", "(Ljava/lang/String;)Z", &[JValue::Object(&jname)])?.z()
+}
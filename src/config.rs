@@ -0,0 +1,380 @@
+//! Persistent settings
+//!
+//! The user's tunables (lens, IPD, zoom, head tracking, controller and UI
+//! options, language, theme), the named lens presets, the folder the Media
+//! Center was last in and its favorites are kept in `settings.toml` in the
+//! app's internal storage, with the floating panels as they were arranged
+//! (kind, position, rotation, size and a browser's page; `restore_panels` puts
+//! them back at launch). lib.rs applies them whenever the UI is created and
+//! passes the UI to `update` every frame, which rewrites the file when
+//! something changed (checked once a second, so dragging a slider doesn't write
+//! on every frame). The file is flat TOML of numbers, booleans and quoted
+//! strings, read and written by hand (no serde here); unknown keys and
+//! unreadable values are skipped and keep their defaults.
+//!
+//! The controller's button remaps (gamepad.rs) and the decoder forced per mime
+//! type (codecs.rs) are kept here too, as `button.*` and `decoder.*` keys; the
+//! gamepad.tsv and decoders.tsv files they used to live in are imported once and
+//! deleted. Picture settings, the 3D layout and video screens belong to the
+//! video being watched and aren't kept.
+
+use log::{error, info};
+use std::collections::HashMap;
+use std::fmt::{Debug, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use glam::{Quat, Vec3, Vec4};
+
+use crate::gamepad::{StickCurve, StickTuning};
+use crate::i18n::Language;
+use crate::playlist::LoopMode;
+use crate::sensors::{FusionMode, SensorRate};
+use crate::theme::{ColorVision, ThemePreset};
+use crate::ui::{AspectMode, BrowserView, SortBy, UiAnchor, VrParams, VrUi};
+use crate::window_manager::{PanelContent, WindowManager};
+
+/// How often the settings are compared with what's on disk.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct Config {
+    file: Option<PathBuf>,
+    /// The file as last read or written.
+    saved: String,
+    last_check: Instant,
+}
+
+/// Parsed `key = value` lines; values are still in TOML form.
+struct Values(HashMap<String, String>);
+
+impl Values {
+    fn parse(text: &str) -> Self {
+        Self(text.lines().filter_map(|line| {
+            let line = line.trim();
+            if line.starts_with('#') {
+                return None;
+            }
+            let (key, value) = line.split_once('=')?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        }).collect())
+    }
+
+    /// Overwrite `field` with the saved number or boolean, if there is one.
+    fn get<T: FromStr>(&self, key: &str, field: &mut T) {
+        if let Some(value) = self.parsed(key) {
+            *field = value;
+        }
+    }
+
+    fn parsed<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.0.get(key)?.parse().ok()
+    }
+
+    /// Overwrite `field` with the variant saved by name.
+    fn pick<T: Copy + Debug>(&self, key: &str, options: &[T], field: &mut T) {
+        let Some(name) = self.string(key) else { return };
+        if let Some(&value) = options.iter().find(|o| format!("{:?}", o) == name) {
+            *field = value;
+        }
+    }
+
+    /// A saved `[a, b, …]` of exactly `N` numbers.
+    fn floats<const N: usize>(&self, key: &str) -> Option<[f32; N]> {
+        let list = self.0.get(key)?.strip_prefix('[')?.strip_suffix(']')?;
+        let numbers: Vec<f32> = list.split(',').map(|n| n.trim().parse().ok()).collect::<Option<_>>()?;
+        numbers.try_into().ok()
+    }
+
+    fn string(&self, key: &str) -> Option<String> {
+        let value = self.0.get(key)?.strip_prefix('"')?.strip_suffix('"')?;
+        Some(value.replace("\\\"", "\"").replace("\\\\", "\\"))
+    }
+
+    fn stick(&self, prefix: &str, stick: &mut StickTuning) {
+        self.get(&format!("{}.deadzone_x", prefix), &mut stick.deadzone_x);
+        self.get(&format!("{}.deadzone_y", prefix), &mut stick.deadzone_y);
+        self.pick(&format!("{}.curve", prefix), &[StickCurve::Linear, StickCurve::Expo], &mut stick.curve);
+        self.get(&format!("{}.sensitivity", prefix), &mut stick.sensitivity);
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn floats(values: &[f32]) -> String {
+    format!("[{}]", values.iter().map(|v| format!("{:?}", v)).collect::<Vec<_>>().join(", "))
+}
+
+/// The settings file for the UI's current state and the panels' arrangement.
+fn render(ui: &VrUi, windows: &WindowManager) -> String {
+    let p = &ui.params;
+    let mut out = String::from("# vr_core settings, rewritten whenever they change\n");
+    let mut num = |key: &str, value: f32| { let _ = writeln!(out, "{} = {:?}", key, value); };
+    num("lens_radius", p.lens_radius);
+    num("lens_center_offset", p.lens_center_offset);
+    num("ipd", p.ipd);
+    num("fov", p.fov);
+    num("content_scale", p.content_scale);
+    num("crop_aspect", p.crop_aspect);
+    num("horizon_roll", p.horizon_roll);
+    num("screen_brightness", p.screen_brightness);
+    num("fusion_gain", p.fusion_gain);
+    num("head_smoothing", p.head_smoothing);
+    num("neck_model", p.neck_model);
+    num("stick_look_speed", p.stick_look_speed);
+    num("nav_repeat_delay", p.nav_repeat_delay);
+    num("nav_repeat_rate", p.nav_repeat_rate);
+    num("gaze_dwell", p.gaze_dwell);
+    for (prefix, stick) in [("left_stick", p.left_stick), ("right_stick", p.right_stick)] {
+        num(&format!("{}.deadzone_x", prefix), stick.deadzone_x);
+        num(&format!("{}.deadzone_y", prefix), stick.deadzone_y);
+        num(&format!("{}.sensitivity", prefix), stick.sensitivity);
+    }
+    let mut line = |key: &str, value: String| { let _ = writeln!(out, "{} = {}", key, value); };
+    for (key, value) in [
+        ("gyro_enabled", p.gyro_enabled),
+        ("max_brightness_vr", p.max_brightness_vr),
+        ("mag_yaw_correction", p.mag_yaw_correction),
+        ("positional_tracking", p.positional_tracking),
+        ("proximity_pause", p.proximity_pause),
+        ("proximity_blank", p.proximity_blank),
+        ("stick_pointer", p.stick_pointer),
+        ("haptics", p.haptics),
+        ("motion_aim", p.motion_aim),
+        ("remote_controller", p.remote_controller),
+        ("button_hints", p.button_hints),
+        ("status_hud", p.status_hud),
+        ("arrange_windows", p.arrange_windows),
+        ("auto_advance", p.auto_advance),
+        ("tutorial_seen", p.tutorial_seen),
+        ("reduce_motion", p.reduce_motion),
+        ("bold_controls", p.bold_controls),
+    ] {
+        line(key, value.to_string());
+    }
+    line("browser_engine", p.browser_engine.to_string());
+    line("aspect_mode", quote(&format!("{:?}", p.aspect_mode)));
+    line("fusion_mode", quote(&format!("{:?}", p.fusion_mode)));
+    line("sensor_rate", quote(&format!("{:?}", p.sensor_rate)));
+    line("loop_mode", quote(&format!("{:?}", p.loop_mode)));
+    line("language", quote(&format!("{:?}", p.language)));
+    line("theme", quote(&format!("{:?}", p.theme)));
+    line("ui_anchor", quote(&format!("{:?}", p.ui_anchor)));
+    line("color_vision", quote(&format!("{:?}", p.color_vision)));
+    if let Some(accent) = p.accent {
+        line("accent", quote(&format!("#{:02x}{:02x}{:02x}", accent.r(), accent.g(), accent.b())));
+    }
+    line("left_stick.curve", quote(&format!("{:?}", p.left_stick.curve)));
+    line("right_stick.curve", quote(&format!("{:?}", p.right_stick.curve)));
+    line("sort_by", quote(&format!("{:?}", ui.file_browser.sort_by)));
+    line("search_subfolders", ui.file_browser.search_subfolders.to_string());
+    line("browser_view", quote(&format!("{:?}", ui.file_browser.view)));
+    line("last_folder", quote(&ui.file_browser.current_path.to_string_lossy()));
+    for (i, path) in ui.file_browser.favorites.iter().enumerate() {
+        line(&format!("favorite.{}", i), quote(&path.to_string_lossy()));
+    }
+    for (i, preset) in ui.lens_presets.iter().enumerate() {
+        line(&format!("lens_preset.{}.name", i), quote(&preset.name));
+        line(&format!("lens_preset.{}.lens_radius", i), format!("{:?}", preset.lens_radius));
+        line(&format!("lens_preset.{}.lens_center_offset", i), format!("{:?}", preset.lens_center_offset));
+        line(&format!("lens_preset.{}.ipd", i), format!("{:?}", preset.ipd));
+        line(&format!("lens_preset.{}.fov", i), format!("{:?}", preset.fov));
+    }
+    let kept = windows.panels().iter().filter_map(|panel| match panel.content_type {
+        PanelContent::Browser { .. } => Some((panel, "Browser")),
+        PanelContent::Dock => Some((panel, "Dock")),
+        PanelContent::Settings => Some((panel, "Settings")),
+        // Screens come and go with their videos
+        PanelContent::Video { .. } => None,
+    });
+    for (i, (panel, kind)) in kept.enumerate() {
+        line(&format!("panel.{}.kind", i), quote(kind));
+        if let PanelContent::Browser { url, .. } = &panel.content_type {
+            line(&format!("panel.{}.url", i), quote(url));
+        }
+        line(&format!("panel.{}.position", i), floats(&panel.position.to_array()));
+        line(&format!("panel.{}.rotation", i), floats(&panel.rotation.to_array()));
+        line(&format!("panel.{}.scale", i), floats(&panel.scale.to_array()));
+    }
+    for (button, code) in crate::gamepad::bindings() {
+        line(&format!("button.{}", button), code.to_string());
+    }
+    for (mime, name) in crate::codecs::preferences() {
+        line(&format!("decoder.{}", mime), quote(&name));
+    }
+    out
+}
+
+/// Move the button remaps and forced decoders out of the TSV files they used to
+/// be kept in (name, value per line), once: the TSVs are deleted only after the
+/// settings file holding them is written.
+fn import_tsvs(dir: &Path, file: &Path, saved: &mut String) {
+    let mut imported = Vec::new();
+    if !saved.is_empty() && !saved.ends_with('\n') {
+        saved.push('\n');
+    }
+    for (tsv, prefix) in [("gamepad.tsv", "button"), ("decoders.tsv", "decoder")] {
+        let tsv = dir.join(tsv);
+        let Ok(text) = std::fs::read_to_string(&tsv) else { continue };
+        for (key, value) in text.lines().filter_map(|line| line.split_once('\t')) {
+            let value = if prefix == "decoder" { quote(value) } else { value.trim().to_string() };
+            let _ = writeln!(saved, "{}.{} = {}", prefix, key, value);
+        }
+        imported.push(tsv);
+    }
+    if imported.is_empty() {
+        return;
+    }
+    if let Err(e) = std::fs::write(file, &*saved) {
+        error!("Config: failed to write {:?}: {}", file, e);
+        return;
+    }
+    for tsv in imported {
+        info!("Config: imported {:?}", tsv);
+        let _ = std::fs::remove_file(tsv);
+    }
+}
+
+impl Config {
+    /// Read `<dir>/settings.toml` (defaults throughout if it's missing).
+    pub fn load(dir: Option<PathBuf>) -> Self {
+        let file = dir.as_ref().map(|d| d.join("settings.toml"));
+        let mut saved = file.as_ref()
+            .and_then(|f| std::fs::read_to_string(f).ok())
+            .unwrap_or_default();
+        if let (Some(dir), Some(file)) = (&dir, &file) {
+            import_tsvs(dir, file, &mut saved);
+        }
+        info!("Config: {} saved settings", Values::parse(&saved).0.len());
+        Self { file, saved, last_check: Instant::now() }
+    }
+
+    /// Set the saved settings on a newly created UI (and the button remaps and
+    /// forced decoders, which live outside it).
+    pub fn apply(&self, ui: &mut VrUi) {
+        let values = Values::parse(&self.saved);
+        let p = &mut ui.params;
+        values.get("lens_radius", &mut p.lens_radius);
+        values.get("lens_center_offset", &mut p.lens_center_offset);
+        values.get("ipd", &mut p.ipd);
+        values.get("fov", &mut p.fov);
+        values.get("content_scale", &mut p.content_scale);
+        p.target_scale = p.content_scale;
+        values.get("crop_aspect", &mut p.crop_aspect);
+        values.get("horizon_roll", &mut p.horizon_roll);
+        values.get("screen_brightness", &mut p.screen_brightness);
+        values.get("max_brightness_vr", &mut p.max_brightness_vr);
+        values.get("fusion_gain", &mut p.fusion_gain);
+        values.get("head_smoothing", &mut p.head_smoothing);
+        values.get("neck_model", &mut p.neck_model);
+        values.get("stick_look_speed", &mut p.stick_look_speed);
+        values.get("nav_repeat_delay", &mut p.nav_repeat_delay);
+        values.get("nav_repeat_rate", &mut p.nav_repeat_rate);
+        values.get("gaze_dwell", &mut p.gaze_dwell);
+        values.stick("left_stick", &mut p.left_stick);
+        values.stick("right_stick", &mut p.right_stick);
+        values.get("gyro_enabled", &mut p.gyro_enabled);
+        values.get("mag_yaw_correction", &mut p.mag_yaw_correction);
+        values.get("positional_tracking", &mut p.positional_tracking);
+        values.get("proximity_pause", &mut p.proximity_pause);
+        values.get("proximity_blank", &mut p.proximity_blank);
+        values.get("stick_pointer", &mut p.stick_pointer);
+        values.get("haptics", &mut p.haptics);
+        values.get("motion_aim", &mut p.motion_aim);
+        values.get("remote_controller", &mut p.remote_controller);
+        values.get("button_hints", &mut p.button_hints);
+        values.get("status_hud", &mut p.status_hud);
+        values.get("arrange_windows", &mut p.arrange_windows);
+        values.get("auto_advance", &mut p.auto_advance);
+        values.get("tutorial_seen", &mut p.tutorial_seen);
+        values.get("reduce_motion", &mut p.reduce_motion);
+        values.get("bold_controls", &mut p.bold_controls);
+        values.get("browser_engine", &mut p.browser_engine);
+        values.pick("aspect_mode", &[AspectMode::Fit, AspectMode::Fill, AspectMode::Stretch, AspectMode::Crop], &mut p.aspect_mode);
+        values.pick("fusion_mode", &[FusionMode::RotationVector, FusionMode::Madgwick, FusionMode::Mahony, FusionMode::External], &mut p.fusion_mode);
+        values.pick("sensor_rate", &[SensorRate::Hz50, SensorRate::Hz90, SensorRate::Hz120, SensorRate::Fastest], &mut p.sensor_rate);
+        values.pick("loop_mode", &[LoopMode::Off, LoopMode::One, LoopMode::All], &mut p.loop_mode);
+        values.pick("language", &Language::ALL, &mut p.language);
+        values.pick("theme", &ThemePreset::ALL, &mut p.theme);
+        values.pick("ui_anchor", &UiAnchor::ALL, &mut p.ui_anchor);
+        values.pick("color_vision", &ColorVision::ALL, &mut p.color_vision);
+        p.accent = values.string("accent").and_then(|hex| egui::Color32::from_hex(&hex).ok());
+        // Values a preset lacks are the defaults'
+        ui.lens_presets = (0..).map_while(|i| {
+            let name = values.string(&format!("lens_preset.{}.name", i))?;
+            let mut preset = VrParams::default().lens_preset(name);
+            values.get(&format!("lens_preset.{}.lens_radius", i), &mut preset.lens_radius);
+            values.get(&format!("lens_preset.{}.lens_center_offset", i), &mut preset.lens_center_offset);
+            values.get(&format!("lens_preset.{}.ipd", i), &mut preset.ipd);
+            values.get(&format!("lens_preset.{}.fov", i), &mut preset.fov);
+            Some(preset)
+        }).collect();
+
+        let browser = &mut ui.file_browser;
+        let (sort_by, subfolders) = (browser.sort_by, browser.search_subfolders);
+        values.pick("sort_by", &[SortBy::Name, SortBy::Size, SortBy::Date], &mut browser.sort_by);
+        values.get("search_subfolders", &mut browser.search_subfolders);
+        values.pick("browser_view", &[BrowserView::Carousel, BrowserView::Grid], &mut browser.view);
+        browser.favorites = (0..).map_while(|i| values.string(&format!("favorite.{}", i))).map(PathBuf::from).collect();
+        let folder = values.string("last_folder").map(PathBuf::from).filter(|f| f.is_dir());
+        if let Some(folder) = folder.filter(|f| *f != browser.current_path) {
+            browser.current_path = folder;
+            browser.refresh_entries();
+        } else if browser.sort_by != sort_by || browser.search_subfolders != subfolders {
+            browser.refresh_entries();
+        }
+
+        crate::gamepad::restore_bindings(|button| values.parsed(&format!("button.{}", button)));
+        crate::codecs::restore_preferences(values.0.keys()
+            .filter_map(|key| Some((key.strip_prefix("decoder.")?.to_string(), values.string(key)?)))
+            .collect());
+    }
+
+    /// Open the floating panels saved with the settings, as they were left.
+    /// Entries that don't read back are skipped.
+    pub fn restore_panels(&self, windows: &mut WindowManager) {
+        let values = Values::parse(&self.saved);
+        for i in 0.. {
+            let Some(kind) = values.string(&format!("panel.{}.kind", i)) else { break };
+            let content = match kind.as_str() {
+                "Browser" => PanelContent::Browser { url: values.string(&format!("panel.{}.url", i)).unwrap_or_default(), window: 0 },
+                "Dock" => PanelContent::Dock,
+                "Settings" => PanelContent::Settings,
+                _ => continue,
+            };
+            let position = values.floats(&format!("panel.{}.position", i)).map(Vec3::from_array);
+            let rotation = values.floats(&format!("panel.{}.rotation", i))
+                .and_then(|r| Vec4::from_array(r).try_normalize())
+                .map(Quat::from_vec4);
+            let scale = values.floats(&format!("panel.{}.scale", i)).map(Vec3::from_array);
+            if let (Some(position), Some(rotation), Some(scale)) = (position, rotation, scale) {
+                windows.restore(content, position, rotation, scale);
+            }
+        }
+    }
+
+    /// Write the settings if they changed since the last write; call every frame.
+    pub fn update(&mut self, ui: &VrUi, windows: &WindowManager) {
+        if self.last_check.elapsed() >= CHECK_INTERVAL {
+            self.save(ui, windows);
+        }
+    }
+
+    /// Write the settings now if they changed (before the UI goes away).
+    pub fn save(&mut self, ui: &VrUi, windows: &WindowManager) {
+        self.last_check = Instant::now();
+        let text = render(ui, windows);
+        if text == self.saved {
+            return;
+        }
+        if let Some(file) = &self.file {
+            if let Err(e) = std::fs::write(file, &text) {
+                error!("Config: failed to write {:?}: {}", file, e);
+                return;
+            }
+        }
+        self.saved = text;
+    }
+}
@@ -0,0 +1,79 @@
+//! System fonts for non-Latin text
+//!
+//! egui's built-in fonts only cover Latin, Greek and Cyrillic, so a Japanese or
+//! Arabic file name would come out as boxes. `install` appends fonts from
+//! `/system/fonts` to the end of egui's Proportional and Monospace families, so
+//! a glyph the built-in fonts lack falls through to them in `SCRIPTS` order.
+//! Each script takes the first of its candidates that exists (file names vary
+//! across Android versions). The CJK font is ~20 MB, so the files are read on a
+//! thread and swapped in when ready; later resumes reuse them.
+//!
+//! egui doesn't shape text, so Arabic and Devanagari show their letters in
+//! isolated forms, left to right. Readable enough for a file name.
+
+use egui::{Context, FontData, FontDefinitions, FontFamily};
+use log::{info, warn};
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+const FONTS_DIR: &str = "/system/fonts";
+
+/// Per script, the files to try in order, and the face to use in a collection.
+const SCRIPTS: &[(&str, &[(&str, u32)])] = &[
+    // The collection's first face is Japanese; its Han glyphs serve Chinese too
+    ("cjk", &[
+        ("NotoSansCJK-Regular.ttc", 0),
+        ("NotoSansSC-Regular.otf", 0),
+        ("DroidSansFallbackFull.ttf", 0),
+        ("DroidSansFallback.ttf", 0),
+    ]),
+    ("arabic", &[("NotoNaskhArabicUI-Regular.ttf", 0), ("NotoNaskhArabic-Regular.ttf", 0)]),
+    ("hebrew", &[("NotoSansHebrew-Regular.ttf", 0)]),
+    ("thai", &[("NotoSansThaiUI-Regular.ttf", 0), ("NotoSansThai-Regular.ttf", 0)]),
+    ("devanagari", &[("NotoSansDevanagariUI-Regular.otf", 0), ("NotoSansDevanagari-Regular.otf", 0)]),
+    ("symbols", &[("NotoSansSymbols-Regular-Subsetted.ttf", 0)]),
+];
+
+static LOADED: OnceLock<Vec<(&'static str, Arc<FontData>)>> = OnceLock::new();
+
+/// Add the system fallback fonts to `ctx`, now if they've been read before,
+/// otherwise once a background thread has read them.
+pub fn install(ctx: &Context) {
+    if let Some(fonts) = LOADED.get() {
+        ctx.set_fonts(definitions(fonts));
+        return;
+    }
+    let ctx = ctx.clone();
+    std::thread::spawn(move || {
+        let fonts = LOADED.get_or_init(load);
+        ctx.set_fonts(definitions(fonts));
+        ctx.request_repaint();
+    });
+}
+
+fn load() -> Vec<(&'static str, Arc<FontData>)> {
+    SCRIPTS.iter().filter_map(|&(script, candidates)| {
+        let found = candidates.iter().find_map(|&(file, index)| {
+            let path = Path::new(FONTS_DIR).join(file);
+            let bytes = std::fs::read(&path).ok()?;
+            info!("Fonts: {} from {} ({} KB)", script, file, bytes.len() / 1024);
+            Some(Arc::new(FontData { index, ..FontData::from_owned(bytes) }))
+        });
+        if found.is_none() {
+            warn!("Fonts: no {} font in {}", script, FONTS_DIR);
+        }
+        Some((script, found?))
+    }).collect()
+}
+
+/// egui's defaults with `fonts` appended to both families.
+fn definitions(fonts: &[(&'static str, Arc<FontData>)]) -> FontDefinitions {
+    let mut defs = FontDefinitions::default();
+    for (script, data) in fonts {
+        defs.font_data.insert(script.to_string(), data.clone());
+        for family in [FontFamily::Proportional, FontFamily::Monospace] {
+            defs.families.entry(family).or_default().push(script.to_string());
+        }
+    }
+    defs
+}
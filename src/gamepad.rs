@@ -1,12 +1,44 @@
 //! Modular gamepad input for Android
 //!
-//! Captures PS5 DualSense controller input via winit KeyboardInput events.
-//! Provides both raw GamepadState and high-level GamepadActions for app control.
+//! Captures PS5 DualSense controller input via winit KeyboardInput events, or
+//! straight from `/dev/input/event*` where the device nodes are readable
+//! (`GamepadReader`; rooted devices and some TV boxes). A controller read
+//! through evdev has its Java events dropped, matched by USB ids or name;
+//! controllers evdev can't open keep using the Java path.
+//! Provides both raw GamepadState and high-level GamepadActions for app control,
+//! plus a left-stick virtual pointer (`StickPointer`) that drives egui directly.
+//!
+//! Keycodes reach `GamepadState` through a remap table (`Button` → keycode),
+//! saved with the settings (config.rs), so controllers whose layout differs from the
+//! DualSense's can be rebound from settings (`start_capture`). Unbound buttons
+//! use the defaults of the pad's `Profile` (DualSense, Xbox, Stadia, generic),
+//! picked from its reported name and vendor.
+//!
+//! Discrete events (menu opened, file picked, seek, recenter) can be confirmed
+//! with a short rumble on the pad's vibrator (`rumble`).
+//!
+//! Holding L2 over a panel aims the pointer with the controller's own gyroscope,
+//! laser-pointer style (`set_motion_aim`, `take_aim_delta`).
+//!
+//! Each controller (by Android device id) keeps its own state. The first one
+//! connected drives everything; with the remote role on, a second one only
+//! contributes media actions (play / pause, seek, skip), so it can be handed to
+//! someone as a remote (`swap_roles`, `set_remote_role`). Controllers coming
+//! and going are queued for the app (`take_connections`).
 
-use std::sync::{Arc, Mutex};
-use log::info;
+use android_activity::AndroidApp;
+use jni::objects::{JObject, JValue};
+use jni::sys::jobject;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use log::{error, info, warn};
 use lazy_static::lazy_static;
 
+use crate::actions::{self, Action};
+
 /// Raw gamepad button/axis state
 #[derive(Debug, Clone, Default)]
 pub struct GamepadState {
@@ -43,23 +75,32 @@ pub struct GamepadState {
 #[derive(Debug, Clone, Default)]
 pub struct GamepadActions {
     // Media controls
-    pub play_pause: bool,       // X button
-    pub seek_back: bool,        // L1 - seek backward 10s
-    pub seek_forward: bool,     // R1 - seek forward 10s
+    pub play_pause: bool,       // X tap
+    pub stop: bool,             // X long-press - stop playback
+    pub seek_back: bool,        // L1 tap - seek backward 10s
+    pub seek_forward: bool,     // R1 tap - seek forward 10s
+    pub prev_track: bool,       // L1 long-press - previous queue entry
+    pub next_track: bool,       // R1 long-press - next queue entry
     
     // UI controls  
     pub toggle_ui: bool,        // △ - show/hide menu
     pub confirm: bool,          // □ - select/confirm
+    pub confirm_tap: bool,      // □ released before a long-press
+    pub confirm_hold: bool,     // □ long-press - bookmark in the Media Center
     pub back: bool,             // ○ - back/cancel
+    pub close_all: bool,        // ○ double-press - close every panel
     
     // VR controls
     pub reset_view: bool,       // L3 - recenter orientation
+    pub yaw_left: bool,         // □ + D-pad left - nudge the world 1° left
+    pub yaw_right: bool,        // □ + D-pad right - nudge the world 1° right
     pub toggle_vr_mode: bool,   // R3 - switch VR/2D
     
     // App controls
-    pub open_settings: bool,    // Options button
+    pub open_settings: bool,    // Options tap
     pub open_file_picker: bool, // Create button
-    pub exit_app: bool,         // PS button
+    pub multi_select: bool,     // Create long-press - multi-select in the Media Center
+    pub exit_app: bool,         // PS button, or Options long-press
     
     // Zoom (analog triggers - for now digital)
     pub zoom_in: bool,          // R2
@@ -74,138 +115,1214 @@ pub struct GamepadActions {
     pub nav_down: bool,         // D-pad down
     pub nav_left: bool,         // D-pad left
     pub nav_right: bool,        // D-pad right
+    pub nav_repeated: bool,     // this frame's nav_* is an auto-repeat of a held direction
     // Analog sticks (current value, not edge-triggered)
     pub left_stick_x: f32,
     pub left_stick_y: f32,
     pub right_stick_x: f32,
     pub right_stick_y: f32,
+    // Two-button shortcuts that went down this frame (actions::CHORDS)
+    pub chords: Vec<Action>,
 }
 
 // Global state
 lazy_static! {
-    static ref GAMEPAD_STATE: Arc<Mutex<GamepadState>> = Arc::new(Mutex::new(GamepadState::default()));
-    static ref PREV_STATE: Arc<Mutex<GamepadState>> = Arc::new(Mutex::new(GamepadState::default()));
+    // Connected controllers in role order (see `Pad`)
+    static ref PADS: Mutex<Vec<Pad>> = Mutex::new(Vec::new());
+    // Connects / disconnects not yet seen by the app
+    static ref CONNECTIONS: Mutex<Vec<Connection>> = Mutex::new(Vec::new());
+    // Auto-repeat (initial delay, interval); a zero interval turns it off
+    static ref NAV_REPEAT: Mutex<(Duration, Duration)> =
+        Mutex::new((Duration::from_millis(400), Duration::from_millis(100)));
+}
+
+/// One controller: its raw state and what its actions are derived from.
+#[derive(Default)]
+struct Pad {
+    device: i32,
+    state: GamepadState,
+    prev: GamepadState,
+    /// Press timing for the buttons with tap / long-press / double-press actions
+    presses: HashMap<Button, Press>,
+    /// Next auto-repeat of each held D-pad direction (up, down, left, right)
+    nav_repeat_at: [Option<Instant>; 4],
+    profile: Profile,
+}
+
+/// Device id for input whose controller isn't known (winit key events); it
+/// goes to the first controller.
+const UNKNOWN_DEVICE: i32 = -1;
+
+/// A second controller only sends media actions.
+static REMOTE_ROLE: AtomicBool = AtomicBool::new(true);
+
+/// A controller came or went (by device id).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Connection {
+    Connected(i32),
+    Disconnected(i32),
+}
+
+/// Connects and disconnects since the last call, oldest first.
+pub fn take_connections() -> Vec<Connection> {
+    CONNECTIONS.lock().map(|mut c| std::mem::take(&mut *c)).unwrap_or_default()
+}
+
+/// Run `f` on `device`'s state, adding the controller on first sight.
+fn with_pad<R>(device: i32, f: impl FnOnce(&mut Pad) -> R) -> R {
+    let mut pads = PADS.lock().unwrap();
+    let index = match pads.iter().position(|p| p.device == device || device == UNKNOWN_DEVICE) {
+        Some(i) => i,
+        None => {
+            info!("gamepad: controller {} connected as #{}", device, pads.len() + 1);
+            pads.push(Pad { device, ..Default::default() });
+            CONNECTIONS.lock().unwrap().push(Connection::Connected(device));
+            pads.len() - 1
+        }
+    };
+    f(&mut pads[index])
+}
+
+/// Forget a disconnected controller; the next one up takes its role.
+pub fn remove_device(device: i32) {
+    let mut pads = PADS.lock().unwrap();
+    if let Some(i) = pads.iter().position(|p| p.device == device) {
+        pads.remove(i);
+        info!("gamepad: controller {} disconnected", device);
+        CONNECTIONS.lock().unwrap().push(Connection::Disconnected(device));
+    }
+}
+
+/// A controller was (re)connected; take it on before its first input.
+pub fn add_device(device: i32) {
+    with_pad(device, |_| ());
+}
+
+/// Connected controllers' device ids, in role order.
+pub fn controllers() -> Vec<i32> {
+    PADS.lock().map(|pads| pads.iter().map(|p| p.device).collect()).unwrap_or_default()
+}
+
+/// Swap the first two controllers' roles.
+pub fn swap_roles() {
+    let mut pads = PADS.lock().unwrap();
+    if pads.len() >= 2 {
+        pads.swap(0, 1);
+        info!("gamepad: controller {} now primary", pads[0].device);
+    }
+}
+
+/// Whether the second controller is limited to media actions.
+pub fn set_remote_role(enabled: bool) {
+    REMOTE_ROLE.store(enabled, Ordering::Relaxed);
+}
+
+/// How a stick's deflection grows from the centre.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StickCurve {
+    Linear,
+    /// Squared: fine control near the centre, full speed at the edge
+    Expo,
+}
+
+impl StickCurve {
+    pub fn label(self) -> &'static str {
+        crate::i18n::tr(match self {
+            StickCurve::Linear => "Linear",
+            StickCurve::Expo => "Expo",
+        })
+    }
 }
 
-/// Android KeyEvent button codes
+/// Shaping applied to one stick's raw axes before anything reads them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StickTuning {
+    /// Travel ignored around the centre, per axis (0…1)
+    pub deadzone_x: f32,
+    pub deadzone_y: f32,
+    pub curve: StickCurve,
+    /// Output multiplier
+    pub sensitivity: f32,
+}
+
+impl StickTuning {
+    /// Left stick: pointer and panning, so expo for precise small moves.
+    pub const LEFT: StickTuning = StickTuning { deadzone_x: 0.15, deadzone_y: 0.15, curve: StickCurve::Expo, sensitivity: 1.0 };
+    /// Right stick: turning the view.
+    pub const RIGHT: StickTuning = StickTuning { deadzone_x: 0.15, deadzone_y: 0.15, curve: StickCurve::Linear, sensitivity: 1.0 };
+
+    /// One axis: zero inside the deadzone, rescaled past it so small tilts still
+    /// start slowly, then curved and scaled.
+    fn shape(&self, v: f32, deadzone: f32) -> f32 {
+        let deadzone = deadzone.clamp(0.0, 0.9);
+        if v.abs() <= deadzone {
+            return 0.0;
+        }
+        let t = ((v.abs() - deadzone) / (1.0 - deadzone)).min(1.0);
+        let t = match self.curve {
+            StickCurve::Linear => t,
+            StickCurve::Expo => t * t,
+        };
+        (t * self.sensitivity).copysign(v)
+    }
+}
+
+/// Tuning for the left and right stick.
+static STICK_TUNING: Mutex<[StickTuning; 2]> = Mutex::new([StickTuning::LEFT, StickTuning::RIGHT]);
+
+pub fn set_stick_tuning(left: StickTuning, right: StickTuning) {
+    *STICK_TUNING.lock().unwrap() = [left, right];
+}
+
+/// A raw stick axis value (`right` stick, `y` axis) shaped by its tuning.
+fn tuned(right: bool, y: bool, v: f32) -> f32 {
+    let tuning = STICK_TUNING.lock().map(|t| t[right as usize]).unwrap_or(StickTuning::LEFT);
+    tuning.shape(v, if y { tuning.deadzone_y } else { tuning.deadzone_x })
+}
+
+/// Held D-pad directions repeat after `delay_s` seconds, `rate_hz` times a
+/// second (0 = no repeat).
+pub fn set_repeat(delay_s: f32, rate_hz: f32) {
+    let interval = if rate_hz > 0.0 { Duration::from_secs_f32(1.0 / rate_hz) } else { Duration::ZERO };
+    *NAV_REPEAT.lock().unwrap() = (Duration::from_secs_f32(delay_s.max(0.0)), interval);
+}
+
+/// Press edge or auto-repeat for one held direction. Returns (fired, was a repeat).
+fn nav_repeat(next: &mut Option<Instant>, down: bool, was_down: bool, now: Instant) -> (bool, bool) {
+    let (delay, interval) = *NAV_REPEAT.lock().unwrap();
+    if !down {
+        *next = None;
+        return (false, false);
+    }
+    if !was_down {
+        *next = Some(now + delay);
+        return (true, false);
+    }
+    match *next {
+        Some(at) if now >= at && !interval.is_zero() => {
+            // From now, not from `at`: a slow frame shouldn't cause a burst
+            *next = Some(now + interval);
+            (true, true)
+        }
+        _ => (false, false),
+    }
+}
+
+/// Holding a button this long is a long-press (L1/R1 skip queue entries instead
+/// of seeking, X stops, Options quits).
+const LONG_PRESS: Duration = Duration::from_millis(600);
+/// A second press within this long of a tap is a double-press.
+const DOUBLE_PRESS: Duration = Duration::from_millis(350);
+
+/// Press timing for one button.
+#[derive(Debug, Clone, Copy, Default)]
+struct Press {
+    start: Option<Instant>,
+    /// This press already did something else (long-press, double-press, chord):
+    /// its release isn't a tap.
+    spent: bool,
+    last_tap: Option<Instant>,
+}
+
+/// What one button did this frame.
+#[derive(Debug, Clone, Copy, Default)]
+struct PressEvents {
+    /// Released before the long-press time (fires on release).
+    tap: bool,
+    /// Held for `LONG_PRESS` (fires once, while still held).
+    long: bool,
+    /// Pressed again within `DOUBLE_PRESS` of a tap (fires on the press).
+    double: bool,
+}
+
+impl Press {
+    fn update(&mut self, down: bool, was_down: bool, now: Instant) -> PressEvents {
+        let mut events = PressEvents::default();
+        if down && !was_down {
+            events.double = self.last_tap.is_some_and(|t| now.duration_since(t) <= DOUBLE_PRESS);
+            *self = Press { start: Some(now), spent: events.double, last_tap: None };
+        }
+        if down {
+            if let (Some(start), false) = (self.start, self.spent) {
+                if now.duration_since(start) >= LONG_PRESS {
+                    self.spent = true;
+                    events.long = true;
+                }
+            }
+        } else if was_down {
+            events.tap = !self.spent;
+            *self = Press { start: None, spent: false, last_tap: events.tap.then_some(now) };
+        }
+        events
+    }
+}
+
+/// Android KeyEvent button codes (DualSense / Xbox / Stadia names)
 pub mod keycodes {
-    pub const BUTTON_A: i32 = 96;      // X
-    pub const BUTTON_B: i32 = 97;      // ○
-    pub const BUTTON_X: i32 = 99;      // □
-    pub const BUTTON_Y: i32 = 100;     // △
-    pub const BUTTON_L1: i32 = 102;
+    pub const BUTTON_A: i32 = 96;      // ✕ / A / A (bottom face button)
+    pub const BUTTON_B: i32 = 97;      // ○ / B / B
+    pub const BUTTON_X: i32 = 99;      // □ / X / X
+    pub const BUTTON_Y: i32 = 100;     // △ / Y / Y
+    pub const BUTTON_L1: i32 = 102;    // L1 / LB / L1
     pub const BUTTON_R1: i32 = 103;
-    pub const BUTTON_L2: i32 = 104;
+    pub const BUTTON_L2: i32 = 104;    // L2 / LT / L2 (digital)
     pub const BUTTON_R2: i32 = 105;
     pub const BUTTON_THUMBL: i32 = 106;
     pub const BUTTON_THUMBR: i32 = 107;
-    pub const BUTTON_START: i32 = 108; // Options
-    pub const BUTTON_SELECT: i32 = 109; // Create
-    pub const BUTTON_MODE: i32 = 110;  // PS button
+    pub const BUTTON_START: i32 = 108; // Options / Menu / Menu
+    pub const BUTTON_SELECT: i32 = 109; // Create / View / Options
+    pub const BUTTON_MODE: i32 = 110;  // PS / Xbox / Stadia
     pub const DPAD_UP: i32 = 19;
     pub const DPAD_DOWN: i32 = 20;
     pub const DPAD_LEFT: i32 = 21;
     pub const DPAD_RIGHT: i32 = 22;
+    /// Numbered buttons of HID pads Android has no layout for (…_16 = 203)
+    pub const BUTTON_1: i32 = 188;
+    pub const BUTTON_16: i32 = 203;
+}
+
+/// MotionEvent axes the Java side forwards (AXIS_X … AXIS_BRAKE, by index).
+pub const AXIS_COUNT: usize = 24;
+mod axes {
+    pub const X: usize = 0;
+    pub const Y: usize = 1;
+    pub const Z: usize = 11;
+    pub const RZ: usize = 14;
+    pub const LTRIGGER: usize = 17;
+    pub const RTRIGGER: usize = 18;
+    pub const GAS: usize = 22;
+    pub const BRAKE: usize = 23;
+}
+
+/// A controller family's layout: default keycodes, button names and which axes
+/// carry the right stick and triggers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Profile {
+    DualSense,
+    Xbox,
+    Stadia,
+    /// Anything Android has a key layout for (8BitDo, most Bluetooth pads)
+    #[default]
+    Generic,
+    /// A HID pad Android has no layout for: numbered buttons, DirectInput order
+    GenericHid,
+}
+
+impl Profile {
+    /// Pick from what the pad reports; unknown pads start `Generic` and turn
+    /// `GenericHid` on their first numbered button.
+    pub fn detect(name: &str, vendor: i32, product: i32) -> Profile {
+        let name = name.to_ascii_lowercase();
+        match (vendor, product) {
+            (0x054c, _) => Profile::DualSense,
+            (0x045e, _) => Profile::Xbox,
+            (0x18d1, 0x9400) => Profile::Stadia,
+            _ if name.contains("dualsense") || name.contains("wireless controller") => Profile::DualSense,
+            _ if name.contains("xbox") => Profile::Xbox,
+            _ if name.contains("stadia") => Profile::Stadia,
+            _ => Profile::Generic,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Profile::DualSense => "DualSense",
+            Profile::Xbox => "Xbox",
+            Profile::Stadia => "Stadia",
+            Profile::Generic => "Generic",
+            Profile::GenericHid => "Generic HID",
+        }
+    }
+
+    /// Keycode `button` sends when not remapped.
+    pub fn keycode(self, button: Button) -> i32 {
+        let numbered = |n: i32| keycodes::BUTTON_1 + n - 1;
+        match (self, button) {
+            (Profile::GenericHid, Button::Square) => numbered(1),
+            (Profile::GenericHid, Button::Cross) => numbered(2),
+            (Profile::GenericHid, Button::Circle) => numbered(3),
+            (Profile::GenericHid, Button::Triangle) => numbered(4),
+            (Profile::GenericHid, Button::L1) => numbered(5),
+            (Profile::GenericHid, Button::R1) => numbered(6),
+            (Profile::GenericHid, Button::L2) => numbered(7),
+            (Profile::GenericHid, Button::R2) => numbered(8),
+            (Profile::GenericHid, Button::Create) => numbered(9),
+            (Profile::GenericHid, Button::Options) => numbered(10),
+            (Profile::GenericHid, Button::L3) => numbered(11),
+            (Profile::GenericHid, Button::R3) => numbered(12),
+            (Profile::GenericHid, Button::Ps) => numbered(13),
+            // Android's key layouts put the others on the standard codes
+            _ => button.default_keycode(),
+        }
+    }
+
+    /// What the button is called on this pad.
+    pub fn label(self, button: Button) -> &'static str {
+        match (self, button) {
+            (Profile::DualSense, _) | (_, Button::DpadUp | Button::DpadDown | Button::DpadLeft | Button::DpadRight) => button.label(),
+            (Profile::GenericHid, _) => match button {
+                Button::Square => "1", Button::Cross => "2", Button::Circle => "3", Button::Triangle => "4",
+                Button::L1 => "5", Button::R1 => "6", Button::L2 => "7", Button::R2 => "8",
+                Button::Create => "9", Button::Options => "10", Button::L3 => "11", Button::R3 => "12",
+                _ => "13",
+            },
+            (_, Button::Cross) => "A",
+            (_, Button::Circle) => "B",
+            (_, Button::Square) => "X",
+            (_, Button::Triangle) => "Y",
+            (Profile::Xbox, Button::L1) => "LB",
+            (Profile::Xbox, Button::R1) => "RB",
+            (Profile::Xbox, Button::L2) => "LT",
+            (Profile::Xbox, Button::R2) => "RT",
+            (Profile::Xbox, Button::L3) => "LS",
+            (Profile::Xbox, Button::R3) => "RS",
+            (Profile::Xbox, Button::Create) => "View",
+            (Profile::Xbox, Button::Options) => "Menu",
+            (Profile::Xbox, _) => "Xbox",
+            (Profile::Stadia, Button::Create) => "Options",
+            (Profile::Stadia, Button::Options) => "Menu",
+            (Profile::Stadia, Button::Ps) => "Stadia",
+            (Profile::Generic, Button::Create) => "Select",
+            (Profile::Generic, Button::Options) => "Start",
+            (Profile::Generic, Button::Ps) => "Home",
+            _ => button.label(),
+        }
+    }
+
+    /// Right stick (x, y) axes and the trigger axes to try, first moving one wins.
+    fn axes(self) -> ((usize, usize), [usize; 2], [usize; 2]) {
+        match self {
+            // Xbox and Stadia report triggers as brake / gas
+            Profile::Xbox | Profile::Stadia => ((axes::Z, axes::RZ), [axes::BRAKE, axes::LTRIGGER], [axes::GAS, axes::RTRIGGER]),
+            _ => ((axes::Z, axes::RZ), [axes::LTRIGGER, axes::BRAKE], [axes::RTRIGGER, axes::GAS]),
+        }
+    }
+}
+
+/// A control as the app sees it (named after the DualSense), whatever keycode
+/// the connected pad actually sends for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Button {
+    Cross, Circle, Square, Triangle,
+    L1, R1, L2, R2, L3, R3,
+    Create, Options, Ps,
+    DpadUp, DpadDown, DpadLeft, DpadRight,
+}
+
+pub const BUTTONS: [Button; 17] = [
+    Button::Cross, Button::Circle, Button::Square, Button::Triangle,
+    Button::L1, Button::R1, Button::L2, Button::R2, Button::L3, Button::R3,
+    Button::Create, Button::Options, Button::Ps,
+    Button::DpadUp, Button::DpadDown, Button::DpadLeft, Button::DpadRight,
+];
+
+impl Button {
+    fn default_keycode(self) -> i32 {
+        match self {
+            Button::Cross => keycodes::BUTTON_A,
+            Button::Circle => keycodes::BUTTON_B,
+            Button::Square => keycodes::BUTTON_X,
+            Button::Triangle => keycodes::BUTTON_Y,
+            Button::L1 => keycodes::BUTTON_L1,
+            Button::R1 => keycodes::BUTTON_R1,
+            Button::L2 => keycodes::BUTTON_L2,
+            Button::R2 => keycodes::BUTTON_R2,
+            Button::L3 => keycodes::BUTTON_THUMBL,
+            Button::R3 => keycodes::BUTTON_THUMBR,
+            Button::Create => keycodes::BUTTON_SELECT,
+            Button::Options => keycodes::BUTTON_START,
+            Button::Ps => keycodes::BUTTON_MODE,
+            Button::DpadUp => keycodes::DPAD_UP,
+            Button::DpadDown => keycodes::DPAD_DOWN,
+            Button::DpadLeft => keycodes::DPAD_LEFT,
+            Button::DpadRight => keycodes::DPAD_RIGHT,
+        }
+    }
+
+    /// Name in the settings file.
+    fn key(self) -> &'static str {
+        match self {
+            Button::Cross => "cross",
+            Button::Circle => "circle",
+            Button::Square => "square",
+            Button::Triangle => "triangle",
+            Button::L1 => "l1",
+            Button::R1 => "r1",
+            Button::L2 => "l2",
+            Button::R2 => "r2",
+            Button::L3 => "l3",
+            Button::R3 => "r3",
+            Button::Create => "create",
+            Button::Options => "options",
+            Button::Ps => "ps",
+            Button::DpadUp => "dpad_up",
+            Button::DpadDown => "dpad_down",
+            Button::DpadLeft => "dpad_left",
+            Button::DpadRight => "dpad_right",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Button::Cross => "✕ Cross",
+            Button::Circle => "○ Circle",
+            Button::Square => "□ Square",
+            Button::Triangle => "△ Triangle",
+            Button::L1 => "L1",
+            Button::R1 => "R1",
+            Button::L2 => "L2",
+            Button::R2 => "R2",
+            Button::L3 => "L3",
+            Button::R3 => "R3",
+            Button::Create => "Create",
+            Button::Options => "Options",
+            Button::Ps => "PS",
+            Button::DpadUp => "D-pad ▲",
+            Button::DpadDown => "D-pad ▼",
+            Button::DpadLeft => "D-pad ◀",
+            Button::DpadRight => "D-pad ▶",
+        }
+    }
+
+    /// What it does when no menu is open (see `GamepadActions`).
+    pub fn role(self) -> &'static str {
+        crate::i18n::tr(match self {
+            Button::Cross => "Play / pause, select; hold: stop",
+            Button::Circle => "Back; twice: close all",
+            Button::Square => "Confirm, yaw trim (held)",
+            Button::Triangle => "Menu",
+            Button::L1 => "Seek back / previous",
+            Button::R1 => "Seek forward / next",
+            Button::L2 => "Zoom out",
+            Button::R2 => "Zoom in",
+            Button::L3 => "Recenter",
+            Button::R3 => "VR / 2D",
+            Button::Create => "Media center",
+            Button::Options => "Settings; hold: quit",
+            Button::Ps => "Exit",
+            Button::DpadUp => "Next chapter, navigate",
+            Button::DpadDown => "Previous chapter, navigate",
+            Button::DpadLeft => "3D layout, navigate",
+            Button::DpadRight => "3D layout, navigate",
+        })
+    }
+
+    fn set(self, state: &mut GamepadState, pressed: bool) {
+        let field = match self {
+            Button::Cross => &mut state.btn_south,
+            Button::Circle => &mut state.btn_east,
+            Button::Square => &mut state.btn_west,
+            Button::Triangle => &mut state.btn_north,
+            Button::L1 => &mut state.btn_l1,
+            Button::R1 => &mut state.btn_r1,
+            Button::L2 => &mut state.btn_l2,
+            Button::R2 => &mut state.btn_r2,
+            Button::L3 => &mut state.btn_thumbl,
+            Button::R3 => &mut state.btn_thumbr,
+            Button::Create => &mut state.btn_select,
+            Button::Options => &mut state.btn_start,
+            Button::Ps => &mut state.btn_mode,
+            Button::DpadUp => &mut state.btn_dpad_up,
+            Button::DpadDown => &mut state.btn_dpad_down,
+            Button::DpadLeft => &mut state.btn_dpad_left,
+            Button::DpadRight => &mut state.btn_dpad_right,
+        };
+        *field = pressed;
+    }
+
+    fn is_down(self, state: &GamepadState) -> bool {
+        match self {
+            Button::Cross => state.btn_south,
+            Button::Circle => state.btn_east,
+            Button::Square => state.btn_west,
+            Button::Triangle => state.btn_north,
+            Button::L1 => state.btn_l1,
+            Button::R1 => state.btn_r1,
+            Button::L2 => state.btn_l2,
+            Button::R2 => state.btn_r2,
+            Button::L3 => state.btn_thumbl,
+            Button::R3 => state.btn_thumbr,
+            Button::Create => state.btn_select,
+            Button::Options => state.btn_start,
+            Button::Ps => state.btn_mode,
+            Button::DpadUp => state.btn_dpad_up,
+            Button::DpadDown => state.btn_dpad_down,
+            Button::DpadLeft => state.btn_dpad_left,
+            Button::DpadRight => state.btn_dpad_right,
+        }
+    }
+}
+
+lazy_static! {
+    // Keycode each button answers to; missing entries use the default
+    static ref BINDINGS: Mutex<HashMap<Button, i32>> = Mutex::new(HashMap::new());
+    // Button waiting for "press the button for …" in settings
+    static ref CAPTURE: Mutex<Option<Button>> = Mutex::new(None);
+}
+
+/// The remap table as (button name, keycode), for the settings file.
+pub fn bindings() -> Vec<(&'static str, i32)> {
+    let bindings = BINDINGS.lock().unwrap();
+    BUTTONS.iter().filter_map(|b| bindings.get(b).map(|&code| (b.key(), code))).collect()
+}
+
+/// Put back a saved remap table; `saved` gives the keycode stored for a button name.
+pub fn restore_bindings(saved: impl Fn(&str) -> Option<i32>) {
+    let mut bindings = BINDINGS.lock().unwrap();
+    bindings.clear();
+    for button in BUTTONS {
+        let Some(code) = saved(button.key()) else { continue };
+        if code != button.default_keycode() {
+            info!("gamepad: {} remapped to keycode {}", button.key(), code);
+        }
+        bindings.insert(button, code);
+    }
+}
+
+/// Keycode `button` currently answers to (on the first controller).
+pub fn binding(button: Button) -> i32 {
+    BINDINGS.lock().ok().and_then(|b| b.get(&button).copied()).unwrap_or(profile().keycode(button))
+}
+
+/// Whether `button` answers to something other than its profile's default.
+pub fn is_remapped(button: Button) -> bool {
+    binding(button) != profile().keycode(button)
+}
+
+/// What to call `button` on screen: its name on the connected pad, or the key
+/// it was remapped to.
+pub fn glyph(button: Button) -> String {
+    if is_remapped(button) {
+        keycode_label(binding(button))
+    } else {
+        profile().label(button).to_string()
+    }
+}
+
+/// Layout of the first controller.
+pub fn profile() -> Profile {
+    PADS.lock().ok().and_then(|pads| pads.first().map(|p| p.profile)).unwrap_or_default()
+}
+
+/// What the pad said about itself (first event from a new device).
+pub fn set_device_info(device: i32, name: &str, vendor: i32, product: i32) {
+    let profile = Profile::detect(name, vendor, product);
+    info!("gamepad: controller {} is \"{}\" ({:04x}:{:04x}), {} layout", device, name, vendor, product, profile.name());
+    with_pad(device, |pad| pad.profile = profile);
+}
+
+/// Bind the next button pressed (on any pad) to `button`.
+pub fn start_capture(button: Button) {
+    *CAPTURE.lock().unwrap() = Some(button);
+}
+
+pub fn cancel_capture() {
+    *CAPTURE.lock().unwrap() = None;
+}
+
+/// The button waiting for a press, if any.
+pub fn capturing() -> Option<Button> {
+    *CAPTURE.lock().unwrap()
+}
+
+/// Back to each pad's own layout.
+pub fn reset_bindings() {
+    let mut bindings = BINDINGS.lock().unwrap();
+    bindings.clear();
+    info!("gamepad: bindings reset");
+}
+
+/// Readable name for an Android keycode.
+pub fn keycode_label(code: i32) -> String {
+    match code {
+        keycodes::BUTTON_A => "A".into(),
+        keycodes::BUTTON_B => "B".into(),
+        98 => "C".into(),
+        keycodes::BUTTON_X => "X".into(),
+        keycodes::BUTTON_Y => "Y".into(),
+        101 => "Z".into(),
+        keycodes::BUTTON_L1 => "L1".into(),
+        keycodes::BUTTON_R1 => "R1".into(),
+        keycodes::BUTTON_L2 => "L2".into(),
+        keycodes::BUTTON_R2 => "R2".into(),
+        keycodes::BUTTON_THUMBL => "Left thumb".into(),
+        keycodes::BUTTON_THUMBR => "Right thumb".into(),
+        keycodes::BUTTON_START => "Start".into(),
+        keycodes::BUTTON_SELECT => "Select".into(),
+        keycodes::BUTTON_MODE => "Mode".into(),
+        keycodes::DPAD_UP => "D-pad up".into(),
+        keycodes::DPAD_DOWN => "D-pad down".into(),
+        keycodes::DPAD_LEFT => "D-pad left".into(),
+        keycodes::DPAD_RIGHT => "D-pad right".into(),
+        188..=203 => format!("Button {}", code - 187),
+        _ => format!("Key {}", code),
+    }
+}
+
+/// Pointer travel per radian the controller turns while aiming.
+const AIM_PX_PER_RAD: f32 = 1600.0;
+
+static AIM_ENABLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    // Pointer movement (canvas px) gathered from controller gyro samples, and the
+    // last sample's timestamp (ns)
+    static ref AIM_DELTA: Mutex<(egui::Vec2, Option<i64>)> = Mutex::new((egui::Vec2::ZERO, None));
+}
+
+/// Whether `button` is down on `pad` (from `get_state`).
+pub fn is_held(pad: &GamepadState, button: Button) -> bool {
+    button.is_down(pad)
+}
+
+/// Whether L2 is held far enough to aim.
+pub fn l2_held(pad: &GamepadState) -> bool {
+    pad.btn_l2 || pad.l2_trigger > 0.5
+}
+
+/// Start or stop the controller gyroscope (only while aiming, to spare its
+/// battery). Cheap to call every frame: Java is only told on a change.
+pub fn set_motion_aim(app: &AndroidApp, enabled: bool) {
+    if AIM_ENABLED.swap(enabled, Ordering::Relaxed) == enabled {
+        return;
+    }
+    if let Ok(mut aim) = AIM_DELTA.lock() {
+        *aim = (egui::Vec2::ZERO, None);
+    }
+    let vm = unsafe { jni::JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM).unwrap() };
+    let Ok(mut env) = vm.attach_current_thread() else { return };
+    let activity = unsafe { JObject::from_raw(app.activity_as_ptr() as jobject) };
+    if let Err(e) = env.call_method(&activity, "setControllerMotionEnabled", "(Z)V", &[JValue::Bool(enabled as u8)]) {
+        error!("gamepad: setControllerMotionEnabled failed: {:?}", e);
+    }
+}
+
+/// Pointer movement from motion aim since the last call.
+pub fn take_aim_delta() -> egui::Vec2 {
+    AIM_DELTA.lock().map(|mut aim| std::mem::take(&mut aim.0)).unwrap_or_default()
+}
+
+/// Controller gyroscope sample (rad/s, the pad's sensor frame: x across it, y
+/// up out of it when held normally) from the input device's sensor manager.
+#[no_mangle]
+pub unsafe extern "C" fn Java_com_vrapp_core_MainActivity_onControllerGyro(
+    _env: jni::JNIEnv,
+    _class: jni::objects::JObject,
+    x: jni::sys::jfloat,
+    y: jni::sys::jfloat,
+    _z: jni::sys::jfloat,
+    timestamp: jni::sys::jlong,
+) {
+    let Ok(mut aim) = AIM_DELTA.lock() else { return };
+    if let Some(last) = aim.1 {
+        let dt = ((timestamp - last) as f32 * 1e-9).clamp(0.0, 0.05);
+        // Turn right (negative about y) moves right, tilt up (positive about x) moves up
+        aim.0 += egui::vec2(-y, -x) * AIM_PX_PER_RAD * dt;
+    }
+    aim.1 = Some(timestamp);
+}
+
+/// How often the first controller's battery is asked for.
+const BATTERY_POLL: Duration = Duration::from_secs(30);
+/// Below this the battery badge turns into a warning.
+pub const BATTERY_LOW: f32 = 0.15;
+
+lazy_static! {
+    // Last battery poll and its result (0…1), None when the pad doesn't report one
+    static ref BATTERY: Mutex<(Option<Instant>, Option<f32>)> = Mutex::new((None, None));
+}
+
+/// Battery level (0…1) of the first controller, if it reports one (Android 12+).
+/// Cheap to call every frame: Java is only asked every `BATTERY_POLL`.
+pub fn battery(app: &AndroidApp) -> Option<f32> {
+    let mut battery = BATTERY.lock().unwrap();
+    if battery.0.is_some_and(|at| at.elapsed() < BATTERY_POLL) {
+        return battery.1;
+    }
+    battery.0 = Some(Instant::now());
+    let Some(&device) = controllers().first() else {
+        battery.1 = None;
+        return None;
+    };
+    let vm = unsafe { jni::JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM).unwrap() };
+    let Ok(mut env) = vm.attach_current_thread() else { return battery.1 };
+    let activity = unsafe { JObject::from_raw(app.activity_as_ptr() as jobject) };
+    let level = match env.call_method(&activity, "controllerBattery", "(I)F", &[JValue::Int(device)]).and_then(|v| v.f()) {
+        Ok(level) if level >= 0.0 => Some(level.min(1.0)),
+        Ok(_) => None,
+        Err(e) => {
+            error!("gamepad: controllerBattery failed: {:?}", e);
+            None
+        }
+    };
+    if let Some(level) = level {
+        if level < BATTERY_LOW && battery.1.is_none_or(|was| was >= BATTERY_LOW) {
+            warn!("gamepad: controller {} battery low ({:.0}%)", device, level * 100.0);
+        }
+    }
+    battery.1 = level;
+    level
+}
+
+/// Kinds of haptic confirmation, strongest last.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Haptic {
+    /// A menu or panel opened.
+    Menu,
+    /// A file was picked to play or queue.
+    Select,
+    /// A seek or chapter jump.
+    Seek,
+    /// The view was recentered.
+    Recenter,
+}
+
+impl Haptic {
+    /// (duration ms, amplitude 1–255)
+    fn pulse(self) -> (i32, i32) {
+        match self {
+            Haptic::Menu => (20, 80),
+            Haptic::Select => (35, 140),
+            Haptic::Seek => (25, 110),
+            Haptic::Recenter => (60, 200),
+        }
+    }
+}
+
+static HAPTICS: AtomicBool = AtomicBool::new(true);
+
+pub fn set_haptics(enabled: bool) {
+    HAPTICS.store(enabled, Ordering::Relaxed);
+}
+
+/// Pulse the vibrator of the pad last used (silently nothing if it has none).
+pub fn rumble(app: &AndroidApp, haptic: Haptic) {
+    if !HAPTICS.load(Ordering::Relaxed) {
+        return;
+    }
+    let (ms, amplitude) = haptic.pulse();
+    let vm = unsafe { jni::JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM).unwrap() };
+    let Ok(mut env) = vm.attach_current_thread() else { return };
+    let activity = unsafe { JObject::from_raw(app.activity_as_ptr() as jobject) };
+    if let Err(e) = env.call_method(&activity, "rumble", "(II)V", &[JValue::Int(ms), JValue::Int(amplitude)]) {
+        error!("gamepad: rumble failed: {:?}", e);
+    }
 }
 
 /// Called from lib.rs when a gamepad button event is received
 pub fn handle_button(key_code: i32, pressed: bool) {
-    let mut state = GAMEPAD_STATE.lock().unwrap();
-    
-    match key_code {
-        keycodes::BUTTON_A => state.btn_south = pressed,
-        keycodes::BUTTON_B => state.btn_east = pressed,
-        keycodes::BUTTON_X => state.btn_west = pressed,
-        keycodes::BUTTON_Y => state.btn_north = pressed,
-        keycodes::BUTTON_L1 => state.btn_l1 = pressed,
-        keycodes::BUTTON_R1 => state.btn_r1 = pressed,
-        keycodes::BUTTON_L2 => state.btn_l2 = pressed,
-        keycodes::BUTTON_R2 => state.btn_r2 = pressed,
-        keycodes::BUTTON_THUMBL => state.btn_thumbl = pressed,
-        keycodes::BUTTON_THUMBR => state.btn_thumbr = pressed,
-        keycodes::BUTTON_START => state.btn_start = pressed,
-        keycodes::BUTTON_SELECT => state.btn_select = pressed,
-        keycodes::BUTTON_MODE => state.btn_mode = pressed,
-        keycodes::DPAD_UP => state.btn_dpad_up = pressed,
-        keycodes::DPAD_DOWN => state.btn_dpad_down = pressed,
-        keycodes::DPAD_LEFT => state.btn_dpad_left = pressed,
-        keycodes::DPAD_RIGHT => state.btn_dpad_right = pressed,
-        _ => {}
+    handle_device_button(UNKNOWN_DEVICE, key_code, pressed);
+}
+
+/// A button event from a known controller.
+pub fn handle_device_button(device: i32, key_code: i32, pressed: bool) {
+    if evdev_reads(device) {
+        return;
+    }
+    press_button(device, key_code, pressed);
+}
+
+fn press_button(device: i32, key_code: i32, pressed: bool) {
+    let profile = with_pad(device, |pad| {
+        if pad.profile == Profile::Generic && (keycodes::BUTTON_1..=keycodes::BUTTON_16).contains(&key_code) {
+            info!("gamepad: controller {} sends numbered buttons, {} layout", device, Profile::GenericHid.name());
+            pad.profile = Profile::GenericHid;
+        }
+        pad.profile
+    });
+    let mut bindings = BINDINGS.lock().unwrap();
+
+    // Settings is waiting for a press: bind it instead of acting on it. A button
+    // already on that keycode takes over the captured one's old keycode.
+    if pressed {
+        if let Some(button) = CAPTURE.lock().unwrap().take() {
+            let previous = bindings.get(&button).copied().unwrap_or(profile.keycode(button));
+            if let Some(&other) = BUTTONS.iter()
+                .find(|b| **b != button && bindings.get(b).copied().unwrap_or(profile.keycode(**b)) == key_code) {
+                bindings.insert(other, previous);
+            }
+            bindings.insert(button, key_code);
+            info!("gamepad: {} bound to keycode {}", button.key(), key_code);
+            return;
+        }
     }
+
+    let Some(&button) = BUTTONS.iter()
+        .find(|b| bindings.get(b).copied().unwrap_or(profile.keycode(**b)) == key_code) else { return };
+    drop(bindings);
+    with_pad(device, |pad| button.set(&mut pad.state, pressed));
 }
 
 /// Called from lib.rs when stick/trigger motion is received (future)
 pub fn handle_axis(left_x: f32, left_y: f32, right_x: f32, right_y: f32, l2: f32, r2: f32) {
-    let mut state = GAMEPAD_STATE.lock().unwrap();
-    state.left_stick_x = left_x;
-    state.left_stick_y = left_y;
-    state.right_stick_x = right_x;
-    state.right_stick_y = right_y;
-    state.l2_trigger = l2;
-    state.r2_trigger = r2;
+    if evdev_reads(UNKNOWN_DEVICE) {
+        return;
+    }
+    with_pad(UNKNOWN_DEVICE, |pad| {
+        let state = &mut pad.state;
+        state.left_stick_x = tuned(false, false, left_x);
+        state.left_stick_y = tuned(false, true, left_y);
+        state.right_stick_x = tuned(true, false, right_x);
+        state.right_stick_y = tuned(true, true, right_y);
+        state.l2_trigger = l2;
+        state.r2_trigger = r2;
+    });
 }
 
-/// Get raw gamepad state
+/// Raw MotionEvent axes from a controller, read with its profile's layout.
+fn handle_device_axes(device: i32, values: &[f32; AXIS_COUNT]) {
+    if evdev_reads(device) {
+        return;
+    }
+    with_pad(device, |pad| {
+        let ((right_x, right_y), l2, r2) = pad.profile.axes();
+        let trigger = |candidates: [usize; 2]| {
+            candidates.iter().map(|&a| values[a]).find(|v| *v != 0.0).unwrap_or(0.0)
+        };
+        let state = &mut pad.state;
+        state.left_stick_x = tuned(false, false, values[axes::X]);
+        state.left_stick_y = tuned(false, true, values[axes::Y]);
+        state.right_stick_x = tuned(true, false, values[right_x]);
+        state.right_stick_y = tuned(true, true, values[right_y]);
+        state.l2_trigger = trigger(l2);
+        state.r2_trigger = trigger(r2);
+    });
+}
+
+/// Get raw gamepad state (of the first controller)
 pub fn get_state() -> GamepadState {
-    GAMEPAD_STATE.lock().unwrap().clone()
+    PADS.lock().ok().and_then(|pads| pads.first().map(|p| p.state.clone())).unwrap_or_default()
 }
 
 /// Get high-level actions (one-shot, fires on button DOWN edge)
 /// Call this once per frame to get triggered actions
 pub fn poll_actions() -> GamepadActions {
-    let current = GAMEPAD_STATE.lock().unwrap().clone();
-    let mut prev = PREV_STATE.lock().unwrap();
+    let now = Instant::now();
+    let remote_role = REMOTE_ROLE.load(Ordering::Relaxed);
+    let mut pads = PADS.lock().unwrap();
+    let mut actions = GamepadActions::default();
+    for (i, pad) in pads.iter_mut().enumerate() {
+        actions.merge(&pad.actions(now), i > 0 && remote_role);
+    }
+    actions
+}
+
+impl GamepadActions {
+    /// Whether any button did something this frame (sticks and triggers aside).
+    pub fn any_press(&self) -> bool {
+        self.play_pause || self.stop || self.seek_back || self.seek_forward || self.prev_track || self.next_track
+            || self.toggle_ui || self.confirm || self.confirm_tap || self.confirm_hold || self.back || self.close_all
+            || self.reset_view || self.yaw_left || self.yaw_right || self.toggle_vr_mode
+            || self.open_settings || self.open_file_picker || self.multi_select || self.exit_app
+            || self.nav_up || self.nav_down || self.nav_left || self.nav_right || !self.chords.is_empty()
+    }
+
+    /// Fold another controller's actions in; `media_only` keeps just playback.
+    fn merge(&mut self, other: &GamepadActions, media_only: bool) {
+        self.play_pause |= other.play_pause;
+        self.stop |= other.stop;
+        self.seek_back |= other.seek_back;
+        self.seek_forward |= other.seek_forward;
+        self.prev_track |= other.prev_track;
+        self.next_track |= other.next_track;
+        if media_only {
+            return;
+        }
+        self.chords.extend_from_slice(&other.chords);
+        self.toggle_ui |= other.toggle_ui;
+        self.confirm |= other.confirm;
+        self.confirm_tap |= other.confirm_tap;
+        self.confirm_hold |= other.confirm_hold;
+        self.back |= other.back;
+        self.close_all |= other.close_all;
+        self.reset_view |= other.reset_view;
+        self.yaw_left |= other.yaw_left;
+        self.yaw_right |= other.yaw_right;
+        self.toggle_vr_mode |= other.toggle_vr_mode;
+        self.open_settings |= other.open_settings;
+        self.open_file_picker |= other.open_file_picker;
+        self.multi_select |= other.multi_select;
+        self.exit_app |= other.exit_app;
+        self.zoom_in |= other.zoom_in;
+        self.zoom_out |= other.zoom_out;
+        self.nav_up |= other.nav_up;
+        self.nav_down |= other.nav_down;
+        self.nav_left |= other.nav_left;
+        self.nav_right |= other.nav_right;
+        self.nav_repeated |= other.nav_repeated;
+        // Analog: whichever is pushed further
+        let further = |a: f32, b: f32| if b.abs() > a.abs() { b } else { a };
+        self.l2_trigger = further(self.l2_trigger, other.l2_trigger);
+        self.r2_trigger = further(self.r2_trigger, other.r2_trigger);
+        self.left_stick_x = further(self.left_stick_x, other.left_stick_x);
+        self.left_stick_y = further(self.left_stick_y, other.left_stick_y);
+        self.right_stick_x = further(self.right_stick_x, other.right_stick_x);
+        self.right_stick_y = further(self.right_stick_y, other.right_stick_y);
+    }
+}
+
+impl Pad {
+    /// This frame's actions from this controller alone.
+    fn actions(&mut self, now: Instant) -> GamepadActions {
+        let current = self.state.clone();
+        let prev = &self.prev;
+
+        // Taps fire on release, holds once while still down
+        let mut events = HashMap::new();
+        for button in BUTTONS {
+            let press = self.presses.entry(button).or_default();
+            events.insert(button, press.update(button.is_down(&current), button.is_down(prev), now));
+        }
+        // Chords: both buttons down, the second just now. The press then belongs
+        // to the chord, so neither button's own tap / hold fires for it.
+        let mut chords = Vec::new();
+        for chord in actions::CHORDS {
+            let [a, b] = chord.buttons;
+            if a.is_down(&current) && b.is_down(&current) && !(a.is_down(prev) && b.is_down(prev)) {
+                chords.push(chord.action);
+                for button in chord.buttons {
+                    self.presses.entry(button).or_default().spent = true;
+                    events.insert(button, PressEvents::default());
+                }
+            }
+        }
+        // A plain press fires on the way down, unless the button is in a chord:
+        // then it waits for the tap
+        let pressed = |button: Button| if actions::chorded(button) {
+            events[&button].tap
+        } else {
+            button.is_down(&current) && !button.is_down(prev)
+        };
+        let (l1, r1) = (events[&Button::L1], events[&Button::R1]);
+        let (cross, circle, options) = (events[&Button::Cross], events[&Button::Circle], events[&Button::Options]);
+        let (square, create) = (events[&Button::Square], events[&Button::Create]);
     
-    // Detect rising edges (button just pressed)
-    let actions = GamepadActions {
-        // Media
-        play_pause: current.btn_south && !prev.btn_south,      // X
-        seek_back: current.btn_l1 && !prev.btn_l1,             // L1
-        seek_forward: current.btn_r1 && !prev.btn_r1,          // R1
+        // D-pad: press edges plus auto-repeat while held
+        let (nav_up, nav_down, nav_left, nav_right, nav_repeated) = {
+            let repeat = &mut self.nav_repeat_at;
+            let (up, up_r) = nav_repeat(&mut repeat[0], current.btn_dpad_up, prev.btn_dpad_up, now);
+            let (down, down_r) = nav_repeat(&mut repeat[1], current.btn_dpad_down, prev.btn_dpad_down, now);
+            let (left, left_r) = nav_repeat(&mut repeat[2], current.btn_dpad_left, prev.btn_dpad_left, now);
+            let (right, right_r) = nav_repeat(&mut repeat[3], current.btn_dpad_right, prev.btn_dpad_right, now);
+            (up, down, left, right, up_r || down_r || left_r || right_r)
+        };
+        // □ held turns D-pad left/right into yaw trim instead of navigation
+        let trim = current.btn_west;
+
+        // Detect rising edges (button just pressed)
+        let actions = GamepadActions {
+            // Media
+            play_pause: cross.tap,                                  // X tap
+            stop: cross.long,                                       // X hold
+            seek_back: l1.tap,                                      // L1 tap
+            seek_forward: r1.tap,                                   // R1 tap
+            prev_track: l1.long,                                    // L1 hold
+            next_track: r1.long,                                    // R1 hold
         
-        // UI
-        toggle_ui: current.btn_north && !prev.btn_north,       // △
-        confirm: current.btn_west && !prev.btn_west,           // □
-        back: current.btn_east && !prev.btn_east,              // ○
+            // UI
+            toggle_ui: pressed(Button::Triangle),                   // △
+            confirm: pressed(Button::Square),                       // □
+            confirm_tap: square.tap,                                // □ tap
+            confirm_hold: square.long,                              // □ hold
+            back: pressed(Button::Circle) && !circle.double,        // ○
+            close_all: circle.double,                               // ○ ○
         
-        // VR
-        reset_view: current.btn_thumbl && !prev.btn_thumbl,    // L3
-        toggle_vr_mode: current.btn_thumbr && !prev.btn_thumbr, // R3
+            // VR
+            reset_view: pressed(Button::L3),                        // L3
+            yaw_left: trim && nav_left,                             // □ + D-pad left
+            yaw_right: trim && nav_right,                           // □ + D-pad right
+            toggle_vr_mode: pressed(Button::R3),                    // R3
         
-        // App
-        open_settings: current.btn_start && !prev.btn_start,   // Options
-        open_file_picker: current.btn_select && !prev.btn_select, // Create
-        exit_app: current.btn_mode && !prev.btn_mode,          // PS
+            // App
+            open_settings: options.tap,                             // Options tap
+            open_file_picker: pressed(Button::Create),              // Create
+            multi_select: create.long,                              // Create hold
+            exit_app: pressed(Button::Ps) || options.long,          // PS, Options hold
         
-        // Zoom (continuous while held)
-        zoom_in: current.btn_r2,
-        zoom_out: current.btn_l2,
-        l2_trigger: current.l2_trigger,
-        r2_trigger: current.r2_trigger,
+            // Zoom (continuous while held)
+            zoom_in: current.btn_r2,
+            zoom_out: current.btn_l2,
+            l2_trigger: current.l2_trigger,
+            r2_trigger: current.r2_trigger,
         
-        // Navigation
-        nav_up: current.btn_dpad_up && !prev.btn_dpad_up,
-        nav_down: current.btn_dpad_down && !prev.btn_dpad_down,
-        nav_left: current.btn_dpad_left && !prev.btn_dpad_left,
-        nav_right: current.btn_dpad_right && !prev.btn_dpad_right,
-        left_stick_x: current.left_stick_x,
-        left_stick_y: current.left_stick_y,
-        right_stick_x: current.right_stick_x,
-        right_stick_y: current.right_stick_y,
-    };
+            // Navigation
+            nav_up,
+            nav_down,
+            nav_left: nav_left && !trim,
+            nav_right: nav_right && !trim,
+            nav_repeated,
+            left_stick_x: current.left_stick_x,
+            left_stick_y: current.left_stick_y,
+            right_stick_x: current.right_stick_x,
+            right_stick_y: current.right_stick_y,
+            chords,
+        };
     
-    // Update previous state
-    *prev = current;
+        // Update previous state
+        self.prev = current;
     
-    actions
+        actions
+    }
+}
+
+/// Side of the square UI canvas the pointer moves over (the egui texture).
+pub const UI_CANVAS: f32 = 2048.0;
+/// Pointer speed at full stick tilt, canvas px/s.
+const POINTER_SPEED: f32 = 1400.0;
+/// The pointer hides after this long without stick movement or clicks.
+const POINTER_IDLE: Duration = Duration::from_secs(3);
+
+/// Left-stick virtual mouse for the UI. While it's shown, the stick moves it and
+/// X clicks, by injecting pointer events into egui's input, so any widget (sliders
+/// included) works without bespoke controller handling. Motion aim moves the
+/// same pointer.
+pub struct StickPointer {
+    pub pos: egui::Pos2,
+    /// Shown until then; pushed back by every use.
+    active_until: Option<Instant>,
+    /// X is held as a pointer press.
+    pressed: bool,
+    /// Moved by motion aim this frame (drawn as a laser dot).
+    aiming: bool,
 }
 
-/// Dummy struct for API compatibility
+impl Default for StickPointer {
+    fn default() -> Self {
+        Self { pos: egui::pos2(UI_CANVAS / 2.0, UI_CANVAS / 2.0), active_until: None, pressed: false, aiming: false }
+    }
+}
+
+impl StickPointer {
+    /// Advance one frame from the pad state (sticks already tuned), appending
+    /// egui events. The stick only wakes the pointer while `enabled` (a panel is
+    /// open and the setting is on); `aim` is motion-aim movement in canvas px, if aiming.
+    pub fn update(&mut self, pad: &GamepadState, dt: f32, enabled: bool, aim: Option<egui::Vec2>, events: &mut Vec<egui::Event>) {
+        let now = Instant::now();
+        let stick = egui::vec2(pad.left_stick_x, pad.left_stick_y);
+        let mut moved = if enabled { stick * POINTER_SPEED * dt } else { egui::Vec2::ZERO };
+        self.aiming = aim.is_some();
+        if let Some(aim) = aim {
+            moved += aim;
+            // Holding L2 shows the pointer even while the controller is still
+            self.active_until = Some(now + POINTER_IDLE);
+        }
+        if moved != egui::Vec2::ZERO {
+            self.pos = (self.pos + moved).clamp(egui::Pos2::ZERO, egui::pos2(UI_CANVAS, UI_CANVAS));
+            self.active_until = Some(now + POINTER_IDLE);
+            events.push(egui::Event::PointerMoved(self.pos));
+        }
+        let active = self.is_active();
+        // X: press and release; a release is always delivered so nothing stays held
+        if (pad.btn_south && active && !self.pressed) || (!pad.btn_south && self.pressed) {
+            self.pressed = pad.btn_south;
+            self.active_until = Some(now + POINTER_IDLE);
+            events.push(egui::Event::PointerButton {
+                pos: self.pos,
+                button: egui::PointerButton::Primary,
+                pressed: self.pressed,
+                modifiers: egui::Modifiers::default(),
+            });
+        }
+        if self.active_until.is_some_and(|t| now >= t) || (!enabled && !self.pressed && active) {
+            self.active_until = None;
+            events.push(egui::Event::PointerGone);
+        }
+    }
+
+    /// Move by `delta` canvas px (the mouse), waking the pointer.
+    pub fn nudge(&mut self, delta: egui::Vec2, events: &mut Vec<egui::Event>) {
+        if delta == egui::Vec2::ZERO {
+            return;
+        }
+        self.pos = (self.pos + delta).clamp(egui::Pos2::ZERO, egui::pos2(UI_CANVAS, UI_CANVAS));
+        self.active_until = Some(Instant::now() + POINTER_IDLE);
+        events.push(egui::Event::PointerMoved(self.pos));
+    }
+
+    /// Whether the pointer is shown (it owns the left stick and X meanwhile).
+    pub fn is_active(&self) -> bool {
+        self.active_until.is_some_and(|t| Instant::now() < t)
+    }
+
+    /// Draw the pointer above every panel.
+    pub fn paint(&self, ctx: &egui::Context) {
+        if !self.is_active() {
+            return;
+        }
+        let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Tooltip, egui::Id::new("stick_pointer")));
+        let fill = if self.aiming { egui::Color32::from_rgb(255, 60, 60) } else { egui::Color32::from_white_alpha(200) };
+        painter.circle(self.pos, if self.pressed { 10.0 } else { 13.0 },
+            fill, egui::Stroke::new(3.0, egui::Color32::from_black_alpha(160)));
+    }
+}
+
+/// Evdev controllers get device ids of their own, counting down from here
+/// (Android's are positive).
+const EVDEV_FIRST_ID: i32 = -1000;
+static EVDEV_NEXT_ID: AtomicI32 = AtomicI32::new(EVDEV_FIRST_ID);
+
+/// A /dev/input node being read, and who it says it is.
+struct EvdevPad {
+    path: PathBuf,
+    id: i32,
+    name: String,
+    vendor: i32,
+    product: i32,
+}
+
+impl EvdevPad {
+    /// Whether Android's view of a controller is this one: same USB ids where
+    /// it has them, else the same name.
+    fn is(&self, name: &str, vendor: i32, product: i32) -> bool {
+        if self.vendor != 0 || self.product != 0 {
+            self.vendor == vendor && self.product == product
+        } else {
+            self.name == name
+        }
+    }
+}
+
+lazy_static! {
+    static ref EVDEV_OPEN: Mutex<Vec<EvdevPad>> = Mutex::new(Vec::new());
+    // (Android device id, evdev id) of controllers seen both ways; their Java
+    // events are dropped so input doesn't arrive twice
+    static ref EVDEV_SHADOWED: Mutex<Vec<(i32, i32)>> = Mutex::new(Vec::new());
+}
+
+/// Whether `device`'s Java / winit events duplicate an evdev reader. Winit key
+/// events carry no device and land on the first controller.
+fn evdev_reads(device: i32) -> bool {
+    if device == UNKNOWN_DEVICE {
+        return PADS.lock().unwrap().first().is_some_and(|p| p.device <= EVDEV_FIRST_ID);
+    }
+    EVDEV_SHADOWED.lock().unwrap().iter().any(|&(android, _)| android == device)
+}
+
+/// Note Android's `device` as a duplicate if evdev reads the same controller.
+fn shadow_if_evdev(device: i32, name: &str, vendor: i32, product: i32) -> bool {
+    let open = EVDEV_OPEN.lock().unwrap();
+    let Some(pad) = open.iter().find(|p| p.is(name, vendor, product)) else { return false };
+    info!("GamepadReader: controller {} is read directly as {}, ignoring its Java events", device, pad.id);
+    let mut shadowed = EVDEV_SHADOWED.lock().unwrap();
+    if !shadowed.iter().any(|&(android, _)| android == device) {
+        shadowed.push((device, pad.id));
+    }
+    true
+}
+
+/// Gamepad input straight from evdev: a blocking reader thread per controller
+/// skips the Java → JNI hop and sees every axis. Without permission to read the
+/// device nodes (the usual case) input keeps coming the winit / JNI way.
 pub struct GamepadReader;
 
 impl GamepadReader {
     pub fn new() -> Self {
-        info!("GamepadReader: Modular winit-based input initialized");
+        if evdev_scan() == 0 {
+            info!("GamepadReader: /dev/input not readable, using winit / JNI input");
+        }
+        for conflict in actions::conflicts() {
+            warn!("Controller shortcut conflict: {}", conflict);
+        }
         Self
     }
     
@@ -218,6 +1335,130 @@ impl GamepadReader {
     }
 }
 
+/// Open the gamepads under /dev/input not already being read; returns how many.
+fn evdev_scan() -> usize {
+    let mut open = EVDEV_OPEN.lock().unwrap();
+    let mut started = 0;
+    for (path, device) in evdev::enumerate() {
+        let is_pad = device.supported_keys()
+            .is_some_and(|k| k.contains(evdev::Key::BTN_SOUTH) || k.contains(evdev::Key::BTN_TRIGGER));
+        if open.iter().any(|p| p.path == path) || !is_pad {
+            continue;
+        }
+        let id = EVDEV_NEXT_ID.fetch_sub(1, Ordering::Relaxed);
+        info!("GamepadReader: reading {} ({}) directly as controller {}",
+            path.display(), device.name().unwrap_or("?"), id);
+        let input = device.input_id();
+        open.push(EvdevPad {
+            path,
+            id,
+            name: device.name().unwrap_or("").to_string(),
+            vendor: input.vendor() as i32,
+            product: input.product() as i32,
+        });
+        started += 1;
+        let spawned = std::thread::Builder::new().name("evdev-pad".into()).spawn(move || {
+            evdev_read(id, device);
+            EVDEV_OPEN.lock().unwrap().retain(|p| p.id != id);
+            // Its Java twin (if still there) is back on the Java path
+            EVDEV_SHADOWED.lock().unwrap().retain(|&(_, evdev)| evdev != id);
+            remove_device(id);
+        });
+        if let Err(e) = spawned {
+            error!("GamepadReader: thread spawn failed: {}", e);
+        }
+    }
+    started
+}
+
+/// Linux gamepad key (positional BTN_SOUTH… naming) → the Android keycode the
+/// bindings use, as in Android's Generic.kl.
+fn evdev_keycode(key: evdev::Key) -> Option<i32> {
+    use evdev::Key;
+    Some(match key {
+        Key::BTN_SOUTH => keycodes::BUTTON_A,
+        Key::BTN_EAST => keycodes::BUTTON_B,
+        Key::BTN_NORTH => keycodes::BUTTON_Y,
+        Key::BTN_WEST => keycodes::BUTTON_X,
+        Key::BTN_TL => keycodes::BUTTON_L1,
+        Key::BTN_TR => keycodes::BUTTON_R1,
+        Key::BTN_TL2 => keycodes::BUTTON_L2,
+        Key::BTN_TR2 => keycodes::BUTTON_R2,
+        Key::BTN_THUMBL => keycodes::BUTTON_THUMBL,
+        Key::BTN_THUMBR => keycodes::BUTTON_THUMBR,
+        Key::BTN_START => keycodes::BUTTON_START,
+        Key::BTN_SELECT => keycodes::BUTTON_SELECT,
+        Key::BTN_MODE => keycodes::BUTTON_MODE,
+        Key::BTN_DPAD_UP => keycodes::DPAD_UP,
+        Key::BTN_DPAD_DOWN => keycodes::DPAD_DOWN,
+        Key::BTN_DPAD_LEFT => keycodes::DPAD_LEFT,
+        Key::BTN_DPAD_RIGHT => keycodes::DPAD_RIGHT,
+        // HID pads without a gamepad mapping: BTN_TRIGGER, BTN_THUMB, …
+        _ if (Key::BTN_TRIGGER.code()..Key::BTN_TRIGGER.code() + 16).contains(&key.code()) =>
+            keycodes::BUTTON_1 + (key.code() - Key::BTN_TRIGGER.code()) as i32,
+        _ => return None,
+    })
+}
+
+/// Read one controller until it goes away (or the read fails).
+fn evdev_read(id: i32, mut device: evdev::Device) {
+    use evdev::{AbsoluteAxisType as Abs, InputEventKind};
+    // Axis ranges, to scale sticks to -1…1 and triggers to 0…1
+    let ranges: Vec<(i32, i32)> = match device.get_abs_state() {
+        Ok(abs) => abs.iter().map(|a| (a.minimum, a.maximum)).collect(),
+        Err(_) => Vec::new(),
+    };
+    let scale = |axis: Abs, value: i32| -> f32 {
+        let (min, max) = ranges.get(axis.0 as usize).copied().unwrap_or((-32768, 32767));
+        if max <= min { return 0.0; }
+        (value - min) as f32 / (max - min) as f32
+    };
+    let input = device.input_id();
+    set_device_info(id, device.name().unwrap_or(""), input.vendor() as i32, input.product() as i32);
+    let mut hat = (0.0, 0.0);
+    loop {
+        let events = match device.fetch_events() {
+            Ok(events) => events,
+            Err(e) => {
+                info!("GamepadReader: controller {} gone ({})", id, e);
+                return;
+            }
+        };
+        for event in events {
+            match event.kind() {
+                InputEventKind::Key(key) => {
+                    if let Some(code) = evdev_keycode(key) {
+                        press_button(id, code, event.value() != 0);
+                    }
+                }
+                InputEventKind::AbsAxis(axis) => {
+                    let value = event.value();
+                    match axis {
+                        Abs::ABS_HAT0X | Abs::ABS_HAT0Y => {
+                            if axis == Abs::ABS_HAT0X { hat.0 = value.signum() as f32; } else { hat.1 = value.signum() as f32; }
+                            set_hat(id, hat.0, hat.1);
+                        }
+                        _ => with_pad(id, |pad| {
+                            let state = &mut pad.state;
+                            let stick = scale(axis, value) * 2.0 - 1.0;
+                            match axis {
+                                Abs::ABS_X => state.left_stick_x = tuned(false, false, stick),
+                                Abs::ABS_Y => state.left_stick_y = tuned(false, true, stick),
+                                Abs::ABS_RX => state.right_stick_x = tuned(true, false, stick),
+                                Abs::ABS_RY => state.right_stick_y = tuned(true, true, stick),
+                                Abs::ABS_Z => state.l2_trigger = scale(axis, value),
+                                Abs::ABS_RZ => state.r2_trigger = scale(axis, value),
+                                _ => {}
+                            }
+                        }),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
 // HAT axis state for D-pad (received from JNI)
 lazy_static! {
     static ref HAT_STATE: Mutex<(f32, f32)> = Mutex::new((0.0, 0.0)); // (hat_x, hat_y)
@@ -233,34 +1474,69 @@ pub fn get_hat_state() -> (f32, f32) {
 pub unsafe extern "C" fn Java_com_vrapp_core_MainActivity_onGamepadButton(
     _env: jni::JNIEnv,
     _class: jni::objects::JObject,
+    device_id: jni::sys::jint,
     button_code: jni::sys::jint,
     pressed: jni::sys::jboolean,
 ) {
     let pressed = pressed != 0;
-    handle_button(button_code, pressed);
+    handle_device_button(device_id, button_code, pressed);
+}
+
+// JNI Export: A controller was connected
+#[no_mangle]
+pub unsafe extern "C" fn Java_com_vrapp_core_MainActivity_onGamepadAdded(
+    _env: jni::JNIEnv,
+    _class: jni::objects::JObject,
+    device_id: jni::sys::jint,
+) {
+    // Read directly if we can; it then turns up as a controller of its own
+    evdev_scan();
+    if !evdev_reads(device_id) {
+        add_device(device_id);
+    }
+}
+
+// JNI Export: A controller was disconnected
+#[no_mangle]
+pub unsafe extern "C" fn Java_com_vrapp_core_MainActivity_onGamepadRemoved(
+    _env: jni::JNIEnv,
+    _class: jni::objects::JObject,
+    device_id: jni::sys::jint,
+) {
+    remove_device(device_id);
 }
 
 // JNI Export: Receive gamepad axis from Java (includes HAT_X/HAT_Y for D-pad)
 #[no_mangle]
 pub unsafe extern "C" fn Java_com_vrapp_core_MainActivity_onGamepadAxis(
-    _env: jni::JNIEnv,
+    env: jni::JNIEnv,
     _class: jni::objects::JObject,
-    left_x: jni::sys::jfloat,
-    left_y: jni::sys::jfloat,
-    right_x: jni::sys::jfloat,
-    right_y: jni::sys::jfloat,
-    l2: jni::sys::jfloat,
-    r2: jni::sys::jfloat,
+    device_id: jni::sys::jint,
+    axes: jni::sys::jfloatArray,
 ) {
-    // Store axis state if needed
-    if let Ok(mut state) = GAMEPAD_STATE.lock() {
-        state.left_stick_x = left_x;
-        state.left_stick_y = left_y;
-        state.right_stick_x = right_x;
-        state.right_stick_y = right_y;
-        state.l2_trigger = l2;
-        state.r2_trigger = r2;
+    let axes = jni::objects::JFloatArray::from_raw(axes);
+    let mut values = [0.0; AXIS_COUNT];
+    if env.get_float_array_region(&axes, 0, &mut values).is_ok() {
+        handle_device_axes(device_id, &values);
+    }
+}
+
+// JNI Export: Name and USB ids of a controller, sent before its first event
+#[no_mangle]
+pub unsafe extern "C" fn Java_com_vrapp_core_MainActivity_onGamepadInfo(
+    mut env: jni::JNIEnv,
+    _class: jni::objects::JObject,
+    device_id: jni::sys::jint,
+    name: jni::objects::JString,
+    vendor: jni::sys::jint,
+    product: jni::sys::jint,
+) {
+    let name: String = env.get_string(&name).map(Into::into).unwrap_or_default();
+    evdev_scan();
+    if shadow_if_evdev(device_id, &name, vendor, product) {
+        return;
     }
+    set_device_info(device_id, &name, vendor, product);
 }
 
 // JNI Export: Receive HAT axis (D-pad) from Java - separate callback for clarity
@@ -268,20 +1544,29 @@ pub unsafe extern "C" fn Java_com_vrapp_core_MainActivity_onGamepadAxis(
 pub unsafe extern "C" fn Java_com_vrapp_core_MainActivity_onDpadAxis(
     _env: jni::JNIEnv,
     _class: jni::objects::JObject,
+    device_id: jni::sys::jint,
     hat_x: jni::sys::jfloat,
     hat_y: jni::sys::jfloat,
 ) {
+    if evdev_reads(device_id) {
+        return;
+    }
+    set_hat(device_id, hat_x, hat_y);
+    info!("JNI: D-pad HAT x={} y={}", hat_x, hat_y);
+}
+
+fn set_hat(device: i32, hat_x: f32, hat_y: f32) {
     if let Ok(mut hat) = HAT_STATE.lock() {
         *hat = (hat_x, hat_y);
     }
     // The D-pad arrives as a HAT axis (not key events), so translate it into the
     // d-pad button booleans — otherwise the nav_up/down/left/right actions (which
     // edge-detect on those booleans) never fire for the D-pad.
-    if let Ok(mut state) = GAMEPAD_STATE.lock() {
+    with_pad(device, |pad| {
+        let state = &mut pad.state;
         state.btn_dpad_left  = hat_x < -0.5;
         state.btn_dpad_right = hat_x >  0.5;
         state.btn_dpad_up    = hat_y < -0.5;
         state.btn_dpad_down  = hat_y >  0.5;
-    }
-    info!("JNI: D-pad HAT x={} y={}", hat_x, hat_y);
+    });
 }
@@ -0,0 +1,100 @@
+//! Gaze pointer with dwell selection
+//!
+//! Without a controller the head is the pointer: the view direction is cast onto
+//! the curved UI panel (same dome as ui_panel.wgsl) and holding the gaze still on
+//! a control for the dwell time clicks it. A ring around the cursor shrinks as
+//! the dwell runs out. After a click the gaze has to move off before it can
+//! click again.
+
+use glam::{Quat, Vec3};
+use std::time::Instant;
+
+/// Side of the square UI canvas (the egui texture).
+const UI_CANVAS: f32 = crate::gamepad::UI_CANVAS;
+/// Panel geometry, as in ui_panel.wgsl.
+const PANEL_RADIUS: f32 = 2.0;
+const PANEL_SIZE: f32 = 1.7;
+/// Gaze wandering less than this (canvas px) still counts as holding still.
+const DWELL_RADIUS: f32 = 28.0;
+/// Ring size at the start of a dwell.
+const RING_RADIUS: f32 = 30.0;
+
+/// Where on the UI canvas `orientation` looks, if it hits the panel.
+pub fn canvas_point(orientation: Quat, content_scale: f32) -> Option<egui::Pos2> {
+    let dir = orientation * Vec3::NEG_Z;
+    let theta = dir.x.atan2(-dir.z);
+    let phi = dir.y.clamp(-1.0, 1.0).asin();
+    let arc = PANEL_SIZE * content_scale.clamp(0.8, 2.6) / PANEL_RADIUS;
+    let u = theta / arc + 0.5;
+    let v = 0.5 - phi / arc;
+    ((0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v))
+        .then(|| egui::pos2(u * UI_CANVAS, v * UI_CANVAS))
+}
+
+#[derive(Default)]
+pub struct GazePointer {
+    /// Where the gaze is on the canvas (None off the panel or while disabled)
+    pos: Option<egui::Pos2>,
+    /// Anchor and start of the current dwell
+    dwell: Option<(egui::Pos2, Instant)>,
+    /// Clicked; waiting for the gaze to move off
+    spent: bool,
+    /// How far the current dwell is (0…1), for the ring
+    progress: f32,
+}
+
+impl GazePointer {
+    /// Advance one frame, appending egui events. `dwell_s` is the hold time;
+    /// dwelling only counts while `over_ui` (egui had the pointer over a panel).
+    pub fn update(&mut self, orientation: Quat, content_scale: f32, dwell_s: f32, enabled: bool, over_ui: bool, events: &mut Vec<egui::Event>) {
+        let pos = if enabled { canvas_point(orientation, content_scale) } else { None };
+        match pos {
+            Some(pos) => events.push(egui::Event::PointerMoved(pos)),
+            None if self.pos.is_some() => events.push(egui::Event::PointerGone),
+            None => {}
+        }
+        self.pos = pos;
+        self.progress = 0.0;
+        let Some(pos) = pos.filter(|_| over_ui) else {
+            self.dwell = None;
+            self.spent = false;
+            return;
+        };
+        let now = Instant::now();
+        match self.dwell {
+            Some((anchor, _)) if anchor.distance(pos) > DWELL_RADIUS => {
+                self.dwell = Some((pos, now));
+                self.spent = false;
+            }
+            Some((_, start)) if !self.spent => {
+                self.progress = (start.elapsed().as_secs_f32() / dwell_s.max(0.1)).min(1.0);
+                if self.progress >= 1.0 {
+                    for pressed in [true, false] {
+                        events.push(egui::Event::PointerButton {
+                            pos,
+                            button: egui::PointerButton::Primary,
+                            pressed,
+                            modifiers: egui::Modifiers::default(),
+                        });
+                    }
+                    self.spent = true;
+                    self.progress = 0.0;
+                }
+            }
+            Some(_) => {}
+            None => self.dwell = Some((pos, now)),
+        }
+    }
+
+    /// Draw the cursor and dwell ring above every panel.
+    pub fn paint(&self, ctx: &egui::Context) {
+        let Some(pos) = self.pos else { return };
+        let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Tooltip, egui::Id::new("gaze_pointer")));
+        painter.circle(pos, 6.0, egui::Color32::from_white_alpha(220), egui::Stroke::new(2.0, egui::Color32::from_black_alpha(160)));
+        if self.progress > 0.0 {
+            let radius = 8.0 + (RING_RADIUS - 8.0) * (1.0 - self.progress);
+            painter.circle_stroke(pos, radius, egui::Stroke::new(4.0, egui::Color32::from_rgb(90, 160, 255)));
+            ctx.request_repaint();
+        }
+    }
+}
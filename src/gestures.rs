@@ -0,0 +1,229 @@
+//! Touch gesture recognizer
+//!
+//! Turns raw winit touches into gestures for lib.rs:
+//!
+//! | Gesture                 | Meaning                                          |
+//! |-------------------------|--------------------------------------------------|
+//! | One-finger drag         | Look around / pan zoomed content (as before)     |
+//! | Horizontal swipe        | Seek, proportional to the distance, with preview |
+//! | Tap                     | Show / hide the 2D controls (upper part)         |
+//! | Double tap              | Play / pause                                     |
+//! | Two-finger tap          | Recenter                                         |
+//! | Pinch                   | Zoom                                             |
+//! | Two-finger twist        | Level the horizon (roll the view)                |
+//!
+//! A swipe seeks when it starts in the bottom band of the screen, or anywhere
+//! once a drag has nothing else to do (VR mode with head tracking). Its first
+//! `SLOP_PX` decide between seek (mostly sideways) and drag. A single tap waits
+//! out the double-tap window before it fires, so `poll` has to run every frame.
+//! A twist only counts past `TWIST_START`, so pinching doesn't tilt the view.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use winit::dpi::PhysicalSize;
+use winit::event::{Touch, TouchPhase};
+
+/// Movement that still counts as a tap, px.
+const SLOP_PX: f64 = 24.0;
+/// Longest press that is still a tap.
+const TAP_TIME: Duration = Duration::from_millis(300);
+/// A second tap within this of the first is a double tap.
+const DOUBLE_TAP: Duration = Duration::from_millis(300);
+/// Bottom share of the screen where a swipe always seeks.
+const SCRUB_BAND: f64 = 0.3;
+/// A swipe across the whole width seeks this far, µs.
+const SWIPE_FULL_WIDTH_US: f64 = 120_000_000.0;
+/// Rotation between two fingers before it's a twist, radians.
+const TWIST_START: f64 = 6.0 * std::f64::consts::PI / 180.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// One finger moved by (dx, dy) px
+    Drag(f64, f64),
+    /// The last finger lifted after a drag
+    DragEnd,
+    /// Two fingers down; pinches are relative to now
+    PinchStart,
+    /// Finger spread relative to the pinch start
+    Pinch(f32),
+    /// Two fingers turned this far (radians, clockwise on screen) since the twist began
+    Twist(f32),
+    /// A single tap at (x, y), after the double-tap window
+    Tap(f64, f64),
+    DoubleTap,
+    TwoFingerTap,
+    /// A finished swipe, µs
+    Seek(i64),
+}
+
+/// What a one-finger touch turned out to be.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stroke {
+    /// Within the slop so far
+    Undecided,
+    Drag,
+    /// Seeking; horizontal travel so far, px
+    Swipe(f64),
+}
+
+pub struct GestureRecognizer {
+    touches: HashMap<u64, (f64, f64)>,
+    /// When the first finger went down and where
+    down: Option<(Instant, (f64, f64))>,
+    /// Travel since the first finger went down, px
+    travel: f64,
+    stroke: Stroke,
+    /// The most fingers down at once in this touch sequence
+    max_fingers: usize,
+    initial_pinch_distance: Option<f64>,
+    /// Angle between the two fingers the twist is measured from, and whether
+    /// it has passed `TWIST_START`
+    twist_from: Option<(f64, bool)>,
+    /// A tap waiting to see if a second follows
+    pending_tap: Option<(Instant, (f64, f64))>,
+    width: f64,
+}
+
+impl GestureRecognizer {
+    pub fn new() -> Self {
+        Self {
+            touches: HashMap::new(),
+            down: None,
+            travel: 0.0,
+            stroke: Stroke::Undecided,
+            max_fingers: 0,
+            initial_pinch_distance: None,
+            twist_from: None,
+            pending_tap: None,
+            width: 1.0,
+        }
+    }
+
+    /// Feed one touch event. `scrub_anywhere`: a sideways swipe seeks wherever it
+    /// starts, because a drag would do nothing.
+    pub fn touch(&mut self, touch: &Touch, size: PhysicalSize<u32>, scrub_anywhere: bool) -> Vec<Gesture> {
+        let mut gestures = Vec::new();
+        let loc = (touch.location.x, touch.location.y);
+        self.width = size.width.max(1) as f64;
+        match touch.phase {
+            TouchPhase::Started => {
+                self.touches.insert(touch.id, loc);
+                if self.touches.len() == 1 {
+                    self.down = Some((Instant::now(), loc));
+                    self.travel = 0.0;
+                    self.max_fingers = 1;
+                    let in_band = loc.1 >= size.height as f64 * (1.0 - SCRUB_BAND);
+                    self.stroke = if in_band || scrub_anywhere { Stroke::Undecided } else { Stroke::Drag };
+                }
+                self.max_fingers = self.max_fingers.max(self.touches.len());
+                if self.touches.len() == 2 {
+                    self.initial_pinch_distance = Some(self.spread());
+                    self.twist_from = Some((self.angle(), false));
+                    gestures.push(Gesture::PinchStart);
+                }
+            }
+            TouchPhase::Moved => {
+                let Some(prev) = self.touches.insert(touch.id, loc) else { return gestures };
+                let (dx, dy) = (loc.0 - prev.0, loc.1 - prev.1);
+                self.travel += (dx * dx + dy * dy).sqrt();
+                if self.touches.len() == 1 && self.max_fingers == 1 {
+                    if self.stroke == Stroke::Undecided && self.travel >= SLOP_PX {
+                        let start = self.down.map_or(loc, |(_, start)| start);
+                        let (tx, ty) = (loc.0 - start.0, loc.1 - start.1);
+                        self.stroke = if tx.abs() > 2.0 * ty.abs() { Stroke::Swipe(0.0) } else { Stroke::Drag };
+                    }
+                    match &mut self.stroke {
+                        Stroke::Swipe(x) => *x += dx,
+                        Stroke::Drag => gestures.push(Gesture::Drag(dx, dy)),
+                        Stroke::Undecided => {}
+                    }
+                }
+                if let (2, Some(initial)) = (self.touches.len(), self.initial_pinch_distance) {
+                    gestures.push(Gesture::Pinch((self.spread() / initial.max(1.0)) as f32));
+                }
+                if let (2, Some((from, twisting))) = (self.touches.len(), self.twist_from) {
+                    let angle = self.angle();
+                    // Wrapped into -π…π
+                    let turned = (angle - from + std::f64::consts::PI).rem_euclid(std::f64::consts::TAU) - std::f64::consts::PI;
+                    if twisting {
+                        gestures.push(Gesture::Twist(turned as f32));
+                    } else if turned.abs() >= TWIST_START {
+                        // Measure from here so the view doesn't jump by the threshold
+                        self.twist_from = Some((angle, true));
+                    }
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.touches.remove(&touch.id);
+                if self.touches.len() < 2 {
+                    self.initial_pinch_distance = None;
+                    self.twist_from = None;
+                }
+                if !self.touches.is_empty() {
+                    return gestures;
+                }
+                let quick = self.down.is_some_and(|(at, _)| at.elapsed() <= TAP_TIME);
+                let tap = touch.phase == TouchPhase::Ended && quick && self.travel < SLOP_PX;
+                match (self.max_fingers, self.stroke) {
+                    (1, Stroke::Swipe(x)) => gestures.push(Gesture::Seek(self.swipe_us(x))),
+                    (1, _) if tap => match self.pending_tap.take() {
+                        Some((at, _)) if at.elapsed() <= DOUBLE_TAP => gestures.push(Gesture::DoubleTap),
+                        _ => self.pending_tap = Some((Instant::now(), loc)),
+                    },
+                    (2, _) if tap => gestures.push(Gesture::TwoFingerTap),
+                    _ => {}
+                }
+                gestures.push(Gesture::DragEnd);
+                self.down = None;
+                self.stroke = Stroke::Undecided;
+            }
+        }
+        gestures
+    }
+
+    /// A single tap whose double-tap window has run out; call every frame.
+    pub fn poll(&mut self) -> Option<Gesture> {
+        match self.pending_tap {
+            Some((at, (x, y))) if at.elapsed() > DOUBLE_TAP => {
+                self.pending_tap = None;
+                Some(Gesture::Tap(x, y))
+            }
+            _ => None,
+        }
+    }
+
+    /// Seek the swipe in progress would do, µs.
+    pub fn swipe_preview(&self) -> Option<i64> {
+        match self.stroke {
+            Stroke::Swipe(x) if !self.touches.is_empty() => Some(self.swipe_us(x)),
+            _ => None,
+        }
+    }
+
+    fn swipe_us(&self, x: f64) -> i64 {
+        (x / self.width * SWIPE_FULL_WIDTH_US) as i64
+    }
+
+    /// Vector from the first finger to the second, ordered by touch id so it
+    /// doesn't flip between events.
+    fn fingers(&self) -> (f64, f64) {
+        let mut touches: Vec<_> = self.touches.iter().collect();
+        touches.sort_by_key(|(id, _)| **id);
+        let (a, b) = (touches[0].1, touches[1].1);
+        (b.0 - a.0, b.1 - a.1)
+    }
+
+    /// Distance between the first two fingers.
+    fn spread(&self) -> f64 {
+        let (dx, dy) = self.fingers();
+        (dx * dx + dy * dy).sqrt()
+    }
+
+    /// Direction from the first finger to the second (screen y is down, so
+    /// positive turns are clockwise).
+    fn angle(&self) -> f64 {
+        let (dx, dy) = self.fingers();
+        dy.atan2(dx)
+    }
+}
@@ -0,0 +1,289 @@
+//! Browser frames on the GPU
+//!
+//! The browser engine renders each page into an `ImageReader` whose buffers the
+//! GPU can read (GeckoViewManager.java). Instead of copying every frame into a
+//! Java byte[] and uploading it, the reader lends its `HardwareBuffer` over JNI
+//! (webview.rs) and `Importer` wraps it here as a Vulkan image bound to the
+//! buffer's own memory (VK_ANDROID_external_memory_android_hardware_buffer).
+//! `Importer::copy` records the copy into the page's texture on the GPU; the
+//! pixels never pass through the CPU.
+//!
+//! The buffer belongs to the producer until it's handed over: the copy acquires
+//! it from `VK_QUEUE_FAMILY_FOREIGN_EXT` (in the general layout the producer
+//! leaves it in, so its contents are kept) and releases it back afterwards.
+//! Java waits on the image's fence before lending it (API 33+, where images have
+//! one), and only gets the buffer back once the copy has completed.
+//!
+//! The device has to be created with the extensions, so `open_device` opens it
+//! through wgpu-hal when the GPU has them. Without them the renderer takes the
+//! plain device and the browser keeps sending RGBA copies.
+
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+use ash::vk;
+use egui_wgpu::wgpu;
+use log::{info, warn};
+use wgpu::hal::api::Vulkan;
+
+/// Device extensions importing needs on top of Vulkan 1.1 (which has the rest).
+const EXTENSIONS: [&CStr; 2] = [
+    ash::android::external_memory_android_hardware_buffer::NAME,
+    ash::ext::queue_family_foreign::NAME,
+];
+
+/// A reference to an `AHardwareBuffer`, released when dropped.
+pub struct HardwareBuffer(NonNull<ndk_sys::AHardwareBuffer>);
+
+// The system reference counts the buffer; it may be released from any thread
+unsafe impl Send for HardwareBuffer {}
+unsafe impl Sync for HardwareBuffer {}
+
+impl HardwareBuffer {
+    /// A reference of our own to the buffer behind an `android.hardware.HardwareBuffer`,
+    /// so it outlives the Java object.
+    ///
+    /// # Safety
+    /// `env` must be the calling thread's JNIEnv and `buffer` a live HardwareBuffer.
+    pub unsafe fn from_java(env: *mut jni::sys::JNIEnv, buffer: jni::sys::jobject) -> Option<Self> {
+        let raw = NonNull::new(ndk_sys::AHardwareBuffer_fromHardwareBuffer(env.cast(), buffer.cast()))?;
+        ndk_sys::AHardwareBuffer_acquire(raw.as_ptr());
+        Some(HardwareBuffer(raw))
+    }
+
+    /// Width and height, in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        let mut desc = unsafe { std::mem::zeroed::<ndk_sys::AHardwareBuffer_Desc>() };
+        unsafe { ndk_sys::AHardwareBuffer_describe(self.0.as_ptr(), &mut desc) };
+        (desc.width, desc.height)
+    }
+}
+
+impl Drop for HardwareBuffer {
+    fn drop(&mut self) {
+        unsafe { ndk_sys::AHardwareBuffer_release(self.0.as_ptr()) };
+    }
+}
+
+/// Open `adapter`'s device with hardware buffer import, or `None` if the GPU
+/// can't (the caller then asks wgpu for a plain one).
+pub fn open_device(adapter: &wgpu::Adapter) -> Option<(wgpu::Device, wgpu::Queue)> {
+    // `Importer::copy` clears the page texture first, for wgpu's layout tracking
+    let desc = wgpu::DeviceDescriptor {
+        required_features: wgpu::Features::CLEAR_TEXTURE,
+        ..Default::default()
+    };
+    // As wgpu-hal opens it, with our extensions added
+    let open = unsafe {
+        adapter.as_hal::<Vulkan, _, _>(|adapter| {
+            let adapter = adapter?;
+            let instance = adapter.shared_instance().raw_instance();
+            let physical = adapter.raw_physical_device();
+            let version = instance.get_physical_device_properties(physical).api_version;
+            let available = instance.enumerate_device_extension_properties(physical).ok()?;
+            let has = |name: &CStr| available.iter().any(|e| e.extension_name_as_c_str().is_ok_and(|n| n == name));
+            if version < vk::API_VERSION_1_1 || !EXTENSIONS.iter().all(|name| has(name)) {
+                info!("Hardware buffers: not supported, browser frames are copied");
+                return None;
+            }
+            // The queue wgpu draws with: the first family that can do graphics and compute
+            let family = instance.get_physical_device_queue_family_properties(physical).iter()
+                .position(|f| f.queue_flags.contains(vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE))?
+                as u32;
+            let mut extensions = adapter.required_device_extensions(desc.required_features);
+            for name in EXTENSIONS {
+                if !extensions.contains(&name) {
+                    extensions.push(name);
+                }
+            }
+            let mut features = adapter.physical_device_features(&extensions, desc.required_features);
+            let priorities = [1.0];
+            let queues = [vk::DeviceQueueCreateInfo::default()
+                .queue_family_index(family)
+                .queue_priorities(&priorities)];
+            let names: Vec<_> = extensions.iter().map(|name| name.as_ptr()).collect();
+            let info = features.add_to_device_create(vk::DeviceCreateInfo::default()
+                .queue_create_infos(&queues)
+                .enabled_extension_names(&names));
+            let raw = instance.create_device(physical, &info, None)
+                .map_err(|e| warn!("Hardware buffers: vkCreateDevice failed: {:?}", e))
+                .ok()?;
+            adapter.device_from_raw(raw, None, &extensions, desc.required_features, &desc.memory_hints, family, 0)
+                .map_err(|e| warn!("Hardware buffers: device_from_raw failed: {:?}", e))
+                .ok()
+        })
+    }?;
+    match unsafe { adapter.create_device_from_hal(open, &desc, None) } {
+        Ok(device) => {
+            info!("Hardware buffers: browser frames stay on the GPU");
+            Some(device)
+        }
+        Err(e) => {
+            warn!("Hardware buffers: create_device_from_hal failed: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Imports hardware buffers and copies them into textures, on a device from
+/// `open_device`.
+pub struct Importer {
+    device: ash::Device,
+    buffers: ash::android::external_memory_android_hardware_buffer::Device,
+    // The queue family wgpu submits to, which takes the buffers over
+    family: u32,
+}
+
+/// A hardware buffer imported as a Vulkan image (`Importer::import`). Dropping
+/// it destroys the image, so it has to outlive the GPU work reading it.
+pub struct Frame {
+    device: ash::Device,
+    image: vk::Image,
+    memory: vk::DeviceMemory,
+    size: (u32, u32),
+}
+
+impl Drop for Frame {
+    fn drop(&mut self) {
+        unsafe {
+            self.device.destroy_image(self.image, None);
+            self.device.free_memory(self.memory, None);
+        }
+    }
+}
+
+impl Importer {
+    /// For `device`, if it was opened by `open_device`.
+    pub fn new(device: &wgpu::Device) -> Option<Self> {
+        unsafe {
+            device.as_hal::<Vulkan, _, _>(|device| {
+                let device = device?;
+                let raw = device.raw_device().clone();
+                let buffers = ash::android::external_memory_android_hardware_buffer::Device::new(
+                    device.shared_instance().raw_instance(), &raw);
+                Some(Importer { device: raw, buffers, family: device.queue_family_index() })
+            })
+        }
+        .flatten()
+    }
+
+    /// `buffer` as an image to copy from (the memory holds a reference to the
+    /// buffer of its own); `None` if it isn't plain RGBA or Vulkan won't take it.
+    pub fn import(&self, buffer: &HardwareBuffer) -> Option<Frame> {
+        let (width, height) = buffer.size();
+        let mut format = vk::AndroidHardwareBufferFormatPropertiesANDROID::default();
+        let mut properties = vk::AndroidHardwareBufferPropertiesANDROID::default().push_next(&mut format);
+        unsafe { self.buffers.get_android_hardware_buffer_properties(buffer.0.as_ptr().cast(), &mut properties) }
+            .map_err(|e| warn!("Hardware buffers: no properties: {:?}", e))
+            .ok()?;
+        let (allocation_size, memory_types) = (properties.allocation_size, properties.memory_type_bits);
+        // RGBX comes through as RGBA; anything else (YUV, external formats) would need a converting sampler
+        if format.format != vk::Format::R8G8B8A8_UNORM || memory_types == 0 {
+            warn!("Hardware buffers: can't import {:?}", format.format);
+            return None;
+        }
+
+        let mut external = vk::ExternalMemoryImageCreateInfo::default()
+            .handle_types(vk::ExternalMemoryHandleTypeFlags::ANDROID_HARDWARE_BUFFER_ANDROID);
+        let image_info = vk::ImageCreateInfo::default()
+            .image_type(vk::ImageType::TYPE_2D)
+            .format(vk::Format::R8G8B8A8_UNORM)
+            .extent(vk::Extent3D { width, height, depth: 1 })
+            .mip_levels(1)
+            .array_layers(1)
+            .samples(vk::SampleCountFlags::TYPE_1)
+            .tiling(vk::ImageTiling::OPTIMAL)
+            .usage(vk::ImageUsageFlags::TRANSFER_SRC)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(vk::ImageLayout::UNDEFINED)
+            .push_next(&mut external);
+        let image = unsafe { self.device.create_image(&image_info, None) }
+            .map_err(|e| warn!("Hardware buffers: vkCreateImage failed: {:?}", e))
+            .ok()?;
+        let mut import = vk::ImportAndroidHardwareBufferInfoANDROID::default().buffer(buffer.0.as_ptr().cast());
+        let mut dedicated = vk::MemoryDedicatedAllocateInfo::default().image(image);
+        let allocate_info = vk::MemoryAllocateInfo::default()
+            .allocation_size(allocation_size)
+            .memory_type_index(memory_types.trailing_zeros())
+            .push_next(&mut import)
+            .push_next(&mut dedicated);
+        let memory = match unsafe { self.device.allocate_memory(&allocate_info, None) } {
+            Ok(memory) => memory,
+            Err(e) => {
+                warn!("Hardware buffers: import failed: {:?}", e);
+                unsafe { self.device.destroy_image(image, None) };
+                return None;
+            }
+        };
+        let frame = Frame { device: self.device.clone(), image, memory, size: (width, height) };
+        if let Err(e) = unsafe { self.device.bind_image_memory(image, memory, 0) } {
+            warn!("Hardware buffers: vkBindImageMemory failed: {:?}", e);
+            return None;
+        }
+        Some(frame)
+    }
+
+    /// Record copying `frame` into the top left of `texture` (which needs
+    /// `COPY_DST`) in `encoder`. Keep `frame` until the submission is done.
+    pub fn copy(&self, encoder: &mut wgpu::CommandEncoder, frame: &Frame, texture: &wgpu::Texture) {
+        let Some(target) = (unsafe { texture.as_hal::<Vulkan, _, _>(|t| t.map(|t| t.raw_handle())) }) else { return };
+        // wgpu doesn't see the raw copy, so the clear leaves the texture, as far as
+        // wgpu knows too, initialized and in the transfer destination layout
+        encoder.clear_texture(texture, &wgpu::ImageSubresourceRange::default());
+
+        let (width, height) = frame.size;
+        let color = vk::ImageSubresourceRange::default()
+            .aspect_mask(vk::ImageAspectFlags::COLOR)
+            .level_count(1)
+            .layer_count(1);
+        let layers = vk::ImageSubresourceLayers::default()
+            .aspect_mask(vk::ImageAspectFlags::COLOR)
+            .layer_count(1);
+        let acquire = [
+            // Ours from the producer, contents kept
+            vk::ImageMemoryBarrier::default()
+                .dst_access_mask(vk::AccessFlags::TRANSFER_READ)
+                .old_layout(vk::ImageLayout::GENERAL)
+                .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+                .src_queue_family_index(vk::QUEUE_FAMILY_FOREIGN_EXT)
+                .dst_queue_family_index(self.family)
+                .image(frame.image)
+                .subresource_range(color),
+            // After the clear
+            vk::ImageMemoryBarrier::default()
+                .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                .dst_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(target)
+                .subresource_range(color),
+        ];
+        // Back to the producer once read
+        let release = [vk::ImageMemoryBarrier::default()
+            .src_access_mask(vk::AccessFlags::TRANSFER_READ)
+            .old_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .new_layout(vk::ImageLayout::GENERAL)
+            .src_queue_family_index(self.family)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_FOREIGN_EXT)
+            .image(frame.image)
+            .subresource_range(color)];
+        let region = vk::ImageCopy::default()
+            .src_subresource(layers)
+            .dst_subresource(layers)
+            .extent(vk::Extent3D { width, height, depth: 1 });
+        unsafe {
+            encoder.as_hal_mut::<Vulkan, _, _>(|encoder| {
+                let Some(encoder) = encoder else { return };
+                let commands = encoder.raw_handle();
+                self.device.cmd_pipeline_barrier(commands, vk::PipelineStageFlags::TRANSFER,
+                    vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(), &[], &[], &acquire);
+                self.device.cmd_copy_image(commands, frame.image, vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    target, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &[region]);
+                self.device.cmd_pipeline_barrier(commands, vk::PipelineStageFlags::TRANSFER,
+                    vk::PipelineStageFlags::BOTTOM_OF_PIPE, vk::DependencyFlags::empty(), &[], &[], &release);
+            });
+        }
+    }
+}
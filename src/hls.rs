@@ -0,0 +1,521 @@
+//! HLS (HTTP Live Streaming) client
+//!
+//! Fetches the m3u8 playlist, downloads media segments on a background thread
+//! into a bounded buffer, and exposes the concatenated segment bytes to the
+//! decoder as a `MediaSource`. Master playlists are followed to the variant
+//! that suits the measured bandwidth, re-checked after every segment. An
+//! extractor can't follow a switch to another rendition partway through its
+//! stream, so a switch happens at a segment boundary and starts a new part: reads
+//! end there, and the decoder opens a fresh extractor on the new variant's bytes
+//! (`MediaSource::next_part`). Handles MPEG-TS and fMP4
+//! (`EXT-X-MAP`) segments, live and VOD; encrypted streams (`EXT-X-KEY`) are
+//! rejected.
+//!
+//! Only a window around the extractor's read position is kept, so the stream
+//! reports itself as not seekable and the player leaves seeking off.
+
+use log::{error, info, warn};
+use std::io;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::http;
+use crate::media_source::MediaSource;
+
+/// Downloading pauses once this much is buffered ahead of the extractor.
+const MAX_AHEAD: usize = 48 * 1024 * 1024;
+/// Already-read data kept behind the extractor (it re-reads a little when probing).
+const KEEP_BEHIND: usize = 4 * 1024 * 1024;
+/// Live streams start this many segments back from the live edge.
+const LIVE_START_SEGMENTS: usize = 3;
+/// Only switch to a variant whose bandwidth fits within this share of what we measured.
+const BANDWIDTH_HEADROOM: f64 = 0.75;
+/// Weight of the latest segment in the smoothed bandwidth estimate.
+const BANDWIDTH_SMOOTHING: f64 = 0.3;
+/// Segments to play from a variant before switching again.
+const MIN_SEGMENTS_PER_VARIANT: u32 = 3;
+const RETRIES: u32 = 3;
+
+/// True for URLs that point at an m3u8 playlist.
+pub fn is_hls_url(url: &str) -> bool {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.to_ascii_lowercase().ends_with(".m3u8")
+}
+
+/// Resolve a playlist URI against the URL of the playlist it came from.
+fn resolve(base: &str, uri: &str) -> String {
+    if uri.contains("://") {
+        return uri.to_string();
+    }
+    let base = base.split(['?', '#']).next().unwrap_or(base);
+    if let Some(rest) = uri.strip_prefix('/') {
+        // Root-relative: keep scheme://host
+        let host_end = base.find("://").map(|i| i + 3)
+            .and_then(|start| base[start..].find('/').map(|j| start + j))
+            .unwrap_or(base.len());
+        return format!("{}/{}", &base[..host_end], rest);
+    }
+    match base.rfind('/') {
+        Some(i) => format!("{}/{}", &base[..i], uri),
+        None => uri.to_string(),
+    }
+}
+
+/// Value of `KEY=value` (optionally quoted) in an attribute list.
+fn attribute<'a>(attrs: &'a str, key: &str) -> Option<&'a str> {
+    let mut rest = attrs;
+    while !rest.is_empty() {
+        let eq = rest.find('=')?;
+        let name = rest[..eq].trim();
+        let value_start = &rest[eq + 1..];
+        let (value, next) = if let Some(quoted) = value_start.strip_prefix('"') {
+            let end = quoted.find('"')?;
+            (&quoted[..end], quoted[end + 1..].trim_start_matches(','))
+        } else {
+            let end = value_start.find(',').unwrap_or(value_start.len());
+            (&value_start[..end], value_start[end..].trim_start_matches(','))
+        };
+        if name == key {
+            return Some(value);
+        }
+        rest = next;
+    }
+    None
+}
+
+struct Variant {
+    bandwidth: u64,
+    url: String,
+}
+
+/// Variants of a master playlist, lowest bandwidth first (empty for a media playlist).
+fn parse_master(text: &str, base: &str) -> Vec<Variant> {
+    let mut variants = Vec::new();
+    let mut lines = text.lines().map(str::trim);
+    while let Some(line) = lines.next() {
+        if let Some(attrs) = line.strip_prefix("#EXT-X-STREAM-INF:") {
+            let bandwidth = attribute(attrs, "BANDWIDTH").and_then(|b| b.parse().ok()).unwrap_or(0);
+            if let Some(uri) = lines.by_ref().find(|l| !l.is_empty() && !l.starts_with('#')) {
+                variants.push(Variant { bandwidth, url: resolve(base, uri) });
+            }
+        }
+    }
+    variants.sort_by_key(|v| v.bandwidth);
+    variants
+}
+
+struct Segment {
+    sequence: u64,
+    url: String,
+    duration: f64,
+}
+
+struct MediaPlaylist {
+    target_duration: f64,
+    segments: Vec<Segment>,
+    /// `#EXT-X-ENDLIST` seen: VOD (or a finished live event).
+    ended: bool,
+    /// fMP4 initialisation segment (`#EXT-X-MAP`).
+    init: Option<String>,
+    encrypted: bool,
+}
+
+impl MediaPlaylist {
+    fn duration_us(&self) -> i64 {
+        (self.segments.iter().map(|s| s.duration).sum::<f64>() * 1_000_000.0) as i64
+    }
+}
+
+fn parse_media(text: &str, base: &str) -> MediaPlaylist {
+    let mut pl = MediaPlaylist { target_duration: 6.0, segments: Vec::new(), ended: false, init: None, encrypted: false };
+    let mut sequence = 0u64;
+    let mut duration = 0.0;
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if let Some(v) = line.strip_prefix("#EXT-X-TARGETDURATION:") {
+            pl.target_duration = v.parse().unwrap_or(6.0);
+        } else if let Some(v) = line.strip_prefix("#EXT-X-MEDIA-SEQUENCE:") {
+            sequence = v.parse().unwrap_or(0);
+        } else if let Some(v) = line.strip_prefix("#EXTINF:") {
+            duration = v.split(',').next().and_then(|d| d.parse().ok()).unwrap_or(0.0);
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-MAP:") {
+            pl.init = attribute(attrs, "URI").map(|u| resolve(base, u));
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-KEY:") {
+            pl.encrypted |= attribute(attrs, "METHOD").map(|m| m != "NONE").unwrap_or(false);
+        } else if line == "#EXT-X-ENDLIST" {
+            pl.ended = true;
+        } else if !line.starts_with('#') {
+            pl.segments.push(Segment { sequence, url: resolve(base, line), duration });
+            sequence += 1;
+        }
+    }
+    pl
+}
+
+fn fetch_text(url: &str) -> Result<String, String> {
+    http::get(url).map(|b| String::from_utf8_lossy(&b).into_owned())
+}
+
+/// The media playlist of `variant`, if it's one we can play.
+fn load_variant(variant: &Variant) -> Result<MediaPlaylist, String> {
+    let playlist = parse_media(&fetch_text(&variant.url)?, &variant.url);
+    if playlist.encrypted {
+        return Err("encrypted".into());
+    }
+    Ok(playlist)
+}
+
+/// The variant to be on at `bandwidth_bps`: the best that fits with headroom,
+/// but stay on `current` while it still fits at all.
+fn pick_variant(variants: &[Variant], current: usize, bandwidth_bps: f64) -> usize {
+    let best = variants.iter()
+        .rposition(|v| (v.bandwidth as f64) <= bandwidth_bps * BANDWIDTH_HEADROOM)
+        .unwrap_or(0);
+    if best < current && (variants[current].bandwidth as f64) <= bandwidth_bps {
+        current
+    } else {
+        best
+    }
+}
+
+/// `http::get` with a few retries (segment fetches on mobile networks do fail).
+fn fetch_retry(url: &str, shared: &Shared) -> Result<Vec<u8>, String> {
+    let mut last = String::new();
+    for attempt in 0..RETRIES {
+        if shared.stop.load(Ordering::SeqCst) {
+            return Err("stopped".into());
+        }
+        match http::get(url) {
+            Ok(b) => return Ok(b),
+            Err(e) => {
+                warn!("HLS: {} (attempt {})", e, attempt + 1);
+                last = e;
+                thread::sleep(Duration::from_secs(1));
+            }
+        }
+    }
+    Err(last)
+}
+
+// ── Segment buffer shared with the extractor ────────────────────────────────────
+
+/// Downloaded bytes, exposed to the extractor as one continuous stream.
+struct Buffer {
+    /// Stream offset of `data[0]` (older data is dropped).
+    base: u64,
+    data: Vec<u8>,
+    /// Highest offset the extractor has read up to.
+    read_pos: u64,
+    /// Stream offset of the current part's start: the extractor's offset 0.
+    part_start: u64,
+    /// Where the next part (another variant) starts, once the downloader has
+    /// switched; reads end there until the decoder moves on.
+    next_part: Option<u64>,
+    /// Furthest offset a blocked read is waiting for, so downloading keeps
+    /// going until it arrives even if that's more than `MAX_AHEAD` away.
+    wanted: u64,
+    eos: bool,
+}
+
+struct Shared {
+    buf: Mutex<Buffer>,
+    cond: Condvar,
+    stop: AtomicBool,
+    /// Decoder's running flag, so a blocked read gives up when playback stops.
+    running: Arc<AtomicBool>,
+    /// Total duration of the segments appended so far.
+    downloaded_us: AtomicI64,
+}
+
+impl Shared {
+    fn stopped(&self) -> bool {
+        self.stop.load(Ordering::SeqCst) || !self.running.load(Ordering::SeqCst)
+    }
+
+    /// Append downloaded bytes, waiting while the extractor is far enough behind.
+    fn append(&self, bytes: &[u8]) -> bool {
+        let Ok(mut buf) = self.buf.lock() else { return false };
+        while (buf.base as usize + buf.data.len()).saturating_sub(buf.read_pos.max(buf.wanted) as usize) > MAX_AHEAD {
+            if self.stopped() {
+                return false;
+            }
+            buf = match self.cond.wait_timeout(buf, Duration::from_millis(200)) {
+                Ok((b, _)) => b,
+                Err(_) => return false,
+            };
+        }
+        let behind = buf.read_pos.saturating_sub(buf.base) as usize;
+        if behind > KEEP_BEHIND {
+            let drop = behind - KEEP_BEHIND;
+            buf.data.drain(..drop);
+            buf.base += drop as u64;
+        }
+        buf.data.extend_from_slice(bytes);
+        self.cond.notify_all();
+        true
+    }
+
+    /// Whether a switch is waiting for the extractor to reach it.
+    fn part_pending(&self) -> bool {
+        self.buf.lock().map_or(true, |buf| buf.next_part.is_some())
+    }
+
+    /// Start a new part at the end of what's been appended so far.
+    fn start_part(&self) {
+        if let Ok(mut buf) = self.buf.lock() {
+            buf.next_part = Some(buf.base + buf.data.len() as u64);
+        }
+    }
+
+    /// Move the reads on to the next part, if one has started.
+    fn next_part(&self) -> bool {
+        let Ok(mut buf) = self.buf.lock() else { return false };
+        let Some(start) = buf.next_part.take() else { return false };
+        buf.part_start = start;
+        buf.read_pos = buf.read_pos.max(start);
+        self.cond.notify_all();
+        true
+    }
+
+    fn finish(&self) {
+        if let Ok(mut buf) = self.buf.lock() {
+            buf.eos = true;
+        }
+        self.cond.notify_all();
+    }
+
+    /// Blocking read for the extractor at `offset` into the current part: bytes
+    /// copied, 0 at the end of the part or stream.
+    fn read_at(&self, offset: u64, out: &mut [u8]) -> io::Result<usize> {
+        let mut buf = self.buf.lock().map_err(|_| io::Error::other("HLS buffer poisoned"))?;
+        let offset = buf.part_start + offset;
+        loop {
+            if offset < buf.base {
+                return Err(io::Error::other(format!(
+                    "HLS: read at {} but buffer starts at {}", offset, buf.base)));
+            }
+            let part_end = buf.next_part.unwrap_or(u64::MAX);
+            if offset >= part_end {
+                return Ok(0);
+            }
+            let start = (offset - buf.base) as usize;
+            if start < buf.data.len() {
+                let n = out.len().min(buf.data.len() - start).min((part_end - offset) as usize);
+                out[..n].copy_from_slice(&buf.data[start..start + n]);
+                buf.read_pos = buf.read_pos.max(offset + n as u64);
+                self.cond.notify_all();
+                return Ok(n);
+            }
+            if buf.eos || self.stopped() {
+                return Ok(0);
+            }
+            if offset > buf.wanted {
+                buf.wanted = offset;
+                self.cond.notify_all();
+            }
+            buf = self.cond.wait_timeout(buf, Duration::from_millis(100))
+                .map_err(|_| io::Error::other("HLS buffer poisoned"))?.0;
+        }
+    }
+}
+
+// ── Public stream handle ────────────────────────────────────────────────────────
+
+/// A running HLS download, read by the decoder as a `MediaSource`.
+pub struct HlsStream {
+    shared: Arc<Shared>,
+    downloader: Option<JoinHandle<()>>,
+    /// Total duration for VOD playlists.
+    duration_us: Option<i64>,
+}
+
+impl HlsStream {
+    /// Fetch the playlist (blocking, so bad URLs fail here) and start downloading.
+    pub fn open(url: &str, running: Arc<AtomicBool>) -> Result<Self, String> {
+        let text = fetch_text(url)?;
+        if !text.trim_start().starts_with("#EXTM3U") {
+            return Err(format!("HLS: not an m3u8 playlist: {}", url));
+        }
+        let variants = parse_master(&text, url);
+        let (variant, media_url, playlist) = if variants.is_empty() {
+            (0, url.to_string(), parse_media(&text, url))
+        } else {
+            // Start on the lowest variant for a fast start; the downloader steps up.
+            info!("HLS: {} variants ({}..{} bps)", variants.len(),
+                variants[0].bandwidth, variants[variants.len() - 1].bandwidth);
+            let media_url = variants[0].url.clone();
+            let playlist = parse_media(&fetch_text(&media_url)?, &media_url);
+            (0, media_url, playlist)
+        };
+        if playlist.encrypted {
+            return Err("HLS: encrypted streams are not supported".into());
+        }
+        if playlist.segments.is_empty() {
+            return Err("HLS: playlist has no segments".into());
+        }
+
+        let duration_us = playlist.ended.then(|| playlist.duration_us());
+        let shared = Arc::new(Shared {
+            buf: Mutex::new(Buffer {
+                base: 0, data: Vec::new(), read_pos: 0, part_start: 0, next_part: None, wanted: 0, eos: false,
+            }),
+            cond: Condvar::new(),
+            stop: AtomicBool::new(false),
+            running,
+            downloaded_us: AtomicI64::new(0),
+        });
+
+        let thread_shared = Arc::clone(&shared);
+        let downloader = thread::spawn(move || {
+            download_loop(&thread_shared, variants, variant, media_url, playlist);
+            thread_shared.finish();
+        });
+
+        Ok(Self { shared, downloader: Some(downloader), duration_us })
+    }
+}
+
+impl MediaSource for HlsStream {
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        self.shared.read_at(offset, buf)
+    }
+
+    fn size(&self) -> Option<u64> {
+        None // growing stream
+    }
+
+    fn seekable(&self) -> bool {
+        false
+    }
+
+    fn duration_us(&self) -> Option<i64> {
+        self.duration_us
+    }
+
+    fn buffered_until_us(&self) -> Option<i64> {
+        // VOD only: live timestamps don't start at zero
+        self.duration_us.map(|_| self.shared.downloaded_us.load(Ordering::SeqCst))
+    }
+
+    fn next_part(&mut self) -> bool {
+        self.shared.next_part()
+    }
+}
+
+impl Drop for HlsStream {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::SeqCst);
+        self.shared.cond.notify_all();
+        if let Some(handle) = self.downloader.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Downloader thread: walk the media sequence and append segments, switching
+/// variant at segment boundaries as the measured bandwidth changes.
+fn download_loop(
+    shared: &Shared,
+    variants: Vec<Variant>,
+    mut variant: usize,
+    mut media_url: String,
+    mut playlist: MediaPlaylist,
+) {
+    let first = &playlist.segments;
+    let mut next_seq = if playlist.ended {
+        first[0].sequence
+    } else {
+        first[first.len().saturating_sub(LIVE_START_SEGMENTS)].sequence
+    };
+    let mut sent_init: Option<String> = None;
+    // Smoothed download rate, and progress on the current variant
+    let mut bandwidth_bps: Option<f64> = None;
+    let mut appended = false;
+    let mut segments_on_variant = 0u32;
+
+    while !shared.stopped() {
+        let Some(seg) = playlist.segments.iter().find(|s| s.sequence >= next_seq) else {
+            if playlist.ended {
+                info!("HLS: end of playlist");
+                return;
+            }
+            // Live: wait for the playlist to grow
+            thread::sleep(Duration::from_secs_f64((playlist.target_duration / 2.0).max(1.0)));
+            match fetch_text(&media_url) {
+                Ok(text) => playlist = parse_media(&text, &media_url),
+                Err(e) => warn!("HLS: playlist reload failed: {}", e),
+            }
+            continue;
+        };
+        if seg.sequence > next_seq {
+            warn!("HLS: fell behind the live window, skipping {} segments", seg.sequence - next_seq);
+        }
+        let (sequence, duration) = (seg.sequence, seg.duration);
+        next_seq = sequence + 1;
+
+        let started = Instant::now();
+        let bytes = match fetch_retry(&seg.url, shared) {
+            Ok(b) => b,
+            Err(e) => { error!("HLS: segment {}: {}", sequence, e); return; }
+        };
+
+        let secs = started.elapsed().as_secs_f64().max(0.001);
+        let measured = bytes.len() as f64 * 8.0 / secs;
+        let estimate = bandwidth_bps.map_or(measured, |b| b + (measured - b) * BANDWIDTH_SMOOTHING);
+        bandwidth_bps = Some(estimate);
+        let best = if variants.len() > 1 { pick_variant(&variants, variant, estimate) } else { variant };
+
+        // Nothing sent yet (the first segment, from the lowest variant): a better
+        // variant just starts over from the same segment
+        if !appended && best != variant {
+            match load_variant(&variants[best]) {
+                Ok(better) => {
+                    info!("HLS: variant {} -> {} ({} bps, measured {:.0})",
+                        variant, best, variants[best].bandwidth, estimate);
+                    variant = best;
+                    media_url = variants[best].url.clone();
+                    playlist = better;
+                    next_seq = sequence;
+                    continue;
+                }
+                Err(e) => warn!("HLS: variant {} unusable ({}), staying on {}", best, e, variant),
+            }
+        }
+
+        if playlist.init != sent_init {
+            if let Some(init_url) = &playlist.init {
+                match fetch_retry(init_url, shared) {
+                    Ok(bytes) => { if !shared.append(&bytes) { return; } }
+                    Err(e) => { error!("HLS: init segment: {}", e); return; }
+                }
+            }
+            sent_init = playlist.init.clone();
+        }
+        if !shared.append(&bytes) {
+            return;
+        }
+        shared.downloaded_us.fetch_add((duration * 1_000_000.0) as i64, Ordering::SeqCst);
+        appended = true;
+        segments_on_variant += 1;
+
+        // Later switches start a new part here, at the segment boundary (one at a
+        // time: the extractor has to reach the last one first)
+        if best != variant && segments_on_variant >= MIN_SEGMENTS_PER_VARIANT && !shared.part_pending() {
+            match load_variant(&variants[best]) {
+                Ok(other) => {
+                    info!("HLS: variant {} -> {} at segment {} ({} bps, measured {:.0})",
+                        variant, best, next_seq, variants[best].bandwidth, estimate);
+                    shared.start_part();
+                    variant = best;
+                    media_url = variants[best].url.clone();
+                    playlist = other;
+                    // The new part needs its own init segment (fMP4)
+                    sent_init = None;
+                    segments_on_variant = 0;
+                }
+                Err(e) => warn!("HLS: variant {} unusable ({}), staying on {}", best, e, variant),
+            }
+        }
+    }
+}
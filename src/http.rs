@@ -0,0 +1,64 @@
+//! Minimal HTTP(S) GET for background threads.
+//!
+//! There's no HTTP/TLS crate in the build, so requests go through Java's
+//! `java.net.URL` over JNI (platform TLS, redirects, proxies for free). Used by
+//! the HLS client to fetch playlists and segments from its downloader thread.
+
+use android_activity::AndroidApp;
+use jni::objects::JValue;
+use jni::{JNIEnv, JavaVM};
+use std::sync::OnceLock;
+
+static JVM: OnceLock<JavaVM> = OnceLock::new();
+
+const CONNECT_TIMEOUT_MS: i32 = 10_000;
+const READ_TIMEOUT_MS: i32 = 15_000;
+const CHUNK: i32 = 64 * 1024;
+
+/// Remember the JavaVM so non-JNI threads can make requests (call once at startup).
+pub fn init(app: &AndroidApp) {
+    if JVM.get().is_none() {
+        if let Ok(vm) = unsafe { JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM) } {
+            let _ = JVM.set(vm);
+        }
+    }
+}
+
+/// Fetch `url` into memory. Blocks; call from a worker thread.
+pub fn get(url: &str) -> Result<Vec<u8>, String> {
+    let vm = JVM.get().ok_or("http: not initialised")?;
+    // Worker threads stay attached; the jni crate detaches them on thread exit.
+    let mut env = vm.attach_current_thread_permanently()
+        .map_err(|e| format!("http: attach failed: {:?}", e))?;
+    let result = env.with_local_frame(16, |env| fetch(env, url));
+    if env.exception_check().unwrap_or(false) {
+        let _ = env.exception_clear();
+    }
+    result.map_err(|e| match e {
+        jni::errors::Error::JavaException => format!("http: request failed: {}", url),
+        e => format!("http: {:?}", e),
+    })
+}
+
+fn fetch(env: &mut JNIEnv, url: &str) -> Result<Vec<u8>, jni::errors::Error> {
+    let jurl = env.new_string(url)?;
+    let url_obj = env.new_object("java/net/URL", "(Ljava/lang/String;)V", &[JValue::Object(&jurl)])?;
+    let conn = env.call_method(&url_obj, "openConnection", "()Ljava/net/URLConnection;", &[])?.l()?;
+    env.call_method(&conn, "setConnectTimeout", "(I)V", &[JValue::Int(CONNECT_TIMEOUT_MS)])?;
+    env.call_method(&conn, "setReadTimeout", "(I)V", &[JValue::Int(READ_TIMEOUT_MS)])?;
+    let stream = env.call_method(&conn, "getInputStream", "()Ljava/io/InputStream;", &[])?.l()?;
+
+    let jbuf = env.new_byte_array(CHUNK)?;
+    let mut chunk = vec![0i8; CHUNK as usize];
+    let mut out = Vec::new();
+    loop {
+        let n = env.call_method(&stream, "read", "([B)I", &[JValue::Object(&jbuf)])?.i()?;
+        if n < 0 {
+            break;
+        }
+        env.get_byte_array_region(&jbuf, 0, &mut chunk[..n as usize])?;
+        out.extend(chunk[..n as usize].iter().map(|&b| b as u8));
+    }
+    env.call_method(&stream, "close", "()V", &[])?;
+    Ok(out)
+}
@@ -0,0 +1,449 @@
+//! UI translations
+//!
+//! Text shown to the user is written in English and passed through `tr` (or
+//! `trf` when values go into it), which looks it up in the current language's
+//! table. The English text is the key, so a string missing from a table simply
+//! stays English. Values go where the text has `{}`, in order; a translation
+//! keeps the same number of `{}`.
+//!
+//! The language follows the system locale (read when the app resumes) unless
+//! one is picked in Settings → Advanced; lib.rs passes that choice to `select`
+//! every frame. To add a language: a `Language` variant, its locale code in
+//! `Language::from_locale` and a table in `table`.
+
+use android_activity::AndroidApp;
+use log::info;
+use std::collections::HashMap;
+use std::fmt::{Display, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    /// Whatever the phone is set to (English if there's no table for it)
+    System,
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::System, Language::English, Language::German];
+
+    /// The language's own name, so it can be found whatever is showing.
+    pub fn label(self) -> &'static str {
+        match self {
+            Language::System => tr("System"),
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    /// The table for an ISO 639 code ("de", "en", …).
+    fn from_locale(code: &str) -> Language {
+        match code {
+            "de" => Language::German,
+            _ => Language::English,
+        }
+    }
+
+    fn index(self) -> u8 {
+        self as u8
+    }
+
+    fn from_index(i: u8) -> Language {
+        Language::ALL.get(i as usize).copied().unwrap_or(Language::English)
+    }
+}
+
+/// The system locale's language and the one in use (`Language` as u8; never System).
+static SYSTEM: AtomicU8 = AtomicU8::new(Language::English as u8);
+static CURRENT: AtomicU8 = AtomicU8::new(Language::English as u8);
+
+/// Read the system locale; call when the app (re)starts.
+pub fn detect(app: &AndroidApp) {
+    let code = app.config().language().unwrap_or_default();
+    let language = Language::from_locale(&code);
+    info!("i18n: system locale {:?} → {:?}", code, language);
+    SYSTEM.store(language.index(), Ordering::Relaxed);
+}
+
+/// Use `choice` from now on.
+pub fn select(choice: Language) {
+    let language = match choice {
+        Language::System => Language::from_index(SYSTEM.load(Ordering::Relaxed)),
+        other => other,
+    };
+    CURRENT.store(language.index(), Ordering::Relaxed);
+}
+
+fn table(language: Language) -> Option<&'static HashMap<&'static str, &'static str>> {
+    static GERMAN_TABLE: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    match language {
+        Language::German => Some(GERMAN_TABLE.get_or_init(|| GERMAN.iter().copied().collect())),
+        Language::System | Language::English => None,
+    }
+}
+
+/// `text` in the current language.
+pub fn tr(text: &'static str) -> &'static str {
+    table(Language::from_index(CURRENT.load(Ordering::Relaxed)))
+        .and_then(|t| t.get(text).copied())
+        .unwrap_or(text)
+}
+
+/// `text` in the current language with `args` in place of its `{}`.
+pub fn trf(text: &'static str, args: &[&dyn Display]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter();
+    for (i, part) in tr(text).split("{}").enumerate() {
+        if i > 0 {
+            if let Some(arg) = args.next() {
+                let _ = write!(out, "{}", arg);
+            }
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+/// Strings that read the same in German (Mono, Video, URL, …) are left out.
+const GERMAN: &[(&str, &str)] = &[
+    // Dock and settings
+    ("Recenter", "Zentrieren"),
+    ("Files", "Dateien"),
+    ("Play URL", "URL abspielen"),
+    ("New Tab", "Neuer Tab"),
+    ("Close Tab", "Tab schließen"),
+    ("3D Mode", "3D-Modus"),
+    ("Play/Pause", "Wiedergabe/Pause"),
+    ("Auto-Play Next", "Nächste automatisch"),
+    ("Loop", "Wiederholen"),
+    ("Aspect", "Seitenverhältnis"),
+    ("Save Frame", "Bild speichern"),
+    ("Queue", "Warteschlange"),
+    ("Lock", "Sperren"),
+    ("Settings", "Einstellungen"),
+    ("Exit VR", "VR beenden"),
+    ("Aspect · Fit", "Seitenverhältnis · Einpassen"),
+    ("Aspect · Fill 16:9", "Seitenverhältnis · Füllen 16:9"),
+    ("Aspect · Stretch 16:9", "Seitenverhältnis · Strecken 16:9"),
+    ("Aspect · Crop {}:1", "Seitenverhältnis · Zuschneiden {}:1"),
+    ("3D · Side-by-Side", "3D · Nebeneinander"),
+    ("3D · Over-Under", "3D · Übereinander"),
+    ("Loop · Off", "Wiederholen · Aus"),
+    ("Loop · Current File", "Wiederholen · Aktuelle Datei"),
+    ("Loop · Folder / Queue", "Wiederholen · Ordner / Warteschlange"),
+    ("🖵 Display", "🖵 Anzeige"),
+    ("▶ Playback", "▶ Wiedergabe"),
+    ("🎮 Input", "🎮 Eingabe"),
+    ("⚙ Advanced", "⚙ Erweitert"),
+    ("Saved as you change them", "Änderungen werden sofort gespeichert"),
+    ("Lens Size", "Linsengröße"),
+    ("Lens Distance", "Linsenabstand"),
+    ("IPD (mm)", "Augenabstand (mm)"),
+    ("Field of View (°)", "Sichtfeld (°)"),
+    ("Menus", "Menüs"),
+    ("Windows", "Fenster"),
+    ("Arrange in a curve", "Im Bogen anordnen"),
+    ("Menus stay, notices follow", "Menüs bleiben, Hinweise folgen"),
+    ("Stay in place", "Bleiben an ihrem Platz"),
+    ("Follow the head", "Folgen dem Kopf"),
+    ("Preset", "Voreinstellung"),
+    ("Custom", "Eigene"),
+    ("None saved yet", "Noch keine gespeichert"),
+    ("Save as…", "Speichern als…"),
+    ("Save", "Speichern"),
+    ("Saved {}", "{} gespeichert"),
+    ("Save lens preset as", "Linsen-Voreinstellung speichern als"),
+    ("Screen Brightness", "Bildschirmhelligkeit"),
+    ("Clock, battery and Wi-Fi", "Uhr, Akku und WLAN"),
+    ("Full brightness in VR", "Volle Helligkeit in VR"),
+    ("Screen", "Leinwand"),
+    ("Fit", "Einpassen"),
+    ("Fill 16:9", "Füllen 16:9"),
+    ("Stretch 16:9", "Strecken 16:9"),
+    ("Crop", "Zuschneiden"),
+    ("Crop Ratio", "Zuschnittformat"),
+    ("Horizon (°)", "Horizont (°)"),
+    ("Picture (this session)", "Bild (diese Sitzung)"),
+    ("Brightness", "Helligkeit"),
+    ("Contrast", "Kontrast"),
+    ("Saturation", "Sättigung"),
+    ("Reset picture", "Bild zurücksetzen"),
+    ("Head Tracking", "Kopf-Tracking"),
+    ("On", "Ein"),
+    ("Sensor Fusion", "Sensorfusion"),
+    ("Sensor Rate", "Sensorrate"),
+    ("Filter Gain", "Filterstärke"),
+    ("Compass Yaw Fix", "Kompasskorrektur"),
+    ("Correct drift", "Drift korrigieren"),
+    ("Smoothing", "Glättung"),
+    ("Positional", "Position"),
+    ("Neck Model", "Halsmodell"),
+    ("Headset Removed", "Headset abgenommen"),
+    ("Pause video", "Video pausieren"),
+    ("Blank screen", "Bildschirm schwarz"),
+    ("Rotation Vector (system)", "Rotationsvektor (System)"),
+    ("Madgwick AHRS", "Madgwick-AHRS"),
+    ("Mahony AHRS", "Mahony-AHRS"),
+    ("External head tracker (Bluetooth)", "Externer Head-Tracker (Bluetooth)"),
+    ("50 Hz · saves battery", "50 Hz · spart Akku"),
+    ("Fastest · lowest latency", "Maximal · geringste Latenz"),
+    ("Diagnostics", "Diagnose"),
+    ("No sensor input", "Keine Sensordaten"),
+    ("Error: {}", "Fehler: {}"),
+    ("Source: {}", "Quelle: {}"),
+    ("last update {} s ago", "letzte Aktualisierung vor {} s"),
+    ("last update {} ms ago", "letzte Aktualisierung vor {} ms"),
+    ("no updates yet", "noch keine Aktualisierung"),
+    ("·  Rendering {} fps", "·  Darstellung {} fps"),
+    ("Auto-advance", "Automatisch weiter"),
+    ("Play the next file in the folder", "Nächste Datei im Ordner abspielen"),
+    ("Off", "Aus"),
+    ("Current file", "Aktuelle Datei"),
+    ("Folder / queue", "Ordner / Warteschlange"),
+    ("3D Layout", "3D-Format"),
+    ("Pointing", "Zeigen"),
+    ("Right Stick Look (°/s)", "Umsehen mit rechtem Stick (°/s)"),
+    ("Left Stick Pointer", "Zeiger mit linkem Stick"),
+    ("In menus", "In Menüs"),
+    ("Motion Aim", "Bewegungszielen"),
+    ("Hold L2 to aim", "L2 halten zum Zielen"),
+    ("Gaze Dwell (s)", "Blickverweilen (s)"),
+    ("Repeat Delay (s)", "Wiederholverzögerung (s)"),
+    ("Repeat Rate (/s)", "Wiederholrate (/s)"),
+    ("Rumble", "Vibration"),
+    ("Confirm actions", "Aktionen bestätigen"),
+    ("Hints", "Hinweise"),
+    ("Show buttons", "Tasten anzeigen"),
+    ("Left Stick", "Linker Stick"),
+    ("Right Stick", "Rechter Stick"),
+    ("Deadzone ↔", "Totzone ↔"),
+    ("Sensitivity", "Empfindlichkeit"),
+    ("Controllers", "Controller"),
+    ("None yet — press a button", "Noch keiner — drücke eine Taste"),
+    ("View", "Ansicht"),
+    ("Remote", "Fernbedienung"),
+    ("Shared", "Gemeinsam"),
+    ("Swap", "Tauschen"),
+    ("Second is a media remote", "Zweiter ist eine Medienfernbedienung"),
+    ("Buttons ({} layout)", "Tasten ({}-Layout)"),
+    ("Press the button to use as {}…", "Drücke die Taste für {}…"),
+    ("Click a control, then press the button to use for it", "Steuerelement anklicken, dann die gewünschte Taste drücken"),
+    ("Reset buttons", "Tasten zurücksetzen"),
+    ("Shortcuts:  {}", "Kurzbefehle:  {}"),
+    ("Play / pause, select; hold: stop", "Wiedergabe / Pause, auswählen; halten: Stopp"),
+    ("Back; twice: close all", "Zurück; zweimal: alles schließen"),
+    ("Confirm, yaw trim (held)", "Bestätigen, Gierkorrektur (gehalten)"),
+    ("Menu", "Menü"),
+    ("Seek back / previous", "Zurückspulen / vorheriger"),
+    ("Seek forward / next", "Vorspulen / nächster"),
+    ("Zoom out", "Verkleinern"),
+    ("Zoom in", "Vergrößern"),
+    ("Media center", "Mediencenter"),
+    ("Settings; hold: quit", "Einstellungen; halten: beenden"),
+    ("Exit", "Beenden"),
+    ("Next chapter, navigate", "Nächstes Kapitel, navigieren"),
+    ("Previous chapter, navigate", "Vorheriges Kapitel, navigieren"),
+    ("3D layout, navigate", "3D-Format, navigieren"),
+    ("Language", "Sprache"),
+    ("Help", "Hilfe"),
+    ("Show the tutorial again", "Einführung erneut zeigen"),
+    ("Theme", "Design"),
+    ("Dark", "Dunkel"),
+    ("OLED black", "OLED-Schwarz"),
+    ("High contrast", "Hoher Kontrast"),
+    ("Light", "Hell"),
+    ("Accent", "Akzent"),
+    ("Theme's own", "Wie Design"),
+    ("Accessibility", "Barrierefreiheit"),
+    ("Reduce motion", "Bewegung reduzieren"),
+    ("High-contrast controls", "Bedienelemente mit hohem Kontrast"),
+    ("Color vision", "Farbsehen"),
+    ("Typical", "Normal"),
+    ("Red-green", "Rot-Grün"),
+    ("Blue-yellow", "Blau-Gelb"),
+    ("Decoders", "Decoder"),
+    ("Applies to the next video opened", "Gilt ab dem nächsten geöffneten Video"),
+    ("Decoder list unavailable", "Decoderliste nicht verfügbar"),
+    ("Auto", "Automatisch"),
+    ("Defaults", "Standardwerte"),
+    ("Reset all settings", "Alle Einstellungen zurücksetzen"),
+    ("Button remaps and decoders are kept", "Tastenbelegung und Decoder bleiben erhalten"),
+    // Media Center
+    ("Media Center", "Mediencenter"),
+    ("☑ Select", "☑ Auswählen"),
+    ("▦ Grid", "▦ Raster"),
+    ("＋ Queue", "＋ Warteschlange"),
+    ("Favorites", "Favoriten"),
+    ("Recent", "Zuletzt"),
+    ("Movies", "Filme"),
+    ("Music", "Musik"),
+    ("Subfolders", "Unterordner"),
+    ("Recently played", "Zuletzt abgespielt"),
+    ("Bookmarked folders and files", "Gemerkte Ordner und Dateien"),
+    ("Storage", "Speicher"),
+    ("Internal storage", "Interner Speicher"),
+    ("External storage ({})", "Externer Speicher ({})"),
+    ("{} selected", "{} ausgewählt"),
+    ("▶ Play all", "▶ Alle abspielen"),
+    ("Select all", "Alle auswählen"),
+    ("Done", "Fertig"),
+    ("⚠  Can't open folder", "⚠  Ordner kann nicht geöffnet werden"),
+    ("Cannot access folder.\nGrant storage permission in Settings.", "Kein Zugriff auf den Ordner.\nSpeicherberechtigung in den Einstellungen erteilen."),
+    ("{} isn't available.\nIt may have been removed.", "{} ist nicht verfügbar.\nEr wurde vielleicht entfernt."),
+    ("Cannot read {}.\nAllow access to all files in Settings, or remove and reinsert it.", "{} kann nicht gelesen werden.\nZugriff auf alle Dateien in den Einstellungen erlauben oder ihn neu einstecken."),
+    ("{} was removed", "{} wurde entfernt"),
+    ("No matches", "Keine Treffer"),
+    ("Nothing played yet", "Noch nichts abgespielt"),
+    ("Hold □ on a folder or file to add it here", "□ auf einem Ordner oder einer Datei halten, um sie hier hinzuzufügen"),
+    ("Nothing here", "Hier ist nichts"),
+    ("Folder", "Ordner"),
+    ("{}  ·  New", "{}  ·  Neu"),
+    ("{}  ·  Watched", "{}  ·  Angesehen"),
+    ("{}  ·  {}% watched", "{}  ·  {} % angesehen"),
+    ("{}  ·  {}% watched  ·  {}", "{}  ·  {} % angesehen  ·  {}"),
+    ("Type", "Typ"),
+    ("Size", "Größe"),
+    ("Modified", "Geändert"),
+    ("Duration", "Dauer"),
+    ("Resolution", "Auflösung"),
+    ("Frame rate", "Bildrate"),
+    ("Reading…", "Wird gelesen…"),
+    ("Watched", "Angesehen"),
+    ("Finished", "Fertig angesehen"),
+    ("{} ch", "{} Kan."),
+    ("☆  Remove from favorites", "☆  Aus Favoriten entfernen"),
+    ("★  Add to favorites", "★  Zu Favoriten hinzufügen"),
+    ("✏  Rename", "✏  Umbenennen"),
+    ("🗑  Delete for good", "🗑  Endgültig löschen"),
+    ("🗑  Delete", "🗑  Löschen"),
+    ("Cancel", "Abbrechen"),
+    ("Delete this file? It can't be undone.", "Diese Datei löschen? Das lässt sich nicht rückgängig machen."),
+    ("Added to favorites", "Zu Favoriten hinzugefügt"),
+    ("Removed from favorites", "Aus Favoriten entfernt"),
+    ("Deleted {}", "{} gelöscht"),
+    ("Renamed to {}", "Umbenannt in {}"),
+    ("Rename", "Umbenennen"),
+    ("Space", "Leertaste"),
+    ("That name can't be used", "Dieser Name ist nicht möglich"),
+    ("{} already exists", "{} gibt es schon"),
+    ("Files on {} can't be changed from here", "Dateien auf {} können hier nicht geändert werden"),
+    ("Not allowed; allow access to all files in Settings", "Nicht erlaubt; Zugriff auf alle Dateien in den Einstellungen erlauben"),
+    ("It's no longer there", "Es ist nicht mehr vorhanden"),
+    ("just now", "gerade eben"),
+    ("{} min ago", "vor {} Min."),
+    ("{} h ago", "vor {} Std."),
+    ("yesterday", "gestern"),
+    ("{} days ago", "vor {} Tagen"),
+    ("{} {} {}", "{}. {} {}"),
+    ("Mar", "Mär"),
+    ("May", "Mai"),
+    ("Oct", "Okt"),
+    ("Dec", "Dez"),
+    // Queue, playback and dialogs
+    ("Up Next", "Als Nächstes"),
+    ("{} items", "{} Einträge"),
+    ("Queue is empty", "Die Warteschlange ist leer"),
+    ("Add videos from the Media Center with R1 or ＋ Queue", "Videos im Mediencenter mit R1 oder ＋ Warteschlange hinzufügen"),
+    ("Queued {} files", "{} Dateien eingereiht"),
+    ("Resume from {}?", "Ab {} fortsetzen?"),
+    ("▶ Resume", "▶ Fortsetzen"),
+    ("⟲ Start Over", "⟲ Von vorn"),
+    ("Limited decoder support", "Eingeschränkte Decoder-Unterstützung"),
+    ("Can't reach the stream", "Stream nicht erreichbar"),
+    ("Check the Wi-Fi connection and the address, then try again.", "WLAN-Verbindung und Adresse prüfen, dann erneut versuchen."),
+    ("Can't read the file", "Datei nicht lesbar"),
+    ("It may be damaged, still being copied, or on a drive that was removed.", "Sie ist vielleicht beschädigt, wird noch kopiert oder liegt auf einem entfernten Laufwerk."),
+    ("No picture in this file", "Kein Bild in dieser Datei"),
+    ("It has no video track, or isn't a video at all.", "Sie hat keine Videospur oder ist gar kein Video."),
+    ("Format not supported", "Format nicht unterstützt"),
+    ("This phone has no working decoder for it. Pick another in Settings → Advanced, or play a copy in H.264 or HEVC.", "Dieses Handy hat keinen funktionierenden Decoder dafür. Wähle einen anderen unter Einstellungen → Erweitert oder spiele eine Kopie in H.264 oder HEVC ab."),
+    ("The decoder stopped", "Der Decoder ist ausgefallen"),
+    ("Try again, or pick another decoder in Settings → Advanced.", "Versuche es erneut oder wähle einen anderen Decoder unter Einstellungen → Erweitert."),
+    ("⟲ Try again", "⟲ Erneut versuchen"),
+    ("Try again", "Erneut versuchen"),
+    ("This device can't hardware-decode {}; software decoding may stutter", "Dieses Gerät kann {} nicht in Hardware dekodieren; Software-Dekodierung kann ruckeln"),
+    ("This device can't decode {}", "Dieses Gerät kann {} nicht dekodieren"),
+    ("Opening…", "Wird geöffnet…"),
+    ("Buffering…", "Wird gepuffert…"),
+    ("Seeking…", "Wird gesucht…"),
+    ("to {}", "nach {}"),
+    ("{} s buffered", "{} s gepuffert"),
+    ("{}% · low", "{} % · schwach"),
+    ("Controller connected", "Controller verbunden"),
+    ("Recentered", "Zentriert"),
+    ("Head tracking on", "Kopf-Tracking an"),
+    ("Head tracking off", "Kopf-Tracking aus"),
+    ("No frame to save yet", "Noch kein Bild zum Speichern"),
+    ("Couldn't save the frame", "Bild konnte nicht gespeichert werden"),
+    ("Frame saved to Pictures/VR Snapshots", "Bild in Pictures/VR Snapshots gespeichert"),
+    ("Couldn't open {} beside", "{} konnte nicht daneben geöffnet werden"),
+    ("Controller disconnected", "Controller getrennt"),
+    ("Connect a controller to lock the controls", "Zum Sperren einen Controller verbinden"),
+    ("Locked · hold {} + {} to unlock", "Gesperrt · zum Entsperren {} + {} halten"),
+    ("Controls unlocked", "Entsperrt"),
+    ("Locked", "Gesperrt"),
+    ("☰  Menu", "☰  Menü"),
+    ("Enter URL…", "URL eingeben…"),
+    ("Play from URL", "Von URL abspielen"),
+    // First-run tutorial
+    ("{} of {}", "{} von {}"),
+    ("Welcome", "Willkommen"),
+    ("Tap Enter VR at the top of the screen, then put the phone in the headset. A tap on the picture shows or hides the controls.", "Tippe oben auf VR starten und setze das Telefon dann ins Headset. Ein Tippen aufs Bild blendet die Bedienelemente ein oder aus."),
+    ("Open a video to start", "Öffne ein Video, um zu beginnen"),
+    ("Enter VR", "VR starten"),
+    ("Pair a controller over Bluetooth. While one is connected, the buttons that do something are listed along the bottom.", "Kopple einen Controller über Bluetooth. Solange einer verbunden ist, stehen die gerade belegten Tasten unten am Rand."),
+    ("Your videos and music, favorites and what you played last.", "Deine Videos und Musik, Favoriten und zuletzt Abgespieltes."),
+    ("Double tap", "Doppeltippen"),
+    ("Swipe along the bottom", "Unten entlangwischen"),
+    ("Pinch", "Zusammenziehen"),
+    ("Twist two fingers", "Zwei Finger drehen"),
+    ("Level the horizon", "Horizont ausrichten"),
+    ("Two-finger tap", "Mit zwei Fingern tippen"),
+    ("Open the Media Center", "Mediencenter öffnen"),
+    ("Next", "Weiter"),
+    ("Skip", "Überspringen"),
+    // Button hints
+    ("hold {}", "{} halten"),
+    ("Left stick", "Linker Stick"),
+    ("Right stick", "Rechter Stick"),
+    ("Save frame", "Bild speichern"),
+    ("3D layout", "3D-Format"),
+    ("Add to queue", "Einreihen"),
+    ("Back", "Zurück"),
+    ("Browse / tabs", "Blättern / Tabs"),
+    ("Browse", "Blättern"),
+    ("Change", "Ändern"),
+    ("Chapters", "Kapitel"),
+    ("Choose", "Wählen"),
+    ("Clear / up a folder", "Löschen / Ordner hoch"),
+    ("Close", "Schließen"),
+    ("Delete", "Löschen"),
+    ("Favorite / rename / delete", "Favorit / umbenennen / löschen"),
+    ("Filter letter", "Filterbuchstabe"),
+    ("Folder path", "Ordnerpfad"),
+    ("Grid / coverflow", "Raster / Coverflow"),
+    ("Look", "Umsehen"),
+    ("Move", "Bewegen"),
+    ("Open", "Öffnen"),
+    ("Play / pause", "Wiedergabe / Pause"),
+    ("Play all", "Alle abspielen"),
+    ("Play beside", "Daneben abspielen"),
+    ("Play", "Abspielen"),
+    ("Point", "Zeigen"),
+    ("Prev / next", "Vorheriger / nächster"),
+    ("Queue all", "Alle einreihen"),
+    ("Remove", "Entfernen"),
+    ("Resume", "Fortsetzen"),
+    ("Search", "Suchen"),
+    ("Section", "Bereich"),
+    ("Seek bar", "Zeitleiste"),
+    ("Seek", "Spulen"),
+    ("Select several", "Mehrere auswählen"),
+    ("Select", "Auswählen"),
+    ("Shift", "Umschalt"),
+    ("Start over", "Von vorn"),
+    ("Stop", "Stopp"),
+    ("Unlock", "Entsperren"),
+];
@@ -1,130 +1,179 @@
 //! Input handling module
 //!
-//! Handles PS5 DualSense controller, keyboard, and mouse input.
+//! Hardware keyboard and mouse (USB-C hub, Bluetooth, the desktop simulator).
+//! Keys become `actions::Action`s, the same ones the controller, dock and media
+//! buttons produce; the mouse drives the UI pointer while a panel is open and
+//! otherwise drags the view and zooms with the wheel.
+//!
+//! | Key            | Action                                      |
+//! |----------------|---------------------------------------------|
+//! | Space          | Play / pause (select in menus)              |
+//! | Enter          | Confirm                                     |
+//! | Esc, Backspace | Back                                        |
+//! | ← →            | Seek ∓10 s (navigate in menus)              |
+//! | ↑ ↓            | Next / previous chapter (navigate in menus) |
+//! | Page Up / Down | Previous / next in the queue                |
+//! | F              | VR / 2D                                     |
+//! | M              | Menu                                        |
+//! | R              | Recenter                                    |
+//! | + −            | Zoom (held)                                 |
 
-use gilrs::{Gilrs, Button, Event, EventType};
 use glam::Vec2;
 use log::info;
-use winit::event::ElementState;
+use std::collections::HashSet;
+use winit::event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta};
 use winit::keyboard::{Key, NamedKey};
 
+use crate::actions::{self, Action, Direction};
+
+/// A key's meaning; held keys are kept by this.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum KeyAction {
+    PlayPause,
+    Confirm,
+    Back,
+    Left,
+    Right,
+    Up,
+    Down,
+    PreviousTrack,
+    NextTrack,
+    ToggleVr,
+    Menu,
+    Recenter,
+    ZoomIn,
+    ZoomOut,
+}
+
+impl KeyAction {
+    fn from_key(key: &Key) -> Option<KeyAction> {
+        Some(match key {
+            Key::Named(NamedKey::Space) => KeyAction::PlayPause,
+            Key::Named(NamedKey::Enter) => KeyAction::Confirm,
+            Key::Named(NamedKey::Escape | NamedKey::Backspace | NamedKey::GoBack) => KeyAction::Back,
+            Key::Named(NamedKey::ArrowLeft) => KeyAction::Left,
+            Key::Named(NamedKey::ArrowRight) => KeyAction::Right,
+            Key::Named(NamedKey::ArrowUp) => KeyAction::Up,
+            Key::Named(NamedKey::ArrowDown) => KeyAction::Down,
+            Key::Named(NamedKey::PageUp) => KeyAction::PreviousTrack,
+            Key::Named(NamedKey::PageDown) => KeyAction::NextTrack,
+            Key::Character(c) => match c.to_lowercase().as_str() {
+                "f" => KeyAction::ToggleVr,
+                "m" => KeyAction::Menu,
+                "r" => KeyAction::Recenter,
+                "+" | "=" => KeyAction::ZoomIn,
+                "-" => KeyAction::ZoomOut,
+                _ => return None,
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// Mouse input gathered since the last frame.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MouseFrame {
+    /// Cursor movement, window px
+    pub delta: Vec2,
+    /// Left button held
+    pub left: bool,
+    /// The left button went up
+    pub released: bool,
+    /// Wheel notches, positive away from the user
+    pub wheel: f32,
+}
 
-/// Controller state for cursor/interaction
+/// Keyboard and mouse state between frames.
+#[derive(Default)]
 pub struct InputState {
-    gilrs: Option<Gilrs>,
-    pub cursor_position: Vec2,
-    pub left_stick: Vec2,
-    pub right_stick: Vec2,
-    pub primary_action: bool,   // Cross / Left Click
-    pub secondary_action: bool, // Circle / Right Click
-    pub menu_action: bool,      // Options / Start / Menu
-    pub play_pause: bool,
-    pub seek_forward: bool,
-    pub seek_backward: bool,
-    pub zoom: bool,
+    /// Presses (and auto-repeats, flagged) since the last frame
+    pressed: Vec<(KeyAction, bool)>,
+    held: HashSet<KeyAction>,
+    cursor: Option<Vec2>,
+    mouse: MouseFrame,
 }
 
 impl InputState {
     pub fn new() -> Self {
-        let gilrs = match Gilrs::new() {
-            Ok(g) => {
-                info!("Gamepad system initialized");
-                Some(g)
-            }
-            Err(e) => {
-                info!("No gamepad support: {:?}", e);
-                None
-            }
-        };
-        
-        Self {
-            gilrs,
-            cursor_position: Vec2::ZERO,
-            left_stick: Vec2::ZERO,
-            right_stick: Vec2::ZERO,
-            primary_action: false,
-            secondary_action: false,
-            menu_action: false,
-            play_pause: false,
-            seek_forward: false,
-            seek_backward: false,
-            zoom: false,
-        }
+        Self::default()
     }
-    
-    /// Poll for gamepad events
-    pub fn update(&mut self) {
-        // Collect events first to avoid borrow checker issues
-        let events: Vec<_> = if let Some(gilrs) = &mut self.gilrs {
-            let mut events = Vec::new();
-            while let Some(Event { id: _, event, .. }) = gilrs.next_event() {
-                events.push(event);
+
+    pub fn handle_keyboard_input(&mut self, event: &KeyEvent) {
+        let Some(action) = KeyAction::from_key(&event.logical_key) else { return };
+        if event.state == ElementState::Pressed {
+            if !event.repeat {
+                info!("KEYBOARD: {:?}", action);
             }
-            events
+            self.pressed.push((action, event.repeat));
+            self.held.insert(action);
         } else {
-            Vec::new()
-        };
-        
-        // Process collected events
-        for event in events {
-            info!("RAW INPUT: {:?}", event);
-            match event {
-                EventType::ButtonPressed(button, _) => {
-                    self.handle_button(button, true);
-                }
-                EventType::ButtonReleased(button, _) => {
-                    self.handle_button(button, false);
-                }
-                EventType::AxisChanged(axis, value, _) => {
-                    self.handle_axis(axis, value);
-                }
-                _ => {}
-            }
+            self.held.remove(&action);
         }
-        
-        // Move cursor based on right stick
-        self.cursor_position += self.right_stick * 0.02;
-        self.cursor_position = self.cursor_position.clamp(Vec2::splat(-1.0), Vec2::splat(1.0));
     }
-    
-    fn handle_button(&mut self, button: Button, pressed: bool) {
-        match button {
-            Button::South => self.play_pause = pressed,       // X -> Play/Pause
-            Button::West => self.primary_action = pressed,    // Square -> Click
-            Button::East => self.secondary_action = pressed,  // Circle -> Back
-            Button::Start => self.menu_action = pressed,      // Options -> Menu UI
-            Button::RightTrigger2 => self.seek_forward = pressed, // R2 -> Seek Forward
-            Button::LeftTrigger2 => self.seek_backward = pressed, // L2 -> Seek Backward
-            Button::RightTrigger => self.zoom = pressed,      // R1 -> Zoom
-            _ => {}
+
+    pub fn handle_cursor_moved(&mut self, x: f64, y: f64) {
+        let pos = Vec2::new(x as f32, y as f32);
+        if let Some(last) = self.cursor {
+            self.mouse.delta += pos - last;
         }
+        self.cursor = Some(pos);
     }
-    
-    fn handle_axis(&mut self, axis: gilrs::Axis, value: f32) {
-        // Apply deadzone
-        let value = if value.abs() < 0.1 { 0.0 } else { value };
-        
-        match axis {
-            gilrs::Axis::LeftStickX => self.left_stick.x = value,
-            gilrs::Axis::LeftStickY => self.left_stick.y = -value, // Inverted
-            gilrs::Axis::RightStickX => self.right_stick.x = value,
-            gilrs::Axis::RightStickY => self.right_stick.y = -value,
-            _ => {}
+
+    pub fn handle_mouse_button(&mut self, button: MouseButton, state: ElementState) {
+        if button == MouseButton::Left {
+            self.mouse.left = state == ElementState::Pressed;
+            self.mouse.released |= !self.mouse.left;
         }
     }
 
-    pub fn handle_keyboard_input(&mut self, event: &winit::event::KeyEvent) {
-        let pressed = event.state == ElementState::Pressed;
-        match &event.logical_key {
-            Key::Named(NamedKey::Space) |
-            Key::Named(NamedKey::Enter) => self.play_pause = pressed,
-            
-            Key::Named(NamedKey::Escape) => self.menu_action = pressed,
-            
-            Key::Named(NamedKey::ArrowRight) => self.seek_forward = pressed,
-            Key::Named(NamedKey::ArrowLeft) => self.seek_backward = pressed,
-            
-             _ => {}
+    pub fn handle_mouse_wheel(&mut self, delta: MouseScrollDelta) {
+        const PX_PER_NOTCH: f32 = 40.0;
+        self.mouse.wheel += match delta {
+            MouseScrollDelta::LineDelta(_, y) => y,
+            MouseScrollDelta::PixelDelta(p) => p.y as f32 / PX_PER_NOTCH,
+        };
+    }
+
+    /// Queue this frame's keys as actions. With a panel open Space selects and
+    /// the arrows navigate (auto-repeating); otherwise left / right seek and
+    /// up / down jump chapters, once per press.
+    pub fn apply(&mut self, panel_open: bool) {
+        const ZOOM_STEP: f32 = 0.02;
+        for (key, repeat) in self.pressed.drain(..) {
+            let action = match key {
+                KeyAction::Left if !panel_open => Action::SeekBy(-actions::SEEK_STEP_US),
+                KeyAction::Right if !panel_open => Action::SeekBy(actions::SEEK_STEP_US),
+                KeyAction::Left => Action::Navigate(Direction::Left),
+                KeyAction::Right => Action::Navigate(Direction::Right),
+                KeyAction::Up if panel_open || !repeat => Action::Navigate(Direction::Up),
+                KeyAction::Down if panel_open || !repeat => Action::Navigate(Direction::Down),
+                _ if repeat => continue,
+                KeyAction::PlayPause if panel_open => Action::Select,
+                KeyAction::PlayPause => Action::PlayPause,
+                KeyAction::Confirm => Action::Confirm,
+                KeyAction::Back => Action::Back,
+                KeyAction::PreviousTrack => Action::PreviousTrack,
+                KeyAction::NextTrack => Action::NextTrack,
+                KeyAction::ToggleVr => Action::ToggleVr,
+                KeyAction::Menu => Action::ToggleMenu,
+                KeyAction::Recenter => Action::Recenter,
+                _ => continue,
+            };
+            actions::push(action);
+        }
+        if self.held.contains(&KeyAction::ZoomIn) {
+            actions::push(Action::Zoom(ZOOM_STEP));
         }
+        if self.held.contains(&KeyAction::ZoomOut) {
+            actions::push(Action::Zoom(-ZOOM_STEP));
+        }
+    }
+
+    /// Mouse input since the last call.
+    pub fn take_mouse(&mut self) -> MouseFrame {
+        let left = self.mouse.left;
+        let frame = std::mem::take(&mut self.mouse);
+        self.mouse.left = left;
+        frame
     }
 }
@@ -4,16 +4,20 @@
 //! and manages floating windows for web content.
 
 use android_activity::AndroidApp;
-use log::info;
+use log::{error, info};
 use std::sync::Arc;
 use std::time::Instant;
 use winit::application::ApplicationHandler;
-use winit::event::{ElementState, Touch, TouchPhase, WindowEvent};
+use winit::event::WindowEvent;
 use winit::event_loop::{ActiveEventLoop, EventLoop};
 use winit::platform::android::EventLoopBuilderExtAndroid;
 use winit::window::{Window, WindowId};
-use glam::Quat;
+use glam::{Mat4, Quat, Vec3};
 
+use actions::Action;
+
+mod actions;
+mod config;
 mod renderer;
 mod input;
 mod window_manager;
@@ -24,12 +28,66 @@ mod video_ndk;
 mod gamepad;
 mod thumbs;
 mod webview;
+mod hardware_buffer;
+mod playlist;
+mod resume;
+mod recent;
+mod http;
+mod hls;
+mod media_source;
+mod codecs;
+mod metadata;
+mod chapters;
+mod snapshot;
+mod media_session;
+mod gaze;
+mod gestures;
+mod arcore;
+mod storage;
+mod i18n;
+mod fonts;
+mod theme;
+mod status;
 
 /// Main application state
 struct VRApp {
     window: Option<Arc<Window>>,
     renderer: Option<renderer::Renderer>,
     sensors: Option<sensors::SensorInput>,
+    // Proximity reading last frame, and whether it paused the video
+    was_near: Option<bool>,
+    proximity_paused: bool,
+    /// Playback state last reported to the MediaSession (headphone / lock-screen buttons)
+    media_session: media_session::MediaSession,
+    /// Playback paused because the last controller dropped; resumed when one returns.
+    controller_paused: bool,
+    /// Since when the unlock buttons have been held together, while locked
+    unlock_held: Option<Instant>,
+    // ARCore positional tracking, while 6DOF is switched on
+    arcore: Option<arcore::ArTracker>,
+    // Drag-to-look orientation, used when the device has no head-tracking sensors
+    drag_look: sensors::DragLook,
+    // Right-stick view offset (yaw, pitch; radians) on top of head tracking
+    stick_look: (f32, f32),
+    // Left-stick mouse pointer for the UI
+    stick_pointer: gamepad::StickPointer,
+    // Hardware keyboard and mouse
+    input: input::InputState,
+    // Head-gaze pointer (dwell to click) while no controller is connected
+    gaze: gaze::GazePointer,
+    // Last frame's view orientation, for the gaze pointer, and the head position the
+    // eyes were drawn from (6DOF or the neck model)
+    view_orientation: Quat,
+    view_position: Vec3,
+    // Heading a menu was planted at (Auto placement), and last frame's panel turn
+    ui_yaw: Option<f32>,
+    ui_turn: Quat,
+    // Where the head points on a floating panel, if at one (panel id, 0…1 from the top
+    // left), and the same with its browser window if that panel is a browser
+    panel_pointer: Option<(u32, glam::Vec2)>,
+    web_pointer: Option<(u32, i32, glam::Vec2)>,
+    // The floating panel held with X, if any
+    grab: Option<PanelGrab>,
     last_frame_time: Instant,
     
     // UI State
@@ -37,12 +95,32 @@ struct VRApp {
     vr_ui: Option<ui::VrUi>,
     app: AndroidApp,
     
-    // Pinch-to-Zoom
-    touches: std::collections::HashMap<u64, (f64, f64)>,
-    initial_pinch_distance: Option<f64>,
+    // Touch gestures; the zoom and horizon a two-finger gesture started from
+    gestures: gestures::GestureRecognizer,
     initial_content_scale: f32,
+    initial_horizon_roll: f32,
     // NDK Video Decoder
     ndk_decoder: Option<video_ndk::NdkVideoDecoder>,
+    // Play queue (survives suspend/resume, unlike the UI)
+    playlist: playlist::Playlist,
+    // Saved per-file playback positions
+    resume: resume::ResumeStore,
+    // Tunables and UI options, saved to settings.toml as they change
+    config: config::Config,
+    // Recently played files (Media Center "Recent" tab)
+    recent: recent::RecentList,
+    // Probed duration / resolution / codecs per file, cached on disk
+    metadata: metadata::MetadataStore,
+    // Chapters of the playing file (path, chapters), read when it starts
+    chapters: Option<(String, Vec<chapters::Chapter>)>,
+    // Floating panels; video screens shown side by side live here
+    window_manager: window_manager::WindowManager,
+    // Extra decoders playing beside the main video
+    side_videos: Vec<SideVideo>,
+    // Next playlist entry, pre-buffered near the end of the current one
+    preloaded: Option<Preloaded>,
+    // Whether this file's next entry was already looked up (so it's done once)
+    preload_checked: bool,
     // Evdev Gamepad Reader
     gamepad_reader: Option<gamepad::GamepadReader>,
     // Stereoscopic 3D layout for video: 0 = mono/2D, 1 = side-by-side, 2 = over-under.
@@ -51,22 +129,290 @@ struct VRApp {
 
 impl VRApp {
     fn new(app: AndroidApp) -> Self {
+        let resume = resume::ResumeStore::load(app.internal_data_path());
+        let recent = recent::RecentList::load(app.internal_data_path());
+        let config = config::Config::load(app.internal_data_path());
+        let metadata = metadata::MetadataStore::load(app.internal_data_path());
+        // The floating panels as they were left last time
+        let mut window_manager = window_manager::WindowManager::new();
+        config.restore_panels(&mut window_manager);
+        window_manager.open_browsers(|url| webview::open_window(&app, url));
         Self {
             window: None,
             renderer: None,
             sensors: None,
+            was_near: None,
+            proximity_paused: false,
+            media_session: media_session::MediaSession::default(),
+            controller_paused: false,
+            unlock_held: None,
+            arcore: None,
+            drag_look: sensors::DragLook::default(),
+            stick_look: (0.0, 0.0),
+            stick_pointer: gamepad::StickPointer::default(),
+            input: input::InputState::new(),
+            gaze: gaze::GazePointer::default(),
+            view_orientation: Quat::IDENTITY,
+            view_position: Vec3::ZERO,
+            ui_yaw: None,
+            ui_turn: Quat::IDENTITY,
+            panel_pointer: None,
+            web_pointer: None,
+            grab: None,
             last_frame_time: Instant::now(),
             egui_state: None,
             vr_ui: None,
             app,
-            touches: std::collections::HashMap::new(),
-            initial_pinch_distance: None,
+            gestures: gestures::GestureRecognizer::new(),
             initial_content_scale: 1.0,
+            initial_horizon_roll: 0.0,
             ndk_decoder: None,
+            playlist: playlist::Playlist::new(),
+            resume,
+            config,
+            recent,
+            metadata,
+            chapters: None,
+            window_manager,
+            side_videos: Vec::new(),
+            preloaded: None,
+            preload_checked: false,
             gamepad_reader: Some(gamepad::GamepadReader::new()),
             stereo_mode: 0,
         }
     }
+
+    /// Carry out a touch gesture. Playback gestures go through the action queue.
+    fn apply_gesture(&mut self, gesture: gestures::Gesture, size: winit::dpi::PhysicalSize<u32>) {
+        use gestures::Gesture;
+        let vr_mode = self.renderer.as_ref().map(|r| r.vr_mode).unwrap_or(false);
+        match gesture {
+            // One finger: without sensors, drag looks around; in non-VR mode it
+            // otherwise pans zoomed content
+            Gesture::Drag(dx, dy) => {
+                if !self.sensors.as_ref().is_some_and(|s| s.is_available()) {
+                    self.drag_look.drag(dx as f32, dy as f32, size.width as f32);
+                } else if let (false, Some(ui)) = (vr_mode, &mut self.vr_ui) {
+                    // Content follows the finger: a full-width drag moves it one view width
+                    let zoom = ui.params.content_scale;
+                    ui.params.pan_by(-(dx / size.width as f64) as f32 / zoom,
+                                     -(dy / size.height as f64) as f32 / zoom);
+                }
+            }
+            Gesture::DragEnd => self.drag_look.release(),
+            Gesture::PinchStart => {
+                if let Some(ui) = &self.vr_ui {
+                    self.initial_content_scale = ui.params.content_scale;
+                    self.initial_horizon_roll = ui.params.horizon_roll;
+                }
+            }
+            Gesture::Pinch(factor) => {
+                if let Some(ui) = &mut self.vr_ui {
+                    ui.params.content_scale = (self.initial_content_scale * factor).clamp(0.5, 3.0);
+                }
+            }
+            // The picture turns with the fingers (rolling the eyes one way turns the scene the other)
+            Gesture::Twist(turned) => {
+                if let Some(ui) = &mut self.vr_ui {
+                    ui.params.horizon_roll = (self.initial_horizon_roll + turned.to_degrees())
+                        .clamp(-ui::MAX_HORIZON_ROLL, ui::MAX_HORIZON_ROLL);
+                }
+            }
+            // 2D controls on / off (single tap, non-VR mode only, above the seek band)
+            Gesture::Tap(_, y) => {
+                if let (false, Some(ui)) = (vr_mode, &mut self.vr_ui) {
+                    if y < size.height as f64 * 0.7 {
+                        ui.flat_controls.toggle();
+                    }
+                }
+            }
+            Gesture::DoubleTap => actions::push(Action::PlayPause),
+            Gesture::TwoFingerTap => actions::push(Action::Recenter),
+            Gesture::Seek(delta) => actions::push(Action::SeekBy(delta)),
+        }
+    }
+}
+
+/// Open `path` (a file or an http(s) URL) in a new NDK decoder. A `primed`
+/// decoder only pre-buffers: it decodes its first frame, then waits for `resume()`.
+fn open_decoder(path: &str, primed: bool) -> Result<video_ndk::NdkVideoDecoder, String> {
+    let mut decoder = video_ndk::NdkVideoDecoder::new();
+    if primed {
+        decoder.prime();
+    }
+    if path.contains("://") {
+        decoder.start_from_url(path)?;
+        return Ok(decoder);
+    }
+
+    // The decoder dups the fd, so the file closes here as usual
+    let file = std::fs::File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+    use std::os::fd::AsFd;
+    decoder.start_from_fd(file.as_fd())?;
+    Ok(decoder)
+}
+
+/// Flash the playback OSD for `event`; `position_us` is where playback is (or
+/// was sent).
+fn show_osd(ui: &mut ui::VrUi, resume: &resume::ResumeStore, decoder: &video_ndk::NdkVideoDecoder, event: ui::OsdEvent, position_us: i64) {
+    let title = resume.playing().map(playlist::display_name).unwrap_or("Video");
+    ui.show_osd(event, title, position_us, decoder.get_duration());
+}
+
+/// The next playlist entry, opened ahead of time so the switch is instant.
+struct Preloaded {
+    path: String,
+    decoder: video_ndk::NdkVideoDecoder,
+}
+
+/// Start pre-buffering the next entry this long before the current one ends.
+const PRELOAD_LEAD_US: i64 = 20_000_000;
+
+/// Seek video and audio to `pos` µs.
+fn seek_media(app: &AndroidApp, decoder: &video_ndk::NdkVideoDecoder, pos: i64) {
+    decoder.seek(pos);
+    video::seek_audio(app, (pos / 1000) as i32);
+    info!("Seek to {}", resume::format_time(pos));
+}
+
+/// Stop playback, remembering where it got to.
+fn stop_media(app: &AndroidApp, ndk_decoder: &mut Option<video_ndk::NdkVideoDecoder>, resume: &mut resume::ResumeStore) {
+    if let Some(mut decoder) = ndk_decoder.take() {
+        resume.record(decoder.get_position(), decoder.get_duration());
+        resume.save();
+        decoder.stop();
+        video::pause_audio(app);
+        info!("Playback stopped");
+    }
+}
+
+/// Stop whatever is playing and start `path` (a file or an http(s) URL): Java
+/// MediaPlayer for audio, the NDK decoder for video. The outgoing file's position
+/// is saved for resuming. A decoder pre-buffered for `path` is used as-is; one
+/// pre-buffered for anything else is dropped. Errors if the video can't be opened.
+fn start_media(
+    app: &AndroidApp,
+    ndk_decoder: &mut Option<video_ndk::NdkVideoDecoder>,
+    preloaded: &mut Option<Preloaded>,
+    resume: &mut resume::ResumeStore,
+    recent: &mut recent::RecentList,
+    path: &str,
+) -> Result<(), String> {
+    if let Some(decoder) = ndk_decoder {
+        resume.record(decoder.get_position(), decoder.get_duration());
+        resume.save();
+        decoder.stop();
+    }
+    resume.begin(path);
+    recent.played(path);
+    recent.save();
+
+    // Start audio playback via Java MediaPlayer (takes URLs too)
+    video::start_audio_from_path(app, path);
+
+    match preloaded.take() {
+        Some(p) if p.path == path => {
+            p.decoder.resume();
+            *ndk_decoder = Some(p.decoder);
+            info!("Started playback (pre-buffered): {}", path);
+        }
+        stale => {
+            drop(stale);
+            match open_decoder(path, false) {
+                Ok(decoder) => {
+                    *ndk_decoder = Some(decoder);
+                    info!("Started playback: {}", path);
+                }
+                Err(e) => {
+                    error!("Failed to start {}: {}", path, e);
+                    // Nothing to play: silence it rather than leave the old one's last frame up
+                    video::pause_audio(app);
+                    *ndk_decoder = None;
+                    return Err(e);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Side-by-side layout: every screen shrinks to this size, spaced this many
+/// radians apart (main screen left of centre, side videos to its right).
+const BESIDE_SCALE: f32 = 0.6;
+const BESIDE_YAW: f32 = 0.2;
+
+/// How far in front of the head a popped-out browser window opens, m.
+const WEB_WINDOW_DISTANCE: f32 = 2.2;
+
+/// A floating panel held with X: it stays `distance` m along the head's direction.
+struct PanelGrab {
+    id: u32,
+    distance: f32,
+}
+
+/// How close and how far a grabbed panel can be moved, m (inside the dome).
+const GRAB_MIN_DISTANCE: f32 = 0.8;
+const GRAB_MAX_DISTANCE: f32 = 4.5;
+
+/// A second video on its own screen next to the main one. Video only: audio
+/// stays with the main file, and it follows the main video's play/pause.
+struct SideVideo {
+    slot: usize, // renderer video slot (1..MAX_VIDEOS)
+    panel: u32,  // window_manager panel id
+    decoder: video_ndk::NdkVideoDecoder,
+}
+
+/// Play `path` on another screen beside the main video, replacing the newest side
+/// video when every slot is taken. The main screen moves over to make room.
+fn open_beside(
+    side_videos: &mut Vec<SideVideo>,
+    window_manager: &mut window_manager::WindowManager,
+    path: &str,
+) {
+    let decoder = match open_decoder(path, false) {
+        Ok(decoder) => decoder,
+        Err(e) => {
+            error!("Failed to open {} beside: {}", path, e);
+            ui::post_toast(i18n::trf("Couldn't open {} beside", &[&playlist::display_name(path)]));
+            return;
+        }
+    };
+    decoder.set_loop_at_eos(true);
+
+    let free = (1..renderer::MAX_VIDEOS).find(|s| side_videos.iter().all(|v| v.slot != *s));
+    let slot = match free {
+        Some(slot) => slot,
+        None => match side_videos.pop() {
+            Some(mut old) => {
+                old.decoder.stop();
+                window_manager.close_panel(old.panel);
+                old.slot
+            }
+            None => return, // MAX_VIDEOS == 1: no room for a second screen
+        },
+    };
+    if window_manager.video_panel(0).is_none() {
+        window_manager.spawn_video(0, -BESIDE_YAW, BESIDE_SCALE);
+    }
+    let panel = window_manager.spawn_video(slot, BESIDE_YAW * (2 * slot - 1) as f32, BESIDE_SCALE);
+    info!("Playing {} beside (slot {})", path, slot);
+    side_videos.push(SideVideo { slot, panel, decoder });
+}
+
+/// Stop side video `index`; the main screen goes back to full size after the last.
+fn close_beside(
+    side_videos: &mut Vec<SideVideo>,
+    window_manager: &mut window_manager::WindowManager,
+    index: usize,
+) {
+    let mut video = side_videos.remove(index);
+    video.decoder.stop();
+    window_manager.close_panel(video.panel);
+    if side_videos.is_empty() {
+        if let Some(main) = window_manager.video_panel(0).map(|p| p.id) {
+            window_manager.close_panel(main);
+        }
+    }
 }
 
 impl ApplicationHandler for VRApp {
@@ -82,6 +428,7 @@ impl ApplicationHandler for VRApp {
         // Initialize wgpu renderer
         self.renderer = Some(pollster::block_on(renderer::Renderer::new(window.clone())));
         info!("Renderer initialized");
+        webview::set_hardware_frames(self.renderer.as_ref().is_some_and(|r| r.imports_hardware_buffers()));
         
         // Initialize sensors only once (preserve across pause/resume)
         if self.sensors.is_none() {
@@ -98,8 +445,16 @@ impl ApplicationHandler for VRApp {
         }
         
         // Initialize UI
+        i18n::detect(&self.app);
         let ctx = egui::Context::default();
-        self.vr_ui = Some(ui::VrUi::new(&ctx));
+        fonts::install(&ctx);
+        let mut vr_ui = ui::VrUi::new(&ctx);
+        self.config.apply(&mut vr_ui);
+        i18n::select(vr_ui.params.language);
+        if !vr_ui.params.tutorial_seen {
+            vr_ui.show_tutorial();
+        }
+        self.vr_ui = Some(vr_ui);
         
         let state = egui_winit::State::new(
             ctx.clone(),
@@ -116,8 +471,20 @@ impl ApplicationHandler for VRApp {
 
     fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
         info!("App suspended - releasing GPU resources");
+        if let Some(decoder) = &self.ndk_decoder {
+            self.resume.record(decoder.get_position(), decoder.get_duration());
+            self.resume.save();
+        }
+        self.recent.save();
+        self.metadata.save();
+        if let Some(ui) = &self.vr_ui {
+            self.config.save(ui, &self.window_manager);
+        }
         self.renderer = None;
         self.sensors = None;
+        self.arcore = None;
+        sensors::set_external(&self.app, false);
+        gamepad::set_motion_aim(&self.app, false);
         self.window = None;
         self.egui_state = None;
         self.vr_ui = None;
@@ -129,6 +496,10 @@ impl ApplicationHandler for VRApp {
         window_id: WindowId,
         event: WindowEvent,
     ) {
+        // Locked: touches go nowhere (a face or a pocket against the screen)
+        if matches!(event, WindowEvent::Touch(_)) && self.vr_ui.as_ref().is_some_and(|ui| ui.controls_locked()) {
+            return;
+        }
         // Pass event to egui
         let response = if let (Some(state), Some(window)) = (&mut self.egui_state, &self.window) {
              state.on_window_event(window, &event)
@@ -150,20 +521,38 @@ impl ApplicationHandler for VRApp {
                 let now = Instant::now();
                 let dt = (now - self.last_frame_time).as_secs_f32();
                 self.last_frame_time = now;
+
+                // A single tap fires once no second one followed
+                let locked = self.vr_ui.as_ref().is_some_and(|ui| ui.controls_locked());
+                if let (Some(gesture), Some(window), false) = (self.gestures.poll(), &self.window, locked) {
+                    let size = window.inner_size();
+                    self.apply_gesture(gesture, size);
+                }
                 
                 // Check for pending video FD from file picker
                 if let Some(fd) = video::get_pending_fd() {
-                    info!("Got pending video FD: {}, starting NDK decoder", fd);
+                    use std::os::fd::{AsFd, AsRawFd};
+                    info!("Got pending video FD: {}, starting NDK decoder", fd.as_raw_fd());
                     // Stop existing decoder if any
                     if let Some(mut old_decoder) = self.ndk_decoder.take() {
+                        self.resume.record(old_decoder.get_position(), old_decoder.get_duration());
+                        self.resume.save();
                         old_decoder.stop();
                     }
+                    // Picked files have no path to key a resume position on
+                    self.resume.begin_untracked();
                     // Start new decoder with the FD
                     let mut decoder = video_ndk::NdkVideoDecoder::new();
-                    if let Err(e) = decoder.start_from_fd(fd) {
-                        log::error!("Failed to start decoder from FD: {}", e);
+                    match decoder.start_from_fd(fd.as_fd()) {
+                        Ok(()) => self.ndk_decoder = Some(decoder),
+                        Err(e) => {
+                            log::error!("Failed to start decoder from FD: {}", e);
+                            video::pause_audio(&self.app);
+                            if let Some(ui) = self.vr_ui.as_mut() {
+                                ui.playback_error.show(&video_ndk::DecodeError::Source(e), None);
+                            }
+                        }
                     }
-                    self.ndk_decoder = Some(decoder);
                 }
                 
                 // UI Logic
@@ -186,6 +575,74 @@ impl ApplicationHandler for VRApp {
                         egui::Pos2::ZERO,
                         egui::vec2(2048.0, 2048.0),
                     ));
+                    let vr_mode = self.renderer.as_ref().is_some_and(|r| r.vr_mode);
+                    // In 2D the canvas is laid flat over the screen instead (renderer.rs
+                    // render_flat_ui): the screen's shape, scaled so its longer side fills the
+                    // texture, with touches scaled to match
+                    if !vr_mode {
+                        let size = window.inner_size();
+                        let scale = renderer::Renderer::ui_flat_scale(size.width, size.height);
+                        raw_input.screen_rect = Some(egui::Rect::from_min_size(
+                            egui::Pos2::ZERO,
+                            egui::vec2(size.width as f32, size.height as f32) * scale,
+                        ));
+                        for event in &mut raw_input.events {
+                            match event {
+                                egui::Event::PointerMoved(pos)
+                                | egui::Event::PointerButton { pos, .. }
+                                | egui::Event::Touch { pos, .. } => *pos = (pos.to_vec2() * scale).to_pos2(),
+                                _ => {}
+                            }
+                        }
+                    }
+                    // Controller pointer events go in with the frame's other input (VR mode only,
+                    // like the panels themselves)
+                    let pad = gamepad::get_state();
+                    // Motion aim: L2 held over a panel points with the controller's gyro
+                    let locked = ui.controls_locked();
+                    let aiming = vr_mode && ui.params.motion_aim && ui.has_open_panel() && gamepad::l2_held(&pad) && !locked;
+                    gamepad::set_motion_aim(&self.app, aiming);
+                    let aim = aiming.then(gamepad::take_aim_delta);
+                    // The mouse moves the same pointer over panels; elsewhere it drags the view
+                    let mouse = self.input.take_mouse();
+                    let size = window.inner_size();
+                    let over_panel = vr_mode && ui.has_open_panel();
+                    let mut pointer_pad = if locked { gamepad::GamepadState::default() } else { pad.clone() };
+                    if !ui.params.stick_pointer {
+                        pointer_pad.left_stick_x = 0.0;
+                        pointer_pad.left_stick_y = 0.0;
+                    }
+                    if over_panel {
+                        let scale = gamepad::UI_CANVAS / size.height.max(1) as f32;
+                        self.stick_pointer.nudge(egui::vec2(mouse.delta.x, mouse.delta.y) * scale, &mut raw_input.events);
+                        pointer_pad.btn_south |= mouse.left;
+                    } else {
+                        if mouse.left {
+                            if self.sensors.as_ref().is_some_and(|s| s.is_available()) {
+                                let per_px = std::f32::consts::PI / size.width.max(1) as f32;
+                                self.stick_look.0 = (self.stick_look.0 - mouse.delta.x * per_px) % std::f32::consts::TAU;
+                                self.stick_look.1 = (self.stick_look.1 - mouse.delta.y * per_px)
+                                    .clamp(-MAX_STICK_PITCH, MAX_STICK_PITCH);
+                            } else {
+                                self.drag_look.drag(mouse.delta.x, mouse.delta.y, size.width as f32);
+                            }
+                        }
+                        if mouse.released {
+                            self.drag_look.release();
+                        }
+                        const WHEEL_ZOOM: f32 = 0.1;
+                        if mouse.wheel != 0.0 {
+                            actions::push(Action::Zoom(mouse.wheel * WHEEL_ZOOM));
+                        }
+                    }
+                    self.stick_pointer.update(&pointer_pad, dt, over_panel, aim, &mut raw_input.events);
+                    // No controller: the head points, holding still on a control clicks it
+                    ui.gaze_active = vr_mode && ui.params.gaze_dwell > 0.0 && gamepad::controllers().is_empty();
+                    let over_ui = state.egui_ctx().is_pointer_over_area();
+                    self.gaze.update(self.ui_turn.inverse() * self.view_orientation, ui.params.content_scale, ui.params.gaze_dwell,
+                        ui.gaze_active, over_ui, &mut raw_input.events);
+                    // D-pad / ✕ for egui's focus (settings, Media Center toolbar, queue buttons), queued last frame
+                    raw_input.events.extend(ui.take_focus_events(state.egui_ctx()));
                     state.egui_ctx().begin_frame(raw_input);
 
                     // Media Center thumbnails (hardware-accelerated): upload finished
@@ -205,7 +662,17 @@ impl ApplicationHandler for VRApp {
                         }
                     }
 
+                    // Target time of a touch swipe in progress
+                    ui.seek_preview = match (self.gestures.swipe_preview(), &self.ndk_decoder) {
+                        (Some(delta), Some(d)) if d.is_seekable() => Some(ui::SeekPreview {
+                            target_us: (d.get_position() + delta).clamp(0, if d.get_duration() > 0 { d.get_duration() } else { i64::MAX }),
+                            delta_us: delta,
+                        }),
+                        _ => None,
+                    };
                     ui.render(state.egui_ctx(), self.renderer.as_ref().map(|r| r.vr_mode).unwrap_or(false));
+                    self.stick_pointer.paint(state.egui_ctx());
+                    self.gaze.paint(state.egui_ctx());
                     
                     let output = state.egui_ctx().end_frame();
                     
@@ -214,35 +681,19 @@ impl ApplicationHandler for VRApp {
                     full_output = Some(output);
                     ctx_clone = Some(state.egui_ctx().clone());
                     
-                    // Apply UI Params
-                    // 1. Recenter
-                    if ui.params.recenter_flag {
-                         if let Some(sensors) = &self.sensors {
-                            sensors.recenter();
-                         }
-                         ui.params.recenter_flag = false; // Reset flag
-                    }
-                    
-                    // 2. Gyro Toggle (handled in update below)
-                    // 3. Distortion (passed to renderer later)
+                    // Apply UI Params (dock buttons queue actions, applied below)
+                    // 1. Gyro Toggle (handled in update below)
+                    // 2. Distortion (passed to renderer later)
 
-                    // 4. Check Selection
+                    // 3. Check Selection
                     if ui.params.select_video_flag {
                          info!("UI: Select Video Requested");
                          ui.params.select_video_flag = false;
                          video::VideoManager::pick_video(&self.app);
                     }
-                    
-                    // 5. Check Exit Request
-                    if ui.params.vr_exit_requested {
-                        if let Some(renderer) = &mut self.renderer {
-                             renderer.vr_mode = false;
-                             info!("Exited VR Mode via Menu");
-                        }
-                        ui.params.vr_exit_requested = false; // Reset flag
-                    }
 
-                    // 5b. Browser: engine activation, URL load, and toolbar nav flags.
+
+                    // 4. Browser: engine activation, URL load, and toolbar nav flags.
                     if let Some(engine) = ui.params.pending_engine.take() {
                         webview::set_engine(&self.app, engine);
                     }
@@ -258,96 +709,431 @@ impl ApplicationHandler for VRApp {
                     if let Some((w, h)) = ui.web_browser.pending_resize.take() {
                         webview::resize(&self.app, w, h);
                     }
+                    self.window_manager.set_auto_layout(ui.params.arrange_windows);
+                    // The page leaves the screen, tab and all, for a window of its own where the
+                    // head points (or beside the others); the screen goes on with its next tab
+                    if std::mem::take(&mut ui.web_browser.pop_out) {
+                        if let Some(window) = webview::pop_out(&self.app) {
+                            let ahead = self.view_orientation * Vec3::new(0.0, 0.0, -WEB_WINDOW_DISTANCE);
+                            self.window_manager.spawn_browser(&ui.web_browser.current_url, window, ahead);
+                            ui.params.web_mode = false;
+                            ui.main_menu_visible = false;
+                        }
+                    }
+                    for (window, url) in webview::take_window_urls() {
+                        self.window_manager.set_browser_url(window, &url);
+                    }
+                    self.panel_pointer = self.window_manager.hit(self.view_position, self.view_orientation * Vec3::NEG_Z);
+                    self.web_pointer = self.panel_pointer
+                        .and_then(|(id, at)| Some((id, self.window_manager.browser_window(id)?, at)));
+
+                    let playing = self.resume.playing();
+                    if self.chapters.as_ref().map(|(p, _)| p.as_str()) != playing {
+                        self.chapters = playing.map(|p| (p.to_string(), chapters::read(p)));
+                    }
                     
-                    // 6. Handle Playback Controls (from UI buttons)
-                    if ui.params.toggle_play_pause {
-                        if let Some(decoder) = &self.ndk_decoder {
-                            if decoder.is_paused() {
-                                decoder.resume();
-                                info!("Video Resumed");
-                            } else {
-                                decoder.pause();
-                                info!("Video Paused");
+                    // 7. Handle Gamepad Actions (poll once per frame)
+                    gamepad::set_repeat(ui.params.nav_repeat_delay, ui.params.nav_repeat_rate);
+                    gamepad::set_haptics(ui.params.haptics);
+                    gamepad::set_remote_role(ui.params.remote_controller);
+                    gamepad::set_stick_tuning(ui.params.left_stick, ui.params.right_stick);
+                    i18n::select(ui.params.language);
+                    self.config.update(ui, &self.window_manager);
+                    ui.controller_battery = gamepad::battery(&self.app);
+                    ui.device_status = if ui.params.status_hud { status::poll(&self.app) } else { None };
+                    let mut gp_actions = gamepad::poll_actions();
+                    // Locked: the controller only unlocks (hold L1 + R1) and looks around
+                    if locked {
+                        let held = actions::UNLOCK.iter().all(|&b| gamepad::is_held(&pad, b));
+                        if held {
+                            self.unlock_held.get_or_insert_with(Instant::now);
+                        } else {
+                            self.unlock_held = None;
+                        }
+                        ui.unlock_progress = self.unlock_held.map_or(0.0, |since|
+                            (since.elapsed().as_secs_f32() / actions::UNLOCK_HOLD.as_secs_f32()).min(1.0));
+                        if ui.unlock_progress >= 1.0 {
+                            self.unlock_held = None;
+                            ui.unlock_controls();
+                        } else if gp_actions.any_press() && !held {
+                            ui.locked_press();
+                        }
+                        gp_actions = gamepad::GamepadActions {
+                            right_stick_x: gp_actions.right_stick_x,
+                            right_stick_y: gp_actions.right_stick_y,
+                            ..Default::default()
+                        };
+                    }
+                    // A grabbed panel follows the head until X is let go, snapping into line
+                    // near the grid and its neighbours: the left stick pushes it away or
+                    // pulls it in, R2 / L2 grow and shrink it. It owns the
+                    // controller meanwhile, all but looking around.
+                    if let Some(grab) = &mut self.grab {
+                        if locked || !gamepad::is_held(&pad, gamepad::Button::Cross) || self.window_manager.panel(grab.id).is_none() {
+                            self.grab = None;
+                            self.window_manager.end_snap();
+                        } else {
+                            const PUSH_SPEED: f32 = 1.5;   // m/s at full tilt
+                            const RESIZE_SPEED: f32 = 0.8; // growth per second at a full trigger
+                            const TRIGGER_DEADZONE: f32 = 0.08;
+                            grab.distance = (grab.distance - gp_actions.left_stick_y * PUSH_SPEED * dt)
+                                .clamp(GRAB_MIN_DISTANCE, GRAB_MAX_DISTANCE);
+                            let target = self.view_orientation * Vec3::new(0.0, 0.0, -grab.distance);
+                            self.window_manager.place_snapped(grab.id, target);
+                            let resize = gp_actions.r2_trigger - gp_actions.l2_trigger;
+                            if resize.abs() > TRIGGER_DEADZONE {
+                                self.window_manager.scale_panel(grab.id, 1.0 + resize * RESIZE_SPEED * dt);
                             }
+                            gp_actions = gamepad::GamepadActions {
+                                right_stick_x: gp_actions.right_stick_x,
+                                right_stick_y: gp_actions.right_stick_y,
+                                ..Default::default()
+                            };
                         }
-                        ui.params.toggle_play_pause = false;
                     }
+                    // The stick pointer, while shown, owns the left stick and X
+                    if self.stick_pointer.is_active() {
+                        gp_actions.play_pause = false;
+                        gp_actions.stop = false;
+                        gp_actions.left_stick_x = 0.0;
+                        gp_actions.left_stick_y = 0.0;
+                    }
+                    // Everything else asks through the action queue: dock buttons, keys,
+                    // taps, media buttons. Menu actions go to the open panel like
+                    // controller buttons; the rest join what the controller adds below.
+                    self.input.apply(ui.has_open_panel());
+                    let mut frame_actions = actions::drain();
+                    if locked {
+                        // Media buttons and keys still play, pause and seek; the menus stay shut
+                        frame_actions.retain(|a| !matches!(a, Action::Navigate(_) | Action::Select | Action::Confirm
+                            | Action::Back | Action::ToggleMenu));
+                    }
+                    if ui.settings_open() {
+                        // egui already has the keyboard's arrows, Space and Enter for the settings focus
+                        frame_actions.retain(|a| !matches!(a, Action::Navigate(_) | Action::Select | Action::Confirm));
+                    }
+                    actions::fold_menu(&mut frame_actions, &mut gp_actions);
                     
-                    if ui.params.seek_forward_flag {
+                    // ── Always-active controls ──────────────────────────────
+                    // Quit (PS, or hold Options): save what a suspend would, then leave
+                    if gp_actions.exit_app {
+                        info!("Quit requested from the controller");
                         if let Some(decoder) = &self.ndk_decoder {
-                            let pos = decoder.get_position();
-                            decoder.seek(pos + 10_000_000); // +10 seconds
-                            info!("Seek Forward +10s");
+                            self.resume.record(decoder.get_position(), decoder.get_duration());
+                            self.resume.save();
                         }
-                        ui.params.seek_forward_flag = false;
+                        self.recent.save();
+                        self.metadata.save();
+                        event_loop.exit();
                     }
-                    
-                    if ui.params.seek_backward_flag {
+                    // Close every panel (○ twice)
+                    if gp_actions.close_all {
+                        ui.close_all_panels();
+                    }
+                    // Recenter (L3), VR/2D (R3), queue skips (hold L1/R1; hold R1 is play beside
+                    // in the Media Center), chords (L1 + R1, Create + Options)
+                    frame_actions.append(&mut gp_actions.chords);
+                    let media_center = ui.file_browser.visible && !ui.url_dialog.visible && !ui.keyboard.visible;
+                    for (pressed, action) in [
+                        (gp_actions.reset_view, Action::Recenter),
+                        (gp_actions.toggle_vr_mode, Action::ToggleVr),
+                        (gp_actions.next_track && !media_center, Action::NextTrack),
+                        (gp_actions.prev_track, Action::PreviousTrack),
+                    ] {
+                        if pressed { frame_actions.push(action); }
+                    }
+                    // Headset removed / put back (proximity): pause and resume on the change
+                    let near = self.sensors.as_ref().and_then(|s| s.is_near());
+                    if near != self.was_near {
                         if let Some(decoder) = &self.ndk_decoder {
-                            let pos = decoder.get_position();
-                            decoder.seek((pos - 10_000_000).max(0)); // -10 seconds
-                            info!("Seek Backward -10s");
+                            if near == Some(false) && ui.params.proximity_pause && !decoder.is_paused() {
+                                decoder.pause();
+                                self.proximity_paused = true;
+                                info!("Proximity: headset removed, paused");
+                            } else if near == Some(true) && std::mem::take(&mut self.proximity_paused) {
+                                decoder.resume();
+                                info!("Proximity: headset back, resumed");
+                            }
                         }
-                        ui.params.seek_backward_flag = false;
+                        self.was_near = near;
                     }
-                    
-                    // 7. Handle Gamepad Actions (poll once per frame)
-                    let gp_actions = gamepad::poll_actions();
-                    
-                    // ── Always-active controls ──────────────────────────────
-                    // Recenter (L3)
-                    if gp_actions.reset_view {
-                        if let Some(sensors) = &self.sensors { sensors.recenter(); }
-                    }
-                    // VR/2D toggle (R3)
-                    if gp_actions.toggle_vr_mode {
-                        if let Some(renderer) = &mut self.renderer {
-                            renderer.vr_mode = !renderer.vr_mode;
+                    // Controller dropped (Bluetooth): pause until one is back
+                    for connection in gamepad::take_connections() {
+                        match connection {
+                            gamepad::Connection::Disconnected(_) if gamepad::controllers().is_empty() => {
+                                // Nothing left to unlock with
+                                ui.unlock_controls();
+                                ui.show_toast(i18n::tr("Controller disconnected"));
+                                if let Some(decoder) = &self.ndk_decoder {
+                                    if !decoder.is_paused() {
+                                        decoder.pause();
+                                        self.controller_paused = true;
+                                        info!("Controller disconnected, paused");
+                                    }
+                                }
+                            }
+                            gamepad::Connection::Connected(_) if self.controller_paused => {
+                                self.controller_paused = false;
+                                ui.show_toast(i18n::tr("Controller connected"));
+                                if let Some(decoder) = &self.ndk_decoder {
+                                    decoder.resume();
+                                    info!("Controller reconnected, resumed");
+                                }
+                            }
+                            _ => {}
                         }
                     }
 
                     // ── Menu-gated controls ─────────────────────────────────
-                    if ui.file_browser.visible {
-                        // Media Center: left-stick coverflow sweep + D-pad; X open; O up; △ close
-                        ui.file_browser.handle_stick(gp_actions.left_stick_x);
-                        if gp_actions.nav_up   || gp_actions.nav_left  { ui.file_browser.move_up(); }
-                        if gp_actions.nav_down || gp_actions.nav_right { ui.file_browser.move_down(); }
-                        if gp_actions.play_pause || gp_actions.confirm { ui.file_browser.select_current(); }
+                    if ui.tutorial_visible() {
+                        // First-run tutorial: X / ▶ next, ○ / ◀ back, △ skip
+                        if gp_actions.play_pause || gp_actions.nav_right { ui.tutorial_next(); }
+                        if gp_actions.back || gp_actions.nav_left { ui.tutorial_back(); }
+                        if gp_actions.toggle_ui { ui.close_tutorial(); }
+                    } else if ui.playback_error.is_modal() {
+                        // Playback failure card: X try again, ○/△ close
+                        if gp_actions.play_pause { ui.playback_error.try_again(); }
+                        if gp_actions.back || gp_actions.toggle_ui { ui.playback_error.dismiss(); }
+                    } else if ui.url_dialog.visible {
+                        // URL dialog: D-pad keys, X type, □ delete, △ shift, L1/R1 recent URLs, Options play, ○ cancel
+                        if gp_actions.nav_up    { ui.keyboard.move_up(); }
+                        if gp_actions.nav_down  { ui.keyboard.move_down(); }
+                        if gp_actions.nav_left  { ui.keyboard.move_left(); }
+                        if gp_actions.nav_right { ui.keyboard.move_right(); }
+                        if gp_actions.play_pause { ui.keyboard.press(); }
+                        if gp_actions.confirm    { ui.keyboard.backspace(); }
+                        if gp_actions.toggle_ui  { ui.keyboard.toggle_shift(); }
+                        if gp_actions.seek_back    { ui.url_history_step(true); }
+                        if gp_actions.seek_forward { ui.url_history_step(false); }
+                        if gp_actions.open_settings { ui.submit_url_dialog(); }
+                        if gp_actions.back { ui.close_url_dialog(); }
+                    } else if ui.keyboard.visible {
+                        // Media Center filter / rename / lens preset name keyboard: D-pad keys, X type,
+                        // □ delete, △ shift; filter: Options/○ done; rename and preset: Options save, ○ cancel
+                        if gp_actions.nav_up    { ui.keyboard.move_up(); }
+                        if gp_actions.nav_down  { ui.keyboard.move_down(); }
+                        if gp_actions.nav_left  { ui.keyboard.move_left(); }
+                        if gp_actions.nav_right { ui.keyboard.move_right(); }
+                        if gp_actions.play_pause { ui.keyboard.press(); }
+                        if gp_actions.confirm    { ui.keyboard.backspace(); }
+                        if gp_actions.toggle_ui  { ui.keyboard.toggle_shift(); }
+                        if ui.renaming() {
+                            if gp_actions.open_settings { ui.submit_rename(); }
+                            if gp_actions.back { ui.cancel_rename(); }
+                        } else if ui.naming_preset() {
+                            if gp_actions.open_settings { ui.submit_preset_name(); }
+                            if gp_actions.back { ui.cancel_preset_name(); }
+                        } else {
+                            ui.update_search();
+                            if gp_actions.open_settings || gp_actions.back { ui.close_search(); }
+                        }
+                    } else if ui.file_browser.visible && ui.file_browser.entry_menu.is_some() {
+                        // Media Center entry menu: D-pad up/down, X choose, ○/△ cancel
+                        if let Some(menu) = &mut ui.file_browser.entry_menu {
+                            if gp_actions.nav_up   { menu.move_up(); }
+                            if gp_actions.nav_down { menu.move_down(); }
+                        }
+                        if gp_actions.play_pause {
+                            gamepad::rumble(&self.app, gamepad::Haptic::Select);
+                            ui.entry_menu_activate();
+                        }
+                        if gp_actions.back || gp_actions.toggle_ui { ui.file_browser.entry_menu = None; }
+                    } else if ui.file_browser.visible && ui.file_browser.multi_select {
+                        // Media Center multi-select: stick / D-pad browse; □ mark, hold □ mark all; X play all;
+                        // R1 queue all; ○ or hold Create done; △ close
+                        ui.file_browser.handle_stick(gp_actions.left_stick_x, gp_actions.left_stick_y);
+                        for (pressed, dir) in [
+                            (gp_actions.nav_up, actions::Direction::Up),
+                            (gp_actions.nav_down, actions::Direction::Down),
+                            (gp_actions.nav_left, actions::Direction::Left),
+                            (gp_actions.nav_right, actions::Direction::Right),
+                        ] {
+                            if pressed { ui.file_browser.nav(dir); }
+                        }
+                        if gp_actions.confirm_tap || gp_actions.confirm_hold {
+                            gamepad::rumble(&self.app, gamepad::Haptic::Select);
+                        }
+                        if gp_actions.confirm_tap { ui.file_browser.toggle_mark_current(); }
+                        if gp_actions.confirm_hold { ui.file_browser.mark_all(); }
+                        if gp_actions.play_pause { ui.file_browser.submit_marked(true); }
+                        if gp_actions.seek_forward { ui.file_browser.submit_marked(false); }
+                        if gp_actions.back || gp_actions.multi_select { ui.file_browser.toggle_multi_select(); }
+                        if gp_actions.toggle_ui { ui.file_browser.visible = false; }
+                    } else if ui.file_browser.visible {
+                        // Media Center: left-stick coverflow sweep / tab flick + D-pad (up past the breadcrumb onto the
+                        // toolbar buttons); X open, or click the toolbar button; □ details, hold □ entry menu;
+                        // R1 queue, hold R1 play beside;
+                        // L1 search; L2/R2 filter letter; Create grid; Options URL; O clear filter / up; △ close
+                        ui.file_browser.handle_stick(gp_actions.left_stick_x, gp_actions.left_stick_y);
+                        ui.file_browser.handle_triggers(gp_actions.l2_trigger, gp_actions.r2_trigger);
+                        for (pressed, dir) in [
+                            (gp_actions.nav_up, actions::Direction::Up),
+                            (gp_actions.nav_down, actions::Direction::Down),
+                            (gp_actions.nav_left, actions::Direction::Left),
+                            (gp_actions.nav_right, actions::Direction::Right),
+                        ] {
+                            if pressed { ui.file_browser.nav(dir); }
+                        }
+                        if gp_actions.play_pause || gp_actions.seek_forward || gp_actions.confirm_hold {
+                            gamepad::rumble(&self.app, gamepad::Haptic::Select);
+                        }
+                        if gp_actions.play_pause {
+                            if ui.file_browser.toolbar_focused() { ui.focus_key(egui::Key::Enter); } else { ui.file_browser.select_current(); }
+                        }
+                        // □ waits for the release, so holding it opens the menu without toggling the pane
+                        if gp_actions.confirm_tap { ui.file_browser.toggle_details(); }
+                        if gp_actions.confirm_hold { ui.file_browser.open_entry_menu(); }
+                        if gp_actions.seek_forward { ui.file_browser.enqueue_current(); }
+                        if gp_actions.next_track { ui.file_browser.open_beside_current(); }
+                        if gp_actions.seek_back { ui.open_search(); }
+                        if gp_actions.open_file_picker { ui.file_browser.toggle_view(); }
+                        if gp_actions.multi_select { ui.file_browser.toggle_multi_select(); }
                         if gp_actions.back      { ui.file_browser.go_back(); }
                         if gp_actions.toggle_ui { ui.file_browser.visible = false; }
+                        if gp_actions.open_settings { ui.open_url_dialog(); }
+                    } else if ui.resume_prompt.visible {
+                        // Resume prompt: X resume, ○ start over
+                        if gp_actions.play_pause || gp_actions.confirm { ui.resume_prompt.accept(); }
+                        if gp_actions.back { ui.resume_prompt.dismiss(); }
+                    } else if ui.queue.visible {
+                        // Queue panel: D-pad up/down select (down past the end onto the buttons, left/right
+                        // between them), X play or click the button, □ remove, ○/△ close
+                        for (pressed, dir) in [
+                            (gp_actions.nav_up, actions::Direction::Up),
+                            (gp_actions.nav_down, actions::Direction::Down),
+                            (gp_actions.nav_left, actions::Direction::Left),
+                            (gp_actions.nav_right, actions::Direction::Right),
+                        ] {
+                            if pressed { ui.queue.nav(dir); }
+                        }
+                        if gp_actions.play_pause {
+                            gamepad::rumble(&self.app, gamepad::Haptic::Select);
+                            if ui.queue.controls_focused() { ui.focus_key(egui::Key::Enter); } else { ui.queue.play_selected(); }
+                        }
+                        if gp_actions.confirm    { ui.queue.remove_selected(); }
+                        if gp_actions.back || gp_actions.toggle_ui { ui.queue.visible = false; }
+                    } else if ui.settings_open() {
+                        // Settings: D-pad moves the focus (←/→ step a focused slider), X clicks it,
+                        // L1/R1 switch section, ○ closes an open dropdown or goes back to the dock, △ close
+                        for (pressed, key) in [
+                            (gp_actions.nav_up, egui::Key::ArrowUp),
+                            (gp_actions.nav_down, egui::Key::ArrowDown),
+                            (gp_actions.nav_left, egui::Key::ArrowLeft),
+                            (gp_actions.nav_right, egui::Key::ArrowRight),
+                            (gp_actions.play_pause, egui::Key::Enter),
+                        ] {
+                            if pressed { ui.focus_key(key); }
+                        }
+                        if gp_actions.seek_back    { ui.settings_step_section(false); }
+                        if gp_actions.seek_forward { ui.settings_step_section(true); }
+                        if gp_actions.back {
+                            if state.egui_ctx().memory(|m| m.any_popup_open()) {
+                                ui.focus_key(egui::Key::Escape);
+                            } else {
+                                ui.close_settings();
+                            }
+                        }
+                        if gp_actions.toggle_ui {
+                            ui.close_settings();
+                            ui.main_menu_visible = false;
+                        }
                     } else if ui.main_menu_visible {
-                        // Dock: D-pad left/right move highlight, X/□ activate, △/○ close
+                        // Dock: D-pad left/right move highlight (nudge the seek bar once ▼ is on it), X/□ activate, △/○ close
                         if gp_actions.nav_left  { ui.dock_move_left(); }
                         if gp_actions.nav_right { ui.dock_move_right(); }
+                        if gp_actions.nav_down  { ui.dock_move_down(); }
+                        if gp_actions.nav_up    { ui.dock_move_up(); }
                         if gp_actions.play_pause || gp_actions.confirm { ui.dock_activate(); }
                         if gp_actions.toggle_ui || gp_actions.back { ui.main_menu_visible = false; }
                     } else {
                         // No menu: △ opens dock, Create opens media center, X play/pause,
-                        // L1/R1 seek, D-pad L/R cycle the 3D layout.
-                        if gp_actions.toggle_ui { ui.main_menu_visible = true; }
+                        // L1/R1 tap seek (hold skips queue entries), D-pad L/R cycle the 3D layout,
+                        // D-pad up/down jump to the next / previous chapter, □ + D-pad L/R trims yaw by 1°,
+                        // ○ dismisses a toast or closes a video playing beside the main one, left stick pans zoomed content,
+                        // right stick turns the view, holding X stops playback.
+                        // Looking at the browser window, X taps the page there, the left stick scrolls it
+                        // and ○ closes the window. Holding X on any floating panel grabs it instead.
+                        if gp_actions.toggle_ui {
+                            ui.main_menu_visible = true;
+                            gamepad::rumble(&self.app, gamepad::Haptic::Menu);
+                        }
+                        // Sticks arrive with deadzone, curve and sensitivity applied (gamepad::StickTuning)
+                        const PAN_SPEED: f32 = 0.01;
+                        const WEB_SCROLL_SPEED: f32 = 1500.0; // page px / s at full tilt
+                        if let Some((_, window, at)) = self.web_pointer {
+                            if gp_actions.left_stick_x != 0.0 || gp_actions.left_stick_y != 0.0 {
+                                let step = WEB_SCROLL_SPEED * dt;
+                                webview::inject_scroll(&self.app, window, gp_actions.left_stick_x * step, gp_actions.left_stick_y * step, at.x, at.y);
+                            }
+                        } else {
+                            ui.params.pan_by(gp_actions.left_stick_x * PAN_SPEED, gp_actions.left_stick_y * PAN_SPEED);
+                        }
+                        let turn = ui.params.stick_look_speed.to_radians() * dt;
+                        self.stick_look.0 = (self.stick_look.0 - gp_actions.right_stick_x * turn) % std::f32::consts::TAU;
+                        self.stick_look.1 = (self.stick_look.1 - gp_actions.right_stick_y * turn)
+                            .clamp(-MAX_STICK_PITCH, MAX_STICK_PITCH);
+                        if gp_actions.back && ui.toast_visible() {
+                            ui.dismiss_toast();
+                        } else if let (true, Some((id, window, _))) = (gp_actions.back, self.web_pointer) {
+                            webview::close_window(&self.app, window);
+                            self.window_manager.close_panel(id);
+                            self.web_pointer = None;
+                        } else if gp_actions.back && !self.side_videos.is_empty() {
+                            let last = self.side_videos.len() - 1;
+                            close_beside(&mut self.side_videos, &mut self.window_manager, last);
+                        }
                         if gp_actions.open_file_picker {
                             ui.file_browser.visible = true;
                             ui.file_browser.refresh_entries();
+                            gamepad::rumble(&self.app, gamepad::Haptic::Menu);
                         }
-                        if gp_actions.play_pause {
-                            if let Some(decoder) = &self.ndk_decoder {
-                                if decoder.is_paused() { decoder.resume(); } else { decoder.pause(); }
-                            }
+                        const YAW_TRIM_STEP: f32 = 1.0;
+                        if let Some(sensors) = &self.sensors {
+                            if gp_actions.yaw_left  { sensors.nudge_yaw(-YAW_TRIM_STEP); }
+                            if gp_actions.yaw_right { sensors.nudge_yaw(YAW_TRIM_STEP); }
                         }
-                        if gp_actions.seek_back {
-                            if let Some(d) = &self.ndk_decoder { let p = d.get_position(); d.seek((p - 10_000_000).max(0)); }
+                        match (gp_actions.play_pause, self.web_pointer) {
+                            (true, Some((_, window, at))) => webview::tap(&self.app, window, at.x, at.y),
+                            (true, None) => frame_actions.push(Action::PlayPause),
+                            _ => {}
+                        }
+                        match (gp_actions.stop, self.panel_pointer) {
+                            (true, Some((id, _))) => {
+                                let distance = self.window_manager.panel(id).map_or(GRAB_MIN_DISTANCE, |p| p.position.length());
+                                self.grab = Some(PanelGrab { id, distance });
+                                gamepad::rumble(&self.app, gamepad::Haptic::Select);
+                            }
+                            (true, None) => frame_actions.push(Action::Stop),
+                            _ => {}
                         }
-                        if gp_actions.seek_forward {
-                            if let Some(d) = &self.ndk_decoder { let p = d.get_position(); d.seek(p + 10_000_000); }
+                        if gp_actions.seek_back { frame_actions.push(Action::SeekBy(-actions::SEEK_STEP_US)); }
+                        if gp_actions.seek_forward { frame_actions.push(Action::SeekBy(actions::SEEK_STEP_US)); }
+                        if (gp_actions.seek_back || gp_actions.seek_forward) && self.ndk_decoder.is_some() {
+                            gamepad::rumble(&self.app, gamepad::Haptic::Seek);
                         }
-                        if gp_actions.nav_right {
+                        // Layout and chapter jumps take one press each, not auto-repeat
+                        if gp_actions.nav_right && !gp_actions.nav_repeated {
                             ui.params.stereo_mode = (ui.params.stereo_mode + 1) % 3;
                             info!("3D -> {}", ui::stereo_label(ui.params.stereo_mode));
                         }
-                        if gp_actions.nav_left {
+                        if gp_actions.nav_left && !gp_actions.nav_repeated {
                             ui.params.stereo_mode = (ui.params.stereo_mode + 2) % 3;
                             info!("3D -> {}", ui::stereo_label(ui.params.stereo_mode));
                         }
+                        if let (Some(d), Some((_, list)), false) = (&self.ndk_decoder, &self.chapters, gp_actions.nav_repeated) {
+                            let p = d.get_position();
+                            let jump = if gp_actions.nav_up {
+                                chapters::next_start(list, p)
+                            } else if gp_actions.nav_down {
+                                chapters::previous_start(list, p)
+                            } else {
+                                None
+                            };
+                            if let Some(pos) = jump {
+                                frame_actions.push(Action::SeekTo(pos));
+                                gamepad::rumble(&self.app, gamepad::Haptic::Seek);
+                            }
+                        }
                     }
 
                     // Zoom controls (L2/R2 - always active). DualSense over Bluetooth
@@ -355,60 +1141,389 @@ impl ApplicationHandler for VRApp {
                     // reads r2_trigger/l2_trigger (0.0-1.0) with a small deadzone, scaled
                     // by how far the trigger is pressed - not the (rarely-firing) digital
                     // btn_l2/btn_r2 booleans.
+                    // Over a panel with motion aim on, L2 aims instead of zooming out.
                     const TRIGGER_DEADZONE: f32 = 0.08;
                     const ZOOM_SPEED: f32 = 0.05;
+                    // In the Media Center they type the filter instead.
+                    if ui.params.motion_aim && ui.has_open_panel() {
+                        gp_actions.l2_trigger = 0.0;
+                        gp_actions.zoom_out = false;
+                    }
+                    if ui.file_browser.visible {
+                        gp_actions.l2_trigger = 0.0;
+                        gp_actions.r2_trigger = 0.0;
+                        gp_actions.zoom_out = false;
+                        gp_actions.zoom_in = false;
+                    }
                     if gp_actions.r2_trigger > TRIGGER_DEADZONE {
-                        ui.params.content_scale =
-                            (ui.params.content_scale + ZOOM_SPEED * gp_actions.r2_trigger).min(3.0);
+                        frame_actions.push(Action::Zoom(ZOOM_SPEED * gp_actions.r2_trigger));
                     } else if gp_actions.zoom_in {
-                        ui.params.content_scale = (ui.params.content_scale + 0.02).min(3.0);
+                        frame_actions.push(Action::Zoom(0.02));
                     }
                     if gp_actions.l2_trigger > TRIGGER_DEADZONE {
-                        ui.params.content_scale =
-                            (ui.params.content_scale - ZOOM_SPEED * gp_actions.l2_trigger).max(0.5);
+                        frame_actions.push(Action::Zoom(-ZOOM_SPEED * gp_actions.l2_trigger));
                     } else if gp_actions.zoom_out {
-                        ui.params.content_scale = (ui.params.content_scale - 0.02).max(0.5);
+                        frame_actions.push(Action::Zoom(-0.02));
+                    }
+
+                    // 7. The frame's actions, whichever input asked for them
+                    let (mut next_track, mut prev_track) = (false, false);
+                    for action in frame_actions {
+                        match action {
+                            Action::PlayPause | Action::Play | Action::Pause => {
+                                if let Some(decoder) = &self.ndk_decoder {
+                                    let paused = decoder.is_paused();
+                                    match action {
+                                        Action::Play | Action::PlayPause if paused => {
+                                            decoder.resume();
+                                            info!("Video Resumed");
+                                        }
+                                        Action::Pause | Action::PlayPause if !paused => {
+                                            decoder.pause();
+                                            info!("Video Paused");
+                                        }
+                                        _ => {}
+                                    }
+                                    let event = if decoder.is_paused() { ui::OsdEvent::Pause } else { ui::OsdEvent::Play };
+                                    show_osd(ui, &self.resume, decoder, event, decoder.get_position());
+                                }
+                            }
+                            Action::Stop => stop_media(&self.app, &mut self.ndk_decoder, &mut self.resume),
+                            // Streams that can't seek ignore them (no OSD promising a jump)
+                            Action::SeekBy(delta) => {
+                                if let Some(decoder) = self.ndk_decoder.as_ref().filter(|d| d.is_seekable()) {
+                                    let pos = (decoder.get_position() + delta).max(0);
+                                    seek_media(&self.app, decoder, pos);
+                                    show_osd(ui, &self.resume, decoder, ui::OsdEvent::Seek(delta), pos);
+                                }
+                            }
+                            Action::SeekTo(pos) => {
+                                if let Some(decoder) = self.ndk_decoder.as_ref().filter(|d| d.is_seekable()) {
+                                    let delta = pos - decoder.get_position();
+                                    seek_media(&self.app, decoder, pos);
+                                    show_osd(ui, &self.resume, decoder, ui::OsdEvent::Seek(delta), pos);
+                                }
+                            }
+                            Action::NextTrack => next_track = true,
+                            Action::PreviousTrack => prev_track = true,
+                            Action::ToggleVr | Action::ExitVr => {
+                                if let Some(renderer) = &mut self.renderer {
+                                    if action == Action::ToggleVr { renderer.toggle_vr_mode(); } else { renderer.vr_mode = false; }
+                                    info!("VR mode {}", if renderer.vr_mode { "on" } else { "off" });
+                                }
+                            }
+                            Action::Recenter => {
+                                if let Some(sensors) = &self.sensors { sensors.recenter(); }
+                                if let Some(tracker) = &self.arcore { tracker.recenter(); }
+                                self.drag_look.recenter();
+                                self.stick_look = (0.0, 0.0);
+                                self.ui_yaw = None;
+                                ui.params.reset_pan();
+                                gamepad::rumble(&self.app, gamepad::Haptic::Recenter);
+                                ui.show_toast(i18n::tr("Recentered"));
+                            }
+                            Action::Snapshot => {
+                                if let Some(decoder) = &self.ndk_decoder {
+                                    let title = self.resume.playing().map(playlist::display_name).unwrap_or("Snapshot");
+                                    snapshot::save(&self.app, decoder, title);
+                                }
+                            }
+                            Action::Zoom(delta) => {
+                                ui.params.content_scale = (ui.params.content_scale + delta).clamp(0.5, 3.0);
+                            }
+                            // Folded into gp_actions above
+                            Action::Navigate(_) | Action::Select | Action::Confirm | Action::Back | Action::ToggleMenu => {}
+                        }
                     }
-                    
-                    // D-pad volume controls (when D-pad events work)
-                    // Left = volume down, Right = volume up
-                    // Note: D-pad on PS5 sends MotionEvents, need to handle in nav actions
                     
                     // Check if a file was selected from browser
                     if let Some(selected_path) = ui.file_browser.take_selected_file() {
                         let path_str = selected_path.to_string_lossy().to_string();
                         info!("File Browser: Selected {}", path_str);
-                        
-                        // Start playing the selected video file
-                        if let Some(decoder) = &mut self.ndk_decoder {
-                            decoder.stop();
-                        }
-                        
-                        // Start audio playback via Java MediaPlayer
-                        video::start_audio_from_path(&self.app, &path_str);
-                        
-                        // Open the file and get FD for video decoder
-                        if let Ok(file) = std::fs::File::open(&selected_path) {
-                            use std::os::unix::io::AsRawFd;
-                            let fd = file.as_raw_fd();
-                            
-                            // Create new decoder with file
-                            let mut decoder = video_ndk::NdkVideoDecoder::new();
-                            if decoder.start_from_fd(fd).is_ok() {
-                                self.ndk_decoder = Some(decoder);
-                                info!("Started playback: {}", path_str);
+                        let item = self.playlist.play_now(&path_str);
+                        if let Err(e) = start_media(&self.app, &mut self.ndk_decoder, &mut self.preloaded, &mut self.resume, &mut self.recent, &item) {
+                            ui.playback_error.show_open_failed(&item, e);
+                        }
+                    }
+                    if let Some(url) = ui.url_dialog.submitted.take() {
+                        info!("Streaming {}", url);
+                        ui.file_browser.visible = false;
+                        let item = self.playlist.play_now(&url);
+                        if let Err(e) = start_media(&self.app, &mut self.ndk_decoder, &mut self.preloaded, &mut self.resume, &mut self.recent, &item) {
+                            ui.playback_error.show_open_failed(&item, e);
+                        }
+                    }
+                    if let Some(beside) = ui.file_browser.take_beside_file() {
+                        open_beside(&mut self.side_videos, &mut self.window_manager, &beside.to_string_lossy());
+                    }
+                    if let Some(queued) = ui.file_browser.take_enqueued_file() {
+                        self.playlist.push(&queued.to_string_lossy());
+                    }
+                    if let Some((files, play)) = ui.file_browser.take_batch() {
+                        let items: Vec<String> = files.iter().map(|f| f.to_string_lossy().to_string()).collect();
+                        if play {
+                            if let Some(item) = self.playlist.play_all(&items) {
+                                if let Err(e) = start_media(&self.app, &mut self.ndk_decoder, &mut self.preloaded, &mut self.resume, &mut self.recent, &item) {
+                                    ui.playback_error.show_open_failed(&item, e);
+                                }
                             }
-                            // Keep file open (leak it for now - decoder needs the FD)
-                            std::mem::forget(file);
+                        } else {
+                            for item in &items {
+                                self.playlist.push(item);
+                            }
+                            ui.show_toast(i18n::trf("Queued {} files", &[&items.len()]));
+                        }
+                    }
+
+                    // 8. Play queue requests (panel buttons + L1/R1 long-press)
+                    let mut start_item = None;
+                    if let Some(i) = ui.queue.jump_to.take() { start_item = self.playlist.jump_to(i); }
+                    if let Some(path) = ui.playback_error.retry.take() { start_item = Some(path); }
+                    if let Some(i) = ui.queue.remove.take() { self.playlist.remove(i); }
+                    if std::mem::take(&mut ui.queue.shuffle) { self.playlist.shuffle(); }
+                    if std::mem::take(&mut ui.queue.clear) { self.playlist.clear(); }
+                    if std::mem::take(&mut ui.queue.next) || next_track {
+                        start_item = self.playlist.next().or(start_item);
+                    }
+                    if std::mem::take(&mut ui.queue.previous) || prev_track {
+                        start_item = self.playlist.previous().or(start_item);
+                    }
+                    if let Some(item) = start_item {
+                        if let Err(e) = start_media(&self.app, &mut self.ndk_decoder, &mut self.preloaded, &mut self.resume, &mut self.recent, &item) {
+                            ui.playback_error.show_open_failed(&item, e);
+                        }
+                    }
+                    // 9. Resume prompt: offer the saved position once the new file's
+                    // duration is known, seek if accepted
+                    if let Some(decoder) = &self.ndk_decoder {
+                        if let Some(path) = self.resume.playing() {
+                            self.recent.progress(path, decoder.get_position(), decoder.get_duration());
+                        }
+                        let resumable = self.resume.tick(decoder.get_position(), decoder.get_duration())
+                            .filter(|_| decoder.is_seekable());
+                        if let Some(pos) = resumable {
+                            let title = self.playlist.current().map(playlist::display_name).unwrap_or("");
+                            ui.resume_prompt.show(title, pos);
+                        }
+                        if std::mem::take(&mut ui.resume_prompt.accepted) {
+                            seek_media(&self.app, decoder, ui.resume_prompt.position_us);
+                        }
+                        // Accepted, dismissed or never shown (a stream that can't seek)
+                        if self.resume.offered() && !ui.resume_prompt.visible {
+                            self.resume.answered();
+                        }
+                    }
+                    match &self.ndk_decoder {
+                        Some(decoder) => {
+                            let title = self.resume.playing().map(playlist::display_name).unwrap_or("Video");
+                            self.media_session.update(&self.app, !decoder.is_paused(),
+                                decoder.get_position(), decoder.get_duration(), title);
+                        }
+                        None => self.media_session.update(&self.app, false, 0, 0, ""),
+                    }
+
+                    if ui.url_dialog.visible {
+                        ui.url_dialog.history = self.recent.entries().iter()
+                            .filter(|e| e.path.contains("://"))
+                            .take(ui::URL_HISTORY)
+                            .map(|e| e.path.clone())
+                            .collect();
+                    }
+                    if ui.file_browser.visible {
+                        ui.file_browser.set_recent(self.recent.entries());
+                        if let Some(removed) = ui.file_browser.set_volumes(storage::volumes(&self.app)) {
+                            ui.show_toast(i18n::trf("{} was removed", &[&removed]));
+                        }
+                        if ui.file_browser.progress_stale {
+                            ui.file_browser.annotate_progress(|path| self.resume.progress(path));
+                        }
+                    }
+                    // Details line for the selected file and the dock's now-playing line
+                    self.metadata.poll();
+                    if ui.file_browser.visible {
+                        if let Some(path) = ui.file_browser.selected_without_details() {
+                            if let Some(info) = self.metadata.get(&path.to_string_lossy()) {
+                                ui.file_browser.set_details(&path, info.clone());
+                            }
+                        }
+                    }
+                    ui.now_playing = match (ui.main_menu_visible, self.resume.playing()) {
+                        (true, Some(path)) => {
+                            let title = path.rsplit('/').next().unwrap_or(path);
+                            Some(match self.metadata.get(path) {
+                                Some(info) => format!("▶ {}  ·  {}", title, info.summary()),
+                                None => format!("▶ {}", title),
+                            })
                         }
+                        _ => None,
+                    };
+                    // The OSD's clock runs while playing; a seek shows its target
+                    if let (Some(osd), Some(d)) = (&mut ui.osd, &self.ndk_decoder) {
+                        if osd.event == ui::OsdEvent::Play {
+                            osd.position_us = d.get_position();
+                        }
+                    }
+                    if let Some(level) = ui.volume_request.take() {
+                        video::set_volume(&self.app, level);
+                    }
+                    ui.set_volume(video::volume(&self.app));
+                    let vr_mode = self.renderer.as_ref().is_some_and(|r| r.vr_mode);
+                    let brightness = match ui.params.screen_brightness {
+                        _ if vr_mode && ui.params.max_brightness_vr => 1.0,
+                        b if b > 0.0 => b,
+                        _ => -1.0,
+                    };
+                    video::set_screen_brightness(&self.app, brightness);
+                    ui.sensor_status = self.sensors.as_ref().map(|s| s.status());
+                    if dt > 0.0 {
+                        ui.render_fps += (1.0 / dt - ui.render_fps) * 0.05; // ~20-frame average
+                    }
+                    ui.flat_controls.paused = self.ndk_decoder.as_ref().is_none_or(|d| d.is_paused());
+                    ui.flat_controls.title = self.resume.playing().map(|p| playlist::display_name(p).to_string());
+                    ui.seek_bar = match &self.ndk_decoder {
+                        Some(d) if (ui.main_menu_visible || !vr_mode) && d.get_duration() > 0 => {
+                            let list = self.chapters.as_ref().map_or(&[][..], |(_, c)| c);
+                            let position_us = d.get_position();
+                            Some(ui::SeekBar {
+                                position_us,
+                                duration_us: d.get_duration(),
+                                chapters: list.iter().map(|c| c.start_us).collect(),
+                                chapter: chapters::index_at(list, position_us)
+                                    .map(|i| list[i].title.clone())
+                                    .filter(|t| !t.is_empty()),
+                                seekable: d.is_seekable(),
+                            })
+                        }
+                        _ => None,
+                    };
+                    if ui.queue.visible {
+                        ui.queue.items = self.playlist.items().to_vec();
+                        ui.queue.current = self.playlist.current_index();
+                        ui.queue.selected = ui.queue.selected.min(ui.queue.items.len().saturating_sub(1));
+                    }
+                }
+
+                // End of stream: the decoder repeats the file itself in loop-one mode;
+                // otherwise it finishes and the playlist decides what (if anything) is next.
+                let (loop_mode, auto_advance) = self.vr_ui.as_ref()
+                    .map(|u| (u.params.loop_mode, u.params.auto_advance))
+                    .unwrap_or((playlist::LoopMode::Off, false));
+                // A failed start or an unrecoverable codec error: drop the decoder and tell
+                // the user why (the card offers to try the same file again)
+                if let Some(err) = self.ndk_decoder.as_ref().and_then(|d| d.take_error()) {
+                    error!("Playback failed: {}", err);
+                    video::pause_audio(&self.app);
+                    if let Some(mut decoder) = self.ndk_decoder.take() {
+                        decoder.stop();
+                    }
+                    if let Some(ui) = self.vr_ui.as_mut() {
+                        ui.playback_error.show(&err, self.resume.playing());
+                    }
+                }
+                if let Some(decoder) = &self.ndk_decoder {
+                    // The format isn't (hardware-)decodable here: warn before it stutters or fails
+                    if let Some(warning) = decoder.take_warning() {
+                        if let Some(ui) = self.vr_ui.as_mut() {
+                            ui.playback_error.show_warning(warning);
+                        }
+                    }
+                    decoder.set_loop_at_eos(loop_mode == playlist::LoopMode::One);
+                    // Side-by-side videos follow the main video's play/pause
+                    let paused = decoder.is_paused();
+                    for side in &self.side_videos {
+                        if side.decoder.is_paused() != paused {
+                            if paused { side.decoder.pause(); } else { side.decoder.resume(); }
+                        }
+                    }
+                    // Near the end, open what comes next so the switch doesn't wait on codec setup
+                    let remaining = decoder.get_duration() - decoder.get_position();
+                    if remaining >= PRELOAD_LEAD_US {
+                        self.preload_checked = false;
+                    } else if !self.preload_checked && self.preloaded.is_none()
+                        && loop_mode != playlist::LoopMode::One && decoder.get_duration() > 0
+                    {
+                        self.preload_checked = true;
+                        if let Some(next) = self.playlist.upcoming(loop_mode, auto_advance) {
+                            match open_decoder(&next, true) {
+                                Ok(decoder) => {
+                                    info!("Pre-buffering {}", next);
+                                    self.preloaded = Some(Preloaded { path: next, decoder });
+                                }
+                                Err(e) => error!("Failed to pre-buffer {}: {}", next, e),
+                            }
+                        }
+                    }
+                    // Finished is reported once the last frame has had its display time; the
+                    // pre-buffered next entry's first frame is uploaded below in this same
+                    // frame, so clips join on the frame boundary without a black gap
+                    if decoder.take_finished() {
+                        if let Some(path) = self.resume.playing() {
+                            self.recent.progress(path, 1, 1);
+                            self.recent.save();
+                        }
+                        self.resume.finished(decoder.get_duration());
+                        if let Some(next) = self.playlist.advance(loop_mode, auto_advance) {
+                            info!("Advancing to {}", next);
+                            if let Err(e) = start_media(&self.app, &mut self.ndk_decoder, &mut self.preloaded, &mut self.resume, &mut self.recent, &next) {
+                                if let Some(ui) = self.vr_ui.as_mut() {
+                                    ui.playback_error.show_open_failed(&next, e);
+                                }
+                            }
+                        } else {
+                            info!("Playback finished");
+                            video::pause_audio(&self.app);
+                        }
+                    }
+                }
+
+                // Spinner while opening / seeking / starved
+                if let Some(ui) = self.vr_ui.as_mut() {
+                    let status = self.ndk_decoder.as_ref().map(|d| d.status());
+                    let label = match status {
+                        Some(video_ndk::DecoderStatus::Opening) => Some("Opening…"),
+                        Some(video_ndk::DecoderStatus::Seeking) => Some("Seeking…"),
+                        Some(video_ndk::DecoderStatus::Buffering) => Some("Buffering…"),
+                        _ => None,
+                    };
+                    // The decoder shows the target as its position until the seek lands
+                    let seeking = status == Some(video_ndk::DecoderStatus::Seeking);
+                    let decoder = self.ndk_decoder.as_ref();
+                    ui.buffering.update(label,
+                        decoder.and_then(|d| d.buffered_ahead_us()),
+                        decoder.filter(|_| seeking).map(|d| d.get_position()));
+                }
+
+                // A side video that fails closes its screen (and says why)
+                let mut i = 0;
+                while i < self.side_videos.len() {
+                    match self.side_videos[i].decoder.take_error() {
+                        Some(err) => {
+                            error!("Side video failed: {}", err);
+                            if let Some(ui) = self.vr_ui.as_mut() {
+                                ui.playback_error.show(&err, None);
+                            }
+                            close_beside(&mut self.side_videos, &mut self.window_manager, i);
+                        }
+                        None => i += 1,
                     }
                 }
 
                 
-                // Update sensors
-                let orientation = if let Some(ui) = &self.vr_ui {
+                // Update sensors (or, without any, the drag-to-look fallback)
+                self.drag_look.update(dt);
+                let has_sensors = self.sensors.as_ref().is_some_and(|s| s.is_available());
+                if let Some(ui) = &self.vr_ui {
+                    sensors::set_external(&self.app, ui.params.gyro_enabled && ui.params.fusion_mode == sensors::FusionMode::External);
+                }
+                let orientation = if !has_sensors {
+                    self.drag_look.orientation()
+                } else if let Some(ui) = &self.vr_ui {
                     if ui.params.gyro_enabled {
                          if let Some(ref mut sensors) = self.sensors {
+                            sensors.set_fusion(ui.params.fusion_mode, ui.params.fusion_gain);
+                            sensors.set_yaw_correction(ui.params.mag_yaw_correction);
+                            sensors.set_rate(ui.params.sensor_rate);
+                            sensors.set_smoothing(ui.params.head_smoothing);
                             sensors.update(dt);
                             sensors.get_orientation()
                         } else {
@@ -426,6 +1541,51 @@ impl ApplicationHandler for VRApp {
                         Quat::IDENTITY
                     }
                 };
+
+                // Right-stick offset: body yaw, then pitch, under the tracked head; the
+                // horizon trim rolls about the eyes' own axis (a phone sitting tilted in the tray)
+                let roll = self.vr_ui.as_ref().map_or(0.0, |u| u.params.horizon_roll.to_radians());
+                let orientation = Quat::from_rotation_y(self.stick_look.0)
+                    * Quat::from_rotation_x(self.stick_look.1) * orientation * Quat::from_rotation_z(roll);
+                self.view_orientation = orientation;
+
+                // Positional tracking: ARCore runs only while 6DOF is on (and head tracking is)
+                let wants_6dof = self.vr_ui.as_ref().is_some_and(|u| u.params.positional_tracking && u.params.gyro_enabled);
+                if wants_6dof != self.arcore.is_some() {
+                    self.arcore = if wants_6dof { arcore::ArTracker::start(&self.app) } else { None };
+                    if wants_6dof && self.arcore.is_none() {
+                        info!("6DOF unavailable (no ARCore)");
+                        if let Some(ui) = &mut self.vr_ui { ui.params.positional_tracking = false; }
+                    }
+                }
+                // Real position with 6DOF, else what the neck model makes of the rotation
+                let position = match (&self.arcore, &self.vr_ui) {
+                    (Some(tracker), _) => tracker.position(),
+                    (None, Some(ui)) => sensors::neck_offset(orientation, ui.params.neck_model),
+                    (None, None) => Vec3::ZERO,
+                };
+                self.view_position = position;
+
+                // UI panel placement: planted ahead of the recentered view, planted where
+                // a menu opened (Auto), or carried along with the head
+                let anchor = self.vr_ui.as_ref().map_or(ui::UiAnchor::World, |u| u.params.ui_anchor);
+                let planted = self.vr_ui.as_ref().is_some_and(|u| u.has_open_panel() || u.gaze_active);
+                let (ui_turn, ui_transform) = match anchor {
+                    ui::UiAnchor::World => (Quat::IDENTITY, Mat4::IDENTITY),
+                    ui::UiAnchor::Auto if planted => {
+                        let yaw = *self.ui_yaw.get_or_insert_with(|| {
+                            let forward = orientation * Vec3::NEG_Z;
+                            (-forward.x).atan2(-forward.z)
+                        });
+                        let turn = Quat::from_rotation_y(yaw);
+                        (turn, Mat4::from_quat(turn))
+                    }
+                    ui::UiAnchor::Auto | ui::UiAnchor::Head => (orientation, Mat4::from_rotation_translation(orientation, position)),
+                };
+                if !(anchor == ui::UiAnchor::Auto && planted) {
+                    self.ui_yaw = None;
+                }
+                self.ui_turn = ui_turn;
                 
                 // Render
                 if let Some(renderer) = &mut self.renderer {
@@ -446,32 +1606,83 @@ impl ApplicationHandler for VRApp {
                     let content_scale = self.vr_ui.as_ref()
                         .map(|ui| ui.params.content_scale)
                         .unwrap_or(1.0);
+                    // Zooming back out shrinks how far the view may be panned
+                    if let Some(ui) = self.vr_ui.as_mut() {
+                        ui.params.pan_by(0.0, 0.0);
+                        renderer.content_pan = [ui.params.pan_x, ui.params.pan_y];
+                        renderer.aspect_mode = ui.params.aspect_mode as u32;
+                        renderer.crop_aspect = ui.params.crop_aspect;
+                        renderer.ipd = ui.params.ipd / 1000.0;
+                        renderer.fov = ui.params.fov;
+                        renderer.ui_transform = ui_transform;
+                        renderer.picture = [ui.params.brightness, ui.params.contrast, ui.params.saturation, ui.params.gamma];
+                        renderer.blank = ui.params.proximity_blank && self.was_near == Some(false);
+                    }
                     
                     // Fetch video frame from NDK decoder (Y+UV planes)
                     if let Some(decoder) = &self.ndk_decoder {
                         if let Some((y_data, uv_data, width, height)) = decoder.get_frame() {
                             if !y_data.is_empty() {
-                                renderer.update_video_texture(&y_data, &uv_data, width, height);
+                                renderer.update_video_texture(0, &y_data, &uv_data, width, height);
                             }
                         }
+                        renderer.set_video_rotation(0, decoder.rotation_degrees());
                     } else if let Some(frame) = video::VideoManager::get_video_frame(&self.app) {
                         // Fallback path for Java-based video (not used with NDK decoder)
                         let _ = frame; // NDK path is preferred
                     }
 
-                    // Browser: when in web mode, show the live page on the screen.
+                    // Browser: when in web mode, show the live page on the screen; each
+                    // browser window shows its own.
                     let web_mode = self.vr_ui.as_ref().map(|u| u.params.web_mode).unwrap_or(false);
-                    if web_mode {
-                        if let Some((w, h, rgba)) = webview::get_frame() {
-                            renderer.update_web_texture(&rgba, w, h);
-                        }
-                    } else {
+                    if !web_mode {
                         renderer.has_web = false;
                     }
+                    for (window, frame) in webview::take_frames() {
+                        let shown = window != webview::SCREEN || web_mode;
+                        match frame {
+                            webview::WebFrame::Pixels(w, h, rgba) => {
+                                if shown {
+                                    renderer.update_web_texture(window, &rgba, w, h);
+                                }
+                                webview::recycle(rgba);
+                            }
+                            // Back to RGBA copies if the GPU won't take it after all
+                            webview::WebFrame::Buffer(buffer) => {
+                                if shown && !renderer.update_web_buffer(window, buffer) {
+                                    webview::set_hardware_frames(false);
+                                }
+                            }
+                        }
+                    }
+                    // Buffers whose copies are done (or that weren't shown) go back to the browser
+                    webview::release_frames(&self.app);
 
                     renderer.stereo_mode = self.vr_ui.as_ref()
                         .map(|u| u.params.stereo_mode as u32).unwrap_or(0);
-                    renderer.render(orientation, ui_data, distortion_params, content_scale);
+                    // Videos playing beside the main one, each on its own screen
+                    for side in &self.side_videos {
+                        if let Some((y_data, uv_data, width, height)) = side.decoder.get_frame() {
+                            if !y_data.is_empty() {
+                                renderer.update_video_texture(side.slot, &y_data, &uv_data, width, height);
+                            }
+                        }
+                        renderer.set_video_rotation(side.slot, side.decoder.rotation_degrees());
+                    }
+                    for slot in 0..renderer::MAX_VIDEOS {
+                        renderer.set_video_placement(slot, self.window_manager.video_placement(slot));
+                    }
+                    // Panels with a UI of their own: laid out and painted into their textures
+                    let vr_ui = &mut self.vr_ui;
+                    for (id, size, ctx, output) in self.window_manager.run_uis(|ctx, content| {
+                        if let Some(ui) = vr_ui.as_mut() {
+                            ui.render_panel(ctx, content);
+                        }
+                    }) {
+                        renderer.render_panel_ui(id, size, &ctx, output);
+                    }
+                    renderer.set_window_panels(self.window_manager.scene_panels(self.panel_pointer));
+                    renderer.render(orientation, position, ui_data, distortion_params, content_scale);
                 }
                 
                 // Request next frame
@@ -492,7 +1703,7 @@ impl ApplicationHandler for VRApp {
                 
                 let pressed = event.state == ElementState::Pressed;
                 
-                // Extract Android keycode from physical_key
+                // Extract Android keycode from physical_key; anything else is a keyboard
                 if let PhysicalKey::Unidentified(winit::keyboard::NativeKeyCode::Android(code)) = event.physical_key {
                     // Android gamepad keycodes
                     match code {
@@ -512,19 +1723,13 @@ impl ApplicationHandler for VRApp {
                             gamepad::handle_button(100, pressed);
                             info!("GAMEPAD: △ button {}", if pressed { "pressed" } else { "released" });
                         }
-                        102 => { // BUTTON_L1 - Volume Down
+                        102 => { // BUTTON_L1
                             gamepad::handle_button(102, pressed);
-                            if pressed {
-                                video::volume_down(&self.app);
-                            }
-                            info!("GAMEPAD: L1 button {} (Volume Down)", if pressed { "pressed" } else { "released" });
+                            info!("GAMEPAD: L1 button {}", if pressed { "pressed" } else { "released" });
                         }
-                        103 => { // BUTTON_R1 - Volume Up
+                        103 => { // BUTTON_R1
                             gamepad::handle_button(103, pressed);
-                            if pressed {
-                                video::volume_up(&self.app);
-                            }
-                            info!("GAMEPAD: R1 button {} (Volume Up)", if pressed { "pressed" } else { "released" });
+                            info!("GAMEPAD: R1 button {}", if pressed { "pressed" } else { "released" });
                         }
                         104 => { // BUTTON_L2
                             gamepad::handle_button(104, pressed);
@@ -562,94 +1767,40 @@ impl ApplicationHandler for VRApp {
                             gamepad::handle_button(20, pressed);
                             info!("GAMEPAD: D-pad DOWN {}", if pressed { "pressed" } else { "released" });
                         }
-                        21 => { // DPAD_LEFT - Volume Down
+                        21 => { // DPAD_LEFT
                             gamepad::handle_button(21, pressed);
-                            if pressed {
-                                video::volume_down(&self.app);
-                            }
-                            info!("GAMEPAD: D-pad LEFT {} (Volume Down)", if pressed { "pressed" } else { "released" });
+                            info!("GAMEPAD: D-pad LEFT {}", if pressed { "pressed" } else { "released" });
                         }
-                        22 => { // DPAD_RIGHT - Volume Up
+                        22 => { // DPAD_RIGHT
                             gamepad::handle_button(22, pressed);
-                            if pressed {
-                                video::volume_up(&self.app);
-                            }
-                            info!("GAMEPAD: D-pad RIGHT {} (Volume Up)", if pressed { "pressed" } else { "released" });
+                            info!("GAMEPAD: D-pad RIGHT {}", if pressed { "pressed" } else { "released" });
                         }
                         _ => {
+                            // Other pads' buttons, usable once remapped
+                            gamepad::handle_button(code as i32, pressed);
                             info!("GAMEPAD: Unknown button code={} {}", code, if pressed { "pressed" } else { "released" });
                         }
                     }
+                } else {
+                    self.input.handle_keyboard_input(&event);
                 }
             }
+            WindowEvent::CursorMoved { position, .. } => {
+                self.input.handle_cursor_moved(position.x, position.y);
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                self.input.handle_mouse_button(button, state);
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                self.input.handle_mouse_wheel(delta);
+            }
             WindowEvent::Touch(touch) => {
-                let id = touch.id;
-                let loc = (touch.location.x, touch.location.y);
-                
-                match touch.phase {
-                    TouchPhase::Started => {
-                        self.touches.insert(id, loc);
-                        
-                        // Show hamburger on any tap (resets auto-hide timer)
-                        if let Some(ui) = &mut self.vr_ui {
-                            ui.show_hamburger();
-                        }
-                        
-                        // If 2 fingers touched, start pinch
-                        if self.touches.len() == 2 {
-                            let positions: Vec<_> = self.touches.values().collect();
-                            let dx = positions[1].0 - positions[0].0;
-                            let dy = positions[1].1 - positions[0].1;
-                            self.initial_pinch_distance = Some((dx * dx + dy * dy).sqrt());
-                            self.initial_content_scale = self.vr_ui.as_ref()
-                                .map(|ui| ui.params.content_scale).unwrap_or(1.0);
-                        }
-                    }
-                    TouchPhase::Moved => {
-                        self.touches.insert(id, loc);
-                        
-                        // If 2 fingers, calculate zoom
-                        if self.touches.len() == 2 {
-                            if let Some(initial_dist) = self.initial_pinch_distance {
-                                let positions: Vec<_> = self.touches.values().collect();
-                                let dx = positions[1].0 - positions[0].0;
-                                let dy = positions[1].1 - positions[0].1;
-                                let current_dist = (dx * dx + dy * dy).sqrt();
-                                
-                                // Calculate zoom factor
-                                let scale_factor = (current_dist / initial_dist) as f32;
-                                let new_scale = (self.initial_content_scale * scale_factor)
-                                    .clamp(0.5, 3.0);
-                                
-                                if let Some(ui) = &mut self.vr_ui {
-                                    ui.params.content_scale = new_scale;
-                                }
-                            }
-                        }
-                    }
-                    TouchPhase::Ended | TouchPhase::Cancelled => {
-                        self.touches.remove(&id);
-                        
-                        // Reset pinch state
-                        if self.touches.len() < 2 {
-                            self.initial_pinch_distance = None;
-                        }
-                        
-                        // VR toggle (single tap, non-VR mode only)
-                        if self.touches.is_empty() && self.initial_pinch_distance.is_none() {
-                            if let Some(renderer) = &mut self.renderer {
-                                if !renderer.vr_mode {
-                                    if let Some(window) = &self.window {
-                                        let size = window.inner_size();
-                                        if touch.location.y < (size.height as f64 * 0.7) {
-                                            renderer.toggle_vr_mode();
-                                            info!("Entered VR Mode");
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+                let Some(size) = self.window.as_ref().map(|w| w.inner_size()) else { return };
+                let vr_mode = self.renderer.as_ref().map(|r| r.vr_mode).unwrap_or(false);
+                let has_sensors = self.sensors.as_ref().is_some_and(|s| s.is_available());
+                // In VR with head tracking a one-finger drag has no other job, so any sideways swipe seeks
+                for gesture in self.gestures.touch(&touch, size, vr_mode && has_sensors) {
+                    self.apply_gesture(gesture, size);
                 }
             }
             
@@ -658,6 +1809,9 @@ impl ApplicationHandler for VRApp {
     }
 }
 
+/// Right-stick and mouse look stop short of straight up / down.
+const MAX_STICK_PITCH: f32 = 80.0 * std::f32::consts::PI / 180.0;
+
 /// Android entry point
 #[no_mangle]
 fn android_main(app: AndroidApp) {
@@ -674,6 +1828,8 @@ fn android_main(app: AndroidApp) {
         .build()
         .expect("Failed to create event loop");
     
+    http::init(&app);
+    codecs::probe(&app);
     let mut vr_app = VRApp::new(app);
     event_loop.run_app(&mut vr_app).expect("Event loop failed");
 }
@@ -0,0 +1,83 @@
+//! Media buttons and the lock screen through an Android MediaSession
+//!
+//! Java registers a MediaSession (`MediaSessionBridge`), so play, pause, skip
+//! and seek from Bluetooth headphones, watches and the lock screen arrive here
+//! and are queued as `actions::Action`s for the render loop. The loop reports
+//! back what is playing with `MediaSession::update`, which only calls Java on
+//! a change.
+
+use android_activity::AndroidApp;
+use jni::objects::{JObject, JValue};
+use jni::sys::jobject;
+use log::{error, info};
+use std::time::Instant;
+
+use crate::actions::{self, Action, SEEK_STEP_US};
+
+/// Re-send the position when it is further than this from where Java would
+/// extrapolate it (a seek, a stall).
+const DRIFT_US: i64 = 2_000_000;
+
+/// What was last told to Java.
+#[derive(Default)]
+pub struct MediaSession {
+    /// (playing, title, duration µs)
+    last: Option<(bool, String, i64)>,
+    position_us: i64,
+    sent_at: Option<Instant>,
+}
+
+impl MediaSession {
+    /// Report the playback state; call every frame. `title` empty = nothing loaded.
+    pub fn update(&mut self, app: &AndroidApp, playing: bool, position_us: i64, duration_us: i64, title: &str) {
+        let changed = self.last.as_ref().is_none_or(|(p, t, d)| *p != playing || t != title || *d != duration_us);
+        let expected = match (playing, self.sent_at) {
+            (true, Some(at)) => self.position_us + at.elapsed().as_micros() as i64,
+            _ => self.position_us,
+        };
+        if !changed && (position_us - expected).abs() < DRIFT_US {
+            return;
+        }
+        self.last = Some((playing, title.to_string(), duration_us));
+        self.position_us = position_us;
+        self.sent_at = Some(Instant::now());
+
+        let vm = unsafe { jni::JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM).unwrap() };
+        let Ok(mut env) = vm.attach_current_thread() else { return };
+        let activity = unsafe { JObject::from_raw(app.activity_as_ptr() as jobject) };
+        let Ok(title) = env.new_string(title) else { return };
+        let result = env.call_method(&activity, "setMediaState", "(ZJJLjava/lang/String;)V", &[
+            JValue::Bool(playing as u8),
+            JValue::Long(position_us / 1000),
+            JValue::Long(duration_us / 1000),
+            JValue::Object(&title),
+        ]);
+        if let Err(e) = result {
+            error!("media_session: setMediaState failed: {:?}", e);
+        }
+    }
+}
+
+/// A MediaSession callback (codes as in `MediaSessionBridge`); `arg` is the
+/// seek position in ms for SEEK_TO.
+#[no_mangle]
+pub unsafe extern "C" fn Java_com_vrapp_core_MediaSessionBridge_onMediaCommand(
+    _env: jni::JNIEnv,
+    _class: jni::objects::JClass,
+    command: jni::sys::jint,
+    arg: jni::sys::jlong,
+) {
+    let action = match command {
+        0 => Action::Play,
+        1 => Action::Pause,
+        2 => Action::Stop,
+        3 => Action::NextTrack,
+        4 => Action::PreviousTrack,
+        5 => Action::SeekTo((arg * 1000).max(0)),
+        6 => Action::SeekBy(SEEK_STEP_US),
+        7 => Action::SeekBy(-SEEK_STEP_US),
+        _ => return,
+    };
+    info!("media_session: {:?}", action);
+    actions::push(action);
+}
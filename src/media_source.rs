@@ -0,0 +1,129 @@
+//! Pluggable media IO
+//!
+//! `MediaSource` is anything the decoder can read bytes from at an offset. It's
+//! exposed to AMediaExtractor through `AMediaDataSource` callbacks, so new kinds
+//! of input (HLS, HTTP range reads, network shares, decrypting readers, …) only
+//! implement `read_at`/`size` and reuse the one decode loop in `video_ndk`.
+
+use log::error;
+use ndk_sys::AMediaDataSource;
+use std::io;
+use std::os::raw::c_void;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+
+/// A random-access byte source for the decoder.
+pub trait MediaSource: Send {
+    /// Read up to `buf.len()` bytes at `offset`. `Ok(0)` means end of stream.
+    /// May block (e.g. waiting on the network).
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize>;
+
+    /// Total size in bytes, `None` if unknown or still growing.
+    fn size(&self) -> Option<u64>;
+
+    /// Whether the decoder may seek in it. Sources that only keep a window of
+    /// the stream say no, and the player leaves seeking off.
+    fn seekable(&self) -> bool {
+        true
+    }
+
+    /// Duration hint for containers that don't carry one (e.g. MPEG-TS segments).
+    fn duration_us(&self) -> Option<i64> {
+        None
+    }
+
+    /// Media time up to which data has been fetched, for sources that download
+    /// ahead (shown as the buffered range). `None` if unknown.
+    fn buffered_until_us(&self) -> Option<i64> {
+        None
+    }
+
+    /// Once reads hit the end, move on to the next part of a stream that an
+    /// extractor can't read straight through (an HLS variant switch): reads then
+    /// start again at offset 0 for a fresh extractor. False if there's none.
+    fn next_part(&mut self) -> bool {
+        false
+    }
+}
+
+/// Opens a source on the decode thread (opening may block on IO). Gets the
+/// decoder's running flag so blocking reads can give up when playback stops.
+pub type SourceOpener = Box<dyn FnOnce(&Arc<AtomicBool>) -> Result<Box<dyn MediaSource>, String> + Send>;
+
+/// An `AMediaDataSource` forwarding to a boxed `MediaSource`. Drop it only after
+/// the extractor using `as_ptr()` has been deleted.
+pub struct NdkDataSource {
+    ptr: *mut AMediaDataSource,
+    // Boxed so the callbacks' userdata pointer stays put.
+    source: Box<Mutex<Box<dyn MediaSource>>>,
+}
+
+// The data source is only used by the decode thread that owns it.
+unsafe impl Send for NdkDataSource {}
+
+impl NdkDataSource {
+    pub fn new(source: Box<dyn MediaSource>) -> Result<Self, String> {
+        let source = Box::new(Mutex::new(source));
+        unsafe {
+            let ptr = ndk_sys::AMediaDataSource_new();
+            if ptr.is_null() {
+                return Err("AMediaDataSource_new failed".into());
+            }
+            ndk_sys::AMediaDataSource_setUserdata(ptr, &*source as *const _ as *mut c_void);
+            ndk_sys::AMediaDataSource_setReadAt(ptr, Some(read_at));
+            ndk_sys::AMediaDataSource_setGetSize(ptr, Some(get_size));
+            Ok(Self { ptr, source })
+        }
+    }
+
+    pub fn as_ptr(&self) -> *mut AMediaDataSource {
+        self.ptr
+    }
+
+    pub fn duration_us(&self) -> Option<i64> {
+        self.source.lock().ok().and_then(|s| s.duration_us())
+    }
+
+    pub fn seekable(&self) -> bool {
+        self.source.lock().is_ok_and(|s| s.seekable())
+    }
+
+    /// See `MediaSource::next_part`; call with no extractor reading.
+    pub fn next_part(&self) -> bool {
+        self.source.lock().is_ok_and(|mut s| s.next_part())
+    }
+
+    /// `try_lock`: the extractor may be blocked inside `read_at` holding the source.
+    pub fn buffered_until_us(&self) -> Option<i64> {
+        self.source.try_lock().ok().and_then(|s| s.buffered_until_us())
+    }
+}
+
+impl Drop for NdkDataSource {
+    fn drop(&mut self) {
+        unsafe { ndk_sys::AMediaDataSource_delete(self.ptr); }
+    }
+}
+
+unsafe extern "C" fn read_at(userdata: *mut c_void, offset: i64, buffer: *mut c_void, size: usize) -> isize {
+    if size == 0 {
+        return 0;
+    }
+    let source = &*(userdata as *const Mutex<Box<dyn MediaSource>>);
+    let Ok(mut source) = source.lock() else { return -1 };
+    let out = std::slice::from_raw_parts_mut(buffer as *mut u8, size);
+    // AMediaDataSource wants -1 for both end of stream and errors
+    match source.read_at(offset.max(0) as u64, out) {
+        Ok(0) => -1,
+        Ok(n) => n as isize,
+        Err(e) => {
+            error!("MediaSource: read at {} failed: {}", offset, e);
+            -1
+        }
+    }
+}
+
+unsafe extern "C" fn get_size(userdata: *mut c_void) -> isize {
+    let source = &*(userdata as *const Mutex<Box<dyn MediaSource>>);
+    source.lock().ok().and_then(|s| s.size()).map(|n| n as isize).unwrap_or(-1)
+}
@@ -0,0 +1,253 @@
+//! Media metadata
+//!
+//! Duration, resolution, frame rate, codecs and audio channel layout for local
+//! files, read by briefly opening them with an AMediaExtractor on a background
+//! thread (no codec is created). Shown in the Media Center detail line and the
+//! now-playing line under the dock. Results are cached in internal storage keyed
+//! by path, size and mtime, so browsing a folder again doesn't re-probe it.
+
+use log::{error, info, warn};
+use std::collections::{HashMap, HashSet};
+use std::ffi::{CStr, CString};
+use std::os::unix::io::AsRawFd;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::UNIX_EPOCH;
+
+/// What the container says about a file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaInfo {
+    pub duration_us: i64,
+    pub width:       i32,
+    pub height:      i32,
+    /// 0 when the container doesn't say.
+    pub fps:         f32,
+    pub video_mime:  Option<String>,
+    pub audio_mime:  Option<String>,
+    pub channels:    i32,
+}
+
+impl MediaInfo {
+    /// "1:42:10 · 4K · 59.94 fps · HEVC · AAC 5.1" (parts the file lacks are left out).
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.duration_us > 0 {
+            parts.push(crate::resume::format_time(self.duration_us));
+        }
+        if self.width > 0 && self.height > 0 {
+            parts.push(crate::codecs::resolution_label(self.width, self.height));
+        }
+        if self.fps > 0.0 {
+            let fps = if self.fps.fract().abs() < 0.01 { format!("{:.0}", self.fps) } else { format!("{:.2}", self.fps) };
+            parts.push(format!("{} fps", fps));
+        }
+        if let Some(v) = &self.video_mime {
+            parts.push(crate::codecs::format_label(v).to_string());
+        }
+        if let Some(a) = &self.audio_mime {
+            parts.push(format!("{} {}", audio_label(a), channel_layout(self.channels)));
+        }
+        parts.join("  ·  ")
+    }
+}
+
+/// "AAC", "AC-3", … for an audio mime type.
+pub fn audio_label(mime: &str) -> &str {
+    match mime {
+        "audio/mp4a-latm" => "AAC",
+        "audio/ac3" => "AC-3",
+        "audio/eac3" => "E-AC-3",
+        "audio/opus" => "Opus",
+        "audio/vorbis" => "Vorbis",
+        "audio/mpeg" => "MP3",
+        "audio/flac" => "FLAC",
+        "audio/raw" => "PCM",
+        m => m.strip_prefix("audio/").unwrap_or(m),
+    }
+}
+
+/// "Mono" / "Stereo" / "5.1" / "7.1", else "N ch".
+pub fn channel_layout(channels: i32) -> String {
+    match channels {
+        1 => crate::i18n::tr("Mono").into(),
+        2 => crate::i18n::tr("Stereo").into(),
+        6 => "5.1".into(),
+        8 => "7.1".into(),
+        n => crate::i18n::trf("{} ch", &[&n]),
+    }
+}
+
+/// Size + mtime, so a replaced file gets probed again.
+fn stamp(path: &str) -> Option<(u64, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some((meta.len(), mtime))
+}
+
+struct Entry {
+    size:  u64,
+    mtime: u64,
+    info:  MediaInfo,
+}
+
+pub struct MetadataStore {
+    file: Option<PathBuf>,
+    entries: HashMap<String, Entry>,
+    /// Sent to the probe thread and not back yet.
+    pending: HashSet<String>,
+    requests: Sender<String>,
+    results: Receiver<(String, Option<MediaInfo>)>,
+    dirty: bool,
+}
+
+impl MetadataStore {
+    /// Load the cache from `<dir>/metadata.tsv` and start the probe thread.
+    pub fn load(dir: Option<PathBuf>) -> Self {
+        let file = dir.map(|d| d.join("metadata.tsv"));
+        let entries: HashMap<String, Entry> = file.as_ref()
+            .and_then(|f| std::fs::read_to_string(f).ok())
+            .map(|text| text.lines().filter_map(|line| {
+                let mut parts = line.splitn(10, '\t');
+                let mut next = || parts.next();
+                let size = next()?.parse().ok()?;
+                let mtime = next()?.parse().ok()?;
+                let info = MediaInfo {
+                    duration_us: next()?.parse().ok()?,
+                    width:       next()?.parse().ok()?,
+                    height:      next()?.parse().ok()?,
+                    fps:         next()?.parse().ok()?,
+                    video_mime:  Some(next()?).filter(|m| !m.is_empty()).map(str::to_string),
+                    audio_mime:  Some(next()?).filter(|m| !m.is_empty()).map(str::to_string),
+                    channels:    next()?.parse().ok()?,
+                };
+                Some((next()?.to_string(), Entry { size, mtime, info }))
+            }).collect())
+            .unwrap_or_default();
+        info!("Metadata: {} cached entries", entries.len());
+
+        let (requests, jobs) = mpsc::channel::<String>();
+        let (done, results) = mpsc::channel();
+        // Exits when the store (and with it the sender) is dropped
+        thread::spawn(move || {
+            for path in jobs {
+                let info = probe(&path);
+                if done.send((path, info)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { file, entries, pending: HashSet::new(), requests, results, dirty: false }
+    }
+
+    /// Info for `path` if known; otherwise it's queued for probing and shows up in
+    /// a later call.
+    pub fn get(&mut self, path: &str) -> Option<&MediaInfo> {
+        let (size, mtime) = stamp(path)?;
+        let fresh = self.entries.get(path).is_some_and(|e| e.size == size && e.mtime == mtime);
+        if !fresh {
+            if self.pending.insert(path.to_string()) {
+                let _ = self.requests.send(path.to_string());
+            }
+            return None;
+        }
+        self.entries.get(path).map(|e| &e.info)
+    }
+
+    /// Collect finished probes (call every frame).
+    pub fn poll(&mut self) {
+        while let Ok((path, info)) = self.results.try_recv() {
+            self.pending.remove(&path);
+            let (Some(info), Some((size, mtime))) = (info, stamp(&path)) else { continue };
+            self.entries.insert(path, Entry { size, mtime, info });
+            self.dirty = true;
+        }
+    }
+
+    /// Write to disk if anything changed.
+    pub fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        let Some(file) = &self.file else { return };
+        let text: String = self.entries.iter()
+            .map(|(path, e)| format!("{}\t{}\t{}\t{}\t{}\t{:.3}\t{}\t{}\t{}\t{}\n",
+                e.size, e.mtime, e.info.duration_us, e.info.width, e.info.height, e.info.fps,
+                e.info.video_mime.as_deref().unwrap_or(""), e.info.audio_mime.as_deref().unwrap_or(""),
+                e.info.channels, path))
+            .collect();
+        if let Err(e) = std::fs::write(file, text) {
+            error!("Metadata: failed to write {:?}: {}", file, e);
+        }
+        self.dirty = false;
+    }
+}
+
+/// Read the track formats of a local file (runs on the probe thread).
+fn probe(path: &str) -> Option<MediaInfo> {
+    use ndk_sys::*;
+
+    let file = std::fs::File::open(path).ok()?;
+    let len = file.metadata().map(|m| m.len() as i64).unwrap_or(i64::MAX);
+    unsafe {
+        let extractor = AMediaExtractor_new();
+        if extractor.is_null() {
+            return None;
+        }
+        if AMediaExtractor_setDataSourceFd(extractor, file.as_raw_fd(), 0, len).0 != 0 {
+            warn!("Metadata: can't read {}", path);
+            AMediaExtractor_delete(extractor);
+            return None;
+        }
+
+        let mut info = MediaInfo::default();
+        for i in 0..AMediaExtractor_getTrackCount(extractor) as usize {
+            let format = AMediaExtractor_getTrackFormat(extractor, i);
+            if format.is_null() {
+                continue;
+            }
+            let mime = get_string(format, "mime").unwrap_or_default();
+            let mut duration: i64 = 0;
+            let key = CString::new("durationUs").unwrap();
+            if AMediaFormat_getInt64(format, key.as_ptr(), &mut duration) {
+                info.duration_us = info.duration_us.max(duration);
+            }
+            if mime.starts_with("video/") && info.video_mime.is_none() {
+                info.width = get_i32(format, "width").unwrap_or(0);
+                info.height = get_i32(format, "height").unwrap_or(0);
+                // Stored as int or float depending on the container
+                info.fps = get_i32(format, "frame-rate").map(|f| f as f32)
+                    .or_else(|| get_f32(format, "frame-rate"))
+                    .unwrap_or(0.0);
+                info.video_mime = Some(mime);
+            } else if mime.starts_with("audio/") && info.audio_mime.is_none() {
+                info.channels = get_i32(format, "channel-count").unwrap_or(0);
+                info.audio_mime = Some(mime);
+            }
+            AMediaFormat_delete(format);
+        }
+        AMediaExtractor_delete(extractor);
+        Some(info)
+    }
+}
+
+unsafe fn get_i32(format: *mut ndk_sys::AMediaFormat, key: &str) -> Option<i32> {
+    let key = CString::new(key).ok()?;
+    let mut value = 0;
+    ndk_sys::AMediaFormat_getInt32(format, key.as_ptr(), &mut value).then_some(value)
+}
+
+unsafe fn get_f32(format: *mut ndk_sys::AMediaFormat, key: &str) -> Option<f32> {
+    let key = CString::new(key).ok()?;
+    let mut value = 0.0;
+    ndk_sys::AMediaFormat_getFloat(format, key.as_ptr(), &mut value).then_some(value)
+}
+
+unsafe fn get_string(format: *mut ndk_sys::AMediaFormat, key: &str) -> Option<String> {
+    let key = CString::new(key).ok()?;
+    let mut value: *const std::os::raw::c_char = std::ptr::null();
+    if !ndk_sys::AMediaFormat_getString(format, key.as_ptr(), &mut value) || value.is_null() {
+        return None;
+    }
+    Some(CStr::from_ptr(value).to_string_lossy().into_owned())
+}
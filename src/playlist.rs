@@ -0,0 +1,241 @@
+//! Play queue
+//!
+//! Ordered list of files/URLs with a cursor. lib.rs owns one `Playlist` and asks
+//! it what to start when a video finishes or the user skips (L1/R1 long-press,
+//! queue panel); the Media Center pushes entries into it. The loop modes and
+//! which files count as media are defined here too, and ui.rs shows them.
+
+use log::info;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::i18n::tr;
+
+/// What happens when playback reaches the end of a file.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LoopMode {
+    /// Stop (or continue with the queue / auto-advance, then stop).
+    Off,
+    /// Repeat the current file.
+    One,
+    /// Loop the whole queue, or the file's folder when nothing is queued.
+    All,
+}
+
+impl LoopMode {
+    pub fn next(self) -> Self {
+        match self { LoopMode::Off => LoopMode::One, LoopMode::One => LoopMode::All, LoopMode::All => LoopMode::Off }
+    }
+    pub fn label(self) -> &'static str {
+        tr(match self { LoopMode::Off => "Loop · Off", LoopMode::One => "Loop · Current File", LoopMode::All => "Loop · Folder / Queue" })
+    }
+}
+
+/// What a file is, for the Media Center and the folder auto-advance.
+#[derive(Clone, Copy, PartialEq)]
+pub enum MediaKind { Dir, Video, Audio }
+
+/// Classify a file name by extension (`None` = not something we can play).
+pub fn media_kind(name: &str) -> Option<MediaKind> {
+    let ext = name.rsplit('.').next().map(|e| e.to_lowercase()).unwrap_or_default();
+    if matches!(ext.as_str(), "mp4"|"mkv"|"avi"|"webm"|"mov"|"m4v"|"3gp"|"ts"|"flv") {
+        Some(MediaKind::Video)
+    } else if matches!(ext.as_str(), "mp3"|"flac"|"wav"|"aac"|"ogg"|"m4a"|"opus"|"wma") {
+        Some(MediaKind::Audio)
+    } else { None }
+}
+
+/// Ordered play queue with a current position.
+#[derive(Default)]
+pub struct Playlist {
+    items: Vec<String>,
+    current: Option<usize>,
+}
+
+impl Playlist {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    pub fn current_index(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// The entry that is playing (or about to), if any.
+    pub fn current(&self) -> Option<&str> {
+        self.current.and_then(|i| self.items.get(i)).map(|s| s.as_str())
+    }
+
+    pub fn has_next(&self) -> bool {
+        matches!(self.current, Some(i) if i + 1 < self.items.len())
+    }
+
+    /// Append to the end of the queue (duplicates are ignored).
+    pub fn push(&mut self, item: &str) {
+        if self.items.iter().any(|i| i == item) {
+            return;
+        }
+        self.items.push(item.to_string());
+        info!("Playlist: queued {} ({} items)", item, self.items.len());
+    }
+
+    /// Make `item` the current entry, queueing it right after the current one if
+    /// it isn't in the queue yet. Returns the entry to start.
+    pub fn play_now(&mut self, item: &str) -> String {
+        let idx = match self.items.iter().position(|i| i == item) {
+            Some(idx) => idx,
+            None => {
+                let at = self.current.map(|c| c + 1).unwrap_or(self.items.len());
+                self.items.insert(at, item.to_string());
+                at
+            }
+        };
+        self.current = Some(idx);
+        self.items[idx].clone()
+    }
+
+    /// Queue `items` in order right after the current entry and make the first of
+    /// them current. Returns the entry to start.
+    pub fn play_all(&mut self, items: &[String]) -> Option<String> {
+        let first = self.play_now(items.first()?);
+        let mut at = self.current? + 1;
+        for item in &items[1..] {
+            if self.items.iter().any(|i| i == item) {
+                continue;
+            }
+            self.items.insert(at, item.clone());
+            at += 1;
+        }
+        info!("Playlist: playing {} items ({} queued)", items.len(), self.items.len());
+        Some(first)
+    }
+
+    /// Jump to an index in the queue. Returns the entry to start.
+    pub fn jump_to(&mut self, idx: usize) -> Option<String> {
+        if idx >= self.items.len() {
+            return None;
+        }
+        self.current = Some(idx);
+        Some(self.items[idx].clone())
+    }
+
+    /// Advance to the next entry. Returns it, or `None` at the end of the queue.
+    pub fn next(&mut self) -> Option<String> {
+        let idx = match self.current {
+            Some(i) => i + 1,
+            None => 0,
+        };
+        self.jump_to(idx)
+    }
+
+    /// Step back to the previous entry (stays on the first one).
+    pub fn previous(&mut self) -> Option<String> {
+        let idx = self.current.map(|i| i.saturating_sub(1)).unwrap_or(0);
+        self.jump_to(idx)
+    }
+
+    /// Remove an entry. Removing the current entry leaves the cursor on whatever
+    /// slid into its place, without restarting playback.
+    pub fn remove(&mut self, idx: usize) {
+        if idx >= self.items.len() {
+            return;
+        }
+        self.items.remove(idx);
+        self.current = match self.current {
+            _ if self.items.is_empty() => None,
+            Some(c) if c > idx => Some(c - 1),
+            Some(c) => Some(c.min(self.items.len() - 1)),
+            None => None,
+        };
+    }
+
+    /// Pick what to play after the current entry finishes, moving the cursor to it.
+    /// `None` means stop. `LoopMode::One` never gets here — the decoder loops itself.
+    pub fn advance(&mut self, mode: LoopMode, auto_advance: bool) -> Option<String> {
+        let next = self.upcoming(mode, auto_advance)?;
+        Some(self.play_now(&next))
+    }
+
+    /// What `advance` would pick, without moving the cursor (used to pre-buffer it).
+    /// May list a folder, so don't call it every frame.
+    pub fn upcoming(&self, mode: LoopMode, auto_advance: bool) -> Option<String> {
+        if self.has_next() {
+            return self.items.get(self.current? + 1).cloned();
+        }
+        let current = self.current()?;
+        match mode {
+            LoopMode::All if self.items.len() > 1 => self.items.first().cloned(),
+            LoopMode::All => next_in_folder(current, true),
+            _ if auto_advance => next_in_folder(current, false),
+            _ => None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.current = None;
+    }
+
+    /// Shuffle the queue. The current entry moves to the front so playback carries
+    /// on uninterrupted and everything after it is in the new random order.
+    pub fn shuffle(&mut self) {
+        if self.items.len() < 2 {
+            return;
+        }
+        if let Some(c) = self.current {
+            let cur = self.items.remove(c);
+            self.items.insert(0, cur);
+        }
+        let start = if self.current.is_some() { 1 } else { 0 };
+
+        // xorshift64 seeded from the clock — good enough for a play order.
+        let mut seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x9E37_79B9_7F4A_7C15)
+            | 1;
+        for i in (start + 1..self.items.len()).rev() {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            let j = start + (seed % (i - start + 1) as u64) as usize;
+            self.items.swap(i, j);
+        }
+        if self.current.is_some() {
+            self.current = Some(0);
+        }
+        info!("Playlist: shuffled {} items", self.items.len());
+    }
+}
+
+/// Short display name for a queue entry (file name, or the URL as-is).
+pub fn display_name(item: &str) -> &str {
+    if item.contains("://") {
+        return item;
+    }
+    item.rsplit('/').next().unwrap_or(item)
+}
+
+/// The video that follows `current` alphabetically in the same folder, if any.
+/// With `wrap`, the last video is followed by the first one.
+pub fn next_in_folder(current: &str, wrap: bool) -> Option<String> {
+    let path = Path::new(current);
+    let dir = path.parent()?;
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    let mut videos: Vec<String> = std::fs::read_dir(dir).ok()?
+        .flatten()
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter(|n| !n.starts_with('.') && media_kind(n) == Some(MediaKind::Video))
+        .collect();
+    videos.sort_by_key(|n| n.to_lowercase());
+    let next = match videos.iter().position(|n| n.to_lowercase() > name) {
+        Some(i) => videos.swap_remove(i),
+        None if wrap && !videos.is_empty() => videos.swap_remove(0),
+        None => return None,
+    };
+    Some(dir.join(next).to_string_lossy().to_string())
+}
@@ -0,0 +1,121 @@
+//! Recently played
+//!
+//! The last `MAX_ENTRIES` files that were opened, newest first, with when they
+//! were last played and how far through they got. Backs the Media Center's
+//! "Recent" tab; persisted next to the resume positions in internal storage.
+
+use log::{error, info};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::i18n::{tr, trf};
+
+const MAX_ENTRIES: usize = 30;
+
+#[derive(Clone, PartialEq)]
+pub struct RecentEntry {
+    pub path:      String,
+    /// Unix seconds of the last time it was started.
+    pub played_at: u64,
+    /// 0.0 – 1.0 of the duration reached.
+    pub progress:  f32,
+}
+
+pub struct RecentList {
+    file: Option<PathBuf>,
+    entries: Vec<RecentEntry>,
+    dirty: bool,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+impl RecentList {
+    /// Load from `<dir>/recent.tsv` (empty if missing or unreadable).
+    pub fn load(dir: Option<PathBuf>) -> Self {
+        let file = dir.map(|d| d.join("recent.tsv"));
+        let entries: Vec<RecentEntry> = file.as_ref()
+            .and_then(|f| std::fs::read_to_string(f).ok())
+            .map(|text| text.lines().filter_map(|line| {
+                let mut parts = line.splitn(3, '\t');
+                let played_at = parts.next()?.parse().ok()?;
+                let progress = parts.next()?.parse().ok()?;
+                Some(RecentEntry { played_at, progress, path: parts.next()?.to_string() })
+            }).collect())
+            .unwrap_or_default();
+        info!("Recent: {} entries", entries.len());
+        Self { file, entries, dirty: false }
+    }
+
+    pub fn entries(&self) -> &[RecentEntry] {
+        &self.entries
+    }
+
+    /// `path` was just opened: move it to the top (keeping its progress).
+    pub fn played(&mut self, path: &str) {
+        let progress = self.entries.iter().position(|e| e.path == path)
+            .map(|i| self.entries.remove(i).progress)
+            .unwrap_or(0.0);
+        self.entries.insert(0, RecentEntry { path: path.to_string(), played_at: now_secs(), progress });
+        self.entries.truncate(MAX_ENTRIES);
+        self.dirty = true;
+    }
+
+    /// Update how far through `path` playback is (cheap; call every frame).
+    pub fn progress(&mut self, path: &str, position_us: i64, duration_us: i64) {
+        if duration_us <= 0 {
+            return;
+        }
+        let p = (position_us as f32 / duration_us as f32).clamp(0.0, 1.0);
+        if let Some(e) = self.entries.iter_mut().find(|e| e.path == path) {
+            if (e.progress - p).abs() > 0.001 {
+                e.progress = p;
+                self.dirty = true;
+            }
+        }
+    }
+
+    /// Write to disk if anything changed.
+    pub fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        let Some(file) = &self.file else { return };
+        let text: String = self.entries.iter()
+            .map(|e| format!("{}\t{:.3}\t{}\n", e.played_at, e.progress, e.path))
+            .collect();
+        if let Err(e) = std::fs::write(file, text) {
+            error!("Recent: failed to write {:?}: {}", file, e);
+        }
+        self.dirty = false;
+    }
+}
+
+/// "15 Mar 2024" for a unix timestamp (UTC).
+pub fn format_date(secs: u64) -> String {
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    // Days since 1970-01-01 to a civil date, counting in 400-year eras from March
+    let z = (secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    trf("{} {} {}", &[&day, &tr(MONTHS[(month - 1) as usize]), &year])
+}
+
+/// "just now" / "5 min ago" / "3 h ago" / "2 days ago" for a unix timestamp.
+pub fn time_ago(played_at: u64) -> String {
+    let s = now_secs().saturating_sub(played_at);
+    match s {
+        0..=59 => tr("just now").into(),
+        60..=3599 => trf("{} min ago", &[&(s / 60)]),
+        3600..=86_399 => trf("{} h ago", &[&(s / 3600)]),
+        86_400..=172_799 => tr("yesterday").into(),
+        _ => trf("{} days ago", &[&(s / 86_400)]),
+    }
+}
@@ -3,6 +3,7 @@
 //! Handles GPU initialization and 3D rendering for the VR environment.
 //! Supports both normal and stereoscopic VR rendering modes.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use egui_wgpu::wgpu;
 use wgpu::{
@@ -12,9 +13,12 @@ use wgpu::{
 };
 use winit::window::Window;
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
-use glam::{Mat4, Quat, Vec3};
+use glam::{Mat4, Quat, Vec2, Vec3};
 use bytemuck::{Pod, Zeroable};
 
+use crate::hardware_buffer;
+use crate::webview;
+
 // Camera uniforms
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
@@ -22,16 +26,80 @@ struct CameraUniforms {
     view_proj: [[f32; 4]; 4],
     eye_offset: [f32; 4], // x = eye offset, y = has_video, z = time, w = content_scale
     video_info: [f32; 4], // x = aspect_ratio, y = width, z = height, w = unused
-    stereo: [f32; 4],     // x = mode (0 mono,1 SBS,2 over-under), y = eye_index, zw unused
+    stereo: [f32; 4],     // x = mode (0 mono,1 SBS,2 over-under), y = eye_index, z = video rotation (quarter turns CW), w unused
+    screen: [f32; 4],     // x = yaw (radians, + = right), y = size relative to the main screen, zw = content pan
+    crop: [f32; 4],       // xy = UV scale about the centre (fill / crop), z = screen aspect per eye (0 = the video's), w unused
+    picture: [f32; 4],    // x = brightness, y = contrast, z = saturation, w = gamma (video only)
 }
 
 // Each eye gets its OWN region in the camera uniform buffer, addressed by a dynamic
 // offset, so the two eye passes in one submit don't clobber each other's uniforms
 // (that bug made both eyes read the last write → identical images, no depth, and in
 // SBS both eyes showed the same half). 256 satisfies every GPU's
-// min_uniform_buffer_offset_alignment and holds CameraUniforms (128 B) comfortably.
+// min_uniform_buffer_offset_alignment and holds CameraUniforms (160 B) comfortably.
+// Every video screen has its own set of 3 eye regions.
 const EYE_STRIDE: u64 = 256;
 
+/// Video screens that can be shown at once (slot 0 = the main screen).
+pub const MAX_VIDEOS: usize = 2;
+
+/// Floating window panels drawn at once; more are left out.
+pub const MAX_PANELS: usize = 16;
+
+/// The eye pass's depth buffer. Only the window panels test and write it; the
+/// dome screens and the UI panel keep their draw order.
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+/// Where a video screen sits on the dome.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScreenPlacement {
+    /// Angle from straight ahead (radians, + = right).
+    pub yaw: f32,
+    /// Size relative to the full-size main screen.
+    pub scale: f32,
+}
+
+impl Default for ScreenPlacement {
+    fn default() -> Self {
+        Self { yaw: 0.0, scale: 1.0 }
+    }
+}
+
+/// Screen aspect (0 = keep the video's) and UV scale for an `aspect_mode`, given
+/// the video's per-eye aspect.
+fn fit_video(aspect_mode: u32, crop_aspect: f32, video_aspect: f32) -> (f32, [f32; 2]) {
+    const SCREEN_ASPECT: f32 = 16.0 / 9.0;
+    // Scale down the UVs on the axis that overflows, so the frame covers the screen
+    let cover = |target: f32| if video_aspect > target {
+        [target / video_aspect, 1.0]
+    } else {
+        [1.0, video_aspect / target]
+    };
+    match aspect_mode {
+        1 => (SCREEN_ASPECT, cover(SCREEN_ASPECT)),
+        2 => (SCREEN_ASPECT, [1.0, 1.0]),
+        3 => (crop_aspect, cover(crop_aspect)),
+        _ => (0.0, [1.0, 1.0]),
+    }
+}
+
+/// One decoder's Y/UV textures and the screen they're shown on.
+struct VideoSurface {
+    texture_y: Option<wgpu::Texture>,
+    texture_y_view: Option<wgpu::TextureView>,
+    texture_uv: Option<wgpu::Texture>,
+    texture_uv_view: Option<wgpu::TextureView>,
+    bind_group: wgpu::BindGroup,  // Always valid (placeholder or real)
+    has_video: bool,
+    width: u32,
+    height: u32,
+    // Clockwise rotation the decoded frames need (container metadata): 0/90/180/270.
+    rotation: u32,
+    placement: ScreenPlacement,
+    // Slot 0 is always drawn (test pattern without video); others only while in use.
+    visible: bool,
+}
+
 // Distortion uniforms
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
@@ -42,6 +110,62 @@ struct DistortionUniforms {
     padding2: f32,
 }
 
+/// What a floating window panel shows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PanelSource {
+    /// The egui texture (dock, settings)
+    Ui,
+    /// A browser window's page (`update_web_texture`), by its number in webview.rs
+    Web(i32),
+    /// The panel's own egui texture (`render_panel_ui`), by panel id
+    Own(u32),
+    /// A snap guide line, filled with the theme's accent
+    Guide,
+}
+
+/// What one eye pass is drawn from (`render_eye`).
+#[derive(Clone, Copy)]
+struct EyeView {
+    head_orientation: Quat,
+    head_position: Vec3, // metres from the recentered position (6DOF tracking), else zero
+    index: u32,          // 0 = left, 1 = right, 2 = mono (2D)
+    eye_offset: f32,     // half the IPD, − left / + right
+    lens_center_offset: f32,
+    content_scale: f32,
+}
+
+/// A floating browser window's page.
+struct WebTexture {
+    size: [u32; 2],
+    texture: wgpu::Texture,
+    bind_group: BindGroup,
+}
+
+/// A panel's own UI texture and the egui renderer that paints it.
+struct PanelTexture {
+    size: [u32; 2],
+    view: wgpu::TextureView,
+    bind_group: BindGroup,
+    egui_renderer: egui_wgpu::Renderer,
+}
+
+// One floating window panel (window_panel.wgsl), an instance of the panel quad
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct PanelInstance {
+    model: [[f32; 4]; 4],   // WindowManager::get_transform: the unit quad to the panel in the world
+    cursor: [f32; 4],       // xy = the pointer on the panel (0…1 from the top left), z = 1 if shown, w = panel aspect
+    tint: [f32; 4],         // a > 0: a solid quad of this colour (premultiplied), for guide lines
+}
+
+// UI panel uniforms (ui_panel.wgsl / ui_flat.wgsl)
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct UiUniforms {
+    panel: [[f32; 4]; 4],   // where the curved panel sits in the world
+    flat_extent: [f32; 4],  // xy = the part of the texture the 2D screen covers
+}
+
 pub struct Renderer {
     #[allow(dead_code)]
     window: Arc<Window>,
@@ -58,19 +182,24 @@ pub struct Renderer {
     #[allow(dead_code)]
     camera_bind_group_layout: BindGroupLayout,
     
-    // Video Texture
-    video_texture_y: Option<wgpu::Texture>,
-    video_texture_y_view: Option<wgpu::TextureView>,
-    video_texture_uv: Option<wgpu::Texture>,
-    video_texture_uv_view: Option<wgpu::TextureView>,
+    // Video Textures, one surface per screen
+    videos: Vec<VideoSurface>,
     video_sampler: wgpu::Sampler,
-    video_bind_group: wgpu::BindGroup,  // Always valid (placeholder or real)
     video_bind_group_layout: wgpu::BindGroupLayout,
-    has_video: bool,
-    video_width: u32,
-    video_height: u32,
+    placeholder_view_y: wgpu::TextureView,
+    placeholder_view_uv: wgpu::TextureView,
     // Stereoscopic video layout: 0 = mono, 1 = side-by-side, 2 = over-under.
     pub stereo_mode: u32,
+    // Pan of zoomed content: the point (in screen widths / heights from the centre,
+    // + = right / down) that's brought in front of the viewer.
+    pub content_pan: [f32; 2],
+    // How video fills its screen: 0 = fit, 1 = fill 16:9, 2 = stretch 16:9, 3 = crop to `crop_aspect`.
+    pub aspect_mode: u32,
+    pub crop_aspect: f32,
+    // Brightness (offset), contrast, saturation, gamma; identity is [0, 1, 1, 1].
+    pub picture: [f32; 4],
+    // Draw nothing (black frames), e.g. while the phone is out of the headset.
+    pub blank: bool,
 
     // Web (browser) RGBA texture — shown on the VR screen when in web mode.
     web_texture: wgpu::Texture,
@@ -78,6 +207,10 @@ pub struct Renderer {
     pub has_web: bool,
     web_width: u32,
     web_height: u32,
+    // Floating browser windows' pages, by window
+    web_windows: HashMap<i32, WebTexture>,
+    // Takes the browser's hardware buffers, if the device was opened for it
+    importer: Option<hardware_buffer::Importer>,
 
     // UI Texture (egui renders here; shown as its OWN curved panel, not composited)
     ui_texture: wgpu::Texture,
@@ -85,7 +218,24 @@ pub struct Renderer {
     // Separate curved UI panel (dock / Media Center), drawn front-and-centre.
     ui_panel_pipeline: RenderPipeline,
     ui_panel_bind_group: BindGroup,
+    // 2D mode: the UI texture laid flat over the screen instead
+    ui_flat_pipeline: RenderPipeline,
+    ui_buffer: Buffer,
+    /// Placement of the curved UI panel: identity keeps it straight ahead of the
+    /// recentered view; lib.rs turns it to where a menu opened, or to follow the head
+    pub ui_transform: Mat4,
 
+    // Floating window panels (window_manager), instanced quads over the dome
+    window_panel_pipeline: RenderPipeline,
+    panel_instances: Buffer,
+    ui_panel_bgl: BindGroupLayout,
+    ui_panel_sampler: wgpu::Sampler,
+    // Panels to draw this frame, grouped by source, with the pointer on them
+    window_panels: Vec<(Mat4, PanelSource, Option<Vec2>)>,
+    // Panels' own UI textures, by panel id
+    panel_textures: HashMap<u32, PanelTexture>,
+    depth_texture: wgpu::Texture,
+    depth_view: wgpu::TextureView,
 
     // Post Processing (Distortion)
     offscreen_texture: wgpu::Texture,
@@ -98,6 +248,10 @@ pub struct Renderer {
     
     // VR mode state
     pub vr_mode: bool,
+    /// Distance between the eye cameras, m
+    pub ipd: f32,
+    /// Vertical field of view of each eye in VR, °
+    pub fov: f32,
     
     // UI Renderer
     egui_renderer: egui_wgpu::Renderer,
@@ -107,8 +261,8 @@ pub struct Renderer {
 }
 
 impl Renderer {
-    // Inter-pupillary distance (average human IPD is ~63mm)
-    const IPD: f32 = 0.063;
+    // Default inter-pupillary distance (average human IPD is ~63mm)
+    pub const IPD: f32 = 0.063;
     // Dome screen mesh (must match SCREEN_COLS/ROWS in main.wgsl) and UI panel mesh
     // (must match COLS/ROWS in ui_panel.wgsl).
     const SCREEN_COLS: u32 = 64;
@@ -140,7 +294,12 @@ impl Renderer {
             force_fallback_adapter: false,
         }).await.expect("Failed to find GPU adapter");
         
-        let (device, queue) = adapter.request_device(&DeviceDescriptor::default(), None).await.expect("Failed to create device");
+        // With hardware buffer import if the GPU has it, for the browser's pages
+        let (device, queue) = match hardware_buffer::open_device(&adapter) {
+            Some(opened) => opened,
+            None => adapter.request_device(&DeviceDescriptor::default(), None).await.expect("Failed to create device"),
+        };
+        let importer = hardware_buffer::Importer::new(&device);
         
         // Log wgpu errors instead of panicking
         device.on_uncaptured_error(Box::new(|error| {
@@ -162,10 +321,10 @@ impl Renderer {
         };
         surface.configure(&device, &config);
         
-        // Room for 3 eye uniform regions (left / right / mono), EYE_STRIDE apart.
+        // Room for 3 eye uniform regions (left / right / mono) per screen, EYE_STRIDE apart.
         let camera_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Camera Buffer"),
-            size: EYE_STRIDE * 3,
+            size: EYE_STRIDE * 3 * MAX_VIDEOS as u64,
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
@@ -302,7 +461,7 @@ impl Renderer {
                 compilation_options: Default::default(),
             }),
             primitive: wgpu::PrimitiveState::default(),
-            depth_stencil: None,
+            depth_stencil: Some(Self::depth_state(false)),
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
             cache: None,
@@ -352,14 +511,32 @@ impl Renderer {
                     ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                     count: None,
                 },
+                // Panel placement and flat overlay extent
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         });
+        let ui_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("UI Buffer"),
+            size: std::mem::size_of::<UiUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
         let ui_panel_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("UI Panel Bind Group"),
             layout: &ui_panel_bgl,
             entries: &[
                 wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&ui_texture_view) },
                 wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&ui_panel_sampler) },
+                wgpu::BindGroupEntry { binding: 2, resource: ui_buffer.as_entire_binding() },
             ],
         });
         let ui_panel_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -396,6 +573,79 @@ impl Renderer {
                 compilation_options: Default::default(),
             }),
             primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(Self::depth_state(false)),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        // ── Floating window panels: instanced quads, depth-tested ─────────────
+        let window_panel_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Window Panel Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/window_panel.wgsl").into()),
+        });
+        let panel_instances = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Window Panel Instances"),
+            size: (std::mem::size_of::<PanelInstance>() * MAX_PANELS) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let window_panel_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Window Panel Pipeline"),
+            layout: Some(&ui_panel_layout),
+            vertex: wgpu::VertexState {
+                module: &window_panel_shader, entry_point: Some("vs_main"),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<PanelInstance>() as u64,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float32x4, 1 => Float32x4, 2 => Float32x4, 3 => Float32x4, 4 => Float32x4, 5 => Float32x4],
+                }],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &window_panel_shader, entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(Self::depth_state(true)),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        // ── Flat UI overlay (2D mode), same texture and blending ──────────────
+        let ui_flat_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("UI Flat Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/ui_flat.wgsl").into()),
+        });
+        let ui_flat_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("UI Flat Pipeline Layout"),
+            bind_group_layouts: &[&ui_panel_bgl],
+            push_constant_ranges: &[],
+        });
+        let ui_flat_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("UI Flat Pipeline"),
+            layout: Some(&ui_flat_layout),
+            vertex: wgpu::VertexState {
+                module: &ui_flat_shader, entry_point: Some("vs_main"),
+                buffers: &[], compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &ui_flat_shader, entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
             depth_stencil: None,
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
@@ -440,17 +690,29 @@ impl Renderer {
         });
         let web_texture_view = web_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
-        let video_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Video Bind Group (Placeholder)"),
-            layout: &video_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&placeholder_view_y) },
-                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(&placeholder_view_uv) },
-                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(&video_sampler) },
-                wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(&ui_texture_view) },
-                wgpu::BindGroupEntry { binding: 4, resource: wgpu::BindingResource::TextureView(&web_texture_view) },
-            ],
-        });
+        let videos = (0..MAX_VIDEOS).map(|slot| VideoSurface {
+            texture_y: None,
+            texture_y_view: None,
+            texture_uv: None,
+            texture_uv_view: None,
+            bind_group: device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Video Bind Group (Placeholder)"),
+                layout: &video_bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&placeholder_view_y) },
+                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(&placeholder_view_uv) },
+                    wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(&video_sampler) },
+                    wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(&ui_texture_view) },
+                    wgpu::BindGroupEntry { binding: 4, resource: wgpu::BindingResource::TextureView(&web_texture_view) },
+                ],
+            }),
+            has_video: false,
+            width: 1920,  // Default 16:9
+            height: 1080,
+            rotation: 0,
+            placement: ScreenPlacement::default(),
+            visible: slot == 0,
+        }).collect();
 
         // --- Distortion Pipeline Setup ---
         
@@ -466,6 +728,8 @@ impl Renderer {
         };
         let offscreen_texture = device.create_texture(&texture_desc);
         let offscreen_view = offscreen_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let depth_texture = Self::create_depth_texture(&device, config.width, config.height);
+        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
         
         let offscreen_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
@@ -576,25 +840,29 @@ impl Renderer {
             camera_bind_group_layout: bind_group_layout,
             
             // Video (placeholder initially)
-            video_texture_y: None,
-            video_texture_y_view: None,
-            video_texture_uv: None,
-            video_texture_uv_view: None,
+            videos,
             video_sampler,
-            video_bind_group,
             video_bind_group_layout,
-            has_video: false,
-            video_width: 1920,  // Default 16:9
-            video_height: 1080,
+            placeholder_view_y,
+            placeholder_view_uv,
             stereo_mode: 0,
+            content_pan: [0.0, 0.0],
+            aspect_mode: 0,
+            crop_aspect: 2.39,
+            picture: [0.0, 1.0, 1.0, 1.0],
+            blank: false,
 
             web_texture_view: web_texture.create_view(&wgpu::TextureViewDescriptor::default()),
             web_texture,
             has_web: false,
             web_width: 1920,
             web_height: 1080,
+            web_windows: HashMap::new(),
+            importer,
 
             vr_mode: false,
+            ipd: Self::IPD,
+            fov: 90.0,
             egui_renderer,
             offscreen_texture,
             offscreen_view,
@@ -609,6 +877,17 @@ impl Renderer {
             ui_texture_view,
             ui_panel_pipeline,
             ui_panel_bind_group,
+            ui_flat_pipeline,
+            ui_buffer,
+            ui_transform: Mat4::IDENTITY,
+            window_panel_pipeline,
+            panel_instances,
+            ui_panel_bgl,
+            ui_panel_sampler,
+            window_panels: Vec::new(),
+            panel_textures: HashMap::new(),
+            depth_texture,
+            depth_view,
         }
     }
     
@@ -631,6 +910,8 @@ impl Renderer {
             };
             self.offscreen_texture = self.device.create_texture(&texture_desc);
             self.offscreen_view = self.offscreen_texture.create_view(&wgpu::TextureViewDescriptor::default());
+            self.depth_texture = Self::create_depth_texture(&self.device, width, height);
+            self.depth_view = self.depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
             
             self.distortion_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
                 label: Some("Distortion Bind Group"),
@@ -647,10 +928,161 @@ impl Renderer {
     pub fn toggle_vr_mode(&mut self) {
         self.vr_mode = !self.vr_mode;
     }
+
+    /// Depth buffer for the eye passes, the size of the screen.
+    fn create_depth_texture(device: &Device, width: u32, height: u32) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Depth Texture"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: DEPTH_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        })
+    }
+
+    /// Depth state for a pipeline in the eye pass: tested and written (window
+    /// panels), or ignored so it draws in order (dome screens, UI panel).
+    fn depth_state(test: bool) -> wgpu::DepthStencilState {
+        wgpu::DepthStencilState {
+            format: DEPTH_FORMAT,
+            depth_write_enabled: test,
+            depth_compare: if test { wgpu::CompareFunction::Less } else { wgpu::CompareFunction::Always },
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }
+    }
+
+    /// The floating window panels to draw, each with its model matrix and the
+    /// pointer if it's over it (first `MAX_PANELS` only).
+    pub fn set_window_panels(&mut self, mut panels: Vec<(Mat4, PanelSource, Option<Vec2>)>) {
+        // Closed panels' own textures go with them
+        self.panel_textures.retain(|id, _| panels.iter().any(|(_, source, _)| *source == PanelSource::Own(*id)));
+        self.web_windows.retain(|window, _| panels.iter().any(|(_, source, _)| *source == PanelSource::Web(*window)));
+        panels.truncate(MAX_PANELS);
+        // Grouped by texture, one instanced draw each
+        panels.sort_by_key(|(_, source, _)| *source);
+        self.window_panels = panels;
+    }
+
+    /// Paint panel `id`'s own egui frame into its texture, made on first use
+    /// (and again at a new size). Quads with `PanelSource::Own(id)` show it.
+    pub fn render_panel_ui(&mut self, id: u32, size: [u32; 2], ctx: &egui::Context, output: egui::FullOutput) {
+        if size[0] == 0 || size[1] == 0 { return; }
+        if self.panel_textures.get(&id).is_none_or(|panel| panel.size != size) {
+            let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Panel UI Texture"),
+                size: wgpu::Extent3d { width: size[0], height: size[1], depth_or_array_layers: 1 },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Panel UI Bind Group"),
+                layout: &self.ui_panel_bgl,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.ui_panel_sampler) },
+                    wgpu::BindGroupEntry { binding: 2, resource: self.ui_buffer.as_entire_binding() },
+                ],
+            });
+            // A resized panel keeps its egui renderer: egui only sends the font atlas once
+            match self.panel_textures.get_mut(&id) {
+                Some(panel) => {
+                    panel.size = size;
+                    panel.view = view;
+                    panel.bind_group = bind_group;
+                }
+                None => {
+                    let egui_renderer = egui_wgpu::Renderer::new(&self.device, wgpu::TextureFormat::Rgba8UnormSrgb, None, 1, false);
+                    self.panel_textures.insert(id, PanelTexture { size, view, bind_group, egui_renderer });
+                }
+            }
+        }
+        let Some(panel) = self.panel_textures.get_mut(&id) else { return };
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Panel UI Encoder"),
+        });
+        Self::paint_egui(&self.device, &self.queue, &mut encoder, &mut panel.egui_renderer,
+            &panel.view, size, Some((ctx, &output)));
+        self.queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Clear `view` and paint an egui frame (if any) into it. Each egui context
+    /// needs its own `egui_renderer`, as their texture ids overlap.
+    fn paint_egui(
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        egui_renderer: &mut egui_wgpu::Renderer,
+        view: &wgpu::TextureView,
+        size: [u32; 2],
+        ui: Option<(&egui::Context, &egui::FullOutput)>,
+    ) {
+        let paint = ui.map(|(ctx, full_output)| {
+            let screen_descriptor = egui_wgpu::ScreenDescriptor {
+                size_in_pixels: size,
+                pixels_per_point: ctx.pixels_per_point(),
+            };
+            let paint_jobs = ctx.tessellate(full_output.shapes.clone(), full_output.pixels_per_point);
+            for (id, delta) in &full_output.textures_delta.set {
+                egui_renderer.update_texture(device, queue, *id, delta);
+            }
+            egui_renderer.update_buffers(device, queue, encoder, &paint_jobs, &screen_descriptor);
+            (paint_jobs, screen_descriptor)
+        });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("UI Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            if let Some((paint_jobs, screen_descriptor)) = &paint {
+                let render_pass_static: &mut wgpu::RenderPass<'static> = unsafe { std::mem::transmute(&mut render_pass) };
+                egui_renderer.render(render_pass_static, paint_jobs, screen_descriptor);
+            }
+        }
+        if let Some((_, full_output)) = ui {
+            for id in &full_output.textures_delta.free {
+                egui_renderer.free_texture(id);
+            }
+        }
+    }
     
-    /// Creates Y and UV textures (R8 and Rg8)
-    fn create_video_texture(&mut self, width: u32, height: u32) {
-        if self.video_texture_y.is_some() && self.video_width == width && self.video_height == height {
+    /// A video bind group over `y`/`uv` plus the shared UI and web textures.
+    fn video_bind_group(&self, label: &str, y: &wgpu::TextureView, uv: &wgpu::TextureView) -> wgpu::BindGroup {
+        self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(label),
+            layout: &self.video_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(y) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(uv) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(&self.video_sampler) },
+                wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(&self.ui_texture_view) },
+                wgpu::BindGroupEntry { binding: 4, resource: wgpu::BindingResource::TextureView(&self.web_texture_view) },
+            ],
+        })
+    }
+
+    /// Creates Y and UV textures (R8 and Rg8) for a video slot
+    fn create_video_texture(&mut self, slot: usize, width: u32, height: u32) {
+        let video = &self.videos[slot];
+        if video.texture_y.is_some() && video.width == width && video.height == height {
              return;
         }
 
@@ -678,36 +1110,28 @@ impl Renderer {
         
         let view_y = texture_y.create_view(&wgpu::TextureViewDescriptor::default());
         let view_uv = texture_uv.create_view(&wgpu::TextureViewDescriptor::default());
-        
-        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Video YUV Bind Group"),
-            layout: &self.video_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view_y) },
-                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(&view_uv) },
-                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(&self.video_sampler) },
-                wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(&self.ui_texture_view) },
-                wgpu::BindGroupEntry { binding: 4, resource: wgpu::BindingResource::TextureView(&self.web_texture_view) },
-            ],
-        });
+        let bind_group = self.video_bind_group("Video YUV Bind Group", &view_y, &view_uv);
 
-        self.video_texture_y = Some(texture_y);
-        self.video_texture_y_view = Some(view_y);
-        self.video_texture_uv = Some(texture_uv);
-        self.video_texture_uv_view = Some(view_uv);
-        self.video_bind_group = bind_group;
-        self.video_width = width;
-        self.video_height = height;
+        let video = &mut self.videos[slot];
+        video.texture_y = Some(texture_y);
+        video.texture_y_view = Some(view_y);
+        video.texture_uv = Some(texture_uv);
+        video.texture_uv_view = Some(view_uv);
+        video.bind_group = bind_group;
+        video.width = width;
+        video.height = height;
     }
     
-    /// Updates video texture with new frame data from Java
-    pub fn update_video_texture(&mut self, y_data: &[u8], uv_data: &[u8], width: u32, height: u32) {
-        if self.video_texture_y.is_none() || self.video_width != width || self.video_height != height {
-            self.create_video_texture(width, height);
-            self.has_video = true;
+    /// Updates a video slot's textures with a new decoded frame (Y + interleaved UV)
+    pub fn update_video_texture(&mut self, slot: usize, y_data: &[u8], uv_data: &[u8], width: u32, height: u32) {
+        let Some(video) = self.videos.get(slot) else { return };
+        if video.texture_y.is_none() || video.width != width || video.height != height {
+            self.create_video_texture(slot, width, height);
         }
+        let video = &mut self.videos[slot];
+        video.has_video = true;
 
-        if let Some(ref texture_y) = self.video_texture_y {
+        if let Some(ref texture_y) = video.texture_y {
             self.queue.write_texture(
                 wgpu::ImageCopyTexture {
                     texture: texture_y,
@@ -725,7 +1149,7 @@ impl Renderer {
             );
         }
         
-        if let Some(ref texture_uv) = self.video_texture_uv {
+        if let Some(ref texture_uv) = video.texture_uv {
             self.queue.write_texture(
                 wgpu::ImageCopyTexture {
                     texture: texture_uv,
@@ -744,11 +1168,59 @@ impl Renderer {
         }
     }
 
-    /// Updates the web (browser) RGBA texture with a new frame from GeckoView.
-    /// Recreates the texture (and rebuilds the shared video bind group so binding 4
-    /// points at it) when the size changes, then uploads the pixels.
-    pub fn update_web_texture(&mut self, rgba: &[u8], width: u32, height: u32) {
-        if width == 0 || height == 0 { return; }
+    /// Clockwise rotation (0/90/180/270) a slot's frames need for display.
+    pub fn set_video_rotation(&mut self, slot: usize, degrees: u32) {
+        if let Some(video) = self.videos.get_mut(slot) {
+            video.rotation = degrees;
+        }
+    }
+
+    /// Show a slot's screen at `placement`, or hide it (`None`; the main screen
+    /// only goes back to full size).
+    pub fn set_video_placement(&mut self, slot: usize, placement: Option<ScreenPlacement>) {
+        if let Some(video) = self.videos.get_mut(slot) {
+            video.visible = slot == 0 || placement.is_some();
+            video.placement = placement.unwrap_or_default();
+            if !video.visible {
+                video.has_video = false;
+            }
+        }
+    }
+
+    /// Whether the browser can send its hardware buffers (`update_web_buffer`).
+    pub fn imports_hardware_buffers(&self) -> bool {
+        self.importer.is_some()
+    }
+
+    /// Make the texture browser window `window`'s page goes into
+    /// (`webview::SCREEN`: the main screen's) `width` × `height`, if it isn't.
+    fn size_web_texture(&mut self, window: i32, width: u32, height: u32) {
+        if window != webview::SCREEN {
+            if self.web_windows.get(&window).is_none_or(|web| web.size != [width, height]) {
+                let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("Web Window Texture"),
+                    size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                    usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                    view_formats: &[],
+                });
+                let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+                let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("Web Window Bind Group"),
+                    layout: &self.ui_panel_bgl,
+                    entries: &[
+                        wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+                        wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.ui_panel_sampler) },
+                        wgpu::BindGroupEntry { binding: 2, resource: self.ui_buffer.as_entire_binding() },
+                    ],
+                });
+                self.web_windows.insert(window, WebTexture { size: [width, height], texture, bind_group });
+            }
+            return;
+        }
 
         if self.web_width != width || self.web_height != height || !self.has_web {
             let web_texture = self.device.create_texture(&wgpu::TextureDescriptor {
@@ -763,48 +1235,45 @@ impl Renderer {
             });
             let web_texture_view = web_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
-            // Rebuild the video bind group so binding 4 points at the new web texture.
-            // Reuse the current video textures (or the 1x1 placeholders on first use).
-            let placeholder_y = self.device.create_texture(&wgpu::TextureDescriptor {
-                label: Some("Placeholder Y"), size: wgpu::Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
-                mip_level_count: 1, sample_count: 1, dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::R8Unorm,
-                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST, view_formats: &[],
-            });
-            let placeholder_y_view = placeholder_y.create_view(&wgpu::TextureViewDescriptor::default());
-            let placeholder_uv = self.device.create_texture(&wgpu::TextureDescriptor {
-                label: Some("Placeholder UV"), size: wgpu::Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
-                mip_level_count: 1, sample_count: 1, dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rg8Unorm,
-                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST, view_formats: &[],
-            });
-            let placeholder_uv_view = placeholder_uv.create_view(&wgpu::TextureViewDescriptor::default());
-
-            let y_view = self.video_texture_y_view.as_ref().unwrap_or(&placeholder_y_view);
-            let uv_view = self.video_texture_uv_view.as_ref().unwrap_or(&placeholder_uv_view);
-
-            self.video_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("Video Bind Group (web-updated)"),
-                layout: &self.video_bind_group_layout,
-                entries: &[
-                    wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(y_view) },
-                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(uv_view) },
-                    wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(&self.video_sampler) },
-                    wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(&self.ui_texture_view) },
-                    wgpu::BindGroupEntry { binding: 4, resource: wgpu::BindingResource::TextureView(&web_texture_view) },
-                ],
-            });
-
             self.web_texture = web_texture;
             self.web_texture_view = web_texture_view;
             self.web_width = width;
             self.web_height = height;
             self.has_web = true;
+
+            // Rebuild the video bind groups so binding 4 points at the new web texture.
+            // Reuse the current video textures (or the 1x1 placeholders on first use).
+            for slot in 0..self.videos.len() {
+                let video = &self.videos[slot];
+                let y_view = video.texture_y_view.as_ref().unwrap_or(&self.placeholder_view_y);
+                let uv_view = video.texture_uv_view.as_ref().unwrap_or(&self.placeholder_view_uv);
+                let bind_group = self.video_bind_group("Video Bind Group (web-updated)", y_view, uv_view);
+                self.videos[slot].bind_group = bind_group;
+            }
+        }
+    }
+
+    /// The texture browser window `window`'s page is in, once it has one.
+    fn web_texture_of(&self, window: i32) -> Option<&wgpu::Texture> {
+        if window == webview::SCREEN {
+            self.has_web.then_some(&self.web_texture)
+        } else {
+            self.web_windows.get(&window).map(|web| &web.texture)
         }
+    }
+
+    /// Updates browser window `window`'s page (`webview::SCREEN`: the main
+    /// screen's) with an RGBA frame from GeckoView. Recreates the texture (for the
+    /// screen, rebuilding the video bind groups so binding 4 points at it) when the
+    /// size changes, then uploads the pixels.
+    pub fn update_web_texture(&mut self, window: i32, rgba: &[u8], width: u32, height: u32) {
+        if width == 0 || height == 0 { return; }
+        self.size_web_texture(window, width, height);
+        let Some(texture) = self.web_texture_of(window) else { return };
 
         self.queue.write_texture(
             wgpu::ImageCopyTexture {
-                texture: &self.web_texture,
+                texture,
                 mip_level: 0,
                 origin: wgpu::Origin3d::ZERO,
                 aspect: wgpu::TextureAspect::All,
@@ -819,10 +1288,29 @@ impl Renderer {
         );
     }
 
+    /// Updates browser window `window`'s page like `update_web_texture`, from the
+    /// hardware buffer it was rendered into, copied on the GPU. The buffer goes
+    /// back to the browser once the copy has completed. False if it couldn't be
+    /// imported (the browser should go back to RGBA frames).
+    pub fn update_web_buffer(&mut self, window: i32, lent: webview::LentBuffer) -> bool {
+        let (width, height) = lent.buffer.size();
+        if width == 0 || height == 0 { return true; }
+        let Some(importer) = &self.importer else { return false };
+        let Some(frame) = importer.import(&lent.buffer) else { return false };
+        self.size_web_texture(window, width, height);
+        let (Some(importer), Some(texture)) = (&self.importer, self.web_texture_of(window)) else { return true };
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Web Frame Copy") });
+        importer.copy(&mut encoder, &frame, texture);
+        self.queue.submit(Some(encoder.finish()));
+        self.queue.on_submitted_work_done(move || drop((frame, lent)));
+        true
+    }
 
     pub fn render(
         &mut self, 
         head_orientation: Quat, 
+        head_position: Vec3, // metres from the recentered position (6DOF tracking), else zero
         ui_data: Option<(&egui::Context, egui::FullOutput)>,
         distortion_params: Option<(f32, f32)>, // lens_radius, lens_center_offset
         content_scale: f32, // New scalar for virtual screen size
@@ -868,70 +1356,8 @@ impl Renderer {
         });
 
         // 1. Render UI to Texture (Always render, even if empty/hidden, to clear texture)
-        {
-            // Clear UI Texture
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("UI Clear Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &self.ui_texture_view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            });
-        }
-        
-        if let Some((ctx, ref full_output)) = ui_data {
-            // For ui_texture pass, use texture dimensions (2048x2048)
-            // The direct screen pass later will use screen size
-            let screen_descriptor = egui_wgpu::ScreenDescriptor {
-                size_in_pixels: [2048, 2048],
-                pixels_per_point: ctx.pixels_per_point(),
-            };
-            
-            let paint_jobs = ctx.tessellate(full_output.shapes.clone(), full_output.pixels_per_point);
-            
-            for (id, delta) in &full_output.textures_delta.set {
-                self.egui_renderer.update_texture(&self.device, &self.queue, *id, delta);
-            }
-            
-            self.egui_renderer.update_buffers(
-                &self.device,
-                &self.queue,
-                &mut encoder,
-                &paint_jobs,
-                &screen_descriptor,
-            );
-            
-            {
-                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                    label: Some("UI Render Pass"),
-                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                        view: &self.ui_texture_view,
-                        resolve_target: None,
-                        ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Load, // Load the cleared transparent texture
-                            store: wgpu::StoreOp::Store,
-                        },
-                    })],
-                    depth_stencil_attachment: None,
-                    timestamp_writes: None,
-                    occlusion_query_set: None,
-                });
-                
-                let render_pass_static: &mut wgpu::RenderPass<'static> = unsafe { std::mem::transmute(&mut render_pass) };
-                self.egui_renderer.render(render_pass_static, &paint_jobs, &screen_descriptor);
-            }
-            
-            for id in &full_output.textures_delta.free {
-                self.egui_renderer.free_texture(id);
-            }
-        }
+        Self::paint_egui(&self.device, &self.queue, &mut encoder, &mut self.egui_renderer,
+            &self.ui_texture_view, [2048, 2048], ui_data.as_ref().map(|(ctx, output)| (*ctx, output)));
         
         // 2. Clear Screen
         {
@@ -951,12 +1377,41 @@ impl Renderer {
             });
         }
         
+        // Where the UI panel goes (VR), or how much of it covers the screen (2D)
+        let (width, height) = self.size;
+        let flat_scale = Self::ui_flat_scale(width, height) / crate::gamepad::UI_CANVAS;
+        let ui_uniforms = UiUniforms {
+            panel: self.ui_transform.to_cols_array_2d(),
+            flat_extent: [width as f32 * flat_scale, height as f32 * flat_scale, 0.0, 0.0],
+        };
+        self.queue.write_buffer(&self.ui_buffer, 0, bytemuck::bytes_of(&ui_uniforms));
+        let guide = egui::Rgba::from(crate::theme::current().accent).to_array();
+        let instances: Vec<PanelInstance> = self.window_panels.iter()
+            .map(|(model, source, cursor)| {
+                let aspect = model.x_axis.length() / model.y_axis.length().max(1e-6);
+                PanelInstance {
+                    model: model.to_cols_array_2d(),
+                    cursor: cursor.map_or([0.0, 0.0, 0.0, aspect], |at| [at.x, at.y, 1.0, aspect]),
+                    tint: if *source == PanelSource::Guide { guide } else { [0.0; 4] },
+                }
+            })
+            .collect();
+        if !instances.is_empty() {
+            self.queue.write_buffer(&self.panel_instances, 0, bytemuck::cast_slice(&instances));
+        }
+
         // 3. Render 3D Scene
-        if self.vr_mode {
-            self.render_eye(&mut encoder, target_view, head_orientation, -Self::IPD / 2.0, 0, lens_offset_val, content_scale); 
-            self.render_eye(&mut encoder, target_view, head_orientation, Self::IPD / 2.0, 1, lens_offset_val, content_scale);  
+        let eye = |index, eye_offset, lens_center_offset| EyeView {
+            head_orientation, head_position, index, eye_offset, lens_center_offset, content_scale,
+        };
+        if self.blank {
+            // Phone out of the headset: leave the frame black
+        } else if self.vr_mode {
+            self.render_eye(&mut encoder, target_view, &eye(0, -self.ipd / 2.0, lens_offset_val));
+            self.render_eye(&mut encoder, target_view, &eye(1, self.ipd / 2.0, lens_offset_val));
         } else {
-            self.render_eye(&mut encoder, target_view, head_orientation, 0.0, 2, 0.0, content_scale); 
+            self.render_eye(&mut encoder, target_view, &eye(2, 0.0, 0.0));
+            self.render_flat_ui(&mut encoder, target_view);
         }
         
         // 4. Distortion Pass
@@ -982,14 +1437,18 @@ impl Renderer {
         }
         
         // Note: UI is already rendered to ui_texture and composited via shader
-        // No direct screen overlay needed - VR-only UI rendering
+        // (the curved panel in VR, the flat overlay in 2D)
         
         self.queue.submit(std::iter::once(encoder.finish()));
         output.present();
     }
     
     // --- Phase 9: Proven Asymmetric Projection ---
-    fn render_eye(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, head_orientation: Quat, base_eye_offset: f32, eye_index: u32, lens_center_dist_offset: f32, content_scale: f32) {
+    fn render_eye(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, eye: &EyeView) {
+        let EyeView {
+            head_orientation, head_position, index: eye_index, eye_offset: base_eye_offset,
+            lens_center_offset: lens_center_dist_offset, content_scale,
+        } = *eye;
          let (width, height) = self.size;
         let (viewport_x, viewport_width) = match eye_index {
             0 => (0, width / 2),
@@ -1011,7 +1470,8 @@ impl Renderer {
         
         let near = 0.1;
         let far = 100.0;
-        let fov_y_radians = 90.0_f32.to_radians();
+        // 2D mode keeps the phone-screen view
+        let fov_y_radians = if eye_index == 2 { 90.0_f32 } else { self.fov }.to_radians();
         let aspect = viewport_width as f32 / height as f32;
         
         let top = near * (fov_y_radians / 2.0).tan();
@@ -1037,27 +1497,48 @@ impl Renderer {
             glam::Vec4::new(0.0, 0.0, z_offset, 0.0),
         );
 
-        let view_matrix = Mat4::from_quat(head_orientation.inverse());
+        let view_matrix = Mat4::from_quat(head_orientation.inverse()) * Mat4::from_translation(-head_position);
         let view_proj = proj_matrix * view_matrix;
         
-        // In web mode the browser texture takes over the screen; use its aspect.
-        let (scr_w, scr_h) = if self.has_web {
-            (self.web_width as f32, self.web_height as f32)
-        } else {
-            (self.video_width as f32, self.video_height as f32)
-        };
-        let camera_uniforms = CameraUniforms {
-            view_proj: view_proj.to_cols_array_2d(),
-            // Pass has_video in .y, Time in .z, Content Scale in .w
-            eye_offset: [dynamic_offset, if self.has_video { 1.0 } else { 0.0 }, self.start_time.elapsed().as_secs_f32(), content_scale],
-            // x = aspect, y = width, z = height, w = web flag (1 = show web texture)
-            video_info: [scr_w / scr_h, scr_w, scr_h, if self.has_web { 1.0 } else { 0.0 }],
-            // Stereo: mode + which eye (0 left, 1 right, 2 mono) — drives per-eye UV split.
-            stereo: [self.stereo_mode as f32, eye_index as f32, 0.0, 0.0],
-        };
-        // Write into THIS eye's region so the other eye's pass keeps its own uniforms.
-        let eye_off = eye_index as u64 * EYE_STRIDE;
-        self.queue.write_buffer(&self.camera_buffer, eye_off, bytemuck::bytes_of(&camera_uniforms));
+        // One uniform region per (screen, eye) so every screen keeps its own layout.
+        let mut screens = Vec::with_capacity(self.videos.len());
+        for (slot, video) in self.videos.iter().enumerate().filter(|(_, v)| v.visible) {
+            // In web mode the browser texture takes over the main screen; use its aspect.
+            // Video rotated a quarter turn shows with width and height swapped.
+            let is_web = slot == 0 && self.has_web;
+            let quarter_turns = if is_web { 0 } else { video.rotation / 90 % 4 };
+            let (scr_w, scr_h) = if is_web {
+                (self.web_width as f32, self.web_height as f32)
+            } else if quarter_turns % 2 == 1 {
+                (video.height as f32, video.width as f32)
+            } else {
+                (video.width as f32, video.height as f32)
+            };
+            // Aspect mode works on what one eye sees (half the frame for stereo video)
+            let eye_aspect = match self.stereo_mode { 1 => scr_w / scr_h * 0.5, 2 => scr_w / scr_h * 2.0, _ => scr_w / scr_h };
+            let (screen_aspect, uv_scale) = if is_web {
+                (0.0, [1.0, 1.0])
+            } else {
+                fit_video(self.aspect_mode, self.crop_aspect, eye_aspect)
+            };
+            let camera_uniforms = CameraUniforms {
+                view_proj: view_proj.to_cols_array_2d(),
+                // Pass has_video in .y, Time in .z, Content Scale in .w
+                eye_offset: [dynamic_offset, if video.has_video { 1.0 } else { 0.0 }, self.start_time.elapsed().as_secs_f32(), content_scale],
+                // x = aspect, y = width, z = height, w = web flag (1 = show web texture)
+                video_info: [scr_w / scr_h, scr_w, scr_h, if is_web { 1.0 } else { 0.0 }],
+                // Stereo: mode + which eye (0 left, 1 right, 2 mono) — drives per-eye UV split.
+                stereo: [self.stereo_mode as f32, eye_index as f32, quarter_turns as f32, 0.0],
+                screen: [video.placement.yaw, video.placement.scale, self.content_pan[0], self.content_pan[1]],
+                crop: [uv_scale[0], uv_scale[1], screen_aspect, 0.0],
+                picture: self.picture,
+            };
+            // Write into THIS eye's region so the other eye's pass keeps its own uniforms.
+            let eye_off = (slot as u64 * 3 + eye_index as u64) * EYE_STRIDE;
+            self.queue.write_buffer(&self.camera_buffer, eye_off, bytemuck::bytes_of(&camera_uniforms));
+            screens.push((eye_off as u32, &video.bind_group));
+        }
+        let eye_off = eye_index * EYE_STRIDE as u32;
         
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
@@ -1070,24 +1551,83 @@ impl Renderer {
                         store: wgpu::StoreOp::Store 
                     },
                 })],
-                depth_stencil_attachment: None,
+                // Cleared whole each pass; the other eye's half is already drawn
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.depth_view,
+                    depth_ops: Some(wgpu::Operations { load: wgpu::LoadOp::Clear(1.0), store: wgpu::StoreOp::Discard }),
+                    stencil_ops: None,
+                }),
                 timestamp_writes: None,
                 occlusion_query_set: None,
             });
             render_pass.set_viewport(viewport_x as f32, 0.0, viewport_width as f32, height as f32, 0.0, 1.0);
 
-            // 1) Curved dome screen (video / web / test pattern).
+            // 1) Curved dome screens (video / web / test pattern), main screen first.
             render_pass.set_pipeline(&self.pipeline);
-            render_pass.set_bind_group(0, &self.camera_bind_group, &[eye_off as u32]);
-            render_pass.set_bind_group(1, &self.video_bind_group, &[]);
-            render_pass.draw(0..Self::SCREEN_COLS * Self::SCREEN_ROWS * 6, 0..1);
-
-            // 2) Floating curved UI panel (dock / Media Center) — same dome curvature,
-            //    closer + centred, alpha-blended over the screen.
-            render_pass.set_pipeline(&self.ui_panel_pipeline);
-            render_pass.set_bind_group(0, &self.camera_bind_group, &[eye_off as u32]);
-            render_pass.set_bind_group(1, &self.ui_panel_bind_group, &[]);
-            render_pass.draw(0..Self::PANEL_COLS * Self::PANEL_ROWS * 6, 0..1);
+            for (offset, bind_group) in &screens {
+                render_pass.set_bind_group(0, &self.camera_bind_group, &[*offset]);
+                render_pass.set_bind_group(1, *bind_group, &[]);
+                render_pass.draw(0..Self::SCREEN_COLS * Self::SCREEN_ROWS * 6, 0..1);
+            }
+
+            // 2) Floating window panels, one instanced draw per texture, nearest in front.
+            //    Uses the main screen's uniforms.
+            if !self.window_panels.is_empty() {
+                render_pass.set_pipeline(&self.window_panel_pipeline);
+                render_pass.set_bind_group(0, &self.camera_bind_group, &[eye_off]);
+                render_pass.set_vertex_buffer(0, self.panel_instances.slice(..));
+                let mut first = 0;
+                for group in self.window_panels.chunk_by(|a, b| a.1 == b.1) {
+                    let instances = first..first + group.len() as u32;
+                    first = instances.end;
+                    let bind_group = match group[0].1 {
+                        // Guides don't sample it, but the layout wants a texture
+                        PanelSource::Ui | PanelSource::Guide => Some(&self.ui_panel_bind_group),
+                        PanelSource::Web(window) => self.web_windows.get(&window).map(|web| &web.bind_group),
+                        // Not painted yet
+                        PanelSource::Own(id) => self.panel_textures.get(&id).map(|panel| &panel.bind_group),
+                    };
+                    if let Some(bind_group) = bind_group {
+                        render_pass.set_bind_group(1, bind_group, &[]);
+                        render_pass.draw(0..6, instances);
+                    }
+                }
+            }
+
+            // 3) Floating curved UI panel (dock / Media Center) — same dome curvature,
+            //    closer + centred, alpha-blended over the screen. Uses the main screen's uniforms.
+            //    2D mode lays the UI flat instead (render_flat_ui).
+            if eye_index != 2 {
+                render_pass.set_pipeline(&self.ui_panel_pipeline);
+                render_pass.set_bind_group(0, &self.camera_bind_group, &[eye_off]);
+                render_pass.set_bind_group(1, &self.ui_panel_bind_group, &[]);
+                render_pass.draw(0..Self::PANEL_COLS * Self::PANEL_ROWS * 6, 0..1);
+            }
         }
     }
+
+    /// 2D mode: the UI texture over the whole screen. lib.rs lays the canvas out
+    /// `ui_flat_scale` canvas px per screen px, so the screen shows its top-left
+    /// (width, height) × scale part.
+    fn render_flat_ui(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("UI Flat Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.ui_flat_pipeline);
+        render_pass.set_bind_group(0, &self.ui_panel_bind_group, &[]);
+        render_pass.draw(0..6, 0..1);
+    }
+
+    /// Canvas px per screen px in 2D mode: the screen's longer side fills the texture.
+    pub fn ui_flat_scale(width: u32, height: u32) -> f32 {
+        crate::gamepad::UI_CANVAS / width.max(height).max(1) as f32
+    }
 }
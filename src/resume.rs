@@ -0,0 +1,203 @@
+//! Resume positions
+//!
+//! Remembers where each file was left off so reopening it can offer "Resume from
+//! 12:34", and which files were watched to the end (the Media Center's progress
+//! badges). Entries are keyed by path + duration, so a different file that later
+//! takes the same name doesn't resume at a stale spot. Stored as a small
+//! tab-separated text file in the app's internal storage (no serde here).
+
+use log::{error, info};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Positions this close to the start aren't worth resuming; this close to the end
+/// counts as watched.
+const MIN_POSITION_US: i64 = 10_000_000;
+const END_MARGIN_US: i64 = 15_000_000;
+/// How often the playing position is written back while playing.
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+/// Oldest entries are dropped beyond this.
+const MAX_ENTRIES: usize = 500;
+
+/// 64-bit FNV-1a, for keys that end up on disk (std's hasher is not stable across
+/// Rust releases).
+pub fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    bytes.into_iter().fold(0xcbf2_9ce4_8422_2325, |h, b| (h ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Path + whole seconds of duration.
+fn key(path: &str, duration_us: i64) -> u64 {
+    fnv1a(path.bytes().chain((duration_us / 1_000_000).to_le_bytes()))
+}
+
+struct Entry {
+    key: u64,
+    position_us: i64,
+    duration_us: i64,
+    path: String,
+}
+
+impl Entry {
+    /// Watched to the end (never, while the duration is unknown).
+    fn finished(&self) -> bool {
+        self.duration_us > 0 && self.position_us >= self.duration_us - END_MARGIN_US
+    }
+}
+
+pub struct ResumeStore {
+    file: Option<PathBuf>,
+    /// Least recently updated first.
+    entries: Vec<Entry>,
+    dirty: bool,
+    /// File the decoder is playing, and whether its saved position was looked up yet.
+    playing: Option<String>,
+    checked: bool,
+    /// The saved position was offered and the prompt isn't answered yet: nothing is
+    /// recorded over it until then.
+    offered: bool,
+    last_save: Instant,
+}
+
+impl ResumeStore {
+    /// Load from `<dir>/resume.tsv` (an empty store if it's missing or unreadable).
+    pub fn load(dir: Option<PathBuf>) -> Self {
+        let file = dir.map(|d| d.join("resume.tsv"));
+        let entries: Vec<Entry> = file.as_ref()
+            .and_then(|f| std::fs::read_to_string(f).ok())
+            .map(|text| text.lines().filter_map(|line| {
+                let parts: Vec<&str> = line.splitn(4, '\t').collect();
+                // Lines from before durations were kept have three columns: key,
+                // position, path. Their duration is unknown (0) until replayed.
+                let (duration_us, path) = match parts[..] {
+                    [_, _, duration, path] => (duration.parse().ok()?, path),
+                    [_, _, path] => (0, path),
+                    _ => return None,
+                };
+                Some(Entry {
+                    key:         u64::from_str_radix(parts[0], 16).ok()?,
+                    position_us: parts[1].parse().ok()?,
+                    duration_us,
+                    path:        path.to_string(),
+                })
+            }).collect())
+            .unwrap_or_default();
+        info!("Resume: {} saved positions", entries.len());
+        Self { file, entries, dirty: false, playing: None, checked: true, offered: false, last_save: Instant::now() }
+    }
+
+    /// A new file started; its saved position is looked up once the duration is known.
+    pub fn begin(&mut self, path: &str) {
+        self.playing = Some(path.to_string());
+        self.checked = false;
+        self.offered = false;
+        self.last_save = Instant::now();
+    }
+
+    /// Path of the file being played, if it's one we track.
+    pub fn playing(&self) -> Option<&str> {
+        self.playing.as_deref()
+    }
+
+    /// Something without a usable path started (e.g. a picked content:// fd).
+    pub fn begin_untracked(&mut self) {
+        self.playing = None;
+        self.offered = false;
+    }
+
+    /// How far through `path` the last viewing got: `None` = never watched (or
+    /// not past the first few seconds), `Some(1.0)` = watched to the end.
+    pub fn progress(&self, path: &str) -> Option<f32> {
+        let e = self.entries.iter().rev().find(|e| e.path == path)?;
+        if e.finished() {
+            Some(1.0)
+        } else if e.duration_us <= 0 {
+            Some(0.0)
+        } else {
+            Some((e.position_us as f32 / e.duration_us as f32).clamp(0.0, 1.0))
+        }
+    }
+
+    /// Call every frame with the decoder's position/duration. Returns the saved
+    /// position once, right after a partly watched file starts; positions aren't
+    /// recorded from then until `answered`.
+    pub fn tick(&mut self, position_us: i64, duration_us: i64) -> Option<i64> {
+        let path = self.playing.clone()?;
+        if duration_us <= 0 {
+            return None;
+        }
+        if !self.checked {
+            self.checked = true;
+            let k = key(&path, duration_us);
+            let saved = self.entries.iter().find(|e| e.key == k && !e.finished()).map(|e| e.position_us);
+            self.offered = saved.is_some();
+            return saved;
+        }
+        if self.last_save.elapsed() >= SAVE_INTERVAL {
+            self.last_save = Instant::now();
+            self.record(position_us, duration_us);
+            self.save();
+        }
+        None
+    }
+
+    /// Whether a saved position was offered (`tick`) and the prompt is still open.
+    pub fn offered(&self) -> bool {
+        self.offered
+    }
+
+    /// The resume prompt was answered (resumed or not): record positions again.
+    pub fn answered(&mut self) {
+        self.offered = false;
+    }
+
+    /// Remember the current file's position (e.g. before it's stopped); near the
+    /// end marks it watched. Positions near the start leave a saved one as it was,
+    /// so starting over and backing out doesn't lose it, and nothing is recorded
+    /// while the resume prompt is open.
+    pub fn record(&mut self, position_us: i64, duration_us: i64) {
+        let Some(path) = self.playing.clone() else { return };
+        if duration_us <= 0 || self.offered || position_us < MIN_POSITION_US.min(duration_us) {
+            return;
+        }
+        let k = key(&path, duration_us);
+        self.entries.retain(|e| e.key != k);
+        self.entries.push(Entry { key: k, position_us: position_us.min(duration_us), duration_us, path });
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.dirty = true;
+    }
+
+    /// The current file played to the end: mark it watched.
+    pub fn finished(&mut self, duration_us: i64) {
+        self.offered = false;
+        self.record(duration_us, duration_us);
+        self.playing = None;
+        self.save();
+    }
+
+    /// Write to disk if anything changed.
+    pub fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        let Some(file) = &self.file else { return };
+        let text: String = self.entries.iter()
+            .map(|e| format!("{:016x}\t{}\t{}\t{}\n", e.key, e.position_us, e.duration_us, e.path))
+            .collect();
+        if let Err(e) = std::fs::write(file, text) {
+            error!("Resume: failed to write {:?}: {}", file, e);
+        }
+        self.dirty = false;
+    }
+}
+
+/// "1:02:03" / "12:34" for a microsecond position.
+pub fn format_time(us: i64) -> String {
+    let s = us.max(0) / 1_000_000;
+    if s >= 3600 {
+        format!("{}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60)
+    } else {
+        format!("{}:{:02}", s / 60, s % 60)
+    }
+}
@@ -2,33 +2,749 @@
 //!
 //! Uses DEDICATED THREAD with LOOPER.
 //! Includes aggressive logging to diagnose why events were missing.
+//!
+//! Devices without a rotation-vector sensor fall back to the gyroscope, fused
+//! with the accelerometer by a complementary filter: the gyro drives the
+//! orientation and gravity slowly pulls pitch / roll back into place.
+//!
+//! Where the vendor rotation vector is jittery or laggy, a Madgwick or Mahony
+//! AHRS fed from gyro + accelerometer + magnetometer can be selected instead
+//! (`FusionMode`), with its filter gain as an advanced setting.
+//!
+//! The game rotation vector has no heading reference and slowly drifts in yaw;
+//! optionally the magnetometer pulls it back, very gently and slew-limited so a
+//! magnet or steel nearby can't swing the screen away.
+//!
+//! Sensors report in the device's natural frame; the quaternion is remapped for
+//! the current display rotation (reported by the activity whenever it changes),
+//! so the phone can sit either way round in the headset.
+//!
+//! Where the HAL supports it, the sensors report straight into shared memory (a
+//! direct channel) at the report level nearest the configured rate, and the
+//! thread reads the ring buffer once per sample period (blocking on the looper in
+//! between, so proximity still wakes it); otherwise the looper queue is used at
+//! the configured rate.
+//!
+//! The proximity sensor tells whether the phone is in the headset (`is_near`), so
+//! playback can pause when it's taken out.
+//!
+//! Without positional tracking a neck model turns head rotation into the small
+//! eye translation a real head makes (`neck_offset`).
+//!
+//! An external Bluetooth LE head tracker can stand in for the phone's own
+//! sensors (`FusionMode::External`): `HeadTrackerBle` on the Java side streams
+//! its quaternions here and the thread consumes them like sensor events.
+//!
+//! Tablets without any motion sensors get a drag-to-look fallback (`DragLook`):
+//! one finger turns a synthetic head orientation, with a little momentum.
+//!
+//! For phones with jittery sensors the view can be smoothed (a slerp low-pass on
+//! the recentered orientation, so recentering still snaps at once).
 
-use glam::Quat;
+use android_activity::AndroidApp;
+use glam::{Quat, Vec3};
+use jni::objects::{JObject, JString, JValue};
+use jni::sys::jobject;
 use log::{info, error, warn};
 use std::ptr;
-use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 // Sensor type constants
 const ASENSOR_TYPE_GAME_ROTATION_VECTOR: i32 = 15;
 const ASENSOR_TYPE_ROTATION_VECTOR: i32 = 11;
 const ASENSOR_TYPE_GYROSCOPE: i32 = 4;
+const ASENSOR_TYPE_ACCELEROMETER: i32 = 1;
+const ASENSOR_TYPE_MAGNETIC_FIELD: i32 = 2;
+const ASENSOR_TYPE_PROXIMITY: i32 = 8;
+/// Event type the thread gives external tracker samples (no real sensor uses it).
+const EXTERNAL_EVENT_TYPE: i32 = -1;
+
+/// How fast gravity corrects gyro pitch / roll drift (seconds to close ~63% of
+/// the error). Longer is smoother but lets drift build up further.
+const TILT_TIME_CONSTANT: f32 = 1.0;
+/// Accelerometer readings this far from 1 g (in g) are mostly head motion, not
+/// gravity, and are ignored.
+const GRAVITY_TOLERANCE: f32 = 0.15;
+const STANDARD_GRAVITY: f32 = 9.81;
+
+/// Gyro + accelerometer complementary filter. Works in Android's sensor frame and
+/// yields a device → world quaternion like the rotation-vector sensors do (world
+/// Z up, yaw arbitrary), so it goes through the same axis mapping.
+struct ComplementaryFilter {
+    q: Quat,
+    last_ts: i64,
+    /// Latest accelerometer reading, device frame (m/s²).
+    accel: Option<Vec3>,
+    /// Tilt taken from gravity once, so the view starts level.
+    aligned: bool,
+}
+
+impl ComplementaryFilter {
+    fn new() -> Self {
+        Self { q: Quat::IDENTITY, last_ts: 0, accel: None, aligned: false }
+    }
+
+    fn accelerometer(&mut self, a: Vec3) {
+        self.accel = Some(a);
+        if !self.aligned && a.length() > 1.0 {
+            // Start out with gravity pointing down, so there's no initial tilt to bleed off
+            self.q = Quat::from_rotation_arc(a.normalize(), Vec3::Z);
+            self.aligned = true;
+        }
+    }
+
+    /// Integrate one gyro sample (rad/s, device frame); returns the orientation.
+    fn gyroscope(&mut self, w: Vec3, ts: i64) -> Option<Quat> {
+        let dt = (ts - std::mem::replace(&mut self.last_ts, ts)) as f32 / 1_000_000_000.0;
+        if !(0.0..0.2).contains(&dt) {
+            return None;
+        }
+        self.q = (self.q * Quat::from_scaled_axis(w * dt)).normalize();
+
+        // Nudge "up" as the gyro sees it towards measured gravity. The correction
+        // axis is perpendicular to gravity, so it never touches yaw.
+        if let Some(a) = self.accel {
+            if (a.length() / STANDARD_GRAVITY - 1.0).abs() < GRAVITY_TOLERANCE {
+                let predicted_up = self.q.inverse() * Vec3::Z;
+                let correction = Quat::from_rotation_arc(a.normalize(), predicted_up);
+                let alpha = dt / (TILT_TIME_CONSTANT + dt);
+                self.q = (self.q * Quat::IDENTITY.slerp(correction, alpha)).normalize();
+            }
+        }
+        Some(self.q)
+    }
+}
+
+/// Default filter gain: Madgwick's β; Mahony's proportional gain is this times
+/// `MAHONY_GAIN_SCALE`, so one slider suits both.
+pub const DEFAULT_GAIN: f32 = 0.1;
+const MAHONY_GAIN_SCALE: f32 = 10.0;
+/// Mahony integral gain (slowly learns gyro bias).
+const MAHONY_KI: f32 = 0.05;
+
+/// Magnetometer yaw correction: time constant of the pull towards the magnetic
+/// heading, and the hard limit on how fast it may turn the view (rad/s).
+const YAW_TIME_CONSTANT: f32 = 20.0;
+const MAX_YAW_SLEW: f32 = 0.3 * std::f32::consts::PI / 180.0;
+/// Field strengths outside this range (µT) are disturbances, not the Earth.
+const EARTH_FIELD_UT: std::ops::Range<f32> = 20.0..70.0;
+
+/// Requested head-tracking sample rate: a latency / battery tradeoff. Never
+/// faster than a sensor's `ASensor_getMinDelay`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SensorRate {
+    Hz50,
+    Hz90,
+    Hz120,
+    /// As fast as each sensor goes.
+    Fastest,
+}
+
+impl SensorRate {
+    pub fn next(self) -> Self {
+        match self {
+            SensorRate::Hz50 => SensorRate::Hz90,
+            SensorRate::Hz90 => SensorRate::Hz120,
+            SensorRate::Hz120 => SensorRate::Fastest,
+            SensorRate::Fastest => SensorRate::Hz50,
+        }
+    }
+    pub fn label(self) -> &'static str {
+        crate::i18n::tr(match self {
+            SensorRate::Hz50 => "50 Hz · saves battery",
+            SensorRate::Hz90 => "90 Hz",
+            SensorRate::Hz120 => "120 Hz",
+            SensorRate::Fastest => "Fastest · lowest latency",
+        })
+    }
+    /// Event period for `sensor` in µs.
+    unsafe fn period_us(self, sensor: *const ndk_sys::ASensor) -> i32 {
+        let min_delay = ndk_sys::ASensor_getMinDelay(sensor).max(0);
+        let wanted = match self {
+            SensorRate::Hz50 => 20_000,
+            SensorRate::Hz90 => 11_111,
+            SensorRate::Hz120 => 8_333,
+            SensorRate::Fastest => 0,
+        };
+        wanted.max(min_delay)
+    }
+    /// Direct channel report level for `sensor`: the lowest that keeps up with
+    /// the rate (normal ≈ 50 Hz, fast ≈ 200 Hz), capped at what it supports.
+    unsafe fn direct_level(self, sensor: *const ndk_sys::ASensor) -> i32 {
+        let wanted = match self {
+            SensorRate::Hz50 => ndk_sys::ASENSOR_DIRECT_RATE_NORMAL,
+            SensorRate::Hz90 | SensorRate::Hz120 => ndk_sys::ASENSOR_DIRECT_RATE_FAST,
+            SensorRate::Fastest => ndk_sys::ASENSOR_DIRECT_RATE_VERY_FAST,
+        } as i32;
+        wanted.min(ndk_sys::ASensor_getHighestDirectReportRateLevel(sensor))
+    }
+    /// How long the thread waits between direct channel reads, in ms.
+    fn direct_wait_ms(self) -> i32 {
+        match self {
+            SensorRate::Hz50 => 20,
+            SensorRate::Hz90 => 11,
+            SensorRate::Hz120 => 8,
+            SensorRate::Fastest => 2,
+        }
+    }
+}
+
+/// Where head orientation comes from.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FusionMode {
+    /// The platform's rotation vector sensor (game rotation vector if that's all there is).
+    RotationVector,
+    /// Madgwick gradient-descent AHRS on gyro + accelerometer (+ magnetometer).
+    Madgwick,
+    /// Mahony complementary (PI) AHRS on the same sensors.
+    Mahony,
+    /// An external Bluetooth LE head tracker (see `set_external`).
+    External,
+}
+
+impl FusionMode {
+    pub fn next(self) -> Self {
+        match self {
+            FusionMode::RotationVector => FusionMode::Madgwick,
+            FusionMode::Madgwick => FusionMode::Mahony,
+            FusionMode::Mahony => FusionMode::External,
+            FusionMode::External => FusionMode::RotationVector,
+        }
+    }
+    pub fn label(self) -> &'static str {
+        crate::i18n::tr(match self {
+            FusionMode::RotationVector => "Rotation Vector (system)",
+            FusionMode::Madgwick => "Madgwick AHRS",
+            FusionMode::Mahony => "Mahony AHRS",
+            FusionMode::External => "External head tracker (Bluetooth)",
+        })
+    }
+}
+
+/// What the sensor thread actually runs: the requested mode, or what this
+/// device can do instead.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Backend {
+    RotationVector,
+    Complementary,
+    Madgwick,
+    Mahony,
+    External,
+}
+
+impl Backend {
+    fn choose(mode: FusionMode, has_rv: bool, has_gyro: bool, has_accel: bool) -> Self {
+        let fallback = if has_rv { Backend::RotationVector } else { Backend::Complementary };
+        match mode {
+            FusionMode::Madgwick if has_gyro && has_accel => Backend::Madgwick,
+            FusionMode::Mahony if has_gyro && has_accel => Backend::Mahony,
+            FusionMode::External => Backend::External,
+            _ => fallback,
+        }
+    }
+}
+
+/// Gyro + accelerometer (+ magnetometer) AHRS. Like `ComplementaryFilter` it
+/// keeps a device → world quaternion in Android's sensor frame (world Z up);
+/// with a magnetometer, world X points to magnetic north, otherwise yaw is free.
+struct Ahrs {
+    q: Quat,
+    last_ts: i64,
+    accel: Option<Vec3>,
+    mag: Option<Vec3>,
+    /// Mahony's integrated error (≈ gyro bias).
+    integral: Vec3,
+    aligned: bool,
+}
+
+impl Ahrs {
+    fn new() -> Self {
+        Self { q: Quat::IDENTITY, last_ts: 0, accel: None, mag: None, integral: Vec3::ZERO, aligned: false }
+    }
+
+    fn accelerometer(&mut self, a: Vec3) {
+        self.accel = Some(a);
+        if !self.aligned && a.length() > 1.0 {
+            // Level from the start; Madgwick alone would take seconds to get there
+            self.q = Quat::from_rotation_arc(a.normalize(), Vec3::Z);
+            self.aligned = true;
+        }
+    }
+
+    fn step(&mut self, ts: i64) -> Option<f32> {
+        let dt = (ts - std::mem::replace(&mut self.last_ts, ts)) as f32 / 1_000_000_000.0;
+        (0.0..0.2).contains(&dt).then_some(dt)
+    }
+
+    /// Earth's field in the world frame, flattened to (horizontal, 0, vertical).
+    fn mag_reference(&self, m: Vec3) -> Vec3 {
+        let h = self.q * m;
+        Vec3::new(h.truncate().length(), 0.0, h.z)
+    }
+
+    /// Madgwick's gradient-descent step (`beta` = gain) after one gyro sample.
+    fn madgwick(&mut self, w: Vec3, ts: i64, beta: f32) -> Option<Quat> {
+        let dt = self.step(ts)?;
+        let (q0, q1, q2, q3) = (self.q.w, self.q.x, self.q.y, self.q.z);
+        // Rate of change of quaternion from gyroscope
+        let mut q_dot = [
+            0.5 * (-q1 * w.x - q2 * w.y - q3 * w.z),
+            0.5 * (q0 * w.x + q2 * w.z - q3 * w.y),
+            0.5 * (q0 * w.y - q1 * w.z + q3 * w.x),
+            0.5 * (q0 * w.z + q1 * w.y - q2 * w.x),
+        ];
+        if let Some(a) = self.accel.filter(|a| a.length() > 0.0).map(Vec3::normalize) {
+            // Objective: predicted gravity (and field) in the device frame minus measured
+            let f_g = [
+                2.0 * (q1 * q3 - q0 * q2) - a.x,
+                2.0 * (q0 * q1 + q2 * q3) - a.y,
+                2.0 * (0.5 - q1 * q1 - q2 * q2) - a.z,
+            ];
+            let j_g = [
+                [-2.0 * q2, 2.0 * q3, -2.0 * q0, 2.0 * q1],
+                [2.0 * q1, 2.0 * q0, 2.0 * q3, 2.0 * q2],
+                [0.0, -4.0 * q1, -4.0 * q2, 0.0],
+            ];
+            let mut grad = transpose_mul(&j_g, &f_g);
+            if let Some(m) = self.mag.filter(|m| m.length() > 0.0).map(Vec3::normalize) {
+                let b = self.mag_reference(m);
+                let (bx, bz) = (b.x, b.z);
+                let f_b = [
+                    2.0 * bx * (0.5 - q2 * q2 - q3 * q3) + 2.0 * bz * (q1 * q3 - q0 * q2) - m.x,
+                    2.0 * bx * (q1 * q2 - q0 * q3) + 2.0 * bz * (q0 * q1 + q2 * q3) - m.y,
+                    2.0 * bx * (q0 * q2 + q1 * q3) + 2.0 * bz * (0.5 - q1 * q1 - q2 * q2) - m.z,
+                ];
+                let j_b = [
+                    [-2.0 * bz * q2, 2.0 * bz * q3, -4.0 * bx * q2 - 2.0 * bz * q0, -4.0 * bx * q3 + 2.0 * bz * q1],
+                    [-2.0 * bx * q3 + 2.0 * bz * q1, 2.0 * bx * q2 + 2.0 * bz * q0, 2.0 * bx * q1 + 2.0 * bz * q3, -2.0 * bx * q0 + 2.0 * bz * q2],
+                    [2.0 * bx * q2, 2.0 * bx * q3 - 4.0 * bz * q1, 2.0 * bx * q0 - 4.0 * bz * q2, 2.0 * bx * q1],
+                ];
+                let grad_b = transpose_mul(&j_b, &f_b);
+                for i in 0..4 {
+                    grad[i] += grad_b[i];
+                }
+            }
+            let norm = grad.iter().map(|g| g * g).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                for i in 0..4 {
+                    q_dot[i] -= beta * grad[i] / norm;
+                }
+            }
+        }
+        self.q = Quat::from_xyzw(q1 + q_dot[1] * dt, q2 + q_dot[2] * dt, q3 + q_dot[3] * dt, q0 + q_dot[0] * dt)
+            .normalize();
+        Some(self.q)
+    }
+
+    /// Mahony's PI correction (`kp` = proportional gain) after one gyro sample.
+    /// The magnetometer only steers yaw, so a disturbed field can't tilt the horizon.
+    fn mahony(&mut self, mut w: Vec3, ts: i64, kp: f32) -> Option<Quat> {
+        let dt = self.step(ts)?;
+        if let Some(a) = self.accel.filter(|a| a.length() > 0.0).map(Vec3::normalize) {
+            let up = self.q.inverse() * Vec3::Z;
+            let mut error = a.cross(up);
+            if let Some(m) = self.mag.filter(|m| m.length() > 0.0).map(Vec3::normalize) {
+                let predicted = self.q.inverse() * self.mag_reference(m);
+                error += up * m.cross(predicted).dot(up);
+            }
+            self.integral += error * MAHONY_KI * dt;
+            w += error * kp + self.integral;
+        }
+        self.q = (self.q * Quat::from_scaled_axis(w * dt)).normalize();
+        Some(self.q)
+    }
+}
+
+/// Slow magnetometer-referenced yaw correction for the game rotation vector.
+/// Remembers where magnetic north was in its world frame when enabled and turns
+/// that frame about Z (`offset`) so north stays put as the sensor drifts.
+struct YawCorrection {
+    /// North's angle in the corrected world frame when correction started.
+    north: Option<f32>,
+    offset: f32,
+    last_ts: i64,
+}
+
+impl YawCorrection {
+    fn new() -> Self {
+        Self { north: None, offset: 0.0, last_ts: 0 }
+    }
+
+    fn rotation(&self) -> Quat {
+        Quat::from_rotation_z(self.offset)
+    }
+
+    /// Feed a magnetometer reading (µT, device frame) with the latest raw game
+    /// rotation vector.
+    fn magnetometer(&mut self, m: Vec3, game: Quat, ts: i64) {
+        let dt = (ts - std::mem::replace(&mut self.last_ts, ts)) as f32 / 1_000_000_000.0;
+        if !EARTH_FIELD_UT.contains(&m.length()) || !(0.0..0.5).contains(&dt) {
+            return;
+        }
+        let field = self.rotation() * game * m;
+        if field.truncate().length() < 1.0 {
+            return; // pointing (nearly) along the field: no heading
+        }
+        let angle = field.y.atan2(field.x);
+        let Some(north) = self.north else {
+            self.north = Some(angle);
+            return;
+        };
+        // Drift since then, wrapped to (-π, π]
+        let error = (north - angle + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+        let step = (error * dt / YAW_TIME_CONSTANT).clamp(-MAX_YAW_SLEW * dt, MAX_YAW_SLEW * dt);
+        self.offset += step;
+    }
+}
+
+/// Jᵀ·f for a 3×4 Jacobian.
+fn transpose_mul(j: &[[f32; 4]; 3], f: &[f32; 3]) -> [f32; 4] {
+    let mut out = [0.0; 4];
+    for (row, fi) in j.iter().zip(f) {
+        for (o, jv) in out.iter_mut().zip(row) {
+            *o += jv * fi;
+        }
+    }
+    out
+}
+
+/// Smoothing time constant at full strength (seconds). Much more and the view
+/// visibly trails the head.
+const MAX_SMOOTHING_TIME: f32 = 0.12;
+
+/// Eyes relative to the neck pivot for an average adult (metres: up, forward).
+const NECK_TO_EYES: Vec3 = Vec3::new(0.0, 0.075, -0.08);
+
+/// Eye translation from pivoting `orientation` about the neck instead of the
+/// eyes, for a neck `scale` times the average (0 = off).
+pub fn neck_offset(orientation: Quat, scale: f32) -> Vec3 {
+    let eyes = NECK_TO_EYES * scale;
+    orientation * eyes - eyes
+}
+
+/// Display rotation (`Surface.ROTATION_*`, 0–3) as last reported by the activity.
+/// Starts as 3, what the old fixed landscape layout assumed.
+static DISPLAY_ROTATION: AtomicI32 = AtomicI32::new(3);
+
+/// Record the display rotation; the sensor thread picks it up on its next pass.
+pub fn set_display_rotation(rotation: i32) {
+    let rotation = rotation.rem_euclid(4);
+    if DISPLAY_ROTATION.swap(rotation, Ordering::Relaxed) != rotation {
+        info!("Sensors: display rotation {}°", rotation * 90);
+    }
+}
+
+/// Current display rotation (`Surface.ROTATION_*`).
+pub fn display_rotation() -> i32 {
+    DISPLAY_ROTATION.load(Ordering::Relaxed)
+}
+
+/// Sensor-frame orientation → view orientation for a display rotation.
+///
+/// The screen's axes are the device's turned by -rotation about z (what
+/// `SensorManager.remapCoordinateSystem` does); the result is then inverted,
+/// since a device → world rotation turns the scene opposite to the head.
+fn to_view(q: Quat, rotation: i32) -> Quat {
+    let screen = Quat::from_rotation_z(-(rotation as f32) * std::f32::consts::FRAC_PI_2);
+    (screen * q * screen.inverse()).conjugate().normalize()
+}
+
+/// The sensor's name as the HAL reports it.
+unsafe fn sensor_name(sensor: *const ndk_sys::ASensor) -> String {
+    let name = if sensor.is_null() { ptr::null() } else { ndk_sys::ASensor_getName(sensor) };
+    if name.is_null() {
+        return "?".into();
+    }
+    std::ffi::CStr::from_ptr(name).to_string_lossy().into_owned()
+}
+
+/// View turn for a drag across the whole screen width (radians).
+const DRAG_LOOK_PER_WIDTH: f32 = std::f32::consts::PI;
+/// How fast a flicked view coasts to a stop (1/s).
+const DRAG_LOOK_FRICTION: f32 = 4.0;
+/// Below this the momentum is dropped (rad/s).
+const DRAG_LOOK_MIN_SPEED: f32 = 0.01;
+const DRAG_LOOK_MAX_PITCH: f32 = 89.0 * std::f32::consts::PI / 180.0;
+
+/// Drag-to-look head orientation for devices without sensors: the finger
+/// drags the world (so a drag left turns the view right), and a flick keeps
+/// turning for a moment after release.
+#[derive(Default)]
+pub struct DragLook {
+    yaw: f32,
+    pitch: f32,
+    /// Turn rate (yaw, pitch) in rad/s: followed while dragging, coasting after.
+    velocity: (f32, f32),
+    dragging: bool,
+    /// Turn accumulated since the last update, while dragging.
+    pending: (f32, f32),
+}
+
+impl DragLook {
+    /// A finger moved by (`dx`, `dy`) pixels on a screen `width` pixels wide.
+    pub fn drag(&mut self, dx: f32, dy: f32, width: f32) {
+        let scale = DRAG_LOOK_PER_WIDTH / width.max(1.0);
+        self.dragging = true;
+        self.pending.0 += dx * scale;
+        self.pending.1 += dy * scale;
+    }
+
+    /// The finger lifted: keep coasting at the drag's last speed.
+    pub fn release(&mut self) {
+        self.dragging = false;
+    }
+
+    /// Advance by `dt` seconds (call once per frame).
+    pub fn update(&mut self, dt: f32) {
+        if self.dragging {
+            let (dy, dp) = std::mem::take(&mut self.pending);
+            self.turn(dy, dp);
+            if dt > 0.0 {
+                self.velocity = (dy / dt, dp / dt);
+            }
+        } else {
+            let decay = (-DRAG_LOOK_FRICTION * dt).exp();
+            self.velocity = (self.velocity.0 * decay, self.velocity.1 * decay);
+            if self.velocity.0.hypot(self.velocity.1) < DRAG_LOOK_MIN_SPEED {
+                self.velocity = (0.0, 0.0);
+            }
+            self.turn(self.velocity.0 * dt, self.velocity.1 * dt);
+        }
+    }
+
+    fn turn(&mut self, yaw: f32, pitch: f32) {
+        self.yaw = (self.yaw + yaw) % std::f32::consts::TAU;
+        self.pitch = (self.pitch + pitch).clamp(-DRAG_LOOK_MAX_PITCH, DRAG_LOOK_MAX_PITCH);
+    }
+
+    /// Look straight ahead again and stop coasting.
+    pub fn recenter(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn orientation(&self) -> Quat {
+        Quat::from_rotation_y(self.yaw) * Quat::from_rotation_x(self.pitch)
+    }
+}
+
+/// Events in the direct channel's ring buffer.
+const DIRECT_SLOTS: usize = 256;
+/// Longest wait for an external tracker sample before checking the queue
+/// (proximity) and the running flag again.
+const EXTERNAL_WAIT: Duration = Duration::from_millis(100);
+
+/// A shared-memory direct channel the HAL writes sensor events into.
+struct DirectChannel {
+    manager: *mut ndk_sys::ASensorManager,
+    fd: i32,
+    memory: *const ndk_sys::ASensorEvent,
+    channel: i32,
+    /// Sensors currently reporting into it.
+    sensors: Vec<*const ndk_sys::ASensor>,
+    next_slot: usize,
+    /// Counter of the last event read (the HAL numbers them from 1).
+    counter: u32,
+}
+
+impl DirectChannel {
+    /// Whether every one of `sensors` can report into shared memory.
+    unsafe fn supports(sensors: &[*const ndk_sys::ASensor]) -> bool {
+        sensors.iter().all(|&s| {
+            ndk_sys::ASensor_isDirectChannelTypeSupported(s, ndk_sys::ASENSOR_DIRECT_CHANNEL_TYPE_SHARED_MEMORY as i32)
+                && ndk_sys::ASensor_getHighestDirectReportRateLevel(s) > ndk_sys::ASENSOR_DIRECT_RATE_STOP as i32
+        })
+    }
+
+    unsafe fn new(manager: *mut ndk_sys::ASensorManager) -> Option<Self> {
+        let size = DIRECT_SLOTS * std::mem::size_of::<ndk_sys::ASensorEvent>();
+        let fd = ndk_sys::ASharedMemory_create(c"vr-sensors".as_ptr(), size);
+        if fd < 0 {
+            return None;
+        }
+        let memory = libc::mmap(ptr::null_mut(), size, libc::PROT_READ, libc::MAP_SHARED, fd, 0);
+        if memory == libc::MAP_FAILED {
+            libc::close(fd);
+            return None;
+        }
+        let channel = ndk_sys::ASensorManager_createSharedMemoryDirectChannel(manager, fd, size);
+        if channel <= 0 {
+            libc::munmap(memory, size);
+            libc::close(fd);
+            return None;
+        }
+        Some(Self { manager, fd, memory: memory as *const _, channel, sensors: Vec::new(), next_slot: 0, counter: 0 })
+    }
+
+    /// Report `sensors` (only) at the level for `rate`. False if any refuses.
+    unsafe fn start(&mut self, sensors: &[*const ndk_sys::ASensor], rate: SensorRate) -> bool {
+        self.stop();
+        for &s in sensors {
+            let level = rate.direct_level(s);
+            if ndk_sys::ASensorManager_configureDirectReport(self.manager, s, self.channel, level) <= 0 {
+                error!("THREAD: Direct report failed for sensor type {}", ndk_sys::ASensor_getType(s));
+                self.stop();
+                return false;
+            }
+            self.sensors.push(s);
+        }
+        true
+    }
+
+    /// Move the reporting sensors to the level for `rate`.
+    unsafe fn set_rate(&mut self, rate: SensorRate) {
+        for &s in &self.sensors {
+            let level = rate.direct_level(s);
+            if ndk_sys::ASensorManager_configureDirectReport(self.manager, s, self.channel, level) <= 0 {
+                warn!("THREAD: Direct report level {} refused for sensor type {}", level, ndk_sys::ASensor_getType(s));
+            } else {
+                info!("THREAD: Sensor type {} at direct level {}", ndk_sys::ASensor_getType(s), level);
+            }
+        }
+    }
+
+    unsafe fn stop(&mut self) {
+        for s in self.sensors.drain(..) {
+            ndk_sys::ASensorManager_configureDirectReport(
+                self.manager, s, self.channel, ndk_sys::ASENSOR_DIRECT_RATE_STOP as i32);
+        }
+    }
+
+    /// Append the events written since the last call to `out`.
+    unsafe fn read(&mut self, out: &mut Vec<ndk_sys::ASensorEvent>) {
+        loop {
+            let slot = self.memory.add(self.next_slot);
+            // The counter is written last; read it before the rest of the event
+            let counter = (*(ptr::addr_of!((*slot).reserved0) as *const std::sync::atomic::AtomicU32))
+                .load(Ordering::Acquire);
+            if counter.wrapping_sub(self.counter) as i32 <= 0 {
+                return;
+            }
+            out.push(ptr::read_volatile(slot));
+            self.counter = counter;
+            self.next_slot = (self.next_slot + 1) % DIRECT_SLOTS;
+        }
+    }
+}
+
+impl Drop for DirectChannel {
+    fn drop(&mut self) {
+        unsafe {
+            self.stop();
+            ndk_sys::ASensorManager_destroyDirectChannel(self.manager, self.channel);
+            libc::munmap(self.memory as *mut _, DIRECT_SLOTS * std::mem::size_of::<ndk_sys::ASensorEvent>());
+            libc::close(self.fd);
+        }
+    }
+}
+
+// Latest external tracker sample (device → world) and its connection state,
+// both written from Java's Bluetooth callbacks
+static EXTERNAL_SAMPLE: Mutex<Option<Quat>> = Mutex::new(None);
+// Notified with each new sample, so the thread sleeps until one arrives
+static EXTERNAL_WAKE: Condvar = Condvar::new();
+static EXTERNAL_STATE: Mutex<String> = Mutex::new(String::new());
+static EXTERNAL_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Start or stop the activity's Bluetooth head tracker. Cheap to call every
+/// frame: Java is only told when the wanted state changes.
+pub fn set_external(app: &AndroidApp, enabled: bool) {
+    if EXTERNAL_ENABLED.swap(enabled, Ordering::Relaxed) == enabled {
+        return;
+    }
+    info!("External head tracker {}", if enabled { "on" } else { "off" });
+    let vm = unsafe { jni::JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM).unwrap() };
+    let Ok(mut env) = vm.attach_current_thread() else { return };
+    let activity = unsafe { JObject::from_raw(app.activity_as_ptr() as jobject) };
+    if let Err(e) = env.call_method(&activity, "setHeadTrackerEnabled", "(Z)V", &[JValue::Bool(enabled as u8)]) {
+        error!("setHeadTrackerEnabled failed: {:?}", e);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn Java_com_vrapp_core_HeadTrackerBle_onSample(
+    _env: jni::JNIEnv,
+    _class: JObject,
+    x: jni::sys::jfloat,
+    y: jni::sys::jfloat,
+    z: jni::sys::jfloat,
+    w: jni::sys::jfloat,
+) {
+    let q = Quat::from_xyzw(x, y, z, w);
+    if !q.is_finite() || q.length_squared() < 0.5 {
+        return;
+    }
+    if let Ok(mut sample) = EXTERNAL_SAMPLE.lock() {
+        *sample = Some(q.normalize());
+        EXTERNAL_WAKE.notify_one();
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn Java_com_vrapp_core_HeadTrackerBle_onState(
+    mut env: jni::JNIEnv,
+    _class: JObject,
+    state: JString,
+) {
+    let Ok(state) = env.get_string(&state) else { return };
+    let state: String = state.into();
+    info!("External head tracker: {}", state);
+    if let Ok(mut s) = EXTERNAL_STATE.lock() {
+        *s = state;
+    }
+}
 
 // Static storage for reference orientation (survives activity recreation)
 static SAVED_REFERENCE: OnceLock<Mutex<Quat>> = OnceLock::new();
 
+fn save_reference(reference: Quat) {
+    if let Some(saved) = SAVED_REFERENCE.get() {
+        if let Ok(mut g) = saved.lock() {
+            *g = reference;
+        }
+    }
+}
+
+/// What the sensor thread is doing, for the diagnostics panel.
+#[derive(Debug, Clone, Default)]
+pub struct SensorStatus {
+    /// Backend, sensor and delivery path, e.g. "RotationVector · BMI260 Game Rotation
+    /// Vector · direct channel"; empty until tracking starts.
+    pub source: String,
+    /// Orientation updates per second over the last second.
+    pub rate_hz: f32,
+    /// Time since the last orientation update.
+    pub last_event_age: Option<Duration>,
+    /// Why tracking couldn't start, if it couldn't.
+    pub error: Option<String>,
+}
+
 /// Thread-safe shared state for orientation
 struct SharedState {
     orientation: Quat,        // Current raw orientation from sensor
     reference: Quat,          // Reference orientation (Tare)
     running: bool,
+    fusion: FusionMode,
+    gain: f32,
+    rate: SensorRate,
+    yaw_correction: bool,     // Magnetometer yaw correction for the game rotation vector
+    retare: bool,             // Backend switched: move the reference so the view doesn't jump
+    snap: bool,               // Recentered: skip smoothing for a frame
+    source: String,           // Diagnostics: see SensorStatus
+    rate_hz: f32,
+    last_event: Option<Instant>,
+    error: Option<String>,
+    near: Option<bool>,       // Proximity: something (a face) close to the screen
 }
 
 /// Manages sensor input for VR head tracking
 pub struct SensorInput {
     state: Arc<Mutex<SharedState>>,
     _thread_handle: Option<thread::JoinHandle<()>>,
+    smoothing: f32,           // 0 = off, 1 = MAX_SMOOTHING_TIME
+    smoothed: Option<Quat>,   // Output of the smoothing stage, set by update()
 }
 
 unsafe impl Send for SensorInput {}
@@ -49,6 +765,17 @@ impl SensorInput {
             orientation: Quat::IDENTITY,
             reference: saved_ref,  // Use saved reference
             running: true,
+            fusion: FusionMode::RotationVector,
+            gain: DEFAULT_GAIN,
+            rate: SensorRate::Hz90,
+            yaw_correction: false,
+            retare: false,
+            snap: false,
+            source: String::new(),
+            rate_hz: 0.0,
+            last_event: None,
+            error: None,
+            near: None,
         }));
         
         let thread_state = state.clone();
@@ -61,61 +788,71 @@ impl SensorInput {
         Self {
             state,
             _thread_handle: Some(handle),
+            smoothing: 0.0,
+            smoothed: None,
         }
     }
     
     fn sensor_loop(state: Arc<Mutex<SharedState>>) {
         info!("THREAD: Sensor thread (LOOPER MODE) started");
+        // Log a startup failure and keep it for the diagnostics panel
+        let fail = |message: &str| {
+            error!("THREAD: {}", message);
+            if let Ok(mut s) = state.lock() {
+                s.error = Some(message.to_string());
+            }
+        };
         
         unsafe {
             // 1. Prepare Looper - CRITICAL FIX
             // We must pass ALOOPER_PREPARE_ALLOW_NON_CALLBACKS (1) to handle FDs without callbacks!
             let looper = ndk_sys::ALooper_prepare(ndk_sys::ALOOPER_PREPARE_ALLOW_NON_CALLBACKS as i32);
             if looper.is_null() {
-                error!("THREAD: Failed to prepare ALOOPER");
+                fail("Failed to prepare ALOOPER");
                 return;
             }
             info!("THREAD: Looper prepared correctly");
             
             // 2. Get Manager
-            let mut pt = b"com.vrapp.core\0".as_ptr();
+            let pt = c"com.vrapp.core".as_ptr();
             let mut manager = ndk_sys::ASensorManager_getInstanceForPackage(pt);
             if manager.is_null() {
                 manager = ndk_sys::ASensorManager_getInstance();
             }
             if manager.is_null() {
-                 error!("THREAD: Failed to get Manager");
+                 fail("Failed to get Manager");
                  return;
             }
             
-            // 3. Find Sensor - Prefer Rotation Vector (Type 11) for best compatibility
-            let mut sensor = ndk_sys::ASensorManager_getDefaultSensor(
+            // 3. Find Sensors - Prefer Rotation Vector (Type 11) for best compatibility,
+            // plus the raw sensors the fusion backends work from
+            let mut rv_sensor = ndk_sys::ASensorManager_getDefaultSensor(
                 manager, 
                 ASENSOR_TYPE_ROTATION_VECTOR
             );
-            let mut sensor_type = ASENSOR_TYPE_ROTATION_VECTOR;
+            let mut rv_type = ASENSOR_TYPE_ROTATION_VECTOR;
             
-            if sensor.is_null() {
-                sensor = ndk_sys::ASensorManager_getDefaultSensor(
+            if rv_sensor.is_null() {
+                rv_sensor = ndk_sys::ASensorManager_getDefaultSensor(
                     manager, 
                     ASENSOR_TYPE_GAME_ROTATION_VECTOR
                 );
-                sensor_type = ASENSOR_TYPE_GAME_ROTATION_VECTOR;
+                rv_type = ASENSOR_TYPE_GAME_ROTATION_VECTOR;
             }
+            let gyro_sensor = ndk_sys::ASensorManager_getDefaultSensor(manager, ASENSOR_TYPE_GYROSCOPE);
+            let accel_sensor = ndk_sys::ASensorManager_getDefaultSensor(manager, ASENSOR_TYPE_ACCELEROMETER);
+            let mag_sensor = ndk_sys::ASensorManager_getDefaultSensor(manager, ASENSOR_TYPE_MAGNETIC_FIELD);
             
-            if sensor.is_null() {
-                sensor = ndk_sys::ASensorManager_getDefaultSensor(
-                    manager, 
-                    ASENSOR_TYPE_GYROSCOPE
-                );
-                sensor_type = ASENSOR_TYPE_GYROSCOPE;
-            }
-            
-            if sensor.is_null() {
-                error!("THREAD: No sensor found");
+            if rv_sensor.is_null() && gyro_sensor.is_null() {
+                fail("No sensor found");
                 return;
             }
-            info!("THREAD: Found sensor type: {}", sensor_type);
+            info!("THREAD: Found sensors: rotation vector {}, gyro {}, accel {}, mag {}",
+                if rv_sensor.is_null() { 0 } else { rv_type },
+                !gyro_sensor.is_null(), !accel_sensor.is_null(), !mag_sensor.is_null());
+            if rv_sensor.is_null() && accel_sensor.is_null() {
+                warn!("THREAD: No accelerometer; gyro-only tracking will drift");
+            }
             
             // 4. Create Queue attached to Looper
             let ident = 17; // Random ident
@@ -128,125 +865,342 @@ impl SensorInput {
             );
             
             if queue.is_null() {
-                error!("THREAD: Failed to create Queue");
+                fail("Failed to create Queue");
                 return;
             }
             info!("THREAD: Queue created");
-            
-            // 5. Enable Sensor
-            let status = ndk_sys::ASensorEventQueue_enableSensor(queue, sensor);
-            if status < 0 {
-                error!("THREAD: Enable failed: {}", status);
-                return;
+
+            // Proximity runs for the thread's whole life, always on the queue (it
+            // reports on change, which a direct channel can't carry)
+            let proximity_sensor = ndk_sys::ASensorManager_getDefaultSensor(manager, ASENSOR_TYPE_PROXIMITY);
+            if !proximity_sensor.is_null() && ndk_sys::ASensorEventQueue_enableSensor(queue, proximity_sensor) < 0 {
+                warn!("THREAD: Couldn't enable the proximity sensor");
             }
+            // Largest distance seen: binary sensors report 0 or their range
+            let mut proximity_far = 0.0f32;
             
-            // Set rate (20ms) - safer rate
-            ndk_sys::ASensorEventQueue_setEventRate(queue, sensor, 20000);
-            info!("THREAD: Sensor enabled at 20ms rate");
+            // 5. Sensors are enabled per backend (in the loop), at the rate asked for
+            let mut enabled: Vec<*const ndk_sys::ASensor> = Vec::new();
+            let mut active: Option<(Backend, bool)> = None;
+            let mut active_rate: Option<SensorRate> = None;
+            // Direct channel, made the first time the wanted sensors all support one
+            let mut direct: Option<DirectChannel> = None;
+            let mut direct_active = false;
             
             // 6. Loop
             let mut event: ndk_sys::ASensorEvent = std::mem::zeroed();
+            let mut batch: Vec<ndk_sys::ASensorEvent> = Vec::with_capacity(DIRECT_SLOTS);
             let mut loop_count = 0;
+            // Updates counted toward the rate shown in diagnostics
+            let mut rate_window = (Instant::now(), 0u32);
             
-            // Gyro integration
-            let mut gyro_pitch = 0.0f32;
-            let mut gyro_yaw = 0.0f32;
-            let mut gyro_roll = 0.0f32;
-            let mut last_ts = 0i64;
+            // Gyro + accelerometer fusion (gyro fallback only)
+            let mut filter = ComplementaryFilter::new();
+            // Madgwick / Mahony AHRS
+            let mut ahrs = Ahrs::new();
+            // Game rotation vector yaw drift correction, and its latest raw reading
+            let mut yaw = YawCorrection::new();
+            let mut game_raw = Quat::IDENTITY;
+            let mut rotation = DISPLAY_ROTATION.load(Ordering::Relaxed);
             
             while state.lock().unwrap().running {
                 loop_count += 1;
+
+                // Switch backend when the setting changes (or on the first pass)
+                let (mode, gain, yaw_wanted, rate) = state.lock()
+                    .map(|s| (s.fusion, s.gain, s.yaw_correction, s.rate))
+                    .unwrap_or((FusionMode::RotationVector, DEFAULT_GAIN, false, SensorRate::Hz90));
+                let backend = Backend::choose(mode, !rv_sensor.is_null(), !gyro_sensor.is_null(), !accel_sensor.is_null());
+                // Only the game rotation vector lacks a heading reference
+                let correct_yaw = yaw_wanted && backend == Backend::RotationVector
+                    && rv_type == ASENSOR_TYPE_GAME_ROTATION_VECTOR && !mag_sensor.is_null();
+                if active != Some((backend, correct_yaw)) {
+                    for s in enabled.drain(..) {
+                        ndk_sys::ASensorEventQueue_disableSensor(queue, s);
+                    }
+                    let wanted: Vec<_> = match backend {
+                        Backend::RotationVector if correct_yaw => vec![rv_sensor, mag_sensor],
+                        Backend::RotationVector => vec![rv_sensor],
+                        Backend::Complementary => vec![gyro_sensor, accel_sensor],
+                        Backend::Madgwick | Backend::Mahony => vec![gyro_sensor, accel_sensor, mag_sensor],
+                        Backend::External => vec![],
+                    }.into_iter().filter(|s| !s.is_null()).collect();
+                    let direct_ok = !wanted.is_empty() && DirectChannel::supports(&wanted);
+                    if direct.is_none() && direct_ok {
+                        direct = DirectChannel::new(manager);
+                    }
+                    direct_active = direct_ok
+                        && direct.as_mut().is_some_and(|d| d.start(&wanted, rate));
+                    if let (false, Some(d)) = (direct_active, direct.as_mut()) {
+                        d.stop();
+                    }
+                    for s in wanted.into_iter().filter(|_| !direct_active) {
+                        let status = ndk_sys::ASensorEventQueue_enableSensor(queue, s);
+                        if status < 0 {
+                            error!("THREAD: Enable failed: {}", status);
+                            continue;
+                        }
+                        enabled.push(s);
+                    }
+                    let source = if backend == Backend::External {
+                        "External · Bluetooth LE".to_string()
+                    } else {
+                        format!("{:?} · {}{} · {}", backend,
+                            sensor_name(if backend == Backend::RotationVector { rv_sensor } else { gyro_sensor }),
+                            if correct_yaw { " + magnetic yaw correction" } else { "" },
+                            if direct_active { "direct channel".to_string() } else { format!("{} sensors queued", enabled.len()) })
+                    };
+                    info!("THREAD: Tracking with {}", source);
+                    if let Ok(mut s) = state.lock() {
+                        s.source = source;
+                    }
+                    active_rate = None;
+                    // Keep the view where it is across the switch (the new source's yaw differs)
+                    if active.is_some() {
+                        if let Ok(mut s) = state.lock() {
+                            s.retare = true;
+                        }
+                    }
+                    filter = ComplementaryFilter::new();
+                    ahrs = Ahrs::new();
+                    yaw = YawCorrection::new();
+                    active = Some((backend, correct_yaw));
+                }
+                // The phone was turned round: keep the view continuous under the new mapping
+                let display_rotation = DISPLAY_ROTATION.load(Ordering::Relaxed);
+                if display_rotation != rotation {
+                    rotation = display_rotation;
+                    if let Ok(mut s) = state.lock() {
+                        s.retare = true;
+                    }
+                }
+                if active_rate != Some(rate) {
+                    if let (true, Some(d)) = (direct_active, direct.as_mut()) {
+                        d.set_rate(rate);
+                    }
+                    for &s in &enabled {
+                        let period = rate.period_us(s);
+                        ndk_sys::ASensorEventQueue_setEventRate(queue, s, period);
+                        info!("THREAD: Sensor type {} at {}us", ndk_sys::ASensor_getType(s), period);
+                    }
+                    active_rate = Some(rate);
+                }
                 
-                // Poll Looper
-                let poll_id = ndk_sys::ALooper_pollAll(
-                    100, 
-                    ptr::null_mut(),
-                    ptr::null_mut(),
-                    ptr::null_mut()
-                );
-                
-                if poll_id == ndk_sys::ALOOPER_POLL_TIMEOUT {
-                    continue;
+                let elapsed = rate_window.0.elapsed().as_secs_f32();
+                if elapsed >= 1.0 {
+                    if let Ok(mut s) = state.lock() {
+                        s.rate_hz = rate_window.1 as f32 / elapsed;
+                    }
+                    rate_window = (Instant::now(), 0);
                 }
                 
-                if poll_id == ndk_sys::ALOOPER_POLL_ERROR {
-                    error!("THREAD: Poll Error");
-                    thread::sleep(Duration::from_millis(100));
-                    continue;
+                // This pass's events: from the ring buffer, or from the queue
+                batch.clear();
+                if backend == Backend::External {
+                    // The tracker's latest sample, as a pseudo event; onSample wakes the wait
+                    let q = EXTERNAL_SAMPLE.lock().ok()
+                        .and_then(|s| EXTERNAL_WAKE.wait_timeout_while(s, EXTERNAL_WAIT, |s| s.is_none()).ok())
+                        .and_then(|(mut s, _)| s.take());
+                    if let Some(q) = q {
+                        let mut sample: ndk_sys::ASensorEvent = std::mem::zeroed();
+                        sample.type_ = EXTERNAL_EVENT_TYPE;
+                        sample.__bindgen_anon_1.__bindgen_anon_1.data[..4].copy_from_slice(&q.to_array());
+                        batch.push(sample);
+                    }
+                    while ndk_sys::ASensorEventQueue_getEvents(queue, &mut event, 1) > 0 {
+                        batch.push(event);
+                    }
+                    if batch.is_empty() {
+                        continue;
+                    }
+                } else if let (true, Some(d)) = (direct_active, direct.as_mut()) {
+                    // One read per sample period; the looper still wakes early for proximity
+                    ndk_sys::ALooper_pollAll(rate.direct_wait_ms(), ptr::null_mut(), ptr::null_mut(), ptr::null_mut());
+                    d.read(&mut batch);
+                    while ndk_sys::ASensorEventQueue_getEvents(queue, &mut event, 1) > 0 {
+                        batch.push(event);
+                    }
+                    if batch.is_empty() {
+                        continue;
+                    }
+                } else {
+                    // Poll Looper
+                    let poll_id = ndk_sys::ALooper_pollAll(
+                        100, 
+                        ptr::null_mut(),
+                        ptr::null_mut(),
+                        ptr::null_mut()
+                    );
+                    
+                    if poll_id == ndk_sys::ALOOPER_POLL_TIMEOUT {
+                        continue;
+                    }
+                    
+                    if poll_id == ndk_sys::ALOOPER_POLL_ERROR {
+                        error!("THREAD: Poll Error");
+                        thread::sleep(Duration::from_millis(100));
+                        continue;
+                    }
+                    
+                    if poll_id != ident {
+                        continue;
+                    }
+                    // Data available! Drain it all: several sensors share the queue
+                    while ndk_sys::ASensorEventQueue_getEvents(queue, &mut event, 1) > 0 {
+                        batch.push(event);
+                    }
                 }
                 
-                if poll_id == ident {
-                    // Data available!
-                    let count = ndk_sys::ASensorEventQueue_getEvents(queue, &mut event, 1);
-                    if count > 0 {
-                        let mut new_quat = Quat::IDENTITY;
-                        let mut updated = false;
+                for event in &batch {
+                    let mut new_quat = Quat::IDENTITY;
+                    let mut updated = false;
+                    let d = event.__bindgen_anon_1.__bindgen_anon_1.data;
+                    
+                    // Process
+                    if event.type_ == rv_type && backend == Backend::RotationVector {
+                        let x = d[0];
+                        let y = d[1];
+                        let z = d[2];
+                        let w = d[3];
+                        // Debug raw values
+                        if loop_count % 30 == 0 {
+                            // info!("DATA: {:.3} {:.3} {:.3} {:.3}", x, y, z, w);
+                        }
                         
-                        // Process
-                         if sensor_type == ASENSOR_TYPE_GAME_ROTATION_VECTOR || sensor_type == ASENSOR_TYPE_ROTATION_VECTOR {
-                            let x = event.__bindgen_anon_1.__bindgen_anon_1.data[0];
-                            let y = event.__bindgen_anon_1.__bindgen_anon_1.data[1];
-                            let z = event.__bindgen_anon_1.__bindgen_anon_1.data[2];
-                            let w = event.__bindgen_anon_1.__bindgen_anon_1.data[3];
-                            // Debug raw values
-                            if loop_count % 30 == 0 {
-                                // info!("DATA: {:.3} {:.3} {:.3} {:.3}", x, y, z, w);
+                        game_raw = Quat::from_xyzw(x, y, z, w).normalize();
+                        let q = if correct_yaw { yaw.rotation() * game_raw } else { game_raw };
+                        new_quat = to_view(q, rotation);
+                        updated = true;
+                    
+                    } else if event.type_ == EXTERNAL_EVENT_TYPE {
+                        // Worn like the phone in portrait: x right, y up, z toward the face
+                        new_quat = to_view(Quat::from_xyzw(d[0], d[1], d[2], d[3]), 0);
+                        updated = true;
+                    } else if event.type_ == ASENSOR_TYPE_PROXIMITY {
+                        proximity_far = proximity_far.max(d[0]);
+                        let near = d[0] < proximity_far.max(1.0);
+                        if let Ok(mut s) = state.lock() {
+                            if s.near != Some(near) {
+                                info!("THREAD: Proximity {} ({} cm)", if near { "near" } else { "far" }, d[0]);
                             }
-                            
-                            // Cross-talk-free axis mapping is (-y, x, z, w), but that made
-                            // every direction rotate opposite of head movement. Negating
-                            // the vector part (x,y,z) of a unit quaternion is its inverse
-                            // (conjugate) - it reverses the rotation direction on ALL axes
-                            // uniformly without reintroducing the cross-talk the (-y,x)
-                            // swap was fixing.
-                            new_quat = Quat::from_xyzw(y, -x, -z, w).normalize();
+                            s.near = Some(near);
+                        }
+                    } else if event.type_ == ASENSOR_TYPE_ACCELEROMETER {
+                        let a = Vec3::new(d[0], d[1], d[2]);
+                        filter.accelerometer(a);
+                        ahrs.accelerometer(a);
+                    } else if event.type_ == ASENSOR_TYPE_MAGNETIC_FIELD {
+                        let m = Vec3::new(d[0], d[1], d[2]);
+                        if correct_yaw {
+                            yaw.magnetometer(m, game_raw, event.timestamp);
+                        }
+                        ahrs.mag = Some(m);
+                    } else if event.type_ == ASENSOR_TYPE_GYROSCOPE {
+                        let w = Vec3::new(d[0], d[1], d[2]);
+                        let q = match backend {
+                            Backend::Madgwick => ahrs.madgwick(w, event.timestamp, gain),
+                            Backend::Mahony => ahrs.mahony(w, event.timestamp, gain * MAHONY_GAIN_SCALE),
+                            _ => filter.gyroscope(w, event.timestamp),
+                        };
+                        if let Some(q) = q {
+                            new_quat = to_view(q, rotation);
                             updated = true;
-                        
-                        } else if sensor_type == ASENSOR_TYPE_GYROSCOPE {
-                            let gx = event.__bindgen_anon_1.__bindgen_anon_1.data[0];
-                            let gy = event.__bindgen_anon_1.__bindgen_anon_1.data[1];
-                            let gz = event.__bindgen_anon_1.__bindgen_anon_1.data[2];
-                            let ts = event.timestamp;
-                            
-                            if last_ts > 0 {
-                                let dt = (ts - last_ts) as f32 / 1_000_000_000.0;
-                                if dt < 0.2 {
-                                    // Inverted to match the (y, -x, -z, w) rotation-vector
-                                    // mapping above (full direction flip, same as that fix).
-                                    gyro_pitch += gy * dt;
-                                    gyro_yaw -= gx * dt;
-                                    gyro_roll -= gz * dt;
-                                    
-                                    new_quat = Quat::from_euler(
-                                        glam::EulerRot::YXZ,
-                                        gyro_yaw,
-                                        gyro_pitch,
-                                        gyro_roll,
-                                    );
-                                    updated = true;
-                                }
-                            }
-                            last_ts = ts;
                         }
-                        
-                        if updated {
-                            if let Ok(mut s) = state.lock() {
-                                s.orientation = new_quat;
+                    }
+                    
+                    if updated {
+                        rate_window.1 += 1;
+                        if let Ok(mut s) = state.lock() {
+                            s.last_event = Some(Instant::now());
+                            if std::mem::take(&mut s.retare) {
+                                // view = reference⁻¹ · orientation stays the same
+                                s.reference = new_quat * s.orientation.inverse() * s.reference;
                             }
+                            s.orientation = new_quat;
                         }
                     }
                 }
             }
             
             // Clean
-            ndk_sys::ASensorEventQueue_disableSensor(queue, sensor);
+            drop(direct);
+            for s in enabled.into_iter().chain(Some(proximity_sensor).filter(|s| !s.is_null())) {
+                ndk_sys::ASensorEventQueue_disableSensor(queue, s);
+            }
             ndk_sys::ASensorManager_destroyEventQueue(manager, queue);
         }
     }
     
-    pub fn update(&mut self, _dt: f32) {}
+    /// Set the requested sensor rate (see `SensorRate`).
+    pub fn set_rate(&self, rate: SensorRate) {
+        if let Ok(mut s) = self.state.lock() {
+            s.rate = rate;
+        }
+    }
+
+    /// Turn magnetometer yaw correction on or off (only used with the game
+    /// rotation vector).
+    pub fn set_yaw_correction(&self, enabled: bool) {
+        if let Ok(mut s) = self.state.lock() {
+            s.yaw_correction = enabled;
+        }
+    }
+
+    /// Pick the fusion backend and its gain (see `FusionMode`).
+    pub fn set_fusion(&self, mode: FusionMode, gain: f32) {
+        if let Ok(mut s) = self.state.lock() {
+            s.fusion = mode;
+            s.gain = gain;
+        }
+    }
+
+    /// Smoothing strength, 0 (off) to 1.
+    pub fn set_smoothing(&mut self, strength: f32) {
+        self.smoothing = strength.clamp(0.0, 1.0);
+    }
+
+    /// Snapshot of the tracking source and its health.
+    pub fn status(&self) -> SensorStatus {
+        match self.state.lock() {
+            Ok(s) => SensorStatus {
+                source: match EXTERNAL_STATE.lock() {
+                    Ok(state) if s.fusion == FusionMode::External && !state.is_empty() => format!("{} · {}", s.source, state),
+                    _ => s.source.clone(),
+                },
+                rate_hz: s.rate_hz,
+                last_event_age: s.last_event.map(|t| t.elapsed()),
+                error: s.error.clone(),
+            },
+            Err(_) => SensorStatus { error: Some("Sensor state unavailable".into()), ..Default::default() },
+        }
+    }
+
+    /// Whether the proximity sensor sees something close (the phone is in the
+    /// headset). `None` without a proximity sensor or before its first reading.
+    pub fn is_near(&self) -> Option<bool> {
+        self.state.lock().ok().and_then(|s| s.near)
+    }
+
+    /// Advance the smoothing stage by `dt` seconds (call once per frame).
+    pub fn update(&mut self, dt: f32) {
+        let (target, snap) = match self.state.lock() {
+            Ok(mut s) => (s.reference.inverse() * s.orientation, std::mem::take(&mut s.snap)),
+            Err(_) => return,
+        };
+        self.smoothed = match self.smoothed {
+            Some(previous) if self.smoothing > 0.0 && !snap => {
+                // Frame-rate independent exponential approach
+                let alpha = 1.0 - (-dt / (self.smoothing * MAX_SMOOTHING_TIME)).exp();
+                Some(previous.slerp(target, alpha).normalize())
+            }
+            _ => Some(target),
+        };
+    }
 
     pub fn get_orientation(&self) -> Quat {
+        if let Some(q) = self.smoothed {
+            return q;
+        }
         if let Ok(s) = self.state.lock() {
             // Return: Reference^-1 * Raw
             s.reference.inverse() * s.orientation
@@ -259,20 +1213,31 @@ impl SensorInput {
     pub fn recenter(&self) {
         if let Ok(mut s) = self.state.lock() {
             s.reference = s.orientation;
+            s.snap = true;
             
             // Save to static storage for persistence across activity recreation
-            if let Some(saved) = SAVED_REFERENCE.get() {
-                if let Ok(mut g) = saved.lock() {
-                    *g = s.reference;
-                }
-            }
+            save_reference(s.reference);
             
             info!("Sensor Recalibrated/Centered (saved)");
         }
     }
 
+    /// Turn the world about the vertical by `degrees` (positive moves the screen
+    /// right). Folded into the reference, so it's saved with it and the next
+    /// recenter clears it.
+    pub fn nudge_yaw(&self, degrees: f32) {
+        if let Ok(mut s) = self.state.lock() {
+            s.reference *= Quat::from_rotation_y(-degrees.to_radians());
+            save_reference(s.reference);
+            info!("Yaw trim {:+.0}°", degrees);
+        }
+    }
+
+    /// Whether the sensor thread is (still) running: false once it failed to
+    /// start, e.g. on a device with no orientation sensors.
     pub fn is_available(&self) -> bool {
-        self._thread_handle.is_some()
+        self._thread_handle.as_ref().is_some_and(|h| !h.is_finished())
+            && self.state.lock().is_ok_and(|s| s.error.is_none())
     }
     
     #[allow(dead_code)]
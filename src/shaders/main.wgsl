@@ -5,7 +5,10 @@ struct CameraUniforms {
     view_proj: mat4x4<f32>,
     eye_offset: vec4<f32>,  // x = offset, y = has_video, z = time, w = content_scale
     video_info: vec4<f32>,  // x = aspect_ratio (w/h), y = width, z = height, w = unused
-    stereo: vec4<f32>,      // x = mode (0 mono,1 SBS,2 over-under), y = eye_index
+    stereo: vec4<f32>,      // x = mode (0 mono,1 SBS,2 over-under), y = eye_index, z = rotation (quarter turns CW)
+    screen: vec4<f32>,      // x = yaw (radians), y = size relative to the main screen, zw = content pan
+    crop: vec4<f32>,        // xy = UV scale about the centre, z = screen aspect per eye (0 = the video's)
+    picture: vec4<f32>,     // x = brightness, y = contrast, z = saturation, w = gamma
 };
 
 @group(0) @binding(0)
@@ -43,8 +46,9 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
     var aspect = camera.video_info.x;
     if (smode > 0.5 && smode < 1.5) { aspect = aspect * 0.5; }       // SBS
     else if (smode > 1.5) { aspect = aspect * 2.0; }                 // over-under
+    if (camera.crop.z > 0.0) { aspect = camera.crop.z; }            // Fill / Stretch / Crop screen shape
 
-    let scale  = max(camera.eye_offset.w, 0.1);   // content_scale (zoom)
+    let scale  = max(camera.eye_offset.w, 0.1) * camera.screen.y;   // content_scale (zoom) × screen size
     let radius = 5.3;
     let base_h = 1.6;
     let screen_h = base_h * scale;                // grows uniformly with zoom
@@ -64,8 +68,9 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
     // Angular spans grow with the screen on BOTH axes (aspect preserved).
     let arc_h = screen_w / radius;
     let arc_v = screen_h / radius;
-    let theta = (u_coord - 0.5) * arc_h;
-    let phi   = (0.5 - v_coord) * arc_v;          // v=0 (top) → +phi
+    // Pan slides the (zoomed) screen so the panned-to point sits straight ahead.
+    let theta = (u_coord - 0.5 - camera.screen.z) * arc_h + camera.screen.x;   // yaw places side-by-side screens
+    let phi   = (0.5 - v_coord + camera.screen.w) * arc_v;  // v=0 (top) → +phi
 
     // Point on the sphere (curves horizontally AND vertically), centred at -Z.
     var world_pos = vec3<f32>(
@@ -80,6 +85,25 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
     return output;
 }
 
+// Screen UV → stored-frame UV for a frame that must be shown rotated `turns`
+// quarter turns clockwise (container rotation metadata).
+fn rotate_uv(uv: vec2<f32>, turns: f32) -> vec2<f32> {
+    if (turns > 2.5) { return vec2<f32>(1.0 - uv.y, uv.x); }        // 270°
+    if (turns > 1.5) { return vec2<f32>(1.0 - uv.x, 1.0 - uv.y); }  // 180°
+    if (turns > 0.5) { return vec2<f32>(uv.y, 1.0 - uv.x); }        // 90°
+    return uv;
+}
+
+// Picture settings on gamma-encoded RGB (before linearizing), so the sliders act
+// the way they do on a TV: contrast pivots on mid grey, gamma > 1 lifts shadows.
+fn adjust_picture(rgb: vec3<f32>) -> vec3<f32> {
+    let p = camera.picture;
+    var c = (rgb - 0.5) * p.y + 0.5 + p.x;
+    let luma = dot(c, vec3<f32>(0.299, 0.587, 0.114));
+    c = mix(vec3<f32>(luma), c, p.z);
+    return pow(clamp(c, vec3<f32>(0.0), vec3<f32>(1.0)), vec3<f32>(1.0 / p.w));
+}
+
 @fragment
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
     let uv = input.uv;
@@ -88,13 +112,16 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
 
     // Stereo remap: each eye samples its half of the frame. eye_index 0/2 → first
     // half (left/top), 1 → second half (right/bottom). Shared by web + video.
+    // Rotation (video only; zero for web) is undone first.
     let smode = camera.stereo.x;
     let is_right = camera.stereo.y > 0.5 && camera.stereo.y < 1.5;
-    var suv = uv;
+    // Fill / Crop show the middle of the frame (scale about the centre), in screen
+    // orientation, so before undoing the rotation.
+    var suv = rotate_uv((uv - 0.5) * camera.crop.xy + 0.5, camera.stereo.z);
     if (smode > 0.5 && smode < 1.5) {          // side-by-side
-        suv.x = uv.x * 0.5 + select(0.0, 0.5, is_right);
+        suv.x = suv.x * 0.5 + select(0.0, 0.5, is_right);
     } else if (smode > 1.5) {                  // over-under
-        suv.y = uv.y * 0.5 + select(0.0, 0.5, is_right);
+        suv.y = suv.y * 0.5 + select(0.0, 0.5, is_right);
     }
 
     if (is_web) {
@@ -120,7 +147,7 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
         let g = y - 0.391 * u - 0.813 * v;
         let b = y + 2.018 * u;
         
-        var rgb = vec3<f32>(r, g, b);
+        var rgb = adjust_picture(vec3<f32>(r, g, b));
         // Linearize (approximate gamma 2.2) to prevent double gamma on the sRGB surface.
         rgb = pow(max(rgb, vec3<f32>(0.0)), vec3<f32>(2.2));
         return vec4<f32>(rgb, 1.0);
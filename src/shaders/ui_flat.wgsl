@@ -0,0 +1,39 @@
+// Flat UI overlay for 2D (non-VR) mode: the top-left of the UI texture laid over
+// the whole screen, texel for screen pixel scaled by one factor, so touches map
+// straight onto the widgets (lib.rs lays the canvas out at the same scale).
+// Draw call requests 6 vertices.
+
+// Shared with ui_panel.wgsl
+struct UiUniforms {
+    panel: mat4x4<f32>,
+    flat_extent: vec4<f32>,  // xy = the part of the texture the screen covers (0..1)
+};
+
+@group(0) @binding(0) var ui_tex: texture_2d<f32>;
+@group(0) @binding(1) var ui_samp: sampler;
+@group(0) @binding(2) var<uniform> ui: UiUniforms;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Two triangles covering the screen
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0), vec2<f32>(1.0, 0.0), vec2<f32>(0.0, 1.0),
+        vec2<f32>(0.0, 1.0), vec2<f32>(1.0, 0.0), vec2<f32>(1.0, 1.0));
+    let corner = corners[vertex_index];
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(corner.x * 2.0 - 1.0, 1.0 - corner.y * 2.0, 0.0, 1.0);
+    out.uv = corner * ui.flat_extent.xy;
+    return out;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    // egui outputs premultiplied alpha; pipeline blend is (One, OneMinusSrcAlpha).
+    return textureSample(ui_tex, ui_samp, input.uv);
+}
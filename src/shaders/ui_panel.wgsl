@@ -11,11 +11,20 @@ struct CameraUniforms {
     eye_offset: vec4<f32>,  // x = eye offset, y = has_video, z = time, w = content_scale
     video_info: vec4<f32>,
     stereo: vec4<f32>,
+    screen: vec4<f32>,
+    crop: vec4<f32>,
+    picture: vec4<f32>,
 };
 
 @group(0) @binding(0) var<uniform> camera: CameraUniforms;
+struct UiUniforms {
+    panel: mat4x4<f32>,      // placement: straight ahead, where a menu opened, or on the head
+    flat_extent: vec4<f32>,  // (ui_flat.wgsl)
+};
+
 @group(1) @binding(0) var ui_tex: texture_2d<f32>;
 @group(1) @binding(1) var ui_samp: sampler;
+@group(1) @binding(2) var<uniform> ui: UiUniforms;
 
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
@@ -44,10 +53,11 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
 
     let theta = (u - 0.5) * arc;
     let phi   = (0.5 - v) * arc;
-    var world_pos = vec3<f32>(
+    let on_dome = vec3<f32>(
         radius * cos(phi) * sin(theta),
         radius * sin(phi),
         -radius * cos(phi) * cos(theta));
+    var world_pos = (ui.panel * vec4<f32>(on_dome, 1.0)).xyz;
     world_pos.x += camera.eye_offset.x;       // stereo eye shift
 
     var out: VertexOutput;
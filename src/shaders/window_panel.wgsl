@@ -0,0 +1,79 @@
+// Floating window_manager panels (browser, dock, settings): one flat textured quad
+// per instance, placed by the panel's model matrix (WindowManager::get_transform).
+// Depth-tested against each other; drawn over the dome screens and under the UI
+// panel. Draw call requests 6 vertices per instance. Snap guide lines are thin
+// instances filled with their tint instead of the texture.
+
+struct CameraUniforms {
+    view_proj: mat4x4<f32>,
+    eye_offset: vec4<f32>,  // x = eye offset, y = has_video, z = time, w = content_scale
+    video_info: vec4<f32>,
+    stereo: vec4<f32>,
+    screen: vec4<f32>,
+    crop: vec4<f32>,
+    picture: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> camera: CameraUniforms;
+
+// The UI or web texture, whichever the panels in this draw show
+@group(1) @binding(0) var panel_tex: texture_2d<f32>;
+@group(1) @binding(1) var panel_samp: sampler;
+
+struct PanelInstance {
+    @location(0) model_0: vec4<f32>,
+    @location(1) model_1: vec4<f32>,
+    @location(2) model_2: vec4<f32>,
+    @location(3) model_3: vec4<f32>,
+    @location(4) cursor: vec4<f32>,     // xy = pointer (0…1), z = 1 if shown, w = panel aspect
+    @location(5) tint: vec4<f32>,       // a > 0: fill with this (premultiplied) instead
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) @interpolate(flat) cursor: vec4<f32>,
+    @location(2) @interpolate(flat) tint: vec4<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32, panel: PanelInstance) -> VertexOutput {
+    // Two triangles over the unit square, which the model matrix scales to the panel
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0), vec2<f32>(0.0, 1.0), vec2<f32>(1.0, 0.0),
+        vec2<f32>(1.0, 0.0), vec2<f32>(0.0, 1.0), vec2<f32>(1.0, 1.0));
+    let uv = corners[vertex_index];
+    let model = mat4x4<f32>(panel.model_0, panel.model_1, panel.model_2, panel.model_3);
+    var world_pos = (model * vec4<f32>(uv.x - 0.5, 0.5 - uv.y, 0.0, 1.0)).xyz;
+    world_pos.x += camera.eye_offset.x;       // stereo eye shift
+
+    var out: VertexOutput;
+    out.position = camera.view_proj * vec4<f32>(world_pos, 1.0);
+    out.uv = uv;
+    out.cursor = panel.cursor;
+    out.tint = panel.tint;
+    return out;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(panel_tex, panel_samp, input.uv);
+    if (input.tint.a > 0.0) {
+        return input.tint;
+    }
+    // The pointer: a white dot ringed in black, round on any panel shape
+    if (input.cursor.z > 0.5) {
+        let d = length((input.uv - input.cursor.xy) * vec2<f32>(input.cursor.w, 1.0));
+        if (d < 0.012) {
+            return vec4<f32>(1.0, 1.0, 1.0, 1.0);
+        }
+        if (d < 0.018) {
+            return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+        }
+    }
+    // Clear parts of the UI texture mustn't hide (or write depth over) panels behind
+    if (color.a < 0.01) {
+        discard;
+    }
+    return color;
+}
@@ -0,0 +1,83 @@
+//! Save the current video frame to the gallery
+//!
+//! Takes the decoder's latest NV12 frame, converts it to RGBA on a worker thread
+//! (an 8K frame takes a while; the render loop mustn't wait) and hands it to Java,
+//! which rotates it upright and writes a JPEG to `Pictures/VR Snapshots` through
+//! MediaStore. The full frame is saved, so stereo video keeps both eyes. The
+//! outcome comes back as a toast.
+
+use android_activity::AndroidApp;
+use jni::objects::{JObject, JValue};
+use jni::sys::jobject;
+use log::{error, info};
+use std::thread;
+
+use crate::i18n::tr;
+use crate::ui::post_toast;
+use crate::video_ndk::NdkVideoDecoder;
+
+/// Save the frame on screen. `title` names the file (the timestamp is appended).
+pub fn save(app: &AndroidApp, decoder: &NdkVideoDecoder, title: &str) {
+    let Some((y, uv, width, height)) = decoder.latest_frame() else {
+        info!("Snapshot: no frame yet");
+        post_toast(tr("No frame to save yet"));
+        return;
+    };
+    let pixels = width as usize * height as usize;
+    if y.len() < pixels || uv.len() < pixels / 2 {
+        error!("Snapshot: short frame ({} / {} bytes for {}x{})", y.len(), uv.len(), width, height);
+        post_toast(tr("Couldn't save the frame"));
+        return;
+    }
+    let position = crate::resume::format_time(decoder.get_position()).replace(':', ".");
+    let stem = title.rsplit_once('.').map_or(title, |(stem, _)| stem).replace(['/', ':', '?', '&'], "_");
+    let name = format!("{} {}", stem, position);
+    let rotation = decoder.rotation_degrees();
+    let app = app.clone();
+    thread::spawn(move || {
+        let rgba = nv12_to_rgba(&y, &uv, width as usize, height as usize);
+        match deliver(&app, &rgba, width as i32, height as i32, rotation as i32, &name) {
+            Ok(()) => post_toast(tr("Frame saved to Pictures/VR Snapshots")),
+            Err(e) => {
+                error!("snapshot: {}", e);
+                post_toast(tr("Couldn't save the frame"));
+            }
+        }
+    });
+}
+
+/// BT.601 limited range, the same conversion the video shader does.
+fn nv12_to_rgba(y_plane: &[u8], uv_plane: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let mut rgba = vec![255u8; width * height * 4];
+    for (row, out) in rgba.chunks_exact_mut(width * 4).enumerate() {
+        let y_row = &y_plane[row * width..];
+        let uv_row = &uv_plane[(row / 2) * width..];
+        for (x, px) in out.chunks_exact_mut(4).enumerate() {
+            let y = 1.1643 * (y_row[x] as f32 - 16.0);
+            let u = uv_row[x & !1] as f32 - 128.0;
+            let v = uv_row[(x & !1) + 1] as f32 - 128.0;
+            px[0] = (y + 1.596 * v).clamp(0.0, 255.0) as u8;
+            px[1] = (y - 0.391 * u - 0.813 * v).clamp(0.0, 255.0) as u8;
+            px[2] = (y + 2.018 * u).clamp(0.0, 255.0) as u8;
+        }
+    }
+    rgba
+}
+
+fn deliver(app: &AndroidApp, rgba: &[u8], width: i32, height: i32, rotation: i32, name: &str) -> Result<(), String> {
+    let vm = unsafe { jni::JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM).unwrap() };
+    let mut env = vm.attach_current_thread().map_err(|e| format!("attach failed: {:?}", e))?;
+    let activity = unsafe { JObject::from_raw(app.activity_as_ptr() as jobject) };
+    let (j_pixels, j_name) = match (env.byte_array_from_slice(rgba), env.new_string(name)) {
+        (Ok(p), Ok(n)) => (p, n),
+        _ => return Err(format!("couldn't allocate a {}x{} frame", width, height)),
+    };
+    env.call_method(
+        &activity,
+        "saveSnapshot",
+        "([BIIILjava/lang/String;)V",
+        &[JValue::Object(&j_pixels.into()), JValue::Int(width), JValue::Int(height),
+          JValue::Int(rotation), JValue::Object(&j_name.into())],
+    ).map_err(|e| format!("saveSnapshot call failed: {:?}", e))?;
+    Ok(())
+}
@@ -0,0 +1,83 @@
+//! Phone status for the HUD
+//!
+//! The time, the phone's battery and its Wi-Fi, so they can be read without
+//! taking the phone out of the headset. MainActivity.getDeviceStatus reports
+//! them as one "time\tpercent\tcharging\twifi" line: the time already formatted
+//! for the phone's 12 / 24-hour setting, the battery from BatteryManager and
+//! Wi-Fi from ConnectivityManager. Java is only asked every `POLL`, and only
+//! while the HUD is on.
+
+use android_activity::AndroidApp;
+use jni::objects::{JObject, JString};
+use jni::sys::jobject;
+use log::error;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Often enough that the clock is never more than this late.
+const POLL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Wifi {
+    /// Not on Wi-Fi (mobile data or offline)
+    Off,
+    /// Connected, with 0–3 bars when the phone reports the signal (Android 10+)
+    On(Option<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceStatus {
+    pub time: String,
+    /// 0…1, None when the phone doesn't report it
+    pub battery: Option<f32>,
+    pub charging: bool,
+    pub wifi: Wifi,
+}
+
+static STATUS: Mutex<(Option<Instant>, Option<DeviceStatus>)> = Mutex::new((None, None));
+
+/// The phone's status, cheap to call every frame.
+pub fn poll(app: &AndroidApp) -> Option<DeviceStatus> {
+    let mut status = STATUS.lock().unwrap();
+    if status.0.is_some_and(|at| at.elapsed() < POLL) {
+        return status.1.clone();
+    }
+    status.0 = Some(Instant::now());
+    match query(app) {
+        Ok(line) => status.1 = parse(&line),
+        Err(e) => error!("Status: getDeviceStatus failed: {:?}", e),
+    }
+    status.1.clone()
+}
+
+fn query(app: &AndroidApp) -> Result<String, jni::errors::Error> {
+    let vm = unsafe { jni::JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM)? };
+    let mut env = vm.attach_current_thread()?;
+    let activity = unsafe { JObject::from_raw(app.activity_as_ptr() as jobject) };
+    let text: JString = env.call_method(&activity, "getDeviceStatus", "()Ljava/lang/String;", &[])?.l()?.into();
+    let text: String = env.get_string(&text)?.into();
+    Ok(text)
+}
+
+fn parse(line: &str) -> Option<DeviceStatus> {
+    let mut fields = line.split('\t');
+    let time = fields.next()?.to_string();
+    let battery = fields.next()?.parse::<f32>().ok().filter(|p| *p >= 0.0).map(|p| (p / 100.0).min(1.0));
+    let charging = fields.next()? == "true";
+    let wifi = match fields.next()? {
+        "off" => Wifi::Off,
+        "on" => Wifi::On(None),
+        rssi => Wifi::On(rssi.parse::<i32>().ok().map(bars)),
+    };
+    Some(DeviceStatus { time, battery, charging, wifi })
+}
+
+/// Signal bars for a Wi-Fi RSSI in dBm.
+fn bars(rssi: i32) -> u8 {
+    match rssi {
+        r if r >= -60 => 3,
+        r if r >= -70 => 2,
+        r if r >= -80 => 1,
+        _ => 0,
+    }
+}
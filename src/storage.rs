@@ -0,0 +1,90 @@
+//! Storage volumes
+//!
+//! The internal shared storage plus any mounted SD cards and USB (OTG) drives,
+//! the roots the Media Center browses. Android's StorageManager lists and names
+//! them ("SD card", "SanDisk USB drive") through MainActivity.getStorageVolumes;
+//! if that call fails, the directories under `/storage` are probed instead.
+//! The list is re-read at most every `POLL`, so a drive plugged in while the
+//! Media Center is open shows up.
+
+use android_activity::AndroidApp;
+use jni::objects::{JObject, JString};
+use jni::sys::jobject;
+use log::{error, info};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The internal shared storage, always listed first.
+pub const INTERNAL: &str = "/storage/emulated/0";
+/// Where the Media Center lists the volumes (it isn't browsed as a folder).
+pub const VOLUMES_ROOT: &str = "/storage";
+const POLL: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Volume {
+    pub path: PathBuf,
+    pub label: String,
+    /// SD card or USB drive
+    pub removable: bool,
+}
+
+/// The internal storage alone, until the volumes have been read.
+pub fn internal() -> Volume {
+    Volume { path: PathBuf::from(INTERNAL), label: crate::i18n::tr("Internal storage").into(), removable: false }
+}
+
+static VOLUMES: Mutex<(Option<Instant>, Vec<Volume>)> = Mutex::new((None, Vec::new()));
+
+/// The mounted volumes, internal storage first.
+pub fn volumes(app: &AndroidApp) -> Vec<Volume> {
+    let mut cache = VOLUMES.lock().unwrap();
+    if cache.0.is_some_and(|at| at.elapsed() < POLL) {
+        return cache.1.clone();
+    }
+    cache.0 = Some(Instant::now());
+    let mut found = query(app).unwrap_or_else(|e| {
+        error!("Storage: getStorageVolumes failed ({:?}), probing {}", e, VOLUMES_ROOT);
+        probe()
+    });
+    found.retain(|v| v.path != Path::new(INTERNAL));
+    found.insert(0, internal());
+    if found != cache.1 {
+        info!("Storage: {:?}", found.iter().map(|v| &v.label).collect::<Vec<_>>());
+        cache.1 = found;
+    }
+    cache.1.clone()
+}
+
+fn query(app: &AndroidApp) -> Result<Vec<Volume>, jni::errors::Error> {
+    let vm = unsafe { jni::JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM)? };
+    let mut env = vm.attach_current_thread()?;
+    let activity = unsafe { JObject::from_raw(app.activity_as_ptr() as jobject) };
+    let text: JString = env.call_method(&activity, "getStorageVolumes", "()Ljava/lang/String;", &[])?.l()?.into();
+    let text: String = env.get_string(&text)?.into();
+    Ok(text.lines().filter_map(|line| {
+        let mut fields = line.split('\t');
+        Some(Volume {
+            path: PathBuf::from(fields.next()?),
+            label: fields.next()?.to_string(),
+            removable: fields.next()? == "true",
+        })
+    }).collect())
+}
+
+/// Every directory under `/storage` except the internal storage's own entries
+/// (`emulated`, `self`); these are the SD cards and USB drives.
+fn probe() -> Vec<Volume> {
+    let Ok(rd) = std::fs::read_dir(VOLUMES_ROOT) else { return Vec::new() };
+    rd.flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            (name != "emulated" && name != "self").then(|| Volume {
+                path: e.path(),
+                label: crate::i18n::trf("External storage ({})", &[&name]),
+                removable: true,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,368 @@
+//! UI colour themes
+//!
+//! Panels draw with the roles in `Theme` rather than fixed colours. Dark is the
+//! original look; OLED black makes every panel pure black and opaque (the
+//! pixels switch off, so the headset shows less glow around them); High
+//! contrast is black and white with solid outlines; Light is for 2D use in a
+//! bright room. Each preset has its own accent, which Settings → Advanced can
+//! replace with any colour.
+//!
+//! The Media Center is a "sheet" with colours of its own: light on Dark and
+//! Light, black on the other two. ui.rs passes the choice to `select` every
+//! frame and reads the colours back with `current`, as i18n does with the
+//! language.
+//!
+//! `Accessibility` goes on top of any preset: no animations, solid outlines on
+//! every control, and accent / warning colours that stay apart for red-green
+//! or blue-yellow colour blindness.
+
+use egui::{Color32, Context, Rounding, Stroke, Visuals};
+use std::sync::RwLock;
+
+use crate::i18n::tr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemePreset {
+    Dark,
+    OledBlack,
+    HighContrast,
+    Light,
+}
+
+impl ThemePreset {
+    pub const ALL: [ThemePreset; 4] = [ThemePreset::Dark, ThemePreset::OledBlack, ThemePreset::HighContrast, ThemePreset::Light];
+
+    pub fn label(self) -> &'static str {
+        tr(match self {
+            ThemePreset::Dark => "Dark",
+            ThemePreset::OledBlack => "OLED black",
+            ThemePreset::HighContrast => "High contrast",
+            ThemePreset::Light => "Light",
+        })
+    }
+}
+
+/// Which colour pairs the accent and warnings must not rely on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorVision {
+    Typical,
+    /// Protanopia / deuteranopia: red and green look alike
+    RedGreen,
+    /// Tritanopia: blue and yellow (and green) look alike
+    BlueYellow,
+}
+
+impl ColorVision {
+    pub const ALL: [ColorVision; 3] = [ColorVision::Typical, ColorVision::RedGreen, ColorVision::BlueYellow];
+
+    pub fn label(self) -> &'static str {
+        tr(match self {
+            ColorVision::Typical => "Typical",
+            ColorVision::RedGreen => "Red-green",
+            ColorVision::BlueYellow => "Blue-yellow",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Accessibility {
+    /// Panels appear and move without transitions
+    pub reduce_motion: bool,
+    /// Solid outlines on buttons, sliders and checkboxes, whatever the preset
+    pub bold_controls: bool,
+    pub color_vision: ColorVision,
+}
+
+impl Default for Accessibility {
+    fn default() -> Self {
+        Accessibility { reduce_motion: false, bold_controls: false, color_vision: ColorVision::Typical }
+    }
+}
+
+/// Accents offered next to the colour picker.
+pub const ACCENTS: [Color32; 6] = [
+    Color32::from_rgb(70, 140, 250),
+    Color32::from_rgb(40, 190, 170),
+    Color32::from_rgb(90, 200, 90),
+    Color32::from_rgb(255, 160, 40),
+    Color32::from_rgb(240, 90, 140),
+    Color32::from_rgb(160, 110, 250),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub preset: ThemePreset,
+    /// Dock, settings, queue and dialogs
+    panel: Color32,
+    /// OSD, toasts and the other small overlays
+    overlay: Color32,
+    /// Panels ignore the translucency they ask for
+    opaque: bool,
+    /// Outline of panels
+    pub stroke: Stroke,
+    /// Unselected buttons on a panel
+    control: Color32,
+    pub text: Color32,
+    /// Secondary text (values, times)
+    pub text_soft: Color32,
+    /// Captions and hints
+    pub text_weak: Color32,
+    /// Selection, progress and focus
+    pub accent: Color32,
+    /// Text on `accent`
+    pub on_accent: Color32,
+    /// Switched-on controls that aren't selected, the playing queue entry
+    pub accent_dim: Color32,
+    /// Errors, low battery, Exit
+    pub danger: Color32,
+    /// The Media Center and the windows over it
+    sheet: Color32,
+    sheet_light: bool,
+    pub sheet_stroke: Stroke,
+    pub sheet_text: Color32,
+    pub sheet_text_weak: Color32,
+    pub sheet_accent: Color32,
+    pub on_sheet_accent: Color32,
+    pub sheet_danger: Color32,
+    pub access: Accessibility,
+}
+
+impl Theme {
+    /// `preset`, with `accent` in place of its own if given, adjusted for `access`.
+    pub fn new(preset: ThemePreset, accent: Option<Color32>, access: Accessibility) -> Self {
+        let mut theme = match preset {
+            ThemePreset::Dark => Theme {
+                preset,
+                panel: Color32::from_rgb(24, 24, 32),
+                overlay: Color32::from_rgb(18, 18, 24),
+                opaque: false,
+                stroke: Stroke::new(1.0, Color32::from_white_alpha(30)),
+                control: Color32::from_rgb(45, 45, 58),
+                text: Color32::WHITE,
+                text_soft: Color32::from_gray(205),
+                text_weak: Color32::from_gray(165),
+                accent: Color32::from_rgb(70, 140, 250),
+                on_accent: Color32::WHITE,
+                accent_dim: Color32::from_rgb(45, 90, 150),
+                danger: Color32::from_rgb(255, 110, 100),
+                sheet: Color32::from_rgb(238, 240, 244),
+                sheet_light: true,
+                sheet_stroke: Stroke::new(1.0, Color32::from_black_alpha(28)),
+                sheet_text: Color32::from_rgb(26, 26, 32),
+                sheet_text_weak: Color32::from_rgb(108, 110, 120),
+                sheet_accent: Color32::from_rgb(46, 107, 230),
+                on_sheet_accent: Color32::WHITE,
+                sheet_danger: Color32::from_rgb(214, 58, 52),
+                access,
+            },
+            ThemePreset::OledBlack => Theme {
+                preset,
+                panel: Color32::BLACK,
+                overlay: Color32::BLACK,
+                opaque: true,
+                stroke: Stroke::new(1.0, Color32::from_white_alpha(40)),
+                control: Color32::from_rgb(30, 30, 34),
+                text: Color32::WHITE,
+                text_soft: Color32::from_gray(200),
+                text_weak: Color32::from_gray(150),
+                accent: Color32::from_rgb(70, 140, 250),
+                on_accent: Color32::WHITE,
+                accent_dim: Color32::from_rgb(35, 70, 125),
+                danger: Color32::from_rgb(255, 110, 100),
+                sheet: Color32::BLACK,
+                sheet_light: false,
+                sheet_stroke: Stroke::new(1.0, Color32::from_white_alpha(40)),
+                sheet_text: Color32::WHITE,
+                sheet_text_weak: Color32::from_gray(150),
+                sheet_accent: Color32::from_rgb(70, 140, 250),
+                on_sheet_accent: Color32::WHITE,
+                sheet_danger: Color32::from_rgb(255, 110, 100),
+                access,
+            },
+            ThemePreset::HighContrast => Theme {
+                preset,
+                panel: Color32::BLACK,
+                overlay: Color32::BLACK,
+                opaque: true,
+                stroke: Stroke::new(2.0, Color32::WHITE),
+                control: Color32::from_gray(40),
+                text: Color32::WHITE,
+                text_soft: Color32::WHITE,
+                text_weak: Color32::from_gray(220),
+                accent: Color32::from_rgb(255, 214, 0),
+                on_accent: Color32::BLACK,
+                accent_dim: Color32::from_rgb(120, 100, 0),
+                danger: Color32::from_rgb(255, 90, 90),
+                sheet: Color32::BLACK,
+                sheet_light: false,
+                sheet_stroke: Stroke::new(2.0, Color32::WHITE),
+                sheet_text: Color32::WHITE,
+                sheet_text_weak: Color32::from_gray(220),
+                sheet_accent: Color32::from_rgb(255, 214, 0),
+                on_sheet_accent: Color32::BLACK,
+                sheet_danger: Color32::from_rgb(255, 90, 90),
+                access,
+            },
+            ThemePreset::Light => Theme {
+                preset,
+                panel: Color32::from_rgb(246, 247, 250),
+                overlay: Color32::from_rgb(246, 247, 250),
+                opaque: false,
+                stroke: Stroke::new(1.0, Color32::from_black_alpha(30)),
+                control: Color32::from_rgb(224, 226, 232),
+                text: Color32::from_rgb(22, 22, 28),
+                text_soft: Color32::from_rgb(60, 62, 70),
+                text_weak: Color32::from_rgb(100, 102, 112),
+                accent: Color32::from_rgb(46, 107, 230),
+                on_accent: Color32::WHITE,
+                accent_dim: Color32::from_rgb(160, 190, 240),
+                danger: Color32::from_rgb(200, 40, 40),
+                sheet: Color32::from_rgb(238, 240, 244),
+                sheet_light: true,
+                sheet_stroke: Stroke::new(1.0, Color32::from_black_alpha(28)),
+                sheet_text: Color32::from_rgb(26, 26, 32),
+                sheet_text_weak: Color32::from_rgb(108, 110, 120),
+                sheet_accent: Color32::from_rgb(46, 107, 230),
+                on_sheet_accent: Color32::WHITE,
+                sheet_danger: Color32::from_rgb(214, 58, 52),
+                access,
+            },
+        };
+        // Okabe-Ito colours: the accent keeps clear of the warnings, and neither
+        // leans on the pair that's hard to tell apart. A chosen accent still wins.
+        let (safe_accent, safe_danger) = match access.color_vision {
+            ColorVision::Typical => (None, None),
+            ColorVision::RedGreen => (Some(Color32::from_rgb(40, 130, 220)), Some(Color32::from_rgb(240, 130, 30))),
+            ColorVision::BlueYellow => (Some(Color32::from_rgb(30, 170, 170)), Some(Color32::from_rgb(230, 70, 110))),
+        };
+        if let Some(danger) = safe_danger {
+            theme.danger = if theme.preset == ThemePreset::Light { mix(danger, Color32::BLACK, 0.25) } else { danger };
+            theme.sheet_danger = if theme.sheet_light { mix(danger, Color32::BLACK, 0.25) } else { danger };
+        }
+        // High contrast's yellow already stands apart for either
+        let safe_accent = safe_accent.filter(|_| theme.preset != ThemePreset::HighContrast);
+        if let Some(accent) = accent.or(safe_accent) {
+            theme.accent = accent;
+            theme.on_accent = ink(accent);
+            theme.accent_dim = mix(accent, theme.panel, 0.4);
+            // A little darker on a light sheet, so it still stands out
+            theme.sheet_accent = if theme.sheet_light { mix(accent, Color32::BLACK, 0.2) } else { accent };
+            theme.on_sheet_accent = ink(theme.sheet_accent);
+        }
+        if access.bold_controls && theme.stroke.width < 2.0 {
+            theme.stroke = Stroke::new(2.0, theme.text);
+            theme.sheet_stroke = Stroke::new(2.0, theme.sheet_text);
+        }
+        theme
+    }
+
+    pub fn panel(&self, alpha: u8) -> Color32 {
+        self.translucent(self.panel, alpha)
+    }
+
+    pub fn overlay(&self, alpha: u8) -> Color32 {
+        self.translucent(self.overlay, alpha)
+    }
+
+    pub fn control(&self, alpha: u8) -> Color32 {
+        self.translucent(self.control, alpha)
+    }
+
+    pub fn sheet(&self, alpha: u8) -> Color32 {
+        self.translucent(self.sheet, alpha)
+    }
+
+    /// A shade over the sheet for pills and chips: dark on a light sheet, light on a dark one.
+    pub fn sheet_tint(&self, alpha: u8) -> Color32 {
+        if self.sheet_light { Color32::from_black_alpha(alpha) } else { Color32::from_white_alpha(alpha) }
+    }
+
+    fn translucent(&self, color: Color32, alpha: u8) -> Color32 {
+        let alpha = if self.opaque { 255 } else { alpha };
+        Color32::from_rgba_unmultiplied(color.r(), color.g(), color.b(), alpha)
+    }
+
+    /// egui's own widgets (sliders, checkboxes, selectables) to match.
+    fn visuals(&self) -> Visuals {
+        let mut visuals = match self.preset {
+            ThemePreset::Light => Visuals::light(),
+            _ => Visuals::dark(),
+        };
+        visuals.window_rounding = Rounding::same(18.0);
+        visuals.selection.bg_fill = self.accent_dim;
+        visuals.selection.stroke.color = self.text;
+        visuals.hyperlink_color = self.accent;
+        match self.preset {
+            ThemePreset::OledBlack => {
+                visuals.panel_fill = Color32::BLACK;
+                visuals.window_fill = Color32::BLACK;
+                visuals.extreme_bg_color = Color32::BLACK;
+            }
+            ThemePreset::HighContrast => {
+                visuals.panel_fill = Color32::BLACK;
+                visuals.window_fill = Color32::BLACK;
+                visuals.extreme_bg_color = Color32::BLACK;
+                visuals.override_text_color = Some(Color32::WHITE);
+                visuals.widgets.inactive.bg_stroke = Stroke::new(1.0, Color32::from_gray(200));
+                visuals.widgets.hovered.bg_stroke = Stroke::new(2.0, Color32::WHITE);
+            }
+            ThemePreset::Dark | ThemePreset::Light => {}
+        }
+        if self.access.bold_controls {
+            let soft = Stroke::new(1.5, self.text_soft);
+            visuals.widgets.inactive.bg_stroke = soft;
+            visuals.widgets.noninteractive.bg_stroke = soft;
+            visuals.widgets.hovered.bg_stroke = Stroke::new(2.5, self.text);
+            visuals.widgets.active.bg_stroke = Stroke::new(2.5, self.accent);
+            visuals.selection.stroke = Stroke::new(2.0, self.text);
+        }
+        visuals.error_fg_color = self.danger;
+        visuals
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::new(ThemePreset::Dark, None, Accessibility::default())
+    }
+}
+
+/// Black or white, whichever reads better on `background`.
+fn ink(background: Color32) -> Color32 {
+    let luma = 0.299 * background.r() as f32 + 0.587 * background.g() as f32 + 0.114 * background.b() as f32;
+    if luma > 160.0 { Color32::BLACK } else { Color32::WHITE }
+}
+
+/// `a` moved `t` of the way towards `b`.
+fn mix(a: Color32, b: Color32, t: f32) -> Color32 {
+    let lerp = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+    Color32::from_rgb(lerp(a.r(), b.r()), lerp(a.g(), b.g()), lerp(a.b(), b.b()))
+}
+
+static CURRENT: RwLock<Option<Theme>> = RwLock::new(None);
+
+/// Use `preset` (and `accent`) from now on, updating egui's visuals if it changed.
+pub fn select(ctx: &Context, preset: ThemePreset, accent: Option<Color32>, access: Accessibility) {
+    let theme = Theme::new(preset, accent, access);
+    if *CURRENT.read().unwrap() == Some(theme) {
+        return;
+    }
+    apply_to(ctx, &theme);
+    *CURRENT.write().unwrap() = Some(theme);
+}
+
+/// Give a new egui context the current theme's visuals.
+pub fn apply(ctx: &Context) {
+    apply_to(ctx, &current());
+}
+
+fn apply_to(ctx: &Context, theme: &Theme) {
+    ctx.set_visuals(theme.visuals());
+    // egui's own fades (collapsing headers, scroll-to, tooltips)
+    ctx.style_mut(|style| style.animation_time = if theme.access.reduce_motion { 0.0 } else { 1.0 / 12.0 });
+}
+
+/// The theme in use.
+pub fn current() -> Theme {
+    CURRENT.read().unwrap().unwrap_or_default()
+}
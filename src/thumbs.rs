@@ -1,9 +1,15 @@
 //! Hardware-accelerated video thumbnail pipeline.
 //!
 //! Rust asks Java to extract a poster frame (`request`) — Java uses
-//! MediaMetadataRetriever (hardware-backed) on a thread pool. The finished RGBA
-//! frame comes back through the `onThumbnail` JNI callback, is queued here, and
-//! the UI drains it (`drain`) to upload as a GPU texture.
+//! MediaMetadataRetriever (hardware-backed) on a thread pool, grabbing a frame
+//! about 10% into the video. The finished RGBA frame comes back through the
+//! `onThumbnail` JNI callback, is queued here, and the UI drains it (`drain`) to
+//! upload as a GPU texture.
+//!
+//! Posters are cached as JPEGs under `<internal storage>/thumbs/`, named by a
+//! hash of path, size, mtime and target size (`cache_file`), so revisiting a
+//! folder loads them from disk instead of decoding again. Java keeps the cache
+//! under a size cap, dropping the least recently shown posters first.
 //!
 //! Each thumbnail also gets an average colour (for an ambient glow) computed with
 //! a NEON SIMD reduction, runtime-detected, with a scalar fallback.
@@ -12,7 +18,9 @@ use android_activity::AndroidApp;
 use jni::objects::{JObject, JValue};
 use jni::sys::jobject;
 use log::error;
+use std::path::PathBuf;
 use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
 
 /// A finished thumbnail ready for the UI to upload as a texture.
 pub struct ThumbResult {
@@ -26,8 +34,19 @@ pub struct ThumbResult {
 /// Completed thumbnails waiting to be drained by the UI.
 static DONE: Mutex<Vec<ThumbResult>> = Mutex::new(Vec::new());
 
-/// Ask Java to generate a thumbnail for `path` at target `w`x`h`. Non-blocking;
-/// the result arrives later via the `onThumbnail` JNI callback.
+/// Where the poster for `path` at `w`x`h` is cached. Keyed (FNV-1a, stable across
+/// builds) on size and mtime too, so an edited file gets a fresh poster.
+fn cache_file(app: &AndroidApp, path: &str, w: i32, h: i32) -> Option<PathBuf> {
+    let meta = std::fs::metadata(path).ok()?;
+    let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    let stamp = [meta.len(), mtime, w as u64, h as u64];
+    let key = crate::resume::fnv1a(path.bytes().chain(stamp.iter().flat_map(|v| v.to_le_bytes())));
+    Some(app.internal_data_path()?.join("thumbs").join(format!("{:016x}.jpg", key)))
+}
+
+/// Ask Java to generate a thumbnail for `path` at target `w`x`h` (or load it from
+/// the cache). Non-blocking; the result arrives later via the `onThumbnail` JNI
+/// callback.
 pub fn request(app: &AndroidApp, path: &str, w: i32, h: i32) {
     let vm = unsafe { jni::JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM).unwrap() };
     let mut env = match vm.attach_current_thread() {
@@ -39,11 +58,19 @@ pub fn request(app: &AndroidApp, path: &str, w: i32, h: i32) {
         Ok(s) => s,
         Err(e) => { error!("thumbs: new_string failed: {:?}", e); return; }
     };
+    // No cache file (e.g. unreadable metadata): Java just skips the cache
+    let j_cache = match cache_file(app, path, w, h) {
+        Some(f) => match env.new_string(f.to_string_lossy()) {
+            Ok(s) => JObject::from(s),
+            Err(e) => { error!("thumbs: new_string failed: {:?}", e); return; }
+        },
+        None => JObject::null(),
+    };
     if let Err(e) = env.call_method(
         &activity,
         "requestThumbnail",
-        "(Ljava/lang/String;II)V",
-        &[JValue::Object(&j_path.into()), JValue::Int(w), JValue::Int(h)],
+        "(Ljava/lang/String;IILjava/lang/String;)V",
+        &[JValue::Object(&j_path.into()), JValue::Int(w), JValue::Int(h), JValue::Object(&j_cache)],
     ) {
         error!("thumbs: requestThumbnail call failed: {:?}", e);
     }
@@ -1,6 +1,14 @@
-use egui::{Context, Visuals, Style, Rounding, Color32, Margin, Stroke, FontId, FontFamily};
+use egui::{Context, Style, Rounding, Color32, Margin, Stroke, FontId, FontFamily};
+use std::collections::VecDeque;
+use std::sync::Mutex;
 use std::time::Instant;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::actions::{self, Action, Context as InputContext};
+use crate::i18n::{tr, trf};
+use crate::playlist::{media_kind, LoopMode, MediaKind};
+use crate::storage::{self, Volume};
+use crate::video_ndk::DecodeError;
 
 // ── VR tunable parameters ─────────────────────────────────────────────────────
 
@@ -8,22 +16,68 @@ use std::path::PathBuf;
 pub struct VrParams {
     pub lens_radius:        f32,
     pub lens_center_offset: f32,
+    pub ipd:                f32,   // mm between the eye cameras
+    pub fov:                f32,   // ° each eye's view spans vertically in VR
+    pub ui_anchor:          UiAnchor,  // whether the UI panel stays in the world or follows the head
+    pub arrange_windows:    bool,  // floating windows stand side by side in a curve (window_manager auto layout)
     pub content_scale:      f32,
     pub target_scale:       f32,   // lerp target for smooth zoom
+    // Pan of zoomed content, in screen widths / heights from the centre (+ = right / down)
+    pub pan_x:              f32,
+    pub pan_y:              f32,
+    pub aspect_mode:        AspectMode,
+    pub crop_aspect:        f32,   // target ratio for AspectMode::Crop (e.g. 2.39 scope)
+    pub horizon_roll:       f32,   // ° the view is rolled to level a phone sitting tilted in the headset
+    pub screen_brightness:  f32,   // window brightness 0…1; 0 = follow the system setting
+    pub max_brightness_vr:  bool,  // full brightness while in VR (the lenses eat a lot of light)
+    // Picture (video only, this session): brightness offset, contrast, saturation, gamma
+    pub brightness:         f32,
+    pub contrast:           f32,
+    pub saturation:         f32,
+    pub gamma:              f32,
+    // Head tracking (advanced)
+    pub fusion_mode:        crate::sensors::FusionMode,
+    pub fusion_gain:        f32,
+    pub mag_yaw_correction: bool,
+    pub sensor_rate:        crate::sensors::SensorRate,
+    pub head_smoothing:     f32,   // 0 = off … 1 = strongest
+    pub positional_tracking: bool, // 6DOF: ARCore head position on top of the IMU rotation
+    pub neck_model:         f32,   // neck length × average adult; 0 = off (unused with 6DOF)
+    pub proximity_pause:    bool,  // pause when the proximity sensor says the phone left the headset
+    pub proximity_blank:    bool,  // …and show black meanwhile
+    pub stick_look_speed:   f32,   // right-stick view turn at full tilt, °/s; 0 = off
+    pub left_stick:         crate::gamepad::StickTuning,  // deadzone, curve, sensitivity
+    pub right_stick:        crate::gamepad::StickTuning,
+    pub stick_pointer:      bool,  // left stick + X drive a mouse pointer while a panel is open
+    pub nav_repeat_delay:   f32,   // s a D-pad direction is held before it repeats
+    pub nav_repeat_rate:    f32,   // repeats per second after that; 0 = off
+    pub haptics:            bool,  // rumble the pad to confirm menu / select / seek / recenter
+    pub motion_aim:         bool,  // hold L2 over a panel to aim the pointer with the pad's gyro
+    pub remote_controller:  bool,  // a second controller only plays / pauses, seeks and skips
+    pub button_hints:       bool,  // bar of the controller buttons that do something right now
+    pub status_hud:         bool,  // clock, phone battery and Wi-Fi in the top right corner
+    pub gaze_dwell:         f32,   // s of holding the gaze on a control to click it (no controller); 0 = off
     pub gyro_enabled:       bool,
-    pub recenter_flag:      bool,
     pub select_video_flag:  bool,
-    pub vr_exit_requested:  bool,
     // Playback
-    pub toggle_play_pause:  bool,
-    pub seek_forward_flag:  bool,
-    pub seek_backward_flag: bool,
+    pub auto_advance:       bool,       // play the next video in the folder at EOS
+    pub loop_mode:          LoopMode,
     // Web mode
     pub web_mode:           bool,
     pub browser_engine:     i32,        // 0 = Chromium (unused), 1 = Firefox/Gecko
     pub pending_engine:     Option<i32>,
     // Stereoscopic video layout: 0 = mono, 1 = SBS, 2 = over-under.
     pub stereo_mode:        u8,
+    // Language of the UI (System follows the phone)
+    pub language:           crate::i18n::Language,
+    pub tutorial_seen:      bool,  // the first-run walkthrough was finished or skipped
+    // Colours: a preset, and an accent in place of the preset's own
+    pub theme:              crate::theme::ThemePreset,
+    pub accent:             Option<Color32>,
+    // Accessibility, applied through the theme
+    pub reduce_motion:      bool,
+    pub bold_controls:      bool,
+    pub color_vision:       crate::theme::ColorVision,
 }
 
 impl Default for VrParams {
@@ -31,30 +85,196 @@ impl Default for VrParams {
         Self {
             lens_radius:        1.0,
             lens_center_offset: 0.0,
+            ipd:                63.0,
+            fov:                90.0,
+            ui_anchor:          UiAnchor::Auto,
+            arrange_windows:    true,
             content_scale:      1.0,
             target_scale:       1.0,
+            pan_x:              0.0,
+            pan_y:              0.0,
+            aspect_mode:        AspectMode::Fit,
+            crop_aspect:        2.39,
+            horizon_roll:       0.0,
+            screen_brightness:  0.0,
+            max_brightness_vr:  false,
+            brightness:         0.0,
+            contrast:           1.0,
+            saturation:         1.0,
+            gamma:              1.0,
+            fusion_mode:        crate::sensors::FusionMode::RotationVector,
+            fusion_gain:        crate::sensors::DEFAULT_GAIN,
+            mag_yaw_correction: false,
+            sensor_rate:        crate::sensors::SensorRate::Hz90,
+            head_smoothing:     0.0,
+            positional_tracking: false,
+            neck_model:         1.0,
+            proximity_pause:    false,
+            proximity_blank:    false,
+            stick_look_speed:   90.0,
+            left_stick:         crate::gamepad::StickTuning::LEFT,
+            right_stick:        crate::gamepad::StickTuning::RIGHT,
+            stick_pointer:      true,
+            nav_repeat_delay:   0.4,
+            nav_repeat_rate:    10.0,
+            haptics:            true,
+            motion_aim:         true,
+            remote_controller:  true,
+            button_hints:       true,
+            status_hud:         false,
+            gaze_dwell:         1.2,
             gyro_enabled:       true,
-            recenter_flag:      false,
             select_video_flag:  false,
-            vr_exit_requested:  false,
-            toggle_play_pause:  false,
-            seek_forward_flag:  false,
-            seek_backward_flag: false,
+            auto_advance:       false,
+            loop_mode:          LoopMode::Off,
             web_mode:           false,
             browser_engine:     1,
             pending_engine:     None,
             stereo_mode:        0,
+            language:           crate::i18n::Language::System,
+            tutorial_seen:      false,
+            theme:              crate::theme::ThemePreset::Dark,
+            accent:             None,
+            reduce_motion:      false,
+            bold_controls:      false,
+            color_vision:       crate::theme::ColorVision::Typical,
+        }
+    }
+}
+
+impl VrParams {
+    /// Pan by a delta, keeping the screen's edge from passing the centre of view
+    /// (no panning at all without zoom).
+    pub fn pan_by(&mut self, dx: f32, dy: f32) {
+        let limit = ((self.content_scale - 1.0) / (2.0 * self.content_scale)).max(0.0);
+        self.pan_x = (self.pan_x + dx).clamp(-limit, limit);
+        self.pan_y = (self.pan_y + dy).clamp(-limit, limit);
+    }
+
+    pub fn reset_pan(&mut self) {
+        self.pan_x = 0.0;
+        self.pan_y = 0.0;
+    }
+
+    pub fn reset_picture(&mut self) {
+        self.brightness = 0.0;
+        self.contrast = 1.0;
+        self.saturation = 1.0;
+        self.gamma = 1.0;
+    }
+
+    /// Settings → Headset's values, named.
+    pub fn lens_preset(&self, name: String) -> LensPreset {
+        LensPreset {
+            name,
+            lens_radius: self.lens_radius,
+            lens_center_offset: self.lens_center_offset,
+            ipd: self.ipd,
+            fov: self.fov,
+        }
+    }
+
+    /// Settings → Advanced → Accessibility, for the theme.
+    pub fn accessibility(&self) -> crate::theme::Accessibility {
+        crate::theme::Accessibility {
+            reduce_motion: self.reduce_motion,
+            bold_controls: self.bold_controls,
+            color_vision: self.color_vision,
+        }
+    }
+
+    pub fn apply_lens_preset(&mut self, preset: &LensPreset) {
+        self.lens_radius = preset.lens_radius;
+        self.lens_center_offset = preset.lens_center_offset;
+        self.ipd = preset.ipd;
+        self.fov = preset.fov;
+    }
+
+    pub fn aspect_label(&self) -> String {
+        match self.aspect_mode {
+            AspectMode::Fit     => tr("Aspect · Fit").into(),
+            AspectMode::Fill    => tr("Aspect · Fill 16:9").into(),
+            AspectMode::Stretch => tr("Aspect · Stretch 16:9").into(),
+            AspectMode::Crop    => trf("Aspect · Crop {}:1", &[&format!("{:.2}", self.crop_aspect)]),
         }
     }
 }
 
 pub const STEREO_MODES: u8 = 3;
 
+/// Horizon trim range either way, degrees.
+pub const MAX_HORIZON_ROLL: f32 = 20.0;
+
 pub fn stereo_label(mode: u8) -> &'static str {
-    match mode { 1 => "3D · Side-by-Side", 2 => "3D · Over-Under", _ => "2D · Mono" }
+    tr(match mode { 1 => "3D · Side-by-Side", 2 => "3D · Over-Under", _ => "2D · Mono" })
+}
+
+/// Where the UI panel sits in VR.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum UiAnchor {
+    /// Menus stay where they opened; toasts and the OSD follow the head.
+    Auto,
+    /// Everything stays straight ahead of where the view was recentered.
+    World,
+    /// Everything follows the head.
+    Head,
+}
+
+impl UiAnchor {
+    pub const ALL: [UiAnchor; 3] = [UiAnchor::Auto, UiAnchor::World, UiAnchor::Head];
+
+    pub fn label(self) -> &'static str {
+        tr(match self {
+            UiAnchor::Auto => "Menus stay, notices follow",
+            UiAnchor::World => "Stay in place",
+            UiAnchor::Head => "Follow the head",
+        })
+    }
+}
+
+/// How the video fills its screen. Numbered as the renderer expects.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AspectMode {
+    /// The screen takes the video's own shape.
+    Fit,
+    /// 16:9 screen, video scaled to cover it (overflow cropped).
+    Fill,
+    /// 16:9 screen, video stretched to it.
+    Stretch,
+    /// Screen of `crop_aspect`, video cropped to it (drops the bars of letterboxed films).
+    Crop,
+}
+
+impl AspectMode {
+    pub fn next(self) -> Self {
+        match self {
+            AspectMode::Fit => AspectMode::Fill, AspectMode::Fill => AspectMode::Stretch,
+            AspectMode::Stretch => AspectMode::Crop, AspectMode::Crop => AspectMode::Fit,
+        }
+    }
 }
 
-pub enum MenuState { Main, LensSettings, WebBrowser }
+pub enum MenuState { Main, Settings, WebBrowser }
+
+/// Pages of the settings window.
+#[derive(Clone, Copy, PartialEq)]
+pub enum SettingsSection { Display, Tracking, Playback, Input, Advanced }
+
+impl SettingsSection {
+    pub const ALL: [SettingsSection; 5] = [
+        SettingsSection::Display, SettingsSection::Tracking, SettingsSection::Playback,
+        SettingsSection::Input, SettingsSection::Advanced,
+    ];
+    pub fn label(self) -> &'static str {
+        tr(match self {
+            SettingsSection::Display  => "🖵 Display",
+            SettingsSection::Tracking => "🧭 Tracking",
+            SettingsSection::Playback => "▶ Playback",
+            SettingsSection::Input    => "🎮 Input",
+            SettingsSection::Advanced => "⚙ Advanced",
+        })
+    }
+}
 
 // ── macOS-style center dock ───────────────────────────────────────────────────
 
@@ -63,6 +283,7 @@ pub enum DockItem {
     Recenter,
     Gyro,
     Files,
+    PlayUrl,
     Web,
     Firefox,
     NewTab,
@@ -71,14 +292,21 @@ pub enum DockItem {
     SeekBack,
     PlayPause,
     SeekFwd,
+    AutoNext,
+    Loop,
+    Aspect,
+    Snapshot,
+    Queue,
+    Lock,
     Settings,
     Exit,
 }
 
-pub const DOCK_ITEMS: [DockItem; 13] = [
+pub const DOCK_ITEMS: [DockItem; 20] = [
     DockItem::Recenter,
     DockItem::Gyro,
     DockItem::Files,
+    DockItem::PlayUrl,
     DockItem::Web,
     DockItem::Firefox,
     DockItem::NewTab,
@@ -87,6 +315,12 @@ pub const DOCK_ITEMS: [DockItem; 13] = [
     DockItem::SeekBack,
     DockItem::PlayPause,
     DockItem::SeekFwd,
+    DockItem::AutoNext,
+    DockItem::Loop,
+    DockItem::Aspect,
+    DockItem::Snapshot,
+    DockItem::Queue,
+    DockItem::Lock,
     DockItem::Settings,
     DockItem::Exit,
 ];
@@ -97,6 +331,7 @@ impl DockItem {
             DockItem::Recenter  => "◎",
             DockItem::Gyro      => "🧭",
             DockItem::Files     => "📁",
+            DockItem::PlayUrl   => "🔗",
             DockItem::Web       => "🌐",
             DockItem::Firefox   => "🦊",
             DockItem::NewTab    => "➕",
@@ -105,15 +340,22 @@ impl DockItem {
             DockItem::SeekBack  => "⏪",
             DockItem::PlayPause => "⏯",
             DockItem::SeekFwd   => "⏩",
+            DockItem::AutoNext  => "⤼",
+            DockItem::Loop      => "🔁",
+            DockItem::Aspect    => "⛶",
+            DockItem::Snapshot  => "📷",
+            DockItem::Queue     => "☰",
+            DockItem::Lock      => "🔒",
             DockItem::Settings  => "⚙",
             DockItem::Exit      => "✕",
         }
     }
     fn label(&self) -> &'static str {
-        match self {
+        tr(match self {
             DockItem::Recenter  => "Recenter",
             DockItem::Gyro      => "Gyro",
             DockItem::Files     => "Files",
+            DockItem::PlayUrl   => "Play URL",
             DockItem::Web       => "Web",
             DockItem::Firefox   => "Firefox",
             DockItem::NewTab    => "New Tab",
@@ -122,20 +364,112 @@ impl DockItem {
             DockItem::SeekBack  => "-10s",
             DockItem::PlayPause => "Play/Pause",
             DockItem::SeekFwd   => "+10s",
+            DockItem::AutoNext  => "Auto-Play Next",
+            DockItem::Loop      => "Loop",
+            DockItem::Aspect    => "Aspect",
+            DockItem::Snapshot  => "Save Frame",
+            DockItem::Queue     => "Queue",
+            DockItem::Lock      => "Lock",
             DockItem::Settings  => "Settings",
             DockItem::Exit      => "Exit VR",
+        })
+    }
+}
+
+// ── Controller focus map ──────────────────────────────────────────────────────
+
+/// Buttons the D-pad walks through on a panel whose main list has its own
+/// highlight (the Media Center's toolbar, the queue's controls). The panel adds
+/// each button's response as it draws, a row at a time; `finish` then hands the
+/// button the D-pad is on to egui's focus, which draws it focused and clicks it
+/// on Enter (X, via `VrUi::focus_key`).
+#[derive(Default)]
+struct FocusMap {
+    /// Per row, top to bottom: (widget, centre x), left to right once finished
+    rows: Vec<Vec<(egui::Id, f32)>>,
+    /// (row, column) the D-pad is on; None while it's on the panel's list
+    at: Option<(usize, usize)>,
+}
+
+impl FocusMap {
+    fn active(&self) -> bool { self.at.is_some() }
+
+    /// Start collecting this frame's buttons.
+    fn begin(&mut self) { self.rows.clear(); }
+
+    fn add(&mut self, row: usize, response: &egui::Response) {
+        if self.rows.len() <= row { self.rows.resize_with(row + 1, Vec::new); }
+        self.rows[row].push((response.id, response.rect.center().x));
+    }
+
+    /// Sort the rows, keep the D-pad on a button that still exists and give it
+    /// egui's focus; take the focus back from these buttons once it leaves them.
+    fn finish(&mut self, ctx: &Context) {
+        for row in &mut self.rows { row.sort_by(|a, b| a.1.total_cmp(&b.1)); }
+        self.rows.retain(|row| !row.is_empty());
+        self.at = self.at.filter(|_| !self.rows.is_empty()).map(|(r, c)| {
+            let r = r.min(self.rows.len() - 1);
+            (r, c.min(self.rows[r].len() - 1))
+        });
+        match self.at {
+            Some((r, c)) => ctx.memory_mut(|m| m.request_focus(self.rows[r][c].0)),
+            None => if let Some(id) = ctx.memory(|m| m.focused()).filter(|id| self.contains(*id)) {
+                ctx.memory_mut(|m| m.surrender_focus(id));
+            },
         }
     }
+
+    fn contains(&self, id: egui::Id) -> bool {
+        self.rows.iter().flatten().any(|(i, _)| *i == id)
+    }
+
+    /// Onto the bottom row (`from_below`) or the top one, at its first button.
+    fn enter(&mut self, from_below: bool) {
+        if self.rows.is_empty() { return; }
+        self.at = Some((if from_below { self.rows.len() - 1 } else { 0 }, 0));
+    }
+
+    /// One D-pad step. Down past the last row leaves the map, back to the
+    /// panel's list; up from the top row stays put.
+    fn step(&mut self, dir: actions::Direction) {
+        use actions::Direction;
+        let Some((r, c)) = self.at else { return };
+        let to_row = |row: usize| {
+            // The button in `row` nearest below / above the current one
+            let x = self.rows[r].get(c).map_or(0.0, |b| b.1);
+            let col = self.rows[row].iter().enumerate()
+                .min_by(|a, b| (a.1 .1 - x).abs().total_cmp(&(b.1 .1 - x).abs()))
+                .map_or(0, |(i, _)| i);
+            Some((row, col))
+        };
+        self.at = match dir {
+            Direction::Left => Some((r, c.saturating_sub(1))),
+            Direction::Right => Some((r, (c + 1).min(self.rows[r].len().saturating_sub(1)))),
+            Direction::Up if r > 0 => to_row(r - 1),
+            Direction::Up => Some((r, c)),
+            Direction::Down if r + 1 < self.rows.len() => to_row(r + 1),
+            Direction::Down => None,
+        };
+    }
+
+    fn leave(&mut self) -> bool { self.at.take().is_some() }
 }
 
 // ── File browser / Media Center ───────────────────────────────────────────────
 
-#[derive(Clone, Copy, PartialEq)]
-pub enum MediaKind { Dir, Video, Audio }
-
 /// Top-level media category (visionOS-style tabs).
 #[derive(Clone, Copy, PartialEq)]
-pub enum Category { Movies, Music, Files }
+pub enum Category { Favorites, Recent, Movies, Music, Files }
+
+impl Category {
+    /// The tabs in order, as the pills show them.
+    pub const ALL: [Category; 5] = [Category::Favorites, Category::Recent, Category::Movies, Category::Music, Category::Files];
+
+    /// Favorites and Recent list their own entries rather than the current folder.
+    fn own_list(self) -> bool {
+        matches!(self, Category::Favorites | Category::Recent)
+    }
+}
 
 #[derive(Clone)]
 pub struct FileEntry {
@@ -147,43 +481,159 @@ pub struct FileEntry {
     pub thumbnail: Option<egui::TextureHandle>,
     pub glow:      Option<[u8; 3]>, // ambient colour from the poster frame
     pub thumb_requested: bool,
+    /// Watch progress from the resume store: None = unwatched, 1.0 = finished.
+    pub progress:  Option<f32>,
+    /// Duration / resolution / codecs from the metadata store, once probed.
+    pub info:      Option<crate::metadata::MediaInfo>,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum SortBy { Name, Size, Date }
 
+/// How the Media Center lays out the folder.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BrowserView { Carousel, Grid }
+
+/// Thumbnails per row in the grid view.
+const GRID_COLUMNS: usize = 5;
+
+/// What the entry menu (hold □) can do with the highlighted folder or file.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EntryAction { Favorite, Rename, Delete, Cancel }
+
+/// The hold-□ menu for one Media Center entry.
+pub struct EntryMenu {
+    pub path:     PathBuf,
+    pub name:     String,
+    pub is_dir:   bool,
+    /// A storage volume's root, which can only be bookmarked.
+    fixed:        bool,
+    pub selected: usize,
+    /// Delete was picked; the menu now asks to confirm it.
+    pub confirm_delete: bool,
+}
+
+impl EntryMenu {
+    /// The menu's rows, top to bottom. Only files can be deleted.
+    pub fn actions(&self) -> Vec<EntryAction> {
+        if self.confirm_delete {
+            return vec![EntryAction::Delete, EntryAction::Cancel];
+        }
+        let mut actions = vec![EntryAction::Favorite];
+        if !self.fixed {
+            actions.push(EntryAction::Rename);
+            if !self.is_dir { actions.push(EntryAction::Delete); }
+        }
+        actions.push(EntryAction::Cancel);
+        actions
+    }
+    pub fn move_up(&mut self) { self.selected = self.selected.saturating_sub(1); }
+    pub fn move_down(&mut self) { self.selected = (self.selected + 1).min(self.actions().len() - 1); }
+}
+
+/// What the triggers cycle through when typing a filter.
+const SEARCH_LETTERS: &str = "abcdefghijklmnopqrstuvwxyz0123456789";
+/// A trigger-typed letter is kept once the triggers rest this long.
+const LETTER_SETTLE: std::time::Duration = std::time::Duration::from_millis(1200);
+/// Subfolder search: how deep it goes and how many files it lists at most.
+const SEARCH_DEPTH: usize = 6;
+const SEARCH_LIMIT: usize = 2000;
+
 pub struct FileBrowser {
     pub visible:        bool,
     pub current_path:   PathBuf,
     pub entries:        Vec<FileEntry>,
     pub selected_index: usize,
     pub selected_file:  Option<PathBuf>,
+    pub enqueued_file:  Option<PathBuf>,
+    /// File to play on a second screen beside the current video.
+    pub beside_file:    Option<PathBuf>,
+    /// Multi-select mode: □ marks files instead of showing details.
+    pub multi_select:   bool,
+    /// Files marked in multi-select mode, in the order they were marked (kept
+    /// across folders).
+    pub marked:         Vec<PathBuf>,
+    /// Marked files to hand to the play queue, and whether to play them now.
+    batch:              Option<(Vec<PathBuf>, bool)>,
     pub error_msg:      Option<String>,
+    /// Only entries whose name contains this (any case) are shown.
     pub search_query:   String,
+    /// List every media file below the folder instead of its own entries, named
+    /// by their path from it, so the filter searches the whole library.
+    pub search_subfolders: bool,
+    /// The trigger-typed letter at the end of the filter (index into
+    /// `SEARCH_LETTERS`) and when it last changed.
+    letter:             Option<(usize, Instant)>,
+    /// L2 / R2 pulled, to step once per pull.
+    triggers_held:      [bool; 2],
     pub sort_by:        SortBy,
+    pub view:           BrowserView,
+    /// The details pane for the highlighted entry is showing.
+    pub show_details:   bool,
+    /// When the highlighted entry was last modified (unix s), looked up once
+    /// per entry for the details pane.
+    modified:           Option<(PathBuf, Option<u64>)>,
+    /// The D-pad moved the highlight; the grid scrolls it into view.
+    grid_scroll:        bool,
+    /// The breadcrumb segment the D-pad is on (None: it's on the entries).
+    crumb_focus:        Option<usize>,
+    /// The buttons above the entries (title bar, tabs, filter), for the D-pad.
+    toolbar:            FocusMap,
     pub category:       Category,
+    /// Mounted storage (lib.rs keeps it current), internal storage first.
+    pub volumes:        Vec<Volume>,
+    /// Bookmarked folders and files, oldest first (kept in settings.toml).
+    pub favorites:      Vec<PathBuf>,
+    /// The hold-□ menu, while it's open.
+    pub entry_menu:     Option<EntryMenu>,
+    /// Snapshot of the recently played list (owned by lib.rs) for the Recent tab.
+    pub recent:         Vec<crate::recent::RecentEntry>,
+    /// Set when entries were rebuilt; lib.rs fills in `progress` and clears it.
+    pub progress_stale: bool,
     // Coverflow carousel animation + left-stick repeat.
     pub carousel_pos:   f32,
     pub nav_cooldown:   u8,
     pub nav_hold:       u16,
+    /// The left stick changed tab and hasn't come back to the centre yet.
+    tab_flicked:        bool,
 }
 
 impl FileBrowser {
     pub fn new() -> Self {
-        let start = PathBuf::from("/storage/emulated/0");
+        let start = PathBuf::from(storage::INTERNAL);
         let mut b = Self {
             visible:        false,
             current_path:   start,
             entries:        Vec::new(),
             selected_index: 0,
             selected_file:  None,
+            enqueued_file:  None,
+            beside_file:    None,
+            multi_select:   false,
+            marked:         Vec::new(),
+            batch:          None,
             error_msg:      None,
             search_query:   String::new(),
+            search_subfolders: false,
+            letter:         None,
+            triggers_held:  [false; 2],
             sort_by:        SortBy::Name,
+            view:           BrowserView::Carousel,
+            show_details:   false,
+            modified:       None,
+            grid_scroll:    false,
+            crumb_focus:    None,
+            toolbar:        FocusMap::default(),
             category:       Category::Movies,
+            volumes:        vec![storage::internal()],
+            favorites:      Vec::new(),
+            entry_menu:     None,
+            recent:         Vec::new(),
+            progress_stale: false,
             carousel_pos:   0.0,
             nav_cooldown:   0,
             nav_hold:       0,
+            tab_flicked:    false,
         };
         b.refresh_entries();
         b
@@ -193,44 +643,83 @@ impl FileBrowser {
         use log::{info, error};
         let prev_path = self.entries.get(self.selected_index).map(|e| e.path.clone());
         self.entries.clear();
+        self.progress_stale = true;
         self.selected_index = 0;
         self.error_msg = None;
-        info!("FileBrowser: scanning {:?}", self.current_path);
 
+        if self.category == Category::Recent {
+            for r in &self.recent {
+                let path = PathBuf::from(&r.path);
+                let Ok(meta) = std::fs::metadata(&path) else { continue };
+                let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                let Some(kind) = media_kind(&name) else { continue };
+                self.entries.push(FileEntry { name, path, is_dir: false, kind,
+                    size_mb: meta.len() as f32 / 1_048_576.0, thumbnail: None, glow: None,
+                    thumb_requested: false, progress: None, info: None });
+            }
+            info!("FileBrowser: {} recent", self.entries.len());
+            self.carousel_pos = 0.0;
+            return;
+        }
+        if self.category == Category::Favorites {
+            // Newest first, like Recent; ones that went missing are skipped
+            for path in self.favorites.iter().rev() {
+                let Ok(meta) = std::fs::metadata(path) else { continue };
+                let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                let (kind, size_mb) = if meta.is_dir() {
+                    (MediaKind::Dir, 0.0)
+                } else {
+                    let Some(kind) = media_kind(&name) else { continue };
+                    (kind, meta.len() as f32 / 1_048_576.0)
+                };
+                self.entries.push(FileEntry { name, path: path.clone(), is_dir: meta.is_dir(), kind,
+                    size_mb, thumbnail: None, glow: None, thumb_requested: false, progress: None, info: None });
+            }
+            info!("FileBrowser: {} favorites", self.entries.len());
+            self.carousel_pos = 0.0;
+            return;
+        }
+
+        if self.current_path == Path::new(storage::VOLUMES_ROOT) {
+            for v in &self.volumes {
+                self.entries.push(FileEntry { name: v.label.clone(), path: v.path.clone(), is_dir: true,
+                    kind: MediaKind::Dir, size_mb: 0.0, thumbnail: None, glow: None,
+                    thumb_requested: false, progress: None, info: None });
+            }
+            info!("FileBrowser: {} volumes", self.entries.len());
+            self.carousel_pos = 0.0;
+            return;
+        }
+
+        info!("FileBrowser: scanning {:?}", self.current_path);
         match std::fs::read_dir(&self.current_path) {
             Ok(rd) => {
-                if self.current_path != PathBuf::from("/storage/emulated/0") {
-                    if let Some(parent) = self.current_path.parent() {
-                        self.entries.push(FileEntry {
-                            name: "..".into(), path: parent.to_path_buf(), is_dir: true,
-                            kind: MediaKind::Dir, size_mb: 0.0, thumbnail: None,
-                            glow: None, thumb_requested: false,
-                        });
-                    }
+                if let Some(parent) = self.parent_folder() {
+                    self.entries.push(FileEntry {
+                        name: "..".into(), path: parent, is_dir: true,
+                        kind: MediaKind::Dir, size_mb: 0.0, thumbnail: None,
+                        glow: None, thumb_requested: false, progress: None, info: None,
+                    });
                 }
                 let mut dirs: Vec<FileEntry> = Vec::new();
                 let mut files: Vec<FileEntry> = Vec::new();
-                for entry in rd.flatten() {
-                    let path = entry.path();
-                    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
-                    if name.starts_with('.') { continue; }
-                    let is_dir = entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
-                    if is_dir {
-                        dirs.push(FileEntry { name, path, is_dir: true, kind: MediaKind::Dir,
-                            size_mb: 0.0, thumbnail: None, glow: None, thumb_requested: false });
-                    } else {
-                        let ext = name.rsplit('.').next().map(|e| e.to_lowercase()).unwrap_or_default();
-                        let kind = if matches!(ext.as_str(),
-                                "mp4"|"mkv"|"avi"|"webm"|"mov"|"m4v"|"3gp"|"ts"|"flv") {
-                            Some(MediaKind::Video)
-                        } else if matches!(ext.as_str(),
-                                "mp3"|"flac"|"wav"|"aac"|"ogg"|"m4a"|"opus"|"wma") {
-                            Some(MediaKind::Audio)
-                        } else { None };
-                        if let Some(kind) = kind {
-                            let size_mb = std::fs::metadata(&path).map(|m| m.len() as f32 / 1_048_576.0).unwrap_or(0.0);
-                            files.push(FileEntry { name, path, is_dir: false, kind,
-                                size_mb, thumbnail: None, glow: None, thumb_requested: false });
+                if self.search_subfolders {
+                    Self::scan_subfolders(&self.current_path, &mut files);
+                } else {
+                    for entry in rd.flatten() {
+                        let path = entry.path();
+                        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                        if name.starts_with('.') { continue; }
+                        let is_dir = entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
+                        if is_dir {
+                            dirs.push(FileEntry { name, path, is_dir: true, kind: MediaKind::Dir,
+                                size_mb: 0.0, thumbnail: None, glow: None, thumb_requested: false, progress: None, info: None });
+                        } else {
+                            if let Some(kind) = media_kind(&name) {
+                                let size_mb = std::fs::metadata(&path).map(|m| m.len() as f32 / 1_048_576.0).unwrap_or(0.0);
+                                files.push(FileEntry { name, path, is_dir: false, kind,
+                                    size_mb, thumbnail: None, glow: None, thumb_requested: false, progress: None, info: None });
+                            }
                         }
                     }
                 }
@@ -245,7 +734,12 @@ impl FileBrowser {
             }
             Err(e) => {
                 error!("FileBrowser: {}", e);
-                self.error_msg = Some("Cannot access folder.\nGrant storage permission in Settings.".into());
+                let removable = self.volume_of(&self.current_path).filter(|v| v.removable);
+                self.error_msg = Some(match (e.kind(), removable) {
+                    (std::io::ErrorKind::NotFound, Some(v)) => trf("{} isn't available.\nIt may have been removed.", &[&v.label]),
+                    (_, Some(v)) => trf("Cannot read {}.\nAllow access to all files in Settings, or remove and reinsert it.", &[&v.label]),
+                    _ => tr("Cannot access folder.\nGrant storage permission in Settings.").into(),
+                });
             }
         }
 
@@ -258,6 +752,168 @@ impl FileBrowser {
         self.carousel_pos = fi.iter().position(|&i| i == self.selected_index).unwrap_or(0) as f32;
     }
 
+    /// Media files below `root` (depth-first, hidden folders skipped) for the
+    /// subfolder search, stopping at `SEARCH_DEPTH` folders deep or `SEARCH_LIMIT` files.
+    fn scan_subfolders(root: &std::path::Path, files: &mut Vec<FileEntry>) {
+        let mut pending = vec![(root.to_path_buf(), 0)];
+        while let Some((dir, depth)) = pending.pop() {
+            let Ok(rd) = std::fs::read_dir(&dir) else { continue };
+            for entry in rd.flatten() {
+                let path = entry.path();
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                if file_name.starts_with('.') { continue; }
+                if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
+                    if depth + 1 < SEARCH_DEPTH { pending.push((path, depth + 1)); }
+                    continue;
+                }
+                let Some(kind) = media_kind(&file_name) else { continue };
+                let name = path.strip_prefix(root).map(|p| p.to_string_lossy().to_string()).unwrap_or(file_name);
+                let size_mb = entry.metadata().map(|m| m.len() as f32 / 1_048_576.0).unwrap_or(0.0);
+                files.push(FileEntry { name, path, is_dir: false, kind,
+                    size_mb, thumbnail: None, glow: None, thumb_requested: false, progress: None, info: None });
+                if files.len() >= SEARCH_LIMIT {
+                    log::warn!("FileBrowser: subfolder search stopped at {} files", SEARCH_LIMIT);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// The volume `path` is on.
+    pub fn volume_of(&self, path: &Path) -> Option<&Volume> {
+        self.volumes.iter().filter(|v| path.starts_with(&v.path)).max_by_key(|v| v.path.as_os_str().len())
+    }
+
+    /// Where ".." and ○ lead: the folder above, or from a volume's root the list
+    /// of volumes (if there's more than the internal storage).
+    fn parent_folder(&self) -> Option<PathBuf> {
+        if self.current_path == Path::new(storage::VOLUMES_ROOT) {
+            return None;
+        }
+        if self.volumes.iter().any(|v| v.path == self.current_path) {
+            return (self.volumes.len() > 1).then(|| PathBuf::from(storage::VOLUMES_ROOT));
+        }
+        self.current_path.parent().map(Path::to_path_buf)
+    }
+
+    /// The breadcrumb as (name, folder): the volume list when there's more than
+    /// one volume, then the volume's root and each folder down to the current one.
+    pub fn breadcrumb(&self) -> Vec<(String, PathBuf)> {
+        let mut crumbs = Vec::new();
+        let at_volumes = self.current_path == Path::new(storage::VOLUMES_ROOT);
+        if self.volumes.len() > 1 || at_volumes {
+            crumbs.push((tr("Storage").to_string(), PathBuf::from(storage::VOLUMES_ROOT)));
+        }
+        if at_volumes {
+            return crumbs;
+        }
+        match self.volume_of(&self.current_path) {
+            Some(v) => {
+                crumbs.push((v.label.clone(), v.path.clone()));
+                let mut path = v.path.clone();
+                for part in self.current_path.strip_prefix(&v.path).unwrap_or(Path::new("")) {
+                    path.push(part);
+                    crumbs.push((part.to_string_lossy().to_string(), path.clone()));
+                }
+            }
+            None => crumbs.push((self.current_path.to_string_lossy().to_string(), self.current_path.clone())),
+        }
+        crumbs
+    }
+
+    /// Which breadcrumb segment the D-pad is on, if it's up there.
+    pub fn crumb_focus(&self) -> Option<usize> {
+        self.crumb_focus.filter(|_| !self.category.own_list())
+    }
+
+    /// Browse `folder`, highlighting the subfolder the current one was in when
+    /// going up.
+    pub fn open_folder(&mut self, folder: PathBuf) {
+        let from = std::mem::replace(&mut self.current_path, folder);
+        self.crumb_focus = None;
+        self.set_search_query("");
+        if self.category == Category::Favorites {
+            self.category = Category::Files;
+        }
+        self.refresh_entries();
+        if let Some(i) = self.entries.iter().position(|e| e.is_dir && e.name != ".." && from.starts_with(&e.path)) {
+            self.selected_index = i;
+            let fi = self.filtered_indices();
+            self.carousel_pos = fi.iter().position(|&f| f == i).unwrap_or(0) as f32;
+        }
+    }
+
+    /// Update the mounted volumes. If the one being browsed went away, goes back
+    /// to internal storage and returns its name.
+    pub fn set_volumes(&mut self, volumes: Vec<Volume>) -> Option<String> {
+        if volumes == self.volumes {
+            return None;
+        }
+        let before = self.volume_of(&self.current_path).cloned();
+        self.volumes = volumes;
+        let browsing = self.current_path == Path::new(storage::VOLUMES_ROOT);
+        let removed = before.filter(|b| !self.volumes.contains(b));
+        if removed.is_some() {
+            self.current_path = PathBuf::from(storage::INTERNAL);
+        }
+        if (browsing || removed.is_some()) && !self.category.own_list() {
+            self.refresh_entries();
+        }
+        removed.map(|v| v.label)
+    }
+
+    /// Narrow the list to names containing `query`, keeping the highlight on a match.
+    pub fn set_search_query(&mut self, query: &str) {
+        if self.search_query == query {
+            return;
+        }
+        self.search_query = query.to_string();
+        self.letter = None;
+        let fi = self.filtered_indices();
+        if !fi.contains(&self.selected_index) {
+            self.selected_index = fi.first().copied().unwrap_or(0);
+        }
+    }
+
+    /// Switch the subfolder search on or off (rescans the folder).
+    pub fn set_search_subfolders(&mut self, on: bool) {
+        if self.search_subfolders != on {
+            self.search_subfolders = on;
+            self.refresh_entries();
+        }
+    }
+
+    /// Type the filter with the triggers: each R2 pull steps the last letter
+    /// forward through a–z, 0–9 (L2 back); once they rest for `LETTER_SETTLE`
+    /// the letter is kept and the next pull adds another.
+    pub fn handle_triggers(&mut self, l2: f32, r2: f32) {
+        const PULLED: f32 = 0.5;
+        let held = [l2 > PULLED, r2 > PULLED];
+        let back = held[0] && !self.triggers_held[0];
+        let forward = held[1] && !self.triggers_held[1];
+        self.triggers_held = held;
+        if back == forward {
+            return;
+        }
+        let n = SEARCH_LETTERS.len();
+        let mut query = self.search_query.clone();
+        let next = match self.letter {
+            Some((i, at)) if at.elapsed() < LETTER_SETTLE => {
+                query.pop();
+                if forward { (i + 1) % n } else { (i + n - 1) % n }
+            }
+            _ => if forward { 0 } else { n - 1 },
+        };
+        query.push(SEARCH_LETTERS.as_bytes()[next] as char);
+        self.set_search_query(&query);
+        self.letter = Some((next, Instant::now()));
+    }
+
+    /// Whether the filter's last letter is still being cycled with the triggers.
+    pub fn typing_letter(&self) -> bool {
+        self.letter.is_some_and(|(_, at)| at.elapsed() < LETTER_SETTLE)
+    }
+
     fn filtered_indices(&self) -> Vec<usize> {
         let q = self.search_query.to_lowercase();
         self.entries.iter().enumerate()
@@ -265,7 +921,7 @@ impl FileBrowser {
                 let cat_ok = e.is_dir || match self.category {
                     Category::Movies => e.kind == MediaKind::Video,
                     Category::Music  => e.kind == MediaKind::Audio,
-                    Category::Files  => true,
+                    Category::Files | Category::Recent | Category::Favorites => true,
                 };
                 cat_ok && (q.is_empty() || e.name.to_lowercase().contains(&q))
             })
@@ -273,8 +929,136 @@ impl FileBrowser {
             .collect()
     }
 
-    /// Left-stick coverflow sweep with acceleration.
-    pub fn handle_stick(&mut self, lx: f32) {
+    /// Switch tab; Favorites and Recent have their own entry lists, so switching
+    /// to or from them rebuilds the entries.
+    pub fn set_category(&mut self, cat: Category) {
+        let rebuild = cat.own_list() || self.category.own_list();
+        self.category = cat;
+        self.selected_index = 0;
+        self.crumb_focus = None;
+        if rebuild { self.refresh_entries(); }
+    }
+
+    /// The next (or previous) tab.
+    pub fn step_category(&mut self, forward: bool) {
+        let n = Category::ALL.len();
+        let i = Category::ALL.iter().position(|&c| c == self.category).unwrap_or(0);
+        self.set_category(Category::ALL[if forward { (i + 1) % n } else { (i + n - 1) % n }]);
+    }
+
+    pub fn is_favorite(&self, path: &std::path::Path) -> bool {
+        self.favorites.iter().any(|f| f == path)
+    }
+
+    /// Bookmark a folder or file, or un-bookmark it. Returns whether it's now a favorite.
+    pub fn toggle_favorite(&mut self, path: &Path) -> bool {
+        let added = !self.is_favorite(path);
+        if added {
+            self.favorites.push(path.to_path_buf());
+        } else {
+            self.favorites.retain(|f| f != path);
+        }
+        if self.category == Category::Favorites {
+            self.refresh_entries();
+        }
+        added
+    }
+
+    /// Open the entry menu for the highlighted folder or file.
+    pub fn open_entry_menu(&mut self) {
+        if self.crumb_focus().is_some() { return; }
+        let Some(entry) = self.entries.get(self.selected_index).filter(|e| e.name != "..") else { return };
+        self.entry_menu = Some(EntryMenu {
+            path: entry.path.clone(),
+            name: entry.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| entry.name.clone()),
+            is_dir: entry.is_dir,
+            fixed: self.volumes.iter().any(|v| v.path == entry.path),
+            selected: 0,
+            confirm_delete: false,
+        });
+    }
+
+    /// Delete a file for good, dropping it from favorites and the marks.
+    pub fn delete_file(&mut self, path: &Path) -> Result<(), String> {
+        std::fs::remove_file(path).map_err(|e| self.write_error(path, e))?;
+        log::info!("FileBrowser: deleted {:?}", path);
+        self.favorites.retain(|f| f != path);
+        self.marked.retain(|m| m != path);
+        self.refresh_entries();
+        Ok(())
+    }
+
+    /// Rename a folder or file within its folder and highlight it under the new
+    /// name. Favorites and marks below a renamed folder follow it.
+    pub fn rename(&mut self, path: &Path, name: &str) -> Result<PathBuf, String> {
+        let name = name.trim();
+        // A leading dot would hide it from the Media Center
+        if name.is_empty() || name.starts_with('.') || name.contains('/') {
+            return Err(tr("That name can't be used").into());
+        }
+        let to = path.with_file_name(name);
+        if to == path {
+            return Ok(to);
+        }
+        if to.exists() {
+            return Err(trf("{} already exists", &[&name]));
+        }
+        std::fs::rename(path, &to).map_err(|e| self.write_error(path, e))?;
+        log::info!("FileBrowser: renamed {:?} to {:?}", path, to);
+        for p in self.favorites.iter_mut().chain(self.marked.iter_mut()) {
+            if let Ok(rest) = p.strip_prefix(path) {
+                *p = to.join(rest);
+            }
+        }
+        self.refresh_entries();
+        if let Some(i) = self.entries.iter().position(|e| e.path == to) {
+            self.selected_index = i;
+            let fi = self.filtered_indices();
+            self.carousel_pos = fi.iter().position(|&f| f == i).unwrap_or(0) as f32;
+        }
+        Ok(to)
+    }
+
+    /// Why a file couldn't be changed, for a toast.
+    fn write_error(&self, path: &Path, e: std::io::Error) -> String {
+        log::error!("FileBrowser: {:?}: {}", path, e);
+        match (e.kind(), self.volume_of(path).filter(|v| v.removable)) {
+            (std::io::ErrorKind::PermissionDenied, Some(v)) => trf("Files on {} can't be changed from here", &[&v.label]),
+            (std::io::ErrorKind::PermissionDenied, None) => tr("Not allowed; allow access to all files in Settings").into(),
+            (std::io::ErrorKind::NotFound, _) => tr("It's no longer there").into(),
+            _ => e.to_string(),
+        }
+    }
+
+    /// Fill in each file's watch progress (lib.rs passes a resume-store lookup).
+    pub fn annotate_progress(&mut self, progress: impl Fn(&str) -> Option<f32>) {
+        for e in self.entries.iter_mut().filter(|e| !e.is_dir) {
+            e.progress = progress(&e.path.to_string_lossy());
+        }
+        self.progress_stale = false;
+    }
+
+    /// Update the recently played snapshot (rebuilds the Recent tab if it changed).
+    pub fn set_recent(&mut self, recent: &[crate::recent::RecentEntry]) {
+        let changed = self.recent.len() != recent.len()
+            || self.recent.iter().zip(recent).any(|(a, b)| a.path != b.path);
+        self.recent.clear();
+        self.recent.extend_from_slice(recent);
+        if changed && self.category == Category::Recent {
+            self.refresh_entries();
+        }
+    }
+
+    /// Left-stick coverflow sweep with acceleration; a flick up or down changes tab.
+    pub fn handle_stick(&mut self, lx: f32, ly: f32) {
+        let flicked = ly.abs() > 0.7 && ly.abs() > lx.abs();
+        if flicked && !self.tab_flicked {
+            self.step_category(ly > 0.0);
+        }
+        self.tab_flicked = ly.abs() > 0.3 && (flicked || self.tab_flicked);
+        if self.tab_flicked {
+            return;
+        }
         if lx.abs() < 0.5 {
             self.nav_hold = 0;
             if self.nav_cooldown > 0 { self.nav_cooldown -= 1; }
@@ -299,6 +1083,23 @@ impl FileBrowser {
         out
     }
 
+    /// The selected file, if it's media whose details aren't filled in yet.
+    pub fn selected_without_details(&self) -> Option<PathBuf> {
+        let e = self.entries.get(self.selected_index)?;
+        (!e.is_dir && e.info.is_none()).then(|| e.path.clone())
+    }
+
+    pub fn set_details(&mut self, path: &std::path::Path, info: crate::metadata::MediaInfo) {
+        if let Some(e) = self.entries.iter_mut().find(|e| e.path == path) {
+            e.info = Some(info);
+        }
+    }
+
+    /// Show or hide the details pane beside the Media Center.
+    pub fn toggle_details(&mut self) {
+        self.show_details = !self.show_details;
+    }
+
     pub fn set_thumbnail(&mut self, path: &std::path::Path, tex: egui::TextureHandle, glow: [u8; 3]) {
         if let Some(e) = self.entries.iter_mut().find(|e| e.path == path) {
             e.thumbnail = Some(tex);
@@ -318,65 +1119,583 @@ impl FileBrowser {
             if pos + 1 < idx.len() { self.selected_index = idx[pos + 1]; }
         }
     }
+    /// D-pad step. The carousel runs left to right; the grid goes a row up or
+    /// down (onto the last entry when the row below is shorter). Up from the
+    /// carousel or the grid's top row moves onto the breadcrumb, starting at the
+    /// parent folder; left / right pick a segment there and down comes back.
+    /// Up again (or straight from the entries where there's no breadcrumb)
+    /// reaches the toolbar buttons, and down from its last row comes back.
+    pub fn nav(&mut self, dir: actions::Direction) {
+        use actions::Direction;
+        if self.toolbar.active() {
+            self.toolbar.step(dir);
+            return;
+        }
+        if let Some(focus) = self.crumb_focus() {
+            let last = self.breadcrumb().len().saturating_sub(1);
+            self.crumb_focus = match dir {
+                Direction::Left => Some(focus.saturating_sub(1)),
+                Direction::Right => Some((focus + 1).min(last)),
+                Direction::Up => {
+                    self.toolbar.enter(true);
+                    if self.toolbar.active() { None } else { Some(focus.min(last)) }
+                }
+                Direction::Down => None,
+            };
+            return;
+        }
+        let idx = self.filtered_indices();
+        let pos = idx.iter().position(|&i| i == self.selected_index);
+        let cols = if self.view == BrowserView::Grid { GRID_COLUMNS } else { 1 };
+        let target = pos.and_then(|pos| match dir {
+            Direction::Left => pos.checked_sub(1),
+            Direction::Right => Some(pos + 1),
+            Direction::Up | Direction::Down if cols == 1 => None,
+            Direction::Up => pos.checked_sub(cols),
+            Direction::Down => Some((pos + cols).min(idx.len() - 1)).filter(|&t| t / cols > pos / cols),
+        });
+        if let Some(&i) = target.and_then(|t| idx.get(t)) {
+            self.selected_index = i;
+            self.grid_scroll = true;
+        } else if dir == Direction::Up && !self.category.own_list() {
+            let crumbs = self.breadcrumb().len();
+            self.crumb_focus = Some(crumbs.saturating_sub(2));
+        } else if dir == Direction::Up {
+            self.toolbar.enter(true);
+        }
+    }
+
+    /// The D-pad is on a toolbar button; X clicks it through egui's focus.
+    pub fn toolbar_focused(&self) -> bool { self.toolbar.active() }
+    pub fn toggle_view(&mut self) {
+        self.view = match self.view {
+            BrowserView::Carousel => BrowserView::Grid,
+            BrowserView::Grid => BrowserView::Carousel,
+        };
+        self.grid_scroll = true;
+    }
+    /// Open the highlighted folder or file, or the breadcrumb segment the D-pad is on.
     pub fn select_current(&mut self) {
+        if let Some(focus) = self.crumb_focus() {
+            if let Some((_, folder)) = self.breadcrumb().into_iter().nth(focus) {
+                self.open_folder(folder);
+            }
+            return;
+        }
         if let Some(entry) = self.entries.get(self.selected_index).cloned() {
             if entry.is_dir {
-                self.current_path = entry.path;
-                self.search_query.clear();
-                self.refresh_entries();
+                self.open_folder(entry.path);
             } else {
                 self.selected_file = Some(entry.path);
                 self.visible = false;
             }
         }
     }
+    /// Leave the toolbar or the breadcrumb, clear the filter, or if there is none go up a folder.
     pub fn go_back(&mut self) {
-        if let Some(parent) = self.current_path.parent() {
-            if self.current_path != PathBuf::from("/storage/emulated/0") {
-                self.current_path = parent.to_path_buf();
-                self.search_query.clear();
-                self.refresh_entries();
-            }
+        if self.toolbar.leave() || self.crumb_focus.take().is_some() {
+            return;
+        }
+        if !self.search_query.is_empty() {
+            self.set_search_query("");
+            return;
+        }
+        if self.category.own_list() { return; }
+        if let Some(parent) = self.parent_folder() {
+            self.open_folder(parent);
         }
     }
     pub fn take_selected_file(&mut self) -> Option<PathBuf> {
         self.selected_file.take()
     }
+    /// Queue the highlighted file without opening it (folders are ignored).
+    pub fn enqueue_current(&mut self) {
+        if let Some(entry) = self.entries.get(self.selected_index) {
+            if !entry.is_dir {
+                self.enqueued_file = Some(entry.path.clone());
+            }
+        }
+    }
+    pub fn take_enqueued_file(&mut self) -> Option<PathBuf> {
+        self.enqueued_file.take()
+    }
+    /// Play the highlighted file on a second screen next to the current video.
+    pub fn open_beside_current(&mut self) {
+        if let Some(entry) = self.entries.get(self.selected_index) {
+            if !entry.is_dir {
+                self.beside_file = Some(entry.path.clone());
+                self.visible = false;
+            }
+        }
+    }
+    pub fn take_beside_file(&mut self) -> Option<PathBuf> {
+        self.beside_file.take()
+    }
+
+    /// Enter or leave multi-select mode (leaving drops the marks).
+    pub fn toggle_multi_select(&mut self) {
+        self.multi_select = !self.multi_select;
+        self.marked.clear();
+    }
+    pub fn is_marked(&self, path: &Path) -> bool {
+        self.marked.iter().any(|m| m == path)
+    }
+    /// Mark or unmark the highlighted file (folders are ignored).
+    pub fn toggle_mark_current(&mut self) {
+        let Some(entry) = self.entries.get(self.selected_index).filter(|e| !e.is_dir) else { return };
+        let path = entry.path.clone();
+        if self.is_marked(&path) {
+            self.marked.retain(|m| *m != path);
+        } else {
+            self.marked.push(path);
+        }
+    }
+    /// Mark every file shown (the filter and tab apply), or unmark them if they
+    /// all are already.
+    pub fn mark_all(&mut self) {
+        let shown: Vec<PathBuf> = self.filtered_indices().into_iter()
+            .map(|i| &self.entries[i])
+            .filter(|e| !e.is_dir)
+            .map(|e| e.path.clone())
+            .collect();
+        if shown.iter().all(|p| self.is_marked(p)) {
+            self.marked.retain(|m| !shown.contains(m));
+        } else {
+            for path in shown {
+                if !self.is_marked(&path) { self.marked.push(path); }
+            }
+        }
+    }
+    /// Send the marked files to the queue, played straight away if `play`, and
+    /// leave multi-select mode.
+    pub fn submit_marked(&mut self, play: bool) {
+        if self.marked.is_empty() {
+            return;
+        }
+        self.batch = Some((std::mem::take(&mut self.marked), play));
+        self.multi_select = false;
+        if play {
+            self.visible = false;
+        }
+    }
+    pub fn take_batch(&mut self) -> Option<(Vec<PathBuf>, bool)> {
+        self.batch.take()
+    }
 }
 
-// ── Web browser state ─────────────────────────────────────────────────────────
+/// The round checkbox multi-select draws on a cover.
+fn paint_mark(painter: &egui::Painter, center: egui::Pos2, radius: f32, marked: bool, accent: Color32) {
+    if marked {
+        painter.circle_filled(center, radius, accent);
+        painter.text(center, egui::Align2::CENTER_CENTER, "✔",
+            FontId::new(radius * 1.2, FontFamily::Proportional), Color32::WHITE);
+    } else {
+        painter.circle(center, radius, Color32::from_black_alpha(90), Stroke::new(2.0, Color32::WHITE));
+    }
+}
 
-pub const VIEWPORTS: [(i32, i32, &str); 4] = [
-    (1920, 1080, "Wide"),
-    (1080, 1920, "Tall"),
-    (1080, 2160, "Phone"),
-    (1200, 1200, "Square"),
-];
+// ── Play queue panel ──────────────────────────────────────────────────────────
 
-pub struct WebBrowserState {
-    pub url_bar:        String,
-    pub current_url:    String,
-    pub pending_url:    Option<String>,
-    pub mic_listening:  bool,
-    pub go_back:        bool,
-    pub go_forward:     bool,
-    pub reload:         bool,
-    pub new_tab:        bool,
-    pub close_tab:      bool,
-    pub viewport:       u8,
-    pub pending_resize: Option<(i32, i32)>,
-    pub launched:       bool,
+/// Queue panel state. lib.rs owns the real `Playlist`; it refreshes `items` /
+/// `current` each frame while the panel is open and applies the request flags.
+#[derive(Default)]
+pub struct QueuePanel {
+    pub visible:  bool,
+    pub items:    Vec<String>,
+    pub current:  Option<usize>,
+    pub selected: usize,
+    // Requests for lib.rs
+    pub jump_to:  Option<usize>,
+    pub remove:   Option<usize>,
+    pub shuffle:  bool,
+    pub clear:    bool,
+    pub next:     bool,
+    pub previous: bool,
+    /// The ⏮ ⏭ 🔀 🗑 row under the list, for the D-pad.
+    controls:     FocusMap,
 }
 
-impl Default for WebBrowserState {
-    fn default() -> Self {
+impl QueuePanel {
+    /// D-pad step: up / down through the list, down past its end onto the
+    /// buttons below (left / right between them), up from there back.
+    pub fn nav(&mut self, dir: actions::Direction) {
+        use actions::Direction;
+        if self.controls.active() {
+            if dir == Direction::Up { self.controls.leave(); } else { self.controls.step(dir); }
+            return;
+        }
+        match dir {
+            Direction::Up => self.selected = self.selected.saturating_sub(1),
+            Direction::Down if self.selected + 1 < self.items.len() => self.selected += 1,
+            Direction::Down => self.controls.enter(false),
+            Direction::Left | Direction::Right => {}
+        }
+    }
+    /// The D-pad is on one of the buttons; X clicks it through egui's focus.
+    pub fn controls_focused(&self) -> bool { self.controls.active() }
+    pub fn play_selected(&mut self)   { if self.selected < self.items.len() { self.jump_to = Some(self.selected); } }
+    pub fn remove_selected(&mut self) { if self.selected < self.items.len() { self.remove = Some(self.selected); } }
+}
+
+// ── First-run tutorial ────────────────────────────────────────────────────────
+
+/// Pages of the first-run walkthrough, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialPage {
+    /// Entering VR and the touch gestures
+    Touch,
+    /// What the controller's buttons do during playback
+    Controller,
+    /// Opening and browsing the Media Center
+    MediaCenter,
+}
+
+impl TutorialPage {
+    const ALL: [TutorialPage; 3] = [TutorialPage::Touch, TutorialPage::Controller, TutorialPage::MediaCenter];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+// ── Resume prompt ─────────────────────────────────────────────────────────────
+
+/// "Resume from 12:34?" card shown when a previously watched file is reopened.
+/// lib.rs fills it in and acts on `accepted` (seek) once it's answered.
+#[derive(Default)]
+pub struct ResumePrompt {
+    pub visible:     bool,
+    pub title:       String,
+    pub position_us: i64,
+    pub accepted:    bool,
+}
+
+impl ResumePrompt {
+    pub fn show(&mut self, title: &str, position_us: i64) {
+        self.visible = true;
+        self.title = title.to_string();
+        self.position_us = position_us;
+    }
+    pub fn accept(&mut self)  { self.visible = false; self.accepted = true; }
+    pub fn dismiss(&mut self) { self.visible = false; }
+}
+
+// ── Playback error banner ─────────────────────────────────────────────────────
+
+/// How long a playback error stays on screen.
+const ERROR_BANNER_SECS: u64 = 8;
+
+/// Why playback stopped (unreadable file, unreachable stream, no decoder for
+/// the format, codec that keeps crashing) on a card that stays until answered,
+/// with what to try; or a banner that the device can't hardware-decode the
+/// format, which goes by itself. lib.rs fills it from the decoder and restarts
+/// `retry` when Try again is picked.
+#[derive(Default)]
+pub struct PlaybackError {
+    pub message: Option<String>,
+    /// A heads-up rather than a failure (playback continues).
+    pub warning: bool,
+    /// The failure's headline and what to try about it
+    title:       &'static str,
+    advice:      &'static str,
+    /// The file or URL that failed (not known for picked files and side videos)
+    path:        Option<String>,
+    /// Try again was picked for this
+    pub retry:   Option<String>,
+    shown_at:    Option<Instant>,
+}
+
+impl PlaybackError {
+    /// Playing `path` failed with `error`.
+    pub fn show(&mut self, error: &DecodeError, path: Option<&str>) {
+        let stream = path.is_some_and(|p| p.contains("://"));
+        (self.title, self.advice) = match error {
+            DecodeError::Source(_) if stream => ("Can't reach the stream",
+                "Check the Wi-Fi connection and the address, then try again."),
+            DecodeError::Source(_) => ("Can't read the file",
+                "It may be damaged, still being copied, or on a drive that was removed."),
+            DecodeError::NoVideoTrack => ("No picture in this file",
+                "It has no video track, or isn't a video at all."),
+            DecodeError::CodecUnavailable { .. } => ("Format not supported",
+                "This phone has no working decoder for it. Pick another in Settings → Advanced, or play a copy in H.264 or HEVC."),
+            DecodeError::CodecFailed { .. } => ("The decoder stopped",
+                "Try again, or pick another decoder in Settings → Advanced."),
+        };
+        self.message = Some(error.to_string());
+        self.warning = false;
+        self.path = path.map(str::to_string);
+        self.shown_at = Some(Instant::now());
+    }
+    /// `path` couldn't be opened at all.
+    pub fn show_open_failed(&mut self, path: &str, reason: String) {
+        self.show(&DecodeError::Source(reason), Some(path));
+    }
+    pub fn show_warning(&mut self, message: String) {
+        self.message = Some(message);
+        self.warning = true;
+        self.path = None;
+        self.shown_at = Some(Instant::now());
+    }
+    pub fn dismiss(&mut self) { self.message = None; }
+    /// Close the card and have lib.rs play the same file again.
+    pub fn try_again(&mut self) {
+        self.retry = self.path.take();
+        self.dismiss();
+    }
+    pub fn visible(&self) -> bool {
+        self.message.is_some()
+            && (!self.warning || self.shown_at.is_some_and(|t| t.elapsed().as_secs() < ERROR_BANNER_SECS))
+    }
+    /// A failure card is up and takes the controller.
+    pub fn is_modal(&self) -> bool {
+        self.message.is_some() && !self.warning
+    }
+}
+
+// ── Buffering indicator ───────────────────────────────────────────────────────
+
+/// Spinner card while the decoder opens a file, finds the new position after a
+/// seek or waits on the network. lib.rs sets it from the decoder status every
+/// frame with `update`. It comes up only after `BUFFERING_DELAY`, so a quick
+/// local seek doesn't flash it.
+#[derive(Default)]
+pub struct BufferingIndicator {
+    /// "Opening…" / "Seeking…" / "Buffering…"; `None` hides the card.
+    pub label: Option<&'static str>,
+    /// Data downloaded past the playhead (streams only).
+    pub buffered_ahead_us: Option<i64>,
+    /// Where a seek is going.
+    pub seek_target_us: Option<i64>,
+    /// When the current label came up
+    since: Option<Instant>,
+}
+
+const BUFFERING_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
+
+impl BufferingIndicator {
+    pub fn update(&mut self, label: Option<&'static str>, buffered_ahead_us: Option<i64>, seek_target_us: Option<i64>) {
+        if label.is_none() {
+            self.since = None;
+        } else if self.label.is_none() {
+            self.since = Some(Instant::now());
+        }
+        self.label = label;
+        self.buffered_ahead_us = buffered_ahead_us;
+        self.seek_target_us = seek_target_us;
+    }
+
+    fn due(&self) -> bool {
+        self.since.is_some_and(|since| since.elapsed() >= BUFFERING_DELAY)
+    }
+}
+
+/// D-pad nudges wait this long for another before the seek goes out.
+const SCRUB_SETTLE: std::time::Duration = std::time::Duration::from_millis(600);
+/// Smallest D-pad nudge; long videos move by 1% of their length instead.
+const SCRUB_NUDGE_US: i64 = 5_000_000;
+
+/// Progress bar under the dock with chapter ticks; clicking it seeks, dragging
+/// scrubs (seeking on release), and with the D-pad on it ◀ ▶ nudge the target.
+/// lib.rs fills it while something is playing.
+#[derive(Default)]
+pub struct SeekBar {
+    pub position_us: i64,
+    pub duration_us: i64,
+    /// Chapter starts (µs), drawn as ticks.
+    pub chapters: Vec<i64>,
+    /// Title of the chapter playing.
+    pub chapter: Option<String>,
+    /// False for streams that can't seek (HLS): the bar only shows the time.
+    pub seekable: bool,
+}
+
+/// A seek bar target not sent yet.
+#[derive(Clone, Copy)]
+pub struct Scrub {
+    pub target_us: i64,
+    /// Last D-pad nudge; `None` while dragging
+    nudged: Option<Instant>,
+}
+
+/// What brought up the playback OSD.
+#[derive(Clone, Copy, PartialEq)]
+pub enum OsdEvent {
+    Play,
+    Pause,
+    /// Seeked by this much, µs
+    Seek(i64),
+}
+
+/// Title, time and what just happened, shown for a moment after play / pause or
+/// a seek from the controller, keys, touch or media buttons. lib.rs shows it and
+/// keeps the time current.
+pub struct PlaybackOsd {
+    pub event: OsdEvent,
+    pub title: String,
+    pub position_us: i64,
+    pub duration_us: i64,
+    since: Instant,
+}
+
+/// How long the 2D controls stay up while playing without being touched.
+const FLAT_CONTROLS_SECS: u64 = 4;
+/// Gap between the 2D controls and the screen edges, canvas px.
+const FLAT_MARGIN: f32 = 24.0;
+
+/// The 2D (non-VR) controls: Open, the title and Enter VR along the top; skip
+/// back, play / pause, skip forward and the seek bar along the bottom. A tap on
+/// the picture shows or hides them, and while playing they hide again after
+/// `FLAT_CONTROLS_SECS`. lib.rs fills `paused` and `title`.
+pub struct FlatControls {
+    pub visible: bool,
+    pub paused: bool,
+    pub title: Option<String>,
+    /// Shown or last touched
+    since: Instant,
+}
+
+impl Default for FlatControls {
+    fn default() -> Self {
+        Self { visible: true, paused: true, title: None, since: Instant::now() }
+    }
+}
+
+impl FlatControls {
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        self.since = Instant::now();
+    }
+}
+
+/// Where a touch swipe will seek to; lib.rs fills it while the finger is down.
+pub struct SeekPreview {
+    pub target_us: i64,
+    pub delta_us: i64,
+}
+
+/// Draw the dock's seek bar; returns the position to seek to, once clicked or
+/// when a drag ends. `scrub` is the drag or nudge in progress, `focused` that
+/// the D-pad is on the bar.
+fn seek_bar(ui: &mut egui::Ui, bar: &SeekBar, scrub: &mut Option<Scrub>, focused: bool) -> Option<i64> {
+    let theme = crate::theme::current();
+    let width = ui.available_width().clamp(400.0, 900.0);
+    let sense = if bar.seekable { egui::Sense::click_and_drag() } else { egui::Sense::hover() };
+    let (rect, resp) = ui.allocate_exact_size(egui::vec2(width, 22.0), sense);
+    let track = egui::Rect::from_center_size(rect.center(), egui::vec2(rect.width(), 6.0));
+    let frac = |us: i64| (us as f32 / bar.duration_us.max(1) as f32).clamp(0.0, 1.0);
+    let at = |x: f32| (((x - track.left()) / track.width()).clamp(0.0, 1.0) as f64 * bar.duration_us as f64) as i64;
+    if resp.dragged() {
+        if let Some(pos) = resp.interact_pointer_pos() {
+            *scrub = Some(Scrub { target_us: at(pos.x), nudged: None });
+        }
+    }
+    let painter = ui.painter();
+    if focused {
+        painter.rect_stroke(track.expand(4.0), Rounding::same(6.0), Stroke::new(2.0, theme.accent));
+    }
+    painter.rect_filled(track, Rounding::same(3.0), theme.control(255));
+    let mut played = track;
+    played.set_right(track.left() + track.width() * frac(bar.position_us));
+    painter.rect_filled(played, Rounding::same(3.0), theme.accent);
+    for &start in bar.chapters.iter().filter(|&&s| s > 0) {
+        let x = track.left() + track.width() * frac(start);
+        painter.line_segment([egui::pos2(x, rect.top() + 3.0), egui::pos2(x, rect.bottom() - 3.0)],
+            Stroke::new(2.0, theme.text_soft));
+    }
+    if let Some(s) = scrub {
+        let x = track.left() + track.width() * frac(s.target_us);
+        painter.circle(egui::pos2(x, track.center().y), 9.0, Color32::WHITE, Stroke::new(2.0, theme.accent));
+    }
+    ui.horizontal(|ui| {
+        let shown = match scrub {
+            Some(s) => format!("→ {}", crate::resume::format_time(s.target_us)),
+            None => crate::resume::format_time(bar.position_us),
+        };
+        ui.label(egui::RichText::new(format!("{} / {}", shown,
+            crate::resume::format_time(bar.duration_us))).size(13.0).color(theme.text_weak));
+        if let Some(chapter) = &bar.chapter {
+            ui.label(egui::RichText::new(chapter).size(13.0).color(theme.text_soft));
+        }
+    });
+    if resp.drag_stopped() {
+        return scrub.take().map(|s| s.target_us);
+    }
+    if let Some(s) = scrub.filter(|s| s.nudged.is_some_and(|at| at.elapsed() >= SCRUB_SETTLE)) {
+        *scrub = None;
+        return Some(s.target_us);
+    }
+    if scrub.is_some() {
+        ui.ctx().request_repaint();
+    }
+    let x = resp.interact_pointer_pos().filter(|_| resp.clicked())?.x;
+    Some(at(x))
+}
+
+// ── Stream URL dialog ─────────────────────────────────────────────────────────
+
+/// "Play from URL" dialog for streaming direct http(s) video links. The URL is
+/// typed on the virtual keyboard (shown inside the dialog) or picked from the
+/// ones played before; lib.rs takes `submitted` and starts playback.
+#[derive(Default)]
+pub struct UrlDialog {
+    pub visible:   bool,
+    pub submitted: Option<String>,
+    /// Recently played URLs, newest first (from the recent list; lib.rs fills it).
+    pub history:   Vec<String>,
+    /// Which of them L1 / R1 put in the field.
+    history_index: Option<usize>,
+}
+
+/// How many recent URLs the dialog offers.
+pub const URL_HISTORY: usize = 6;
+
+/// Add `http://` to a bare host/path; `None` if there's nothing to play.
+pub fn normalise_stream_url(input: &str) -> Option<String> {
+    let s = input.trim();
+    if s.is_empty() || s == "http://" || s == "https://" {
+        None
+    } else if s.starts_with("http://") || s.starts_with("https://") {
+        Some(s.to_string())
+    } else {
+        Some(format!("http://{}", s))
+    }
+}
+
+// ── Web browser state ─────────────────────────────────────────────────────────
+
+pub const VIEWPORTS: [(i32, i32, &str); 4] = [
+    (1920, 1080, "Wide"),
+    (1080, 1920, "Tall"),
+    (1080, 2160, "Phone"),
+    (1200, 1200, "Square"),
+];
+
+pub struct WebBrowserState {
+    pub url_bar:        String,
+    pub current_url:    String,
+    pub pending_url:    Option<String>,
+    pub mic_listening:  bool,
+    pub go_back:        bool,
+    pub go_forward:     bool,
+    pub reload:         bool,
+    pub new_tab:        bool,
+    pub close_tab:      bool,
+    /// Move the page from the screen to a floating window
+    pub pop_out:        bool,
+    pub viewport:       u8,
+    pub pending_resize: Option<(i32, i32)>,
+    pub launched:       bool,
+}
+
+impl Default for WebBrowserState {
+    fn default() -> Self {
         Self {
             url_bar: "https://www.google.com".into(),
             current_url: String::new(),
             pending_url: None,
             mic_listening: false,
             go_back: false, go_forward: false, reload: false,
-            new_tab: false, close_tab: false,
+            new_tab: false, close_tab: false, pop_out: false,
             viewport: 0, pending_resize: None, launched: false,
         }
     }
@@ -384,11 +1703,12 @@ impl Default for WebBrowserState {
 
 // ── In-VR virtual keyboard (gamepad-driven) ───────────────────────────────────
 
-const KB_ROWS: [&str; 4] = [
+const KB_ROWS: [&str; 5] = [
     "1234567890",
     "qwertyuiop",
     "asdfghjkl",
     "zxcvbnm",
+    "-_.:/?=&%~ ",
 ];
 
 #[derive(Default)]
@@ -398,6 +1718,7 @@ pub struct VrKeyboard {
     pub col: usize,
     pub input: String,
     pub commit: Option<String>,
+    pub shift: bool,
 }
 
 impl VrKeyboard {
@@ -416,7 +1737,16 @@ impl VrKeyboard {
         if self.col > len { self.col = len; }
     }
     pub fn press(&mut self) {
-        if let Some(c) = self.current_char() { self.input.push(c); }
+        if let Some(c) = self.current_char() {
+            if self.shift { self.input.extend(c.to_uppercase()); } else { self.input.push(c); }
+        }
+    }
+    pub fn toggle_shift(&mut self) { self.shift = !self.shift; }
+    /// Show the keyboard with `text` already typed.
+    pub fn open(&mut self, text: &str) {
+        self.visible = true;
+        self.input = text.to_string();
+        self.commit = None;
     }
     pub fn backspace(&mut self) { self.input.pop(); }
     pub fn submit(&mut self) {
@@ -426,15 +1756,18 @@ impl VrKeyboard {
     pub fn take_commit(&mut self) -> Option<String> { self.commit.take() }
 
     fn render(&self, ui: &mut egui::Ui) {
+        let theme = crate::theme::current();
         for (r, row) in KB_ROWS.iter().enumerate() {
             ui.horizontal(|ui| {
                 for (c, ch) in row.chars().enumerate() {
                     let selected = r == self.row && c == self.col;
-                    let label = egui::RichText::new(ch.to_string())
+                    let ch = if self.shift { ch.to_ascii_uppercase() } else { ch };
+                    let (text, width) = if ch == ' ' { (tr("Space").to_string(), 140.0) } else { (ch.to_string(), 64.0) };
+                    let label = egui::RichText::new(text)
                         .size(if selected { 34.0 } else { 26.0 })
-                        .color(Color32::WHITE);
-                    let mut btn = egui::Button::new(label).min_size(egui::vec2(64.0, 64.0));
-                    if selected { btn = btn.fill(Color32::from_rgb(80, 160, 255)); }
+                        .color(if selected { theme.on_accent } else { theme.text });
+                    let mut btn = egui::Button::new(label).min_size(egui::vec2(width, 64.0));
+                    if selected { btn = btn.fill(theme.accent); }
                     ui.add(btn);
                 }
             });
@@ -442,18 +1775,134 @@ impl VrKeyboard {
     }
 }
 
+// ── Lens presets ──────────────────────────────────────────────────────────────
+
+/// A named set of lens values, for switching one phone between headset shells.
+/// Saved from and picked in Settings → Headset; config.rs keeps them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LensPreset {
+    pub name: String,
+    pub lens_radius: f32,
+    pub lens_center_offset: f32,
+    pub ipd: f32,
+    pub fov: f32,
+}
+
+impl LensPreset {
+    /// Whether `params` has exactly this preset's values.
+    fn matches(&self, params: &VrParams) -> bool {
+        params.lens_preset(self.name.clone()) == *self
+    }
+}
+
+// ── Transitions ───────────────────────────────────────────────────────────────
+
+/// How long a panel takes to come in or go away.
+const TRANSITION_SECS: f32 = 0.18;
+/// How far below its place a panel starts rising from, px.
+const TRANSITION_SLIDE: f32 = 36.0;
+
+/// A 0…1 transition stepped by the frame time, so a panel keeps drawing while it
+/// fades out after being closed. Panels read it eased, via `update`.
+#[derive(Debug, Clone, Copy, Default)]
+struct Tween {
+    linear: f32,
+}
+
+impl Tween {
+    /// Step towards in (`shown`) or out over `secs`; the eased value after it.
+    fn update(&mut self, shown: bool, dt: f32, secs: f32) -> f32 {
+        let step = if secs > 0.0 { dt / secs } else { 1.0 };
+        self.linear = if shown { (self.linear + step).min(1.0) } else { (self.linear - step).max(0.0) };
+        ease_out(self.linear)
+    }
+}
+
+/// Quick start, soft landing.
+fn ease_out(t: f32) -> f32 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+/// Anchor offset of a window `t` of the way into place at `offset`: it rises
+/// from `TRANSITION_SLIDE` below. Its frame and contents fade by `t` as well.
+fn slide_in(offset: egui::Vec2, t: f32) -> egui::Vec2 {
+    offset + egui::vec2(0.0, (1.0 - t) * TRANSITION_SLIDE)
+}
+
+/// `secs`, or none with Reduce motion on: the panel just appears.
+fn transition(secs: f32) -> f32 {
+    if crate::theme::current().access.reduce_motion { 0.0 } else { secs }
+}
+
 // ── VrUi ──────────────────────────────────────────────────────────────────────
 
 pub struct VrUi {
     pub params: VrParams,
     pub main_menu_visible: bool,
     pub menu_state: MenuState,
-    pub hamburger_visible: bool,
-    last_interaction: Instant,
+    pub flat_controls: FlatControls,
     pub file_browser: FileBrowser,
     pub web_browser: WebBrowserState,
     pub keyboard: VrKeyboard,
+    /// The Media Center entry the keyboard is renaming, and the file extension
+    /// it keeps (left off what's typed).
+    renaming: Option<(PathBuf, String)>,
+    /// The keyboard is typing a name to save the lens values under
+    naming_preset: bool,
+    pub lens_presets: Vec<LensPreset>,
+    /// The first-run walkthrough's page, while it's showing
+    tutorial: Option<TutorialPage>,
+    pub queue: QueuePanel,
+    pub resume_prompt: ResumePrompt,
+    pub url_dialog: UrlDialog,
+    pub playback_error: PlaybackError,
+    pub buffering: BufferingIndicator,
+    /// "▶ title · 1:42:10 · 4K · HEVC …" under the dock; lib.rs fills it.
+    pub now_playing: Option<String>,
+    pub seek_bar: Option<SeekBar>,
+    /// The D-pad is on the seek bar rather than the dock icons
+    seek_focused: bool,
+    scrub: Option<Scrub>,
+    /// Head-tracking health and the render rate, for the tracking panel; lib.rs fills them.
+    pub sensor_status: Option<crate::sensors::SensorStatus>,
+    pub render_fps: f32,
+    /// First controller's battery (0…1) when it reports one; lib.rs fills it.
+    pub controller_battery: Option<f32>,
+    /// Time, phone battery and Wi-Fi, filled by lib.rs while the status HUD is on
+    pub device_status: Option<crate::status::DeviceStatus>,
+    /// The head-gaze pointer is on (no controller); lib.rs sets it each frame.
+    pub gaze_active: bool,
+    /// Touch and the controller are ignored but for the unlock hold
+    locked: bool,
+    /// How far through the unlock hold (0…1); lib.rs sets it while locked.
+    pub unlock_progress: f32,
+    pub seek_preview: Option<SeekPreview>,
+    pub osd: Option<PlaybackOsd>,
+    /// Fade / slide of the dock (or settings), the Media Center and the OSD
+    dock_tween: Tween,
+    media_tween: Tween,
+    osd_tween: Tween,
+    /// Media volume (level, max steps); lib.rs fills it with `set_volume`.
+    volume: Option<(i32, i32)>,
+    /// When the volume last changed, for its OSD.
+    volume_changed: Option<Instant>,
+    /// Level picked on the dock slider; lib.rs applies it.
+    pub volume_request: Option<i32>,
+    /// Short notices for the bottom of the view ("Controller disconnected"),
+    /// oldest first; the first is showing, since `toast_since`.
+    toasts: VecDeque<String>,
+    toast_since: Option<Instant>,
+    /// The hint bar's context, and when it came up (playback hints fade out).
+    hint_context: Option<(InputContext, Instant)>,
     pub dock_selected: usize,
+    settings_section: SettingsSection,
+    /// Controller keys for egui's focus (settings, toolbar and queue buttons), fed
+    /// to egui with the next frame's input
+    focus_keys: Vec<egui::Key>,
+    /// Focus the current section's tab on the next frame
+    settings_focus_tab: bool,
+    /// Widget focused last frame, to scroll to a newly focused one
+    settings_focus: Option<egui::Id>,
 }
 
 impl VrUi {
@@ -466,12 +1915,46 @@ impl VrUi {
             params: VrParams::default(),
             main_menu_visible: false,
             menu_state: MenuState::Main,
-            hamburger_visible: true,
-            last_interaction: Instant::now(),
+            flat_controls: FlatControls::default(),
             file_browser: FileBrowser::new(),
             web_browser: WebBrowserState::default(),
             keyboard: VrKeyboard::default(),
+            renaming: None,
+            naming_preset: false,
+            lens_presets: Vec::new(),
+            tutorial: None,
+            queue: QueuePanel::default(),
+            resume_prompt: ResumePrompt::default(),
+            url_dialog: UrlDialog::default(),
+            playback_error: PlaybackError::default(),
+            buffering: BufferingIndicator::default(),
+            now_playing: None,
+            sensor_status: None,
+            render_fps: 0.0,
+            controller_battery: None,
+            device_status: None,
+            gaze_active: false,
+            locked: false,
+            unlock_progress: 0.0,
+            toasts: VecDeque::new(),
+            toast_since: None,
+            seek_preview: None,
+            osd: None,
+            dock_tween: Tween::default(),
+            media_tween: Tween::default(),
+            osd_tween: Tween::default(),
+            volume: None,
+            volume_changed: None,
+            volume_request: None,
+            hint_context: None,
+            seek_bar: None,
+            seek_focused: false,
+            scrub: None,
             dock_selected: 0,
+            settings_section: SettingsSection::Display,
+            focus_keys: Vec::new(),
+            settings_focus_tab: false,
+            settings_focus: None,
         }
     }
 
@@ -480,47 +1963,327 @@ impl VrUi {
         style.spacing.item_spacing = egui::vec2(8.0, 8.0);
         style.spacing.button_padding = egui::vec2(14.0, 10.0);
         style.spacing.slider_width = 160.0;
-        let mut visuals = Visuals::dark();
-        visuals.window_rounding = Rounding::same(18.0);
         style.text_styles.insert(egui::TextStyle::Body, FontId::new(16.0, FontFamily::Proportional));
         style.text_styles.insert(egui::TextStyle::Button, FontId::new(20.0, FontFamily::Proportional));
         ctx.set_style(style);
-        ctx.set_visuals(visuals);
+        crate::theme::apply(ctx);
+    }
+
+    /// Back to just the video: every menu and panel closed.
+    pub fn close_all_panels(&mut self) {
+        self.main_menu_visible = false;
+        self.menu_state = MenuState::Main;
+        self.file_browser.visible = false;
+        self.queue.visible = false;
+        if self.url_dialog.visible {
+            self.close_url_dialog();
+        }
+        self.keyboard.visible = false;
+        self.file_browser.entry_menu = None;
+        self.renaming = None;
+        self.naming_preset = false;
+        if self.tutorial.is_some() {
+            self.close_tutorial();
+        }
     }
 
-    pub fn show_hamburger(&mut self) { self.hamburger_visible = true; self.last_interaction = Instant::now(); }
-    pub fn toggle_hamburger(&mut self) { self.hamburger_visible = !self.hamburger_visible; self.last_interaction = Instant::now(); }
-    pub fn is_hamburger_visible(&self) -> bool { self.hamburger_visible }
+    /// Whether any panel the stick pointer could be used on is showing.
+    pub fn has_open_panel(&self) -> bool {
+        self.tutorial.is_some() || self.playback_error.is_modal() || self.main_menu_visible || self.file_browser.visible || self.queue.visible
+            || self.resume_prompt.visible || self.url_dialog.visible || self.keyboard.visible
+            || self.params.web_mode
+    }
 
     pub fn take_selected_file(&mut self) -> Option<PathBuf> { self.file_browser.take_selected_file() }
 
+    pub fn open_url_dialog(&mut self) {
+        self.url_dialog.visible = true;
+        self.url_dialog.history_index = None;
+        self.keyboard.open("http://");
+    }
+    /// Put the next older (or newer) recent URL in the field.
+    pub fn url_history_step(&mut self, older: bool) {
+        let len = self.url_dialog.history.len();
+        if len == 0 {
+            return;
+        }
+        let i = match (self.url_dialog.history_index, older) {
+            (None, _) => 0,
+            (Some(i), true) => (i + 1).min(len - 1),
+            (Some(i), false) => i.saturating_sub(1),
+        };
+        self.url_dialog.history_index = Some(i);
+        self.keyboard.input = self.url_dialog.history[i].clone();
+    }
+    pub fn close_url_dialog(&mut self) {
+        self.url_dialog.visible = false;
+        self.keyboard.visible = false;
+    }
+    /// Type the Media Center filter on the virtual keyboard.
+    pub fn open_search(&mut self) {
+        self.keyboard.open(&self.file_browser.search_query);
+    }
+    /// Filter by what's typed so far; call after each key while searching.
+    pub fn update_search(&mut self) {
+        self.file_browser.set_search_query(&self.keyboard.input);
+    }
+    pub fn close_search(&mut self) {
+        self.keyboard.visible = false;
+    }
+
+    /// Carry out the entry menu's highlighted row.
+    pub fn entry_menu_activate(&mut self) {
+        let Some(menu) = &self.file_browser.entry_menu else { return };
+        if let Some(&action) = menu.actions().get(menu.selected) {
+            self.run_entry_action(action);
+        }
+    }
+    fn run_entry_action(&mut self, action: EntryAction) {
+        let Some(mut menu) = self.file_browser.entry_menu.take() else { return };
+        match action {
+            EntryAction::Favorite => {
+                let added = self.file_browser.toggle_favorite(&menu.path);
+                self.show_toast(tr(if added { "Added to favorites" } else { "Removed from favorites" }));
+            }
+            EntryAction::Rename => {
+                // Files are renamed without their extension, so it can't be lost
+                let ext = match menu.path.extension() {
+                    Some(ext) if !menu.is_dir => format!(".{}", ext.to_string_lossy()),
+                    _ => String::new(),
+                };
+                let stem = menu.name.strip_suffix(ext.as_str()).unwrap_or(&menu.name).to_string();
+                self.keyboard.open(&stem);
+                self.renaming = Some((menu.path, ext));
+            }
+            EntryAction::Delete if !menu.confirm_delete => {
+                menu.confirm_delete = true;
+                menu.selected = 1;
+                self.file_browser.entry_menu = Some(menu);
+            }
+            EntryAction::Delete => match self.file_browser.delete_file(&menu.path) {
+                Ok(()) => self.show_toast(trf("Deleted {}", &[&menu.name])),
+                Err(e) => self.show_toast(e),
+            },
+            EntryAction::Cancel => {}
+        }
+    }
+    /// The keyboard is typing a new name for a Media Center entry.
+    pub fn renaming(&self) -> bool {
+        self.renaming.is_some()
+    }
+    /// Rename the entry to what's typed; the keyboard stays up if that fails.
+    pub fn submit_rename(&mut self) {
+        let Some((path, ext)) = self.renaming.clone() else { return };
+        let name = format!("{}{}", self.keyboard.input.trim(), ext);
+        match self.file_browser.rename(&path, &name) {
+            Ok(_) => {
+                self.cancel_rename();
+                self.show_toast(trf("Renamed to {}", &[&name]));
+            }
+            Err(e) => self.show_toast(e),
+        }
+    }
+    pub fn cancel_rename(&mut self) {
+        self.renaming = None;
+        self.keyboard.visible = false;
+    }
+    /// Name the lens values on the keyboard, to save them as a preset.
+    pub fn open_preset_name(&mut self) {
+        self.keyboard.open(&trf("Headset {}", &[&(self.lens_presets.len() + 1)]));
+        self.naming_preset = true;
+    }
+    /// The keyboard is typing a lens preset's name.
+    pub fn naming_preset(&self) -> bool {
+        self.naming_preset
+    }
+    /// Save the lens values under what's typed, replacing a preset of that name.
+    pub fn submit_preset_name(&mut self) {
+        let name = self.keyboard.input.trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let preset = self.params.lens_preset(name.clone());
+        match self.lens_presets.iter_mut().find(|p| p.name == name) {
+            Some(existing) => *existing = preset,
+            None => self.lens_presets.push(preset),
+        }
+        self.cancel_preset_name();
+        self.show_toast(trf("Saved {}", &[&name]));
+    }
+    pub fn cancel_preset_name(&mut self) {
+        self.naming_preset = false;
+        self.keyboard.visible = false;
+    }
+    /// Take what's typed as the stream URL (ignored if empty).
+    pub fn submit_url_dialog(&mut self) {
+        if let Some(url) = normalise_stream_url(&self.keyboard.input) {
+            self.url_dialog.submitted = Some(url);
+            self.close_url_dialog();
+        }
+    }
+
+    // ── Settings navigation (controller; wired from lib.rs) ───────────────────
+    pub fn settings_open(&self) -> bool {
+        self.main_menu_visible && matches!(self.menu_state, MenuState::Settings)
+    }
+
+    /// Back from the settings window to the dock.
+    pub fn close_settings(&mut self) {
+        crate::gamepad::cancel_capture();
+        self.menu_state = MenuState::Main;
+    }
+
+    /// Next / previous section, with the focus on its tab.
+    pub fn settings_step_section(&mut self, forward: bool) {
+        let all = SettingsSection::ALL;
+        let i = all.iter().position(|s| *s == self.settings_section).unwrap_or(0);
+        let step = if forward { 1 } else { all.len() - 1 };
+        crate::gamepad::cancel_capture();
+        self.settings_section = all[(i + step) % all.len()];
+        self.settings_focus_tab = true;
+    }
+
+    /// Arrow keys move the focus (and step a focused slider), Enter clicks it,
+    /// Escape closes an open dropdown.
+    pub fn focus_key(&mut self, key: egui::Key) {
+        self.focus_keys.push(key);
+    }
+
+    /// The queued focus keys as egui events. With nothing focused yet they're
+    /// dropped; in the settings the first press puts the focus on the current
+    /// section's tab.
+    pub fn take_focus_events(&mut self, ctx: &Context) -> Vec<egui::Event> {
+        if self.focus_keys.is_empty() {
+            return Vec::new();
+        }
+        if ctx.memory(|m| m.focused()).is_none() {
+            self.focus_keys.clear();
+            self.settings_focus_tab = self.settings_open();
+            return Vec::new();
+        }
+        self.focus_keys.drain(..).flat_map(|key| [true, false].map(|pressed| egui::Event::Key {
+            key,
+            physical_key: None,
+            pressed,
+            repeat: false,
+            modifiers: egui::Modifiers::default(),
+        })).collect()
+    }
+
+    /// Everything the settings window saves back to its default. Button
+    /// remaps and decoder choices have their own resets.
+    fn reset_settings(&mut self) {
+        let p = &self.params;
+        self.params = VrParams {
+            pan_x: p.pan_x,
+            pan_y: p.pan_y,
+            select_video_flag: p.select_video_flag,
+            web_mode: p.web_mode,
+            browser_engine: p.browser_engine,
+            pending_engine: p.pending_engine,
+            stereo_mode: p.stereo_mode,
+            tutorial_seen: p.tutorial_seen,
+            ..VrParams::default()
+        };
+    }
+
     // ── Dock navigation (D-pad driven; wired from lib.rs) ─────────────────────
-    pub fn dock_move_left(&mut self)  { if self.dock_selected > 0 { self.dock_selected -= 1; } }
-    pub fn dock_move_right(&mut self) { if self.dock_selected + 1 < DOCK_ITEMS.len() { self.dock_selected += 1; } }
+    pub fn dock_move_left(&mut self) {
+        if self.seek_focused { self.nudge_seek_bar(false); }
+        else if self.dock_selected > 0 { self.dock_selected -= 1; }
+    }
+    pub fn dock_move_right(&mut self) {
+        if self.seek_focused { self.nudge_seek_bar(true); }
+        else if self.dock_selected + 1 < DOCK_ITEMS.len() { self.dock_selected += 1; }
+    }
+    /// ▼ onto the seek bar (while something seekable is playing), ▲ back to the icons.
+    pub fn dock_move_down(&mut self) { self.seek_focused = self.seek_bar.as_ref().is_some_and(|b| b.seekable); }
+    pub fn dock_move_up(&mut self)   { self.seek_focused = false; }
+
+    /// Move the seek bar's target; the seek goes out once the nudges stop.
+    fn nudge_seek_bar(&mut self, forward: bool) {
+        let Some(bar) = &self.seek_bar else { return };
+        let step = SCRUB_NUDGE_US.max(bar.duration_us / 100);
+        let from = self.scrub.map_or(bar.position_us, |s| s.target_us);
+        let target_us = (from + if forward { step } else { -step }).clamp(0, bar.duration_us);
+        self.scrub = Some(Scrub { target_us, nudged: Some(Instant::now()) });
+    }
 
     pub fn dock_activate(&mut self) {
+        if self.seek_focused {
+            // Seek to the nudged target now rather than after the pause
+            if let Some(scrub) = self.scrub.take() {
+                actions::push(Action::SeekTo(scrub.target_us));
+            }
+            return;
+        }
         match DOCK_ITEMS[self.dock_selected] {
-            DockItem::Recenter  => self.params.recenter_flag = true,
-            DockItem::Gyro      => self.params.gyro_enabled = !self.params.gyro_enabled,
+            DockItem::Recenter  => actions::push(Action::Recenter),
+            DockItem::Gyro      => {
+                self.params.gyro_enabled = !self.params.gyro_enabled;
+                self.show_toast(tr(if self.params.gyro_enabled { "Head tracking on" } else { "Head tracking off" }));
+            }
             DockItem::Files     => {
                 self.file_browser.visible = true;
                 if self.file_browser.entries.is_empty() { self.file_browser.refresh_entries(); }
                 self.main_menu_visible = false;
             }
+            DockItem::PlayUrl   => {
+                self.open_url_dialog();
+                self.main_menu_visible = false;
+            }
             DockItem::Web | DockItem::Firefox => self.activate_browser(1),
             DockItem::NewTab    => { if !self.params.web_mode { self.activate_browser(1); } self.web_browser.new_tab = true; self.main_menu_visible = false; }
             DockItem::CloseTab  => self.web_browser.close_tab = true,
             DockItem::Stereo3D  => {
                 self.params.stereo_mode = (self.params.stereo_mode + 1) % STEREO_MODES;
             }
-            DockItem::SeekBack  => self.params.seek_backward_flag = true,
-            DockItem::PlayPause => self.params.toggle_play_pause = true,
-            DockItem::SeekFwd   => self.params.seek_forward_flag = true,
-            DockItem::Settings  => self.menu_state = MenuState::LensSettings,
-            DockItem::Exit      => self.params.vr_exit_requested = true,
+            DockItem::SeekBack  => actions::push(Action::SeekBy(-actions::SEEK_STEP_US)),
+            DockItem::PlayPause => actions::push(Action::PlayPause),
+            DockItem::SeekFwd   => actions::push(Action::SeekBy(actions::SEEK_STEP_US)),
+            DockItem::AutoNext  => self.params.auto_advance = !self.params.auto_advance,
+            DockItem::Loop      => self.params.loop_mode = self.params.loop_mode.next(),
+            DockItem::Aspect    => self.params.aspect_mode = self.params.aspect_mode.next(),
+            DockItem::Snapshot  => actions::push(Action::Snapshot),
+            DockItem::Queue     => {
+                self.queue.visible = true;
+                self.main_menu_visible = false;
+            }
+            DockItem::Lock      => self.lock_controls(),
+            DockItem::Settings  => self.menu_state = MenuState::Settings,
+            DockItem::Exit      => actions::push(Action::ExitVr),
+        }
+    }
+
+    // ── Lock (touch and controller ignored until the unlock hold) ─────────────
+    /// Lock the controls for undisturbed playback. Only with a controller
+    /// connected: it's the only way back.
+    pub fn lock_controls(&mut self) {
+        if crate::gamepad::controllers().is_empty() {
+            self.show_toast(tr("Connect a controller to lock the controls"));
+            return;
+        }
+        self.close_all_panels();
+        self.locked = true;
+        self.locked_press();
+    }
+
+    pub fn unlock_controls(&mut self) {
+        if std::mem::take(&mut self.locked) {
+            self.unlock_progress = 0.0;
+            self.show_toast(tr("Controls unlocked"));
         }
     }
 
+    pub fn controls_locked(&self) -> bool { self.locked }
+
+    /// A press the lock swallowed: say how to unlock.
+    pub fn locked_press(&mut self) {
+        let [a, b] = actions::UNLOCK;
+        self.show_toast(trf("Locked · hold {} + {} to unlock",
+            &[&crate::gamepad::glyph(a), &crate::gamepad::glyph(b)]));
+    }
+
     fn activate_browser(&mut self, engine: i32) {
         if self.params.web_mode { self.params.web_mode = false; return; }
         self.params.web_mode = true;
@@ -537,159 +2300,1001 @@ impl VrUi {
     }
 
     // ── Render ────────────────────────────────────────────────────────────────
+    /// A floating window panel's own context (window_manager `PanelUi`): the
+    /// dock or settings drawn into it alone, fully shown.
+    pub fn render_panel(&mut self, ctx: &Context, content: &crate::window_manager::PanelContent) {
+        use crate::window_manager::PanelContent;
+        match content {
+            PanelContent::Dock => self.render_main_dock(ctx, 1.0),
+            PanelContent::Settings => self.render_settings(ctx, 1.0),
+            PanelContent::Browser { .. } | PanelContent::Video { .. } => {}
+        }
+    }
+
     pub fn render(&mut self, ctx: &Context, vr_mode_active: bool) {
-        if !vr_mode_active { return; }
         ctx.set_pixels_per_point(1.0);
+        crate::theme::select(ctx, self.params.theme, self.params.accent, self.params.accessibility());
+        // Touch seeking works in 2D too, where nothing else is drawn
+        if let Some(preview) = &self.seek_preview {
+            Self::render_seek_preview(ctx, preview);
+        }
+        // So does the walkthrough, which starts before the phone is in the headset
+        if self.tutorial.is_some() {
+            self.render_tutorial(ctx);
+        }
+        for text in std::mem::take(&mut *POSTED_TOASTS.lock().unwrap()) {
+            self.show_toast(text);
+        }
+        if !vr_mode_active {
+            // Flat over the screen: its own controls and the prompts that come with playback
+            if self.locked {
+                self.render_lock_badge(ctx);
+            } else {
+                self.render_flat_controls(ctx);
+            }
+            if self.resume_prompt.visible {
+                self.render_resume_prompt(ctx);
+            }
+            if self.buffering.label.is_some() {
+                self.render_buffering(ctx);
+            }
+            if self.playback_error.visible() {
+                self.render_playback_error(ctx);
+            }
+            if !self.toasts.is_empty() {
+                self.render_toast(ctx);
+            }
+            return;
+        }
 
-        if self.main_menu_visible {
-            self.render_main_dock(ctx);
+        // Closed panels keep drawing until they've faded out
+        let dt = ctx.input(|i| i.stable_dt).min(0.1);
+        let dock = self.dock_tween.update(self.main_menu_visible, dt, transition(TRANSITION_SECS));
+        let media = self.media_tween.update(self.file_browser.visible, dt, transition(TRANSITION_SECS));
+        if (dock > 0.0 && dock < 1.0) || (media > 0.0 && media < 1.0) {
+            ctx.request_repaint();
+        }
+        if dock > 0.0 {
+            self.render_main_dock(ctx, dock);
+        }
+        if media > 0.0 {
+            self.render_media_center(ctx, media);
         }
         if self.file_browser.visible {
-            self.render_media_center(ctx);
+            if self.file_browser.show_details {
+                self.render_media_details(ctx);
+            }
+            if self.file_browser.entry_menu.is_some() {
+                self.render_entry_menu(ctx);
+            }
+        }
+        if self.queue.visible {
+            self.render_queue(ctx);
+        }
+        if self.resume_prompt.visible {
+            self.render_resume_prompt(ctx);
+        }
+        if self.buffering.label.is_some() {
+            self.render_buffering(ctx);
+        }
+        if self.playback_error.visible() {
+            self.render_playback_error(ctx);
         }
         if self.params.web_mode {
             self.render_web_toolbar(ctx);
         }
-        if self.keyboard.visible {
+        if self.url_dialog.visible {
+            self.render_url_dialog(ctx);
+        } else if self.keyboard.visible {
             self.render_keyboard(ctx);
         }
+        // The dock shows the same, with the seek bar
+        if self.osd.is_some() && !self.main_menu_visible {
+            self.render_osd(ctx);
+        }
+        if self.volume_changed.is_some() && !self.main_menu_visible {
+            self.render_volume_osd(ctx);
+        }
+        if !self.toasts.is_empty() {
+            self.render_toast(ctx);
+        }
+        if self.params.button_hints && !crate::gamepad::controllers().is_empty() {
+            self.render_hint_bar(ctx);
+        }
+        if self.gaze_active && !self.has_open_panel() {
+            self.render_gaze_menu_button(ctx);
+        }
+        if self.locked {
+            self.render_lock_badge(ctx);
+        }
+        let status = self.device_status.clone().filter(|_| self.params.status_hud);
+        if let Some(status) = &status {
+            self.render_status_hud(ctx, status);
+        }
+        if let Some(level) = self.controller_battery {
+            // Shown with the menu, or on its own once it runs low; under the status HUD
+            if self.main_menu_visible || level < crate::gamepad::BATTERY_LOW {
+                self.render_controller_battery(ctx, level, if status.is_some() { 100.0 } else { 40.0 });
+            }
+        }
     }
 
-    // ── macOS-style dock ──────────────────────────────────────────────────────
-    fn render_main_dock(&mut self, ctx: &Context) {
-        if let MenuState::LensSettings = self.menu_state {
-            self.render_lens_settings(ctx);
+    // ── 2D controls ───────────────────────────────────────────────────────────
+    fn render_flat_controls(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
+        let controls = &mut self.flat_controls;
+        if ctx.is_pointer_over_area() && ctx.input(|i| i.pointer.any_down()) {
+            controls.since = Instant::now();
+        }
+        let playing = self.seek_bar.is_some() && !controls.paused;
+        if playing && controls.since.elapsed().as_secs() >= FLAT_CONTROLS_SECS {
+            controls.visible = false;
+        }
+        if !controls.visible {
+            self.scrub = None;
             return;
         }
-        egui::Window::new("dock")
-            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+        let width = ctx.screen_rect().width() - 2.0 * FLAT_MARGIN;
+        let frame = egui::Frame::window(&ctx.style())
+            .inner_margin(Margin::symmetric(20.0, 12.0))
+            .rounding(Rounding::same(24.0))
+            .stroke(theme.stroke)
+            .fill(theme.overlay(215));
+        let button = |ui: &mut egui::Ui, text: String| ui.add(
+            egui::Button::new(egui::RichText::new(text).size(34.0).color(theme.text))
+                .min_size(egui::vec2(96.0, 96.0))
+                .rounding(Rounding::same(20.0))
+                .fill(theme.control(255)));
+
+        egui::Window::new("flat_top")
+            .anchor(egui::Align2::LEFT_TOP, egui::vec2(FLAT_MARGIN, FLAT_MARGIN))
             .resizable(false).collapsible(false).title_bar(false)
-            .frame(egui::Frame::window(&ctx.style())
-                .inner_margin(Margin::same(18.0))
-                .rounding(Rounding::same(28.0))
-                .stroke(Stroke::new(1.0, Color32::from_white_alpha(30)))
-                .fill(Color32::from_rgba_unmultiplied(24, 24, 32, 235)))
+            .frame(frame)
             .show(ctx, |ui| {
-                ui.vertical_centered(|ui| {
-                    ui.horizontal(|ui| {
-                        ui.spacing_mut().item_spacing.x = 12.0;
+                ui.set_width(width - 40.0);
+                ui.horizontal(|ui| {
+                    if button(ui, format!("📂 {}", tr("Open"))).clicked() {
+                        self.params.select_video_flag = true;
+                    }
+                    ui.add_space(16.0);
+                    match &self.flat_controls.title {
+                        Some(title) => ui.label(egui::RichText::new(title).size(28.0).strong().color(theme.text)),
+                        None => ui.label(egui::RichText::new(tr("Open a video to start")).size(24.0).color(theme.text_weak)),
+                    };
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let enter = egui::Button::new(egui::RichText::new(format!("👓 {}", tr("Enter VR"))).size(34.0).color(theme.on_accent))
+                            .min_size(egui::vec2(96.0, 96.0))
+                            .rounding(Rounding::same(20.0))
+                            .fill(theme.accent);
+                        if ui.add(enter).clicked() {
+                            actions::push(Action::ToggleVr);
+                        }
+                    });
+                });
+            });
+
+        let Some(bar) = &self.seek_bar else { return };
+        egui::Window::new("flat_bottom")
+            .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(FLAT_MARGIN, -FLAT_MARGIN))
+            .resizable(false).collapsible(false).title_bar(false)
+            .frame(frame)
+            .show(ctx, |ui| {
+                ui.set_width(width - 40.0);
+                ui.horizontal(|ui| {
+                    if button(ui, "⏪".into()).clicked() {
+                        actions::push(Action::SeekBy(-actions::SEEK_STEP_US));
+                    }
+                    let play = if self.flat_controls.paused { "▶" } else { "⏸" };
+                    if button(ui, play.into()).clicked() {
+                        actions::push(Action::PlayPause);
+                    }
+                    if button(ui, "⏩".into()).clicked() {
+                        actions::push(Action::SeekBy(actions::SEEK_STEP_US));
+                    }
+                    ui.add_space(16.0);
+                    ui.vertical(|ui| {
+                        ui.add_space(24.0);
+                        if let Some(pos) = seek_bar(ui, bar, &mut self.scrub, false) {
+                            actions::push(Action::SeekTo(pos));
+                        }
+                    });
+                });
+            });
+    }
+
+    // ── macOS-style dock ──────────────────────────────────────────────────────
+    /// The dock, or the settings in its place; `t` is how far it has come in.
+    fn render_main_dock(&mut self, ctx: &Context, t: f32) {
+        let theme = crate::theme::current();
+        if let MenuState::Settings = self.menu_state {
+            self.render_settings(ctx, t);
+            return;
+        }
+        egui::Window::new("dock")
+            .anchor(egui::Align2::CENTER_CENTER, slide_in(egui::vec2(0.0, 0.0), t))
+            .resizable(false).collapsible(false).title_bar(false)
+            .interactable(self.main_menu_visible)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::same(18.0))
+                .rounding(Rounding::same(28.0))
+                .stroke(theme.stroke)
+                .fill(theme.panel(235))
+                .multiply_with_opacity(t))
+            .show(ctx, |ui| {
+                ui.multiply_opacity(t);
+                ui.vertical_centered(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.spacing_mut().item_spacing.x = 12.0;
                         for (i, item) in DOCK_ITEMS.iter().enumerate() {
-                            let selected = i == self.dock_selected;
+                            let selected = i == self.dock_selected && !self.seek_focused;
                             let toggled = matches!(item, DockItem::Gyro if self.params.gyro_enabled)
-                                || matches!(item, DockItem::Web | DockItem::Firefox if self.params.web_mode);
+                                || matches!(item, DockItem::Web | DockItem::Firefox if self.params.web_mode)
+                                || matches!(item, DockItem::AutoNext if self.params.auto_advance)
+                                || matches!(item, DockItem::Loop if self.params.loop_mode != LoopMode::Off);
                             let size = if selected { 100.0 } else { 74.0 };
                             let icon_size = if selected { 52.0 } else { 36.0 };
-                            let bg = if selected { Color32::from_rgb(70, 140, 250) }
-                                else if toggled { Color32::from_rgb(45, 90, 150) }
-                                else { Color32::from_rgba_unmultiplied(45, 45, 58, 230) };
+                            let bg = if selected { theme.accent }
+                                else if toggled { theme.accent_dim }
+                                else { theme.control(230) };
                             let icon_col = if *item == DockItem::Exit && !selected {
-                                Color32::from_rgb(255, 110, 110)
-                            } else { Color32::WHITE };
+                                theme.danger
+                            } else if selected { theme.on_accent } else { theme.text };
                             let btn = egui::Button::new(
                                     egui::RichText::new(item.icon()).size(icon_size).color(icon_col))
                                 .min_size(egui::vec2(size, size))
                                 .rounding(Rounding::same(20.0))
                                 .fill(bg);
                             let resp = ui.add(btn);
-                            if resp.clicked() { self.dock_selected = i; self.dock_activate(); }
-                            if resp.hovered() { self.dock_selected = i; }
+                            if resp.clicked() { self.dock_selected = i; self.seek_focused = false; self.dock_activate(); }
+                            if resp.hovered() { self.dock_selected = i; self.seek_focused = false; }
                         }
                     });
                     ui.add_space(10.0);
                     let sel = DOCK_ITEMS[self.dock_selected];
-                    let label = if sel == DockItem::Stereo3D {
-                        stereo_label(self.params.stereo_mode)
-                    } else { sel.label() };
-                    ui.label(egui::RichText::new(label).size(26.0).strong().color(Color32::WHITE));
+                    let label = match sel {
+                        DockItem::Stereo3D => stereo_label(self.params.stereo_mode).to_string(),
+                        DockItem::Loop     => self.params.loop_mode.label().to_string(),
+                        DockItem::Aspect   => self.params.aspect_label(),
+                        _ => sel.label().to_string(),
+                    };
+                    ui.label(egui::RichText::new(label).size(26.0).strong().color(theme.text));
+                    if let Some(playing) = &self.now_playing {
+                        ui.label(egui::RichText::new(playing).size(14.0).color(theme.text_weak));
+                    }
+                    if let Some(bar) = &self.seek_bar {
+                        ui.add_space(6.0);
+                        if let Some(pos) = seek_bar(ui, bar, &mut self.scrub, self.seek_focused) {
+                            actions::push(Action::SeekTo(pos));
+                        }
+                    } else {
+                        self.seek_focused = false;
+                        self.scrub = None;
+                    }
+                    if let Some((mut level, max)) = self.volume {
+                        ui.add_space(6.0);
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(Self::volume_icon(level, max)).size(20.0).color(theme.text));
+                            if ui.add(egui::Slider::new(&mut level, 0..=max).show_value(false)).changed() {
+                                self.volume = Some((level, max));
+                                self.volume_request = Some(level);
+                            }
+                            ui.label(egui::RichText::new(format!("{} / {}", level, max)).size(13.0).color(theme.text_weak));
+                        });
+                    }
                 });
             });
     }
 
-    fn render_lens_settings(&mut self, ctx: &Context) {
-        egui::Window::new("lens_settings")
-            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+    // ── Settings window: sections down the left, the section's settings beside ─
+    fn render_settings(&mut self, ctx: &Context, t: f32) {
+        let theme = crate::theme::current();
+        use crate::gamepad;
+        egui::Window::new("settings")
+            .anchor(egui::Align2::CENTER_CENTER, slide_in(egui::vec2(0.0, 0.0), t))
             .resizable(false).collapsible(false).title_bar(false)
+            .interactable(self.main_menu_visible)
             .frame(egui::Frame::window(&ctx.style())
                 .inner_margin(Margin::same(20.0))
                 .rounding(Rounding::same(24.0))
-                .fill(Color32::from_rgba_unmultiplied(24, 24, 32, 240)))
+                .fill(theme.panel(240))
+                .multiply_with_opacity(t))
             .show(ctx, |ui| {
+                ui.multiply_opacity(t);
                 ui.horizontal(|ui| {
                     if ui.add(egui::Button::new(egui::RichText::new("⬅").size(24.0))
                         .min_size(egui::vec2(50.0, 50.0))).clicked() {
-                        self.menu_state = MenuState::Main;
+                        self.close_settings();
                     }
                     ui.add_space(12.0);
+                    ui.label(egui::RichText::new(tr("Settings")).size(24.0).strong().color(theme.text));
+                    ui.add_space(24.0);
+                    ui.label(egui::RichText::new(tr("Saved as you change them")).size(14.0).color(theme.text_weak));
+                });
+                ui.add_space(12.0);
+                ui.horizontal_top(|ui| {
                     ui.vertical(|ui| {
-                        ui.label("Lens Size");
-                        ui.add(egui::Slider::new(&mut self.params.lens_radius, 0.5..=1.5).fixed_decimals(2));
-                    });
-                    ui.add_space(12.0);
-                    ui.vertical(|ui| {
-                        ui.label("Lens Dist");
-                        ui.add(egui::Slider::new(&mut self.params.lens_center_offset, -0.15..=0.15).fixed_decimals(3));
+                        for section in SettingsSection::ALL {
+                            let selected = self.settings_section == section;
+                            let tab = ui.add(egui::Button::new(egui::RichText::new(section.label()).size(20.0))
+                                .selected(selected).min_size(egui::vec2(200.0, 56.0)));
+                            if selected && std::mem::take(&mut self.settings_focus_tab) {
+                                tab.request_focus();
+                            }
+                            if tab.clicked() && !selected {
+                                gamepad::cancel_capture();
+                                self.settings_section = section;
+                            }
+                        }
                     });
-                    ui.add_space(12.0);
-                    ui.vertical(|ui| {
-                        ui.label("Zoom");
-                        ui.add(egui::Slider::new(&mut self.params.content_scale, 0.5..=3.0).fixed_decimals(2));
+                    ui.add_space(16.0);
+                    egui::ScrollArea::vertical().max_height(820.0).show(ui, |ui| {
+                        ui.set_width(980.0);
+                        match self.settings_section {
+                            SettingsSection::Display => self.settings_display(ui),
+                            SettingsSection::Tracking => self.settings_tracking(ui),
+                            SettingsSection::Playback => self.settings_playback(ui),
+                            SettingsSection::Input => self.settings_input(ui),
+                            SettingsSection::Advanced => self.settings_advanced(ui),
+                        }
+                        // Keep the setting the D-pad moved to in view
+                        let focused = ui.ctx().memory(|m| m.focused());
+                        if focused != self.settings_focus {
+                            self.settings_focus = focused;
+                            if let Some(response) = focused.and_then(|id| ui.ctx().read_response(id)) {
+                                ui.scroll_to_rect(response.rect, None);
+                            }
+                        }
                     });
                 });
             });
     }
 
+    fn settings_heading(ui: &mut egui::Ui, text: &str) {
+        let theme = crate::theme::current();
+        ui.add_space(8.0);
+        ui.label(egui::RichText::new(text).size(18.0).strong().color(theme.text));
+        ui.add_space(4.0);
+    }
+
+    /// Lens, eye spacing, how the screen is framed, and the picture.
+    fn settings_display(&mut self, ui: &mut egui::Ui) {
+        let theme = crate::theme::current();
+        Self::settings_heading(ui, tr("Headset"));
+        egui::Grid::new("settings_headset").num_columns(2).spacing(egui::vec2(24.0, 14.0)).show(ui, |ui| {
+            // Saved lens values, one per headset shell
+            ui.label(tr("Preset"));
+            ui.horizontal(|ui| {
+                let current = self.lens_presets.iter().position(|p| p.matches(&self.params));
+                let shown = current.map_or(tr("Custom"), |i| self.lens_presets[i].name.as_str());
+                let mut picked = None;
+                egui::ComboBox::from_id_salt("lens_preset").selected_text(shown).width(220.0).show_ui(ui, |ui| {
+                    for (i, preset) in self.lens_presets.iter().enumerate() {
+                        if ui.selectable_label(current == Some(i), &preset.name).clicked() {
+                            picked = Some(i);
+                        }
+                    }
+                    if self.lens_presets.is_empty() {
+                        ui.label(egui::RichText::new(tr("None saved yet")).color(theme.text_weak));
+                    }
+                });
+                if let Some(i) = picked {
+                    let preset = self.lens_presets[i].clone();
+                    self.params.apply_lens_preset(&preset);
+                }
+                if ui.button(tr("Save as…")).clicked() {
+                    self.open_preset_name();
+                }
+                if let Some(i) = current {
+                    if ui.button(tr("Delete")).clicked() {
+                        let removed = self.lens_presets.remove(i);
+                        self.show_toast(trf("Deleted {}", &[&removed.name]));
+                    }
+                }
+            });
+            ui.end_row();
+            ui.label(tr("Lens Size"));
+            ui.add(egui::Slider::new(&mut self.params.lens_radius, 0.5..=1.5).fixed_decimals(2));
+            ui.end_row();
+            ui.label(tr("Lens Distance"));
+            ui.add(egui::Slider::new(&mut self.params.lens_center_offset, -0.15..=0.15).fixed_decimals(3));
+            ui.end_row();
+            ui.label(tr("IPD (mm)"));
+            ui.add(egui::Slider::new(&mut self.params.ipd, 54.0..=74.0).fixed_decimals(1));
+            ui.end_row();
+            ui.label(tr("Field of View (°)"));
+            ui.add(egui::Slider::new(&mut self.params.fov, 70.0..=120.0).fixed_decimals(0));
+            ui.end_row();
+            ui.label(tr("Screen Brightness"));
+            ui.add_enabled(!self.params.max_brightness_vr, egui::Slider::new(&mut self.params.screen_brightness, 0.0..=1.0)
+                .custom_formatter(|v, _| if v <= 0.0 { tr("System").into() } else { format!("{:.0}%", v * 100.0) }));
+            ui.end_row();
+            ui.label("");
+            ui.checkbox(&mut self.params.max_brightness_vr, tr("Full brightness in VR"));
+            ui.end_row();
+            ui.label(tr("Status"));
+            ui.checkbox(&mut self.params.status_hud, tr("Clock, battery and Wi-Fi"));
+            ui.end_row();
+        });
+        Self::settings_heading(ui, tr("Screen"));
+        egui::Grid::new("settings_screen").num_columns(2).spacing(egui::vec2(24.0, 14.0)).show(ui, |ui| {
+            ui.label(tr("Zoom"));
+            if ui.add(egui::Slider::new(&mut self.params.content_scale, 0.5..=3.0).fixed_decimals(2)).changed() {
+                self.params.target_scale = self.params.content_scale;
+            }
+            ui.end_row();
+            ui.label(tr("Aspect"));
+            ui.horizontal(|ui| {
+                for (mode, label) in [(AspectMode::Fit, "Fit"), (AspectMode::Fill, "Fill 16:9"),
+                    (AspectMode::Stretch, "Stretch 16:9"), (AspectMode::Crop, "Crop")] {
+                    ui.selectable_value(&mut self.params.aspect_mode, mode, tr(label));
+                }
+            });
+            ui.end_row();
+            ui.label(tr("Crop Ratio"));
+            ui.add_enabled(self.params.aspect_mode == AspectMode::Crop,
+                egui::Slider::new(&mut self.params.crop_aspect, 1.33..=2.76).fixed_decimals(2));
+            ui.end_row();
+            ui.label(tr("Menus"));
+            ui.horizontal(|ui| {
+                for anchor in UiAnchor::ALL {
+                    ui.selectable_value(&mut self.params.ui_anchor, anchor, anchor.label());
+                }
+            });
+            ui.end_row();
+            ui.label(tr("Windows"));
+            ui.checkbox(&mut self.params.arrange_windows, tr("Arrange in a curve"));
+            ui.end_row();
+            // Also set with a two-finger twist on the screen
+            ui.label(tr("Horizon (°)"));
+            ui.add(egui::Slider::new(&mut self.params.horizon_roll, -MAX_HORIZON_ROLL..=MAX_HORIZON_ROLL).fixed_decimals(1));
+            ui.end_row();
+        });
+        Self::settings_heading(ui, tr("Picture (this session)"));
+        egui::Grid::new("settings_picture").num_columns(2).spacing(egui::vec2(24.0, 14.0)).show(ui, |ui| {
+            ui.label(tr("Brightness"));
+            ui.add(egui::Slider::new(&mut self.params.brightness, -0.3..=0.3).fixed_decimals(2));
+            ui.end_row();
+            ui.label(tr("Contrast"));
+            ui.add(egui::Slider::new(&mut self.params.contrast, 0.5..=1.5).fixed_decimals(2));
+            ui.end_row();
+            ui.label(tr("Saturation"));
+            ui.add(egui::Slider::new(&mut self.params.saturation, 0.0..=2.0).fixed_decimals(2));
+            ui.end_row();
+            ui.label(tr("Gamma"));
+            ui.add(egui::Slider::new(&mut self.params.gamma, 0.5..=2.0).fixed_decimals(2));
+            ui.end_row();
+            ui.label("");
+            if ui.button(tr("Reset picture")).clicked() {
+                self.params.reset_picture();
+            }
+            ui.end_row();
+        });
+    }
+
+    /// Head-tracking source, filtering and the headset sensor.
+    fn settings_tracking(&mut self, ui: &mut egui::Ui) {
+        use crate::sensors::FusionMode;
+        egui::Grid::new("settings_tracking").num_columns(2).spacing(egui::vec2(24.0, 14.0)).show(ui, |ui| {
+            ui.label(tr("Head Tracking"));
+            ui.checkbox(&mut self.params.gyro_enabled, tr("On"));
+            ui.end_row();
+            ui.label(tr("Sensor Fusion"));
+            if ui.add(egui::Button::new(egui::RichText::new(self.params.fusion_mode.label()).size(16.0))
+                .min_size(egui::vec2(260.0, 40.0))).clicked() {
+                self.params.fusion_mode = self.params.fusion_mode.next();
+            }
+            ui.end_row();
+            ui.label(tr("Sensor Rate"));
+            if ui.add(egui::Button::new(egui::RichText::new(self.params.sensor_rate.label()).size(16.0))
+                .min_size(egui::vec2(220.0, 40.0))).clicked() {
+                self.params.sensor_rate = self.params.sensor_rate.next();
+            }
+            ui.end_row();
+            // Higher follows gravity / north harder (less drift, more jitter)
+            ui.label(tr("Filter Gain"));
+            ui.add_enabled(!matches!(self.params.fusion_mode, FusionMode::RotationVector | FusionMode::External),
+                egui::Slider::new(&mut self.params.fusion_gain, 0.01..=0.5).logarithmic(true).fixed_decimals(3));
+            ui.end_row();
+            // Slow, rate-limited; for phones whose rotation vector has no compass
+            ui.label(tr("Compass Yaw Fix"));
+            ui.add_enabled(self.params.fusion_mode == FusionMode::RotationVector,
+                egui::Checkbox::new(&mut self.params.mag_yaw_correction, tr("Correct drift")));
+            ui.end_row();
+            // Damps jitter at the cost of a little lag; 0 is off
+            ui.label(tr("Smoothing"));
+            ui.add(egui::Slider::new(&mut self.params.head_smoothing, 0.0..=1.0).fixed_decimals(2));
+            ui.end_row();
+            // Needs ARCore and the camera; leaning moves the viewpoint
+            ui.label(tr("Positional"));
+            ui.checkbox(&mut self.params.positional_tracking, tr("6DOF (ARCore)"));
+            ui.end_row();
+            // Eyes pivot about the neck, not about themselves; 0 is off
+            ui.label(tr("Neck Model"));
+            ui.add_enabled(!self.params.positional_tracking,
+                egui::Slider::new(&mut self.params.neck_model, 0.0..=2.0).fixed_decimals(1));
+            ui.end_row();
+            // Proximity sensor: the phone was taken out of the headset
+            ui.label(tr("Headset Removed"));
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.params.proximity_pause, tr("Pause video"));
+                ui.checkbox(&mut self.params.proximity_blank, tr("Blank screen"));
+            });
+            ui.end_row();
+        });
+        ui.add_space(16.0);
+        self.render_sensor_diagnostics(ui);
+    }
+
+    /// What happens at the end of a file, and the current video's 3D layout.
+    fn settings_playback(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("settings_playback").num_columns(2).spacing(egui::vec2(24.0, 14.0)).show(ui, |ui| {
+            ui.label(tr("Auto-advance"));
+            ui.checkbox(&mut self.params.auto_advance, tr("Play the next file in the folder"));
+            ui.end_row();
+            ui.label(tr("Loop"));
+            ui.horizontal(|ui| {
+                for (mode, label) in [(LoopMode::Off, "Off"), (LoopMode::One, "Current file"), (LoopMode::All, "Folder / queue")] {
+                    ui.selectable_value(&mut self.params.loop_mode, mode, tr(label));
+                }
+            });
+            ui.end_row();
+            // Per video; D-pad ←/→ during playback cycles it too
+            ui.label(tr("3D Layout"));
+            ui.horizontal(|ui| {
+                for mode in 0..STEREO_MODES {
+                    ui.selectable_value(&mut self.params.stereo_mode, mode, stereo_label(mode));
+                }
+            });
+            ui.end_row();
+        });
+    }
+
+    /// Controller sticks and buttons, the pointer, and gaze clicking.
+    fn settings_input(&mut self, ui: &mut egui::Ui) {
+        let theme = crate::theme::current();
+        use crate::gamepad;
+        let capturing = gamepad::capturing();
+        Self::settings_heading(ui, tr("Pointing"));
+        egui::Grid::new("settings_pointing").num_columns(2).spacing(egui::vec2(24.0, 14.0)).show(ui, |ui| {
+            // Seated: turn the view with the right stick instead of the neck
+            ui.label(tr("Right Stick Look (°/s)"));
+            ui.add(egui::Slider::new(&mut self.params.stick_look_speed, 0.0..=180.0).fixed_decimals(0));
+            ui.end_row();
+            // Left stick moves a pointer over open panels, X clicks
+            ui.label(tr("Left Stick Pointer"));
+            ui.checkbox(&mut self.params.stick_pointer, tr("In menus"));
+            ui.end_row();
+            // Hold L2 over a panel and point the controller (needs Android 12+)
+            ui.label(tr("Motion Aim"));
+            ui.checkbox(&mut self.params.motion_aim, tr("Hold L2 to aim"));
+            ui.end_row();
+            // No controller connected: look at a control and hold still to click
+            ui.label(tr("Gaze Dwell (s)"));
+            ui.add(egui::Slider::new(&mut self.params.gaze_dwell, 0.0..=3.0).fixed_decimals(1));
+            ui.end_row();
+        });
+        Self::settings_heading(ui, tr("Controller"));
+        egui::Grid::new("settings_controller").num_columns(2).spacing(egui::vec2(24.0, 14.0)).show(ui, |ui| {
+            // Holding a D-pad direction scrolls lists
+            ui.label(tr("Repeat Delay (s)"));
+            ui.add(egui::Slider::new(&mut self.params.nav_repeat_delay, 0.15..=1.0).fixed_decimals(2));
+            ui.end_row();
+            ui.label(tr("Repeat Rate (/s)"));
+            ui.add(egui::Slider::new(&mut self.params.nav_repeat_rate, 0.0..=30.0).fixed_decimals(0));
+            ui.end_row();
+            // Short pulses on menu open, file picked, seek and recenter
+            ui.label(tr("Rumble"));
+            ui.checkbox(&mut self.params.haptics, tr("Confirm actions"));
+            ui.end_row();
+            ui.label(tr("Hints"));
+            ui.checkbox(&mut self.params.button_hints, tr("Show buttons"));
+            ui.end_row();
+            // Per-stick shaping, applied as the axes come in
+            for (name, tuning) in [("Left Stick", &mut self.params.left_stick), ("Right Stick", &mut self.params.right_stick)] {
+                ui.label(tr(name));
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Deadzone ↔"));
+                        ui.add(egui::Slider::new(&mut tuning.deadzone_x, 0.0..=0.5).fixed_decimals(2));
+                        ui.add_space(12.0);
+                        ui.label("↕");
+                        ui.add(egui::Slider::new(&mut tuning.deadzone_y, 0.0..=0.5).fixed_decimals(2));
+                    });
+                    ui.horizontal(|ui| {
+                        for curve in [gamepad::StickCurve::Linear, gamepad::StickCurve::Expo] {
+                            ui.selectable_value(&mut tuning.curve, curve, curve.label());
+                        }
+                        ui.add_space(12.0);
+                        ui.label(tr("Sensitivity"));
+                        ui.add(egui::Slider::new(&mut tuning.sensitivity, 0.25..=3.0).fixed_decimals(2));
+                    });
+                });
+                ui.end_row();
+            }
+        });
+        let pads = gamepad::controllers();
+        Self::settings_heading(ui, tr("Controllers"));
+        ui.horizontal(|ui| {
+            if pads.is_empty() {
+                ui.label(egui::RichText::new(tr("None yet — press a button")).size(14.0).color(theme.text_weak));
+            }
+            for (i, device) in pads.iter().enumerate() {
+                let role = tr(match i {
+                    0 => "View",
+                    1 if self.params.remote_controller => "Remote",
+                    _ => "Shared",
+                });
+                ui.label(egui::RichText::new(format!("#{} {} ({})", i + 1, role, device))
+                    .size(14.0).color(theme.text_soft));
+                ui.add_space(8.0);
+            }
+            if pads.len() >= 2 && ui.add(egui::Button::new(egui::RichText::new(tr("Swap")).size(16.0))
+                .min_size(egui::vec2(80.0, 34.0))).clicked() {
+                gamepad::swap_roles();
+            }
+        });
+        ui.checkbox(&mut self.params.remote_controller, tr("Second is a media remote"));
+        let profile = gamepad::profile();
+        Self::settings_heading(ui, &trf("Buttons ({} layout)", &[&profile.name()]));
+        let hint = match capturing {
+            Some(button) => trf("Press the button to use as {}…", &[&profile.label(button)]),
+            None => tr("Click a control, then press the button to use for it").into(),
+        };
+        ui.horizontal(|ui| {
+            if ui.button(tr("Reset buttons")).clicked() {
+                gamepad::cancel_capture();
+                gamepad::reset_bindings();
+            }
+            ui.add_space(16.0);
+            ui.label(egui::RichText::new(hint).size(14.0).color(theme.text_weak));
+        });
+        egui::Grid::new("controller_grid").spacing(egui::vec2(16.0, 6.0)).show(ui, |ui| {
+            for button in gamepad::BUTTONS {
+                ui.label(egui::RichText::new(profile.label(button)).size(16.0).color(theme.text));
+                ui.label(egui::RichText::new(button.role()).size(14.0).color(theme.text_weak));
+                let (text, color) = if capturing == Some(button) {
+                    ("…".to_string(), Color32::from_rgb(255, 200, 80))
+                } else if gamepad::is_remapped(button) {
+                    (gamepad::keycode_label(gamepad::binding(button)), theme.accent)
+                } else {
+                    (gamepad::keycode_label(gamepad::binding(button)), theme.text)
+                };
+                if ui.add(egui::Button::new(egui::RichText::new(text).size(15.0).color(color))
+                    .min_size(egui::vec2(150.0, 34.0))).clicked() {
+                    if capturing == Some(button) {
+                        gamepad::cancel_capture();
+                    } else {
+                        gamepad::start_capture(button);
+                    }
+                }
+                ui.end_row();
+            }
+        });
+        ui.add_space(8.0);
+        let shortcuts: Vec<String> = actions::CHORDS.iter()
+            .map(|c| format!("{} + {}  {}", profile.label(c.buttons[0]), profile.label(c.buttons[1]), tr(c.name)))
+            .collect();
+        ui.label(egui::RichText::new(trf("Shortcuts:  {}", &[&shortcuts.join("  ·  ")]))
+            .size(14.0).color(theme.text_weak));
+    }
+
+    /// The UI language and colours, forced decoders per format, the tutorial,
+    /// and putting everything back.
+    fn settings_advanced(&mut self, ui: &mut egui::Ui) {
+        let theme = crate::theme::current();
+        Self::settings_heading(ui, tr("Language"));
+        ui.horizontal(|ui| {
+            for language in crate::i18n::Language::ALL {
+                ui.selectable_value(&mut self.params.language, language, language.label());
+            }
+        });
+        Self::settings_heading(ui, tr("Theme"));
+        ui.horizontal(|ui| {
+            for preset in crate::theme::ThemePreset::ALL {
+                ui.selectable_value(&mut self.params.theme, preset, preset.label());
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(tr("Accent")).size(16.0).color(theme.text));
+            ui.selectable_value(&mut self.params.accent, None, tr("Theme's own"));
+            for color in crate::theme::ACCENTS {
+                let chosen = self.params.accent == Some(color);
+                if ui.add(egui::Button::new("").min_size(egui::vec2(36.0, 36.0)).rounding(Rounding::same(18.0))
+                    .fill(color).stroke(if chosen { Stroke::new(3.0, theme.text) } else { Stroke::NONE })).clicked() {
+                    self.params.accent = Some(color);
+                }
+            }
+            // Any other colour
+            let mut rgb = [theme.accent.r(), theme.accent.g(), theme.accent.b()];
+            if ui.color_edit_button_srgb(&mut rgb).changed() {
+                self.params.accent = Some(Color32::from_rgb(rgb[0], rgb[1], rgb[2]));
+            }
+        });
+        Self::settings_heading(ui, tr("Accessibility"));
+        ui.checkbox(&mut self.params.reduce_motion, tr("Reduce motion"));
+        ui.checkbox(&mut self.params.bold_controls, tr("High-contrast controls"));
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(tr("Color vision")).size(16.0).color(theme.text));
+            for vision in crate::theme::ColorVision::ALL {
+                ui.selectable_value(&mut self.params.color_vision, vision, vision.label());
+            }
+        });
+        // Each button cycles Auto → every decoder the device lists for that format
+        Self::settings_heading(ui, tr("Decoders"));
+        ui.label(egui::RichText::new(tr("Applies to the next video opened"))
+            .size(14.0).color(theme.text_weak));
+        let mimes = crate::codecs::video_mimes();
+        if mimes.is_empty() {
+            ui.label(egui::RichText::new(tr("Decoder list unavailable")).size(16.0).color(theme.text_soft));
+        }
+        egui::Grid::new("decoder_grid").spacing(egui::vec2(16.0, 8.0)).show(ui, |ui| {
+            for mime in mimes {
+                let names: Vec<&str> = crate::codecs::decoders_for(mime).map(|c| c.name.as_str()).collect();
+                let current = crate::codecs::preferred(mime);
+                ui.label(egui::RichText::new(crate::codecs::format_label(mime)).size(16.0).color(theme.text));
+                let label = current.as_deref().unwrap_or(tr("Auto"));
+                if ui.add(egui::Button::new(egui::RichText::new(label).size(15.0))
+                    .min_size(egui::vec2(360.0, 40.0))).clicked() {
+                    // Auto → first → … → last → Auto
+                    let next = match current.as_deref().and_then(|c| names.iter().position(|n| *n == c)) {
+                        None => names.first(),
+                        Some(i) => names.get(i + 1),
+                    };
+                    crate::codecs::set_preferred(mime, next.copied());
+                }
+                ui.end_row();
+            }
+        });
+        Self::settings_heading(ui, tr("Help"));
+        if ui.button(tr("Show the tutorial again")).clicked() {
+            self.close_all_panels();
+            self.show_tutorial();
+        }
+        Self::settings_heading(ui, tr("Defaults"));
+        ui.horizontal(|ui| {
+            if ui.button(tr("Reset all settings")).clicked() {
+                self.reset_settings();
+            }
+            ui.label(egui::RichText::new(tr("Button remaps and decoders are kept"))
+                .size(14.0).color(theme.text_weak));
+        });
+    }
+
+    /// Diagnostics lines under the tracking settings: is bad tracking the sensors
+    /// (low rate, stale events, startup error) or the renderer (low fps)?
+    fn render_sensor_diagnostics(&self, ui: &mut egui::Ui) {
+        const STALE: std::time::Duration = std::time::Duration::from_millis(100);
+        let theme = crate::theme::current();
+        let warn = Color32::from_rgb(255, 170, 60);
+        ui.label(egui::RichText::new(tr("Diagnostics")).strong());
+        let Some(status) = &self.sensor_status else {
+            ui.colored_label(warn, tr("No sensor input"));
+            return;
+        };
+        if let Some(error) = &status.error {
+            ui.colored_label(theme.danger, trf("Error: {}", &[error]));
+        }
+        if !status.source.is_empty() {
+            ui.label(trf("Source: {}", &[&status.source]));
+        }
+        let age = match status.last_event_age {
+            Some(age) if age > STALE => egui::RichText::new(trf("last update {} s ago", &[&format!("{:.1}", age.as_secs_f32())])).color(warn),
+            Some(age) => egui::RichText::new(trf("last update {} ms ago", &[&age.as_millis()])),
+            None => egui::RichText::new(tr("no updates yet")).color(warn),
+        };
+        ui.horizontal(|ui| {
+            ui.label(trf("Tracking {} Hz  ·", &[&format!("{:.0}", status.rate_hz)]));
+            ui.label(age);
+            ui.label(trf("·  Rendering {} fps", &[&format!("{:.0}", self.render_fps)]));
+        });
+    }
+
     // ── Media Center — Nokia coverflow carousel (light frosted glass) ─────────
-    fn render_media_center(&mut self, ctx: &Context) {
-        let txt    = Color32::from_rgb(26, 26, 32);
-        let txt2   = Color32::from_rgb(108, 110, 120);
-        let accent = Color32::from_rgb(46, 107, 230);
+    /// `t` is how far it has come in (or gone away, after closing).
+    fn render_media_center(&mut self, ctx: &Context, t: f32) {
+        let theme = crate::theme::current();
+        let txt    = theme.sheet_text;
+        let txt2   = theme.sheet_text_weak;
+        let accent = theme.sheet_accent;
 
+        self.file_browser.toolbar.begin();
         egui::Window::new("media_center")
-            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .anchor(egui::Align2::CENTER_CENTER, slide_in(egui::vec2(0.0, 0.0), t))
             .resizable(false).collapsible(false).title_bar(false)
+            .interactable(self.file_browser.visible)
             .fixed_size(egui::vec2(980.0, 660.0))
             .frame(egui::Frame::window(&ctx.style())
                 .inner_margin(Margin::same(26.0))
                 .rounding(Rounding::same(30.0))
-                .stroke(Stroke::new(1.0, Color32::from_black_alpha(28)))
-                .fill(Color32::from_rgba_unmultiplied(238, 240, 244, 216)))
+                .stroke(theme.sheet_stroke)
+                .fill(theme.sheet(216))
+                .multiply_with_opacity(t))
             .show(ctx, |ui| {
+                ui.multiply_opacity(t);
                 // Title + close
                 ui.horizontal(|ui| {
-                    ui.label(egui::RichText::new("Media Center").size(26.0).strong().color(txt));
+                    ui.label(egui::RichText::new(tr("Media Center")).size(26.0).strong().color(txt));
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        if ui.add(egui::Button::new(egui::RichText::new("✕").size(18.0).color(txt))
+                        let close = ui.add(egui::Button::new(egui::RichText::new("✕").size(18.0).color(txt))
                             .min_size(egui::vec2(34.0, 34.0)).rounding(Rounding::same(17.0))
-                            .fill(Color32::from_black_alpha(16))).clicked() {
+                            .fill(theme.sheet_tint(16)));
+                        self.file_browser.toolbar.add(0, &close);
+                        if close.clicked() {
                             self.file_browser.visible = false;
                         }
+                        let select = ui.add(egui::Button::new(egui::RichText::new(tr("☑ Select")).size(15.0).color(txt))
+                            .min_size(egui::vec2(96.0, 34.0)).rounding(Rounding::same(17.0))
+                            .fill(if self.file_browser.multi_select { theme.sheet_tint(40) } else { theme.sheet_tint(16) }));
+                        self.file_browser.toolbar.add(0, &select);
+                        if select.clicked() {
+                            self.file_browser.toggle_multi_select();
+                        }
+                        let details = ui.add(egui::Button::new(egui::RichText::new("ⓘ").size(17.0).color(txt))
+                            .min_size(egui::vec2(34.0, 34.0)).rounding(Rounding::same(17.0))
+                            .fill(if self.file_browser.show_details { theme.sheet_tint(40) } else { theme.sheet_tint(16) }));
+                        self.file_browser.toolbar.add(0, &details);
+                        if details.clicked() {
+                            self.file_browser.toggle_details();
+                        }
+                        let more = ui.add(egui::Button::new(egui::RichText::new("⋯").size(17.0).color(txt))
+                            .min_size(egui::vec2(34.0, 34.0)).rounding(Rounding::same(17.0))
+                            .fill(theme.sheet_tint(16)));
+                        self.file_browser.toolbar.add(0, &more);
+                        if more.clicked() {
+                            self.file_browser.open_entry_menu();
+                        }
+                        let view = tr(match self.file_browser.view {
+                            BrowserView::Carousel => "▦ Grid",
+                            BrowserView::Grid => "◫ Coverflow",
+                        });
+                        let toggle = ui.add(egui::Button::new(egui::RichText::new(view).size(15.0).color(txt))
+                            .min_size(egui::vec2(96.0, 34.0)).rounding(Rounding::same(17.0))
+                            .fill(theme.sheet_tint(16)));
+                        self.file_browser.toolbar.add(0, &toggle);
+                        if toggle.clicked() {
+                            self.file_browser.toggle_view();
+                        }
+                        let enqueue = ui.add(egui::Button::new(egui::RichText::new(tr("＋ Queue")).size(15.0).color(txt))
+                            .min_size(egui::vec2(96.0, 34.0)).rounding(Rounding::same(17.0))
+                            .fill(theme.sheet_tint(16)));
+                        self.file_browser.toolbar.add(0, &enqueue);
+                        if enqueue.clicked() {
+                            self.file_browser.enqueue_current();
+                        }
+                        let url = ui.add(egui::Button::new(egui::RichText::new(tr("🔗 URL")).size(15.0).color(txt))
+                            .min_size(egui::vec2(96.0, 34.0)).rounding(Rounding::same(17.0))
+                            .fill(theme.sheet_tint(16)));
+                        self.file_browser.toolbar.add(0, &url);
+                        if url.clicked() {
+                            self.open_url_dialog();
+                        }
                     });
                 });
                 ui.add_space(12.0);
                 // Category pills
                 ui.horizontal(|ui| {
                     for (cat, label, icon) in [
+                        (Category::Favorites, "Favorites", "★"),
+                        (Category::Recent, "Recent", "🕘"),
                         (Category::Movies, "Movies", "🎬"),
                         (Category::Music,  "Music",  "🎵"),
                         (Category::Files,  "Files",  "🗂"),
                     ] {
                         let on = self.file_browser.category == cat;
                         let pill = egui::Button::new(
-                                egui::RichText::new(format!("{}  {}", icon, label)).size(15.0)
-                                    .color(if on { Color32::WHITE } else { txt2 }))
+                                egui::RichText::new(format!("{}  {}", icon, tr(label))).size(15.0)
+                                    .color(if on { theme.on_sheet_accent } else { txt2 }))
                             .min_size(egui::vec2(134.0, 40.0)).rounding(Rounding::same(20.0))
-                            .fill(if on { accent } else { Color32::from_black_alpha(12) });
-                        if ui.add(pill).clicked() {
-                            self.file_browser.category = cat;
-                            self.file_browser.selected_index = 0;
+                            .fill(if on { accent } else { theme.sheet_tint(12) });
+                        let pill = ui.add(pill);
+                        self.file_browser.toolbar.add(1, &pill);
+                        if pill.clicked() {
+                            self.file_browser.set_category(cat);
                         }
                         ui.add_space(8.0);
                     }
                 });
                 ui.add_space(10.0);
-                // Breadcrumb
-                let path_str = self.file_browser.current_path.to_string_lossy().to_string();
-                ui.label(egui::RichText::new(path_str).size(13.0).color(txt2));
+                // Breadcrumb + filter
+                let mut jump_to: Option<PathBuf> = None;
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let deep = self.file_browser.search_subfolders;
+                        let subfolders = ui.add(egui::Button::new(egui::RichText::new(tr("Subfolders")).size(13.0)
+                                .color(if deep { theme.on_sheet_accent } else { txt2 }))
+                            .min_size(egui::vec2(96.0, 30.0)).rounding(Rounding::same(15.0))
+                            .fill(if deep { accent } else { theme.sheet_tint(12) }));
+                        self.file_browser.toolbar.add(2, &subfolders);
+                        if subfolders.clicked() {
+                            self.file_browser.set_search_subfolders(!deep);
+                        }
+                        if !self.file_browser.search_query.is_empty() {
+                            let clear = ui.add(egui::Button::new(egui::RichText::new("✕").size(13.0).color(txt))
+                                .min_size(egui::vec2(30.0, 30.0)).rounding(Rounding::same(15.0))
+                                .fill(theme.sheet_tint(12)));
+                            self.file_browser.toolbar.add(2, &clear);
+                            if clear.clicked() {
+                                self.file_browser.set_search_query("");
+                            }
+                        }
+                        let query = &self.file_browser.search_query;
+                        let label = if query.is_empty() {
+                            egui::RichText::new(tr("🔍 Filter")).color(txt2)
+                        } else if self.file_browser.typing_letter() {
+                            egui::RichText::new(format!("🔍 {}▏", query)).color(accent)
+                        } else {
+                            egui::RichText::new(format!("🔍 {}", query)).color(txt)
+                        };
+                        let filter = ui.add(egui::Button::new(label.size(13.0))
+                            .min_size(egui::vec2(180.0, 30.0)).rounding(Rounding::same(15.0))
+                            .fill(theme.sheet_tint(12)));
+                        self.file_browser.toolbar.add(2, &filter);
+                        if filter.clicked() {
+                            self.open_search();
+                        }
+                        // The rest of the row, scrolled to keep the focused segment in view
+                        ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                            let fixed = match self.file_browser.category {
+                                Category::Recent => Some("Recently played"),
+                                Category::Favorites => Some("Bookmarked folders and files"),
+                                _ => None,
+                            };
+                            if let Some(text) = fixed {
+                                ui.label(egui::RichText::new(tr(text)).size(13.0).color(txt2));
+                                return;
+                            }
+                            let crumbs = self.file_browser.breadcrumb();
+                            let focus = self.file_browser.crumb_focus();
+                            egui::ScrollArea::horizontal().stick_to_right(true).show(ui, |ui| {
+                                ui.spacing_mut().item_spacing.x = 2.0;
+                                for (i, (name, folder)) in crumbs.iter().enumerate() {
+                                    if i > 0 {
+                                        ui.label(egui::RichText::new("›").size(13.0).color(txt2));
+                                    }
+                                    let current = i + 1 == crumbs.len();
+                                    let focused = focus == Some(i);
+                                    let resp = ui.add(egui::Button::new(egui::RichText::new(name).size(13.0)
+                                            .color(if focused { theme.on_sheet_accent } else if current { txt } else { txt2 }))
+                                        .rounding(Rounding::same(10.0))
+                                        .fill(if focused { accent } else { Color32::TRANSPARENT }));
+                                    if focused {
+                                        resp.scroll_to_me(None);
+                                    }
+                                    if resp.clicked() && !current {
+                                        jump_to = Some(folder.clone());
+                                    }
+                                }
+                            });
+                        });
+                    });
+                });
+                if let Some(folder) = jump_to {
+                    self.file_browser.open_folder(folder);
+                }
+                if self.file_browser.typing_letter() { ctx.request_repaint(); }
+                if self.file_browser.multi_select {
+                    ui.add_space(6.0);
+                    ui.horizontal(|ui| {
+                        let n = self.file_browser.marked.len();
+                        ui.label(egui::RichText::new(trf("{} selected", &[&n])).size(15.0).strong().color(txt));
+                        ui.add_space(12.0);
+                        let pill = |text: String, fill: Color32, color: Color32| egui::Button::new(
+                                egui::RichText::new(text).size(14.0).color(color))
+                            .min_size(egui::vec2(110.0, 32.0)).rounding(Rounding::same(16.0)).fill(fill);
+                        if ui.add_enabled(n > 0, pill(tr("▶ Play all").into(), accent, theme.on_sheet_accent)).clicked() {
+                            self.file_browser.submit_marked(true);
+                        }
+                        if ui.add_enabled(n > 0, pill(tr("＋ Queue").into(), theme.sheet_tint(16), txt)).clicked() {
+                            self.file_browser.submit_marked(false);
+                        }
+                        if ui.add(pill(tr("Select all").into(), theme.sheet_tint(16), txt)).clicked() {
+                            self.file_browser.mark_all();
+                        }
+                        if ui.add(pill(tr("Done").into(), theme.sheet_tint(16), txt)).clicked() {
+                            self.file_browser.toggle_multi_select();
+                        }
+                    });
+                }
                 ui.add_space(8.0);
 
                 let indices = self.file_browser.filtered_indices();
@@ -699,22 +3304,33 @@ impl VrUi {
                 if let Some(err) = self.file_browser.error_msg.clone() {
                     ui.add_space(50.0);
                     ui.vertical_centered(|ui| {
-                        ui.label(egui::RichText::new("⚠  Permission Denied").size(20.0).color(Color32::from_rgb(200, 90, 40)));
+                        ui.label(egui::RichText::new(tr("⚠  Can't open folder")).size(20.0).color(theme.sheet_danger));
                         ui.add_space(8.0);
                         ui.label(egui::RichText::new(err).size(13.0).color(txt2));
                     });
                 } else if indices.is_empty() {
                     ui.add_space(70.0);
                     ui.vertical_centered(|ui| {
-                        ui.label(egui::RichText::new("Nothing here").size(18.0).color(txt2));
+                        let empty = if !self.file_browser.search_query.is_empty() { "No matches" }
+                            else if self.file_browser.category == Category::Recent { "Nothing played yet" }
+                            else if self.file_browser.category == Category::Favorites { "Hold □ on a folder or file to add it here" }
+                            else { "Nothing here" };
+                        ui.label(egui::RichText::new(tr(empty)).size(18.0).color(txt2));
                     });
+                } else if self.file_browser.view == BrowserView::Grid {
+                    if !indices.contains(&self.file_browser.selected_index) {
+                        self.file_browser.selected_index = indices[0];
+                    }
+                    if let Some((ei, highlighted)) = self.media_grid(ui, &indices, txt, accent) {
+                        if highlighted { open_index = Some(ei); } else { select_index = Some(ei); }
+                    }
                 } else {
                     if !indices.contains(&self.file_browser.selected_index) {
                         self.file_browser.selected_index = indices[0];
                     }
                     let sel_pos = indices.iter().position(|&i| i == self.file_browser.selected_index).unwrap_or(0) as f32;
                     let cp = self.file_browser.carousel_pos;
-                    let np = cp + (sel_pos - cp) * 0.22;
+                    let np = if theme.access.reduce_motion { sel_pos } else { cp + (sel_pos - cp) * 0.22 };
                     self.file_browser.carousel_pos = if (np - sel_pos).abs() < 0.002 { sel_pos } else { np };
                     if (self.file_browser.carousel_pos - sel_pos).abs() > 0.002 { ctx.request_repaint(); }
                     let pos = self.file_browser.carousel_pos;
@@ -766,7 +3382,35 @@ impl VrUi {
                                 Color32::from_white_alpha((alpha * 210.0) as u8));
                         }
                         ui.painter().rect_stroke(rect, Rounding::same(if focused { 6.0 } else { 4.0 }),
-                            Stroke::new(if focused { 2.0 } else { 1.0 }, Color32::from_black_alpha((alpha * 55.0) as u8)));
+                            Stroke::new(if focused { 2.0 } else { 1.0 }, theme.sheet_tint((alpha * 55.0) as u8)));
+                        if self.file_browser.multi_select && !entry.is_dir {
+                            paint_mark(ui.painter(), rect.left_top() + egui::vec2(18.0, 18.0) * scale, 12.0 * scale,
+                                self.file_browser.is_marked(&entry.path), accent);
+                        }
+
+                        // Watch badges: blue dot = new, bar = in progress, ✓ = watched
+                        match entry.progress {
+                            _ if entry.is_dir => {}
+                            None => {
+                                ui.painter().circle_filled(rect.right_top() + egui::vec2(-12.0, 12.0) * scale,
+                                    7.0 * scale, theme.sheet_accent.gamma_multiply(alpha));
+                            }
+                            Some(p) if p >= 1.0 => {
+                                let c = rect.right_top() + egui::vec2(-18.0, 18.0) * scale;
+                                ui.painter().circle_filled(c, 13.0 * scale, Color32::from_black_alpha((alpha * 150.0) as u8));
+                                ui.painter().text(c, egui::Align2::CENTER_CENTER, "✓",
+                                    FontId::new(16.0 * scale, FontFamily::Proportional),
+                                    Color32::from_white_alpha((alpha * 255.0) as u8));
+                            }
+                            Some(p) => {
+                                let bar = egui::Rect::from_min_size(rect.left_bottom() - egui::vec2(0.0, 5.0),
+                                    egui::vec2(rect.width(), 5.0));
+                                ui.painter().rect_filled(bar, Rounding::ZERO, Color32::from_black_alpha((alpha * 140.0) as u8));
+                                ui.painter().rect_filled(
+                                    egui::Rect::from_min_size(bar.min, egui::vec2(bar.width() * p, bar.height())),
+                                    Rounding::ZERO, Color32::from_rgba_unmultiplied(230, 60, 60, (alpha * 255.0) as u8));
+                            }
+                        }
 
                         if focused && entry.kind == MediaKind::Video && entry.thumbnail.is_some() {
                             let c = rect.center();
@@ -782,15 +3426,34 @@ impl VrUi {
                     }
 
                     let sel = &self.file_browser.entries[self.file_browser.selected_index];
+                    let title = if self.file_browser.is_favorite(&sel.path) { format!("★ {}", sel.name) } else { sel.name.clone() };
                     ui.painter().text(egui::pos2(center.x, center.y + focus_h * 0.5 + 38.0),
-                        egui::Align2::CENTER_CENTER, &sel.name,
+                        egui::Align2::CENTER_CENTER, title,
                         FontId::new(19.0, FontFamily::Proportional), txt);
-                    let meta = if sel.is_dir { "Folder".to_string() }
+                    let mut meta = if sel.is_dir { tr("Folder").to_string() }
                         else if sel.size_mb > 1000.0 { format!("{:.1} GB", sel.size_mb / 1024.0) }
                         else { format!("{:.0} MB", sel.size_mb) };
+                    if self.file_browser.category == Category::Recent {
+                        let path = sel.path.to_string_lossy();
+                        if let Some(r) = self.file_browser.recent.iter().find(|r| r.path == path) {
+                            meta = trf("{}  ·  {}% watched  ·  {}", &[&meta, &format!("{:.0}", r.progress * 100.0),
+                                &crate::recent::time_ago(r.played_at)]);
+                        }
+                    } else if !sel.is_dir {
+                        meta = match sel.progress {
+                            None => trf("{}  ·  New", &[&meta]),
+                            Some(p) if p >= 1.0 => trf("{}  ·  Watched", &[&meta]),
+                            Some(p) => trf("{}  ·  {}% watched", &[&meta, &format!("{:.0}", p * 100.0)]),
+                        };
+                    }
                     ui.painter().text(egui::pos2(center.x, center.y + focus_h * 0.5 + 62.0),
                         egui::Align2::CENTER_CENTER, &meta,
                         FontId::new(13.0, FontFamily::Proportional), txt2);
+                    if let Some(info) = &sel.info {
+                        ui.painter().text(egui::pos2(center.x, center.y + focus_h * 0.5 + 82.0),
+                            egui::Align2::CENTER_CENTER, info.summary(),
+                            FontId::new(13.0, FontFamily::Proportional), txt2);
+                    }
 
                     let n = indices.len();
                     if n > 1 && n <= 40 {
@@ -800,48 +3463,877 @@ impl VrUi {
                             let dx = center.x - total * 0.5 + k as f32 * spacing;
                             let on = (k as f32 - sel_pos).abs() < 0.5;
                             ui.painter().circle_filled(egui::pos2(dx, dy), if on { 3.6 } else { 2.2 },
-                                if on { accent } else { Color32::from_black_alpha(55) });
+                                if on { accent } else { theme.sheet_tint(55) });
                         }
                     }
                 }
 
                 if let Some(ei) = select_index { self.file_browser.selected_index = ei; }
-                if open_index.is_some() { self.file_browser.select_current(); }
+                if let Some(ei) = open_index {
+                    if self.file_browser.multi_select && !self.file_browser.entries[ei].is_dir {
+                        self.file_browser.toggle_mark_current();
+                    } else {
+                        self.file_browser.select_current();
+                    }
+                }
 
-                ui.add_space(8.0);
+            });
+        if !self.file_browser.visible {
+            self.file_browser.toolbar.leave();
+        }
+        self.file_browser.toolbar.finish(ctx);
+    }
+
+    /// The highlighted entry's size, date and what the metadata store knows about
+    /// it, in a pane to the right of the Media Center.
+    fn render_media_details(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
+        let txt  = theme.sheet_text;
+        let txt2 = theme.sheet_text_weak;
+        let browser = &mut self.file_browser;
+        let Some(entry) = browser.entries.get(browser.selected_index).filter(|e| e.name != "..") else { return };
+        if browser.modified.as_ref().is_none_or(|(path, _)| *path != entry.path) {
+            let secs = std::fs::metadata(&entry.path).and_then(|m| m.modified()).ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs());
+            browser.modified = Some((entry.path.clone(), secs));
+        }
+        let modified = browser.modified.as_ref().and_then(|(_, secs)| *secs);
+
+        let mut rows: Vec<(&str, String)> = Vec::new();
+        if entry.is_dir {
+            rows.push(("Type", tr("Folder").into()));
+        } else {
+            rows.push(("Size", if entry.size_mb > 1000.0 { format!("{:.2} GB", entry.size_mb / 1024.0) }
+                else { format!("{:.1} MB", entry.size_mb) }));
+        }
+        if let Some(secs) = modified {
+            rows.push(("Modified", format!("{}  ({})", crate::recent::format_date(secs), crate::recent::time_ago(secs))));
+        }
+        match &entry.info {
+            Some(info) => {
+                if info.duration_us > 0 {
+                    rows.push(("Duration", crate::resume::format_time(info.duration_us)));
+                }
+                if info.width > 0 && info.height > 0 {
+                    rows.push(("Resolution", format!("{}×{}  ·  {}", info.width, info.height,
+                        crate::codecs::resolution_label(info.width, info.height))));
+                }
+                if info.fps > 0.0 {
+                    let fps = if info.fps.fract().abs() < 0.01 { format!("{:.0}", info.fps) } else { format!("{:.2}", info.fps) };
+                    rows.push(("Frame rate", format!("{} fps", fps)));
+                }
+                if let Some(v) = &info.video_mime {
+                    rows.push(("Video", crate::codecs::format_label(v).to_string()));
+                }
+                if let Some(a) = &info.audio_mime {
+                    rows.push(("Audio", format!("{}  ·  {}", crate::metadata::audio_label(a),
+                        crate::metadata::channel_layout(info.channels))));
+                }
+            }
+            None if !entry.is_dir => rows.push(("", tr("Reading…").into())),
+            None => {}
+        }
+        if let Some(p) = entry.progress.filter(|_| !entry.is_dir) {
+            rows.push(("Watched", if p >= 1.0 { tr("Finished").into() } else { format!("{:.0}%", p * 100.0) }));
+        }
+
+        egui::Window::new("media_details")
+            .anchor(egui::Align2::LEFT_CENTER, egui::vec2(ctx.screen_rect().width() * 0.5 + 510.0, 0.0))
+            .resizable(false).collapsible(false).title_bar(false)
+            .fixed_size(egui::vec2(340.0, 0.0))
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::same(20.0))
+                .rounding(Rounding::same(24.0))
+                .stroke(theme.sheet_stroke)
+                .fill(theme.sheet(216)))
+            .show(ctx, |ui| {
+                ui.add(egui::Label::new(egui::RichText::new(&entry.name).size(18.0).strong().color(txt)).wrap());
+                ui.add_space(10.0);
+                egui::Grid::new("media_details_grid").num_columns(2).spacing([14.0, 8.0]).show(ui, |ui| {
+                    for (label, value) in rows {
+                        ui.label(egui::RichText::new(tr(label)).size(14.0).color(txt2));
+                        ui.label(egui::RichText::new(value).size(14.0).color(txt));
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+
+    /// The hold-□ menu over the Media Center: bookmark, rename or delete the entry.
+    fn render_entry_menu(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
+        let txt    = theme.sheet_text;
+        let txt2   = theme.sheet_text_weak;
+        let accent = theme.sheet_accent;
+        let danger = theme.sheet_danger;
+        let Some(menu) = &self.file_browser.entry_menu else { return };
+        let favorite = self.file_browser.is_favorite(&menu.path);
+        let mut picked = None;
+        egui::Window::new("entry_menu")
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .resizable(false).collapsible(false).title_bar(false)
+            .fixed_size(egui::vec2(360.0, 0.0))
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::same(20.0))
+                .rounding(Rounding::same(24.0))
+                .stroke(theme.sheet_stroke)
+                .fill(theme.sheet(255)))
+            .show(ctx, |ui| {
+                ui.add(egui::Label::new(egui::RichText::new(&menu.name).size(18.0).strong().color(txt)).wrap());
+                if menu.confirm_delete {
+                    ui.label(egui::RichText::new(tr("Delete this file? It can't be undone.")).size(14.0).color(danger));
+                }
+                ui.add_space(10.0);
+                for (i, action) in menu.actions().into_iter().enumerate() {
+                    let label = tr(match action {
+                        EntryAction::Favorite if favorite => "☆  Remove from favorites",
+                        EntryAction::Favorite => "★  Add to favorites",
+                        EntryAction::Rename => "✏  Rename",
+                        EntryAction::Delete if menu.confirm_delete => "🗑  Delete for good",
+                        EntryAction::Delete => "🗑  Delete",
+                        EntryAction::Cancel => "Cancel",
+                    });
+                    let selected = i == menu.selected;
+                    let color = if selected && action == EntryAction::Delete { Color32::WHITE } else if selected { theme.on_sheet_accent } else if action == EntryAction::Delete { danger }
+                        else if action == EntryAction::Cancel { txt2 } else { txt };
+                    let fill = match (selected, action) {
+                        (true, EntryAction::Delete) => danger,
+                        (true, _) => accent,
+                        _ => theme.sheet_tint(12),
+                    };
+                    if ui.add(egui::Button::new(egui::RichText::new(label).size(16.0).color(color))
+                        .min_size(egui::vec2(320.0, 40.0)).rounding(Rounding::same(20.0)).fill(fill)).clicked() {
+                        picked = Some(action);
+                    }
+                }
+            });
+        if let Some(action) = picked {
+            self.run_entry_action(action);
+        }
+    }
+
+    /// Media Center grid: thumbnails with their names underneath, `GRID_COLUMNS`
+    /// to a row. Returns a clicked entry and whether it was already highlighted.
+    fn media_grid(&mut self, ui: &mut egui::Ui, indices: &[usize], txt: Color32, accent: Color32) -> Option<(usize, bool)> {
+        const GAP: f32 = 14.0;
+        const NAME_H: f32 = 24.0;
+        let scroll = std::mem::take(&mut self.file_browser.grid_scroll);
+        let browser = &self.file_browser;
+        let mut clicked = None;
+        egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
+            ui.spacing_mut().item_spacing = egui::vec2(GAP, GAP);
+            let cell_w = (ui.available_width() - GAP * (GRID_COLUMNS as f32 - 1.0)) / GRID_COLUMNS as f32;
+            let thumb_h = cell_w * 9.0 / 16.0;
+            for row in indices.chunks(GRID_COLUMNS) {
+                ui.horizontal(|ui| {
+                    for &ei in row {
+                        let entry = &browser.entries[ei];
+                        let highlighted = ei == browser.selected_index;
+                        let (rect, resp) = ui.allocate_exact_size(egui::vec2(cell_w, thumb_h + NAME_H), egui::Sense::click());
+                        let thumb = egui::Rect::from_min_size(rect.min, egui::vec2(cell_w, thumb_h));
+                        let painter = ui.painter();
+                        if let Some(tex) = &entry.thumbnail {
+                            let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+                            painter.image(tex.id(), thumb, uv, Color32::WHITE);
+                        } else {
+                            painter.rect_filled(thumb, Rounding::same(8.0), Color32::from_rgb(70, 74, 84));
+                            let glyph = match entry.kind {
+                                MediaKind::Dir => "📁", MediaKind::Video => "🎬", MediaKind::Audio => "🎵",
+                            };
+                            painter.text(thumb.center(), egui::Align2::CENTER_CENTER, glyph,
+                                FontId::new(30.0, FontFamily::Proportional), Color32::from_white_alpha(210));
+                        }
+                        // Watch progress along the bottom edge
+                        if let Some(p) = entry.progress.filter(|_| !entry.is_dir) {
+                            let bar = egui::Rect::from_min_size(thumb.left_bottom() - egui::vec2(0.0, 4.0),
+                                egui::vec2(thumb.width() * p.min(1.0), 4.0));
+                            painter.rect_filled(bar, Rounding::ZERO, Color32::from_rgb(230, 60, 60));
+                        }
+                        if highlighted {
+                            painter.rect_stroke(thumb.expand(3.0), Rounding::same(8.0), Stroke::new(3.0, accent));
+                        }
+                        if browser.multi_select && !entry.is_dir {
+                            paint_mark(painter, thumb.left_top() + egui::vec2(16.0, 16.0), 11.0,
+                                browser.is_marked(&entry.path), accent);
+                        }
+                        let name = if browser.is_favorite(&entry.path) { format!("★ {}", entry.name) } else { entry.name.clone() };
+                        let mut job = egui::text::LayoutJob::simple_singleline(name,
+                            FontId::new(13.0, FontFamily::Proportional), if highlighted { accent } else { txt });
+                        job.wrap = egui::text::TextWrapping::truncate_at_width(cell_w);
+                        let galley = ui.fonts(|f| f.layout_job(job));
+                        let name_pos = egui::pos2(thumb.center().x - galley.size().x * 0.5, thumb.max.y + 5.0);
+                        ui.painter().galley(name_pos, galley, txt);
+                        if highlighted && scroll {
+                            resp.scroll_to_me(Some(egui::Align::Center));
+                        }
+                        if resp.clicked() {
+                            clicked = Some((ei, highlighted));
+                        }
+                    }
+                });
+            }
+        });
+        clicked
+    }
+
+    // ── Play queue ────────────────────────────────────────────────────────────
+    fn render_queue(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
+        let accent = theme.accent;
+        egui::Window::new("queue")
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .resizable(false).collapsible(false).title_bar(false)
+            .fixed_size(egui::vec2(720.0, 560.0))
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::same(22.0))
+                .rounding(Rounding::same(26.0))
+                .stroke(theme.stroke)
+                .fill(theme.panel(235)))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(tr("Up Next")).size(26.0).strong().color(theme.text));
+                    ui.label(egui::RichText::new(trf("{} items", &[&self.queue.items.len()]))
+                        .size(15.0).color(theme.text_weak));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if Self::icon_btn(ui, "✕").clicked() { self.queue.visible = false; }
+                    });
+                });
+                ui.add_space(10.0);
+
+                if self.queue.items.is_empty() {
+                    ui.add_space(80.0);
+                    ui.vertical_centered(|ui| {
+                        ui.label(egui::RichText::new(tr("Queue is empty")).size(18.0).color(theme.text_weak));
+                        ui.label(egui::RichText::new(tr("Add videos from the Media Center with R1 or ＋ Queue"))
+                            .size(13.0).color(theme.text_weak));
+                    });
+                } else {
+                    egui::ScrollArea::vertical().max_height(380.0).show(ui, |ui| {
+                        for (i, item) in self.queue.items.iter().enumerate() {
+                            let playing = self.queue.current == Some(i);
+                            let selected = i == self.queue.selected && !self.queue.controls.active();
+                            let fill = if selected { accent }
+                                else if playing { theme.accent_dim }
+                                else { theme.control(200) };
+                            egui::Frame::none().fill(fill).rounding(Rounding::same(12.0))
+                                .inner_margin(Margin::symmetric(12.0, 8.0))
+                                .show(ui, |ui| {
+                                    ui.set_width(ui.available_width());
+                                    ui.horizontal(|ui| {
+                                        let marker = if playing { "▶" } else { " " };
+                                        let resp = ui.add(egui::Label::new(
+                                            egui::RichText::new(format!("{}  {}", marker, crate::playlist::display_name(item)))
+                                                .size(17.0).color(if selected { theme.on_accent } else { theme.text })).sense(egui::Sense::click()));
+                                        if resp.clicked() { self.queue.selected = i; self.queue.jump_to = Some(i); }
+                                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                            if ui.small_button("✕").clicked() { self.queue.remove = Some(i); }
+                                        });
+                                    });
+                                });
+                        }
+                    });
+                }
+
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    self.queue.controls.begin();
+                    for (icon, flag) in [
+                        ("⏮", &mut self.queue.previous),
+                        ("⏭", &mut self.queue.next),
+                        ("🔀", &mut self.queue.shuffle),
+                        ("🗑", &mut self.queue.clear),
+                    ] {
+                        let button = Self::icon_btn(ui, icon);
+                        self.queue.controls.add(0, &button);
+                        if button.clicked() { *flag = true; }
+                    }
+                });
+            });
+        self.queue.controls.finish(ctx);
+    }
+
+    // ── First-run tutorial ────────────────────────────────────────────────────
+    /// Start the walkthrough from its first page.
+    pub fn show_tutorial(&mut self) {
+        self.tutorial = Some(TutorialPage::Touch);
+    }
+
+    pub fn tutorial_visible(&self) -> bool { self.tutorial.is_some() }
+
+    /// The next page, or done after the last.
+    pub fn tutorial_next(&mut self) {
+        let Some(page) = self.tutorial else { return };
+        match TutorialPage::ALL.get(page.index() + 1) {
+            Some(&next) => self.tutorial = Some(next),
+            None => self.close_tutorial(),
+        }
+    }
+
+    pub fn tutorial_back(&mut self) {
+        if let Some(page) = self.tutorial {
+            self.tutorial = Some(TutorialPage::ALL[page.index().saturating_sub(1)]);
+        }
+    }
+
+    /// Finished or skipped: it comes back only from Settings.
+    pub fn close_tutorial(&mut self) {
+        self.tutorial = None;
+        self.params.tutorial_seen = true;
+    }
+
+    fn render_tutorial(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
+        let Some(page) = self.tutorial else { return };
+        let (title, intro) = match page {
+            TutorialPage::Touch => (tr("Welcome"),
+                tr("Tap Enter VR at the top of the screen, then put the phone in the headset. A tap on the picture shows or hides the controls.")),
+            TutorialPage::Controller => (tr("Controller"),
+                tr("Pair a controller over Bluetooth. While one is connected, the buttons that do something are listed along the bottom.")),
+            TutorialPage::MediaCenter => (tr("Media Center"),
+                tr("Your videos and music, favorites and what you played last.")),
+        };
+        // (control, what it does)
+        let rows: Vec<(String, &str)> = match page {
+            TutorialPage::Touch => [
+                ("Double tap", "Play / pause"),
+                ("Swipe along the bottom", "Seek"),
+                ("Pinch", "Zoom"),
+                ("Twist two fingers", "Level the horizon"),
+                ("Two-finger tap", "Recenter"),
+            ].iter().map(|&(control, action)| (tr(control).to_string(), tr(action))).collect(),
+            TutorialPage::Controller => actions::hints(InputContext::Playback),
+            TutorialPage::MediaCenter => {
+                let open = (crate::gamepad::glyph(crate::gamepad::Button::Create), tr("Open the Media Center"));
+                std::iter::once(open).chain(actions::hints(InputContext::MediaCenter).into_iter().take(7)).collect()
+            }
+        };
+        let last = page.index() + 1 == TutorialPage::ALL.len();
+        let mut step: Option<bool> = None;
+        let mut skip = false;
+        egui::Window::new("tutorial")
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .resizable(false).collapsible(false).title_bar(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::same(24.0))
+                .rounding(Rounding::same(22.0))
+                .stroke(theme.stroke)
+                .fill(theme.panel(245)))
+            .show(ctx, |ui| {
+                ui.set_width(560.0);
+                ui.label(egui::RichText::new(trf("{} of {}", &[&(page.index() + 1), &TutorialPage::ALL.len()]))
+                    .size(14.0).color(theme.text_weak));
+                ui.label(egui::RichText::new(title).size(26.0).strong().color(theme.text));
+                ui.add_space(4.0);
+                ui.label(egui::RichText::new(intro).size(16.0).color(theme.text_soft));
+                ui.add_space(10.0);
+                egui::Grid::new("tutorial_grid").spacing(egui::vec2(20.0, 6.0)).show(ui, |ui| {
+                    for (control, action) in &rows {
+                        ui.label(egui::RichText::new(control).size(16.0).strong().color(theme.text));
+                        ui.label(egui::RichText::new(*action).size(16.0).color(theme.text_soft));
+                        ui.end_row();
+                    }
+                });
+                ui.add_space(14.0);
+                ui.horizontal(|ui| {
+                    if ui.add(egui::Button::new(egui::RichText::new(tr("Skip")).size(16.0).color(theme.text_weak))
+                        .frame(false)).clicked() {
+                        skip = true;
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let next = if last { tr("Done") } else { tr("Next") };
+                        if ui.add(egui::Button::new(egui::RichText::new(next).size(18.0).color(theme.on_accent))
+                            .min_size(egui::vec2(130.0, 44.0)).fill(theme.accent)).clicked() {
+                            step = Some(true);
+                        }
+                        if page.index() > 0 && ui.add(egui::Button::new(egui::RichText::new(tr("Back")).size(18.0))
+                            .min_size(egui::vec2(130.0, 44.0))).clicked() {
+                            step = Some(false);
+                        }
+                    });
+                });
+            });
+        match (skip, step) {
+            (true, _) => self.close_tutorial(),
+            (_, Some(true)) => self.tutorial_next(),
+            (_, Some(false)) => self.tutorial_back(),
+            _ => {}
+        }
+    }
+
+    // ── Resume prompt ─────────────────────────────────────────────────────────
+    fn render_resume_prompt(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
+        egui::Window::new("resume_prompt")
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -160.0))
+            .resizable(false).collapsible(false).title_bar(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::same(20.0))
+                .rounding(Rounding::same(22.0))
+                .stroke(theme.stroke)
+                .fill(theme.panel(240)))
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(&self.resume_prompt.title).size(15.0).color(theme.text_weak));
+                ui.label(egui::RichText::new(trf("Resume from {}?",
+                        &[&crate::resume::format_time(self.resume_prompt.position_us)]))
+                    .size(24.0).strong().color(theme.text));
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    let resume = egui::Button::new(egui::RichText::new(tr("▶ Resume")).size(18.0).color(theme.on_accent))
+                        .min_size(egui::vec2(150.0, 44.0)).fill(theme.accent);
+                    if ui.add(resume).clicked() { self.resume_prompt.accept(); }
+                    let over = egui::Button::new(egui::RichText::new(tr("⟲ Start Over")).size(18.0))
+                        .min_size(egui::vec2(150.0, 44.0));
+                    if ui.add(over).clicked() { self.resume_prompt.dismiss(); }
+                });
+            });
+    }
+
+    // ── Playback error banner ─────────────────────────────────────────────────
+    fn render_playback_error(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
+        let Some(message) = self.playback_error.message.clone() else { return };
+        if !self.playback_error.warning {
+            self.render_playback_failure(ctx, &message);
+            return;
+        }
+        let (title, accent, fill) =
+            (tr("Limited decoder support"), Color32::from_rgb(240, 180, 70), Color32::from_rgba_unmultiplied(56, 42, 18, 240));
+        egui::Window::new("playback_error")
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 40.0))
+            .resizable(false).collapsible(false).title_bar(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::same(16.0))
+                .rounding(Rounding::same(18.0))
+                .stroke(Stroke::new(1.0, accent))
+                .fill(fill))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("⚠").size(26.0).color(accent));
+                    ui.vertical(|ui| {
+                        ui.label(egui::RichText::new(title).size(18.0).strong().color(Color32::WHITE));
+                        ui.label(egui::RichText::new(message).size(14.0).color(theme.text_soft));
+                    });
+                    if Self::icon_btn(ui, "✕").clicked() { self.playback_error.dismiss(); }
+                });
+            });
+    }
+
+    /// The failure card: what went wrong, the decoder's reason, what to try.
+    fn render_playback_failure(&mut self, ctx: &Context, reason: &str) {
+        let theme = crate::theme::current();
+        let danger = theme.danger;
+        let can_retry = self.playback_error.path.is_some();
+        egui::Window::new("playback_failure")
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .resizable(false).collapsible(false).title_bar(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::same(24.0))
+                .rounding(Rounding::same(22.0))
+                .stroke(Stroke::new(theme.stroke.width, danger))
+                .fill(theme.panel(245)))
+            .show(ctx, |ui| {
+                ui.set_width(520.0);
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("⚠").size(30.0).color(danger));
+                    ui.label(egui::RichText::new(tr(self.playback_error.title)).size(24.0).strong().color(theme.text));
+                });
+                ui.add_space(6.0);
+                ui.label(egui::RichText::new(tr(self.playback_error.advice)).size(17.0).color(theme.text_soft));
+                ui.add_space(4.0);
+                ui.label(egui::RichText::new(reason).size(13.0).color(theme.text_weak));
+                ui.add_space(14.0);
+                ui.horizontal(|ui| {
+                    if can_retry && ui.add(egui::Button::new(egui::RichText::new(tr("⟲ Try again")).size(18.0).color(theme.on_accent))
+                        .min_size(egui::vec2(150.0, 44.0)).fill(theme.accent)).clicked() {
+                        self.playback_error.try_again();
+                    }
+                    if ui.add(egui::Button::new(egui::RichText::new(tr("Close")).size(18.0))
+                        .min_size(egui::vec2(150.0, 44.0))).clicked() {
+                        self.playback_error.dismiss();
+                    }
+                });
+            });
+    }
+
+    fn render_buffering(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
+        let Some(label) = self.buffering.label else { return };
+        ctx.request_repaint();
+        if !self.buffering.due() {
+            return;
+        }
+        egui::Window::new("buffering")
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .resizable(false).collapsible(false).title_bar(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::same(16.0))
+                .rounding(Rounding::same(18.0))
+                .fill(theme.overlay(220)))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(egui::Spinner::new().size(28.0).color(theme.accent));
+                    ui.vertical(|ui| {
+                        ui.label(egui::RichText::new(tr(label)).size(18.0).strong().color(theme.text));
+                        if let Some(target) = self.buffering.seek_target_us {
+                            ui.label(egui::RichText::new(trf("to {}", &[&crate::resume::format_time(target)]))
+                                .size(14.0).color(theme.text_soft));
+                        }
+                        if let Some(ahead) = self.buffering.buffered_ahead_us {
+                            ui.label(egui::RichText::new(trf("{} s buffered", &[&format!("{:.0}", ahead as f64 / 1e6)]))
+                                .size(14.0).color(theme.text_soft));
+                        }
+                    });
+                });
+            });
+    }
+
+    /// With only the head to point, something to look at to bring the menu up.
+    fn render_gaze_menu_button(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
+        egui::Window::new("gaze_menu")
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -200.0))
+            .resizable(false).collapsible(false).title_bar(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::same(8.0))
+                .rounding(Rounding::same(30.0))
+                .fill(theme.overlay(160)))
+            .show(ctx, |ui| {
+                if ui.add(egui::Button::new(egui::RichText::new(tr("☰  Menu")).size(22.0))
+                    .min_size(egui::vec2(160.0, 56.0))).clicked() {
+                    self.main_menu_visible = true;
+                    self.menu_state = MenuState::Main;
+                }
+            });
+    }
+
+    /// The media volume as read this frame; a change from outside the dock
+    /// (volume keys, the media remote) brings up the volume OSD.
+    pub fn set_volume(&mut self, volume: Option<(i32, i32)>) {
+        if self.volume.is_some() && volume.is_some() && volume != self.volume {
+            self.volume_changed = Some(Instant::now());
+        }
+        self.volume = volume;
+    }
+
+    fn volume_icon(level: i32, max: i32) -> &'static str {
+        match level {
+            0 => "🔇",
+            l if l * 2 < max => "🔉",
+            _ => "🔊",
+        }
+    }
+
+    fn render_volume_osd(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
+        const VOLUME_SECS: f32 = 2.0;
+        let (Some(since), Some((level, max))) = (self.volume_changed, self.volume) else { return };
+        if since.elapsed().as_secs_f32() > VOLUME_SECS {
+            self.volume_changed = None;
+            return;
+        }
+        egui::Window::new("volume_osd")
+            .anchor(egui::Align2::RIGHT_CENTER, egui::vec2(-160.0, 0.0))
+            .resizable(false).collapsible(false).title_bar(false).interactable(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::symmetric(16.0, 16.0))
+                .rounding(Rounding::same(20.0))
+                .fill(theme.overlay(220)))
+            .show(ctx, |ui| {
                 ui.vertical_centered(|ui| {
-                    ui.label(egui::RichText::new("◀ ▶ / left-stick: browse    X: open    ○: up a folder    △: close")
-                        .size(12.0).color(txt2));
+                    let (rect, _) = ui.allocate_exact_size(egui::vec2(14.0, 220.0), egui::Sense::hover());
+                    let painter = ui.painter();
+                    painter.rect_filled(rect, Rounding::same(7.0), theme.control(255));
+                    let mut filled = rect;
+                    filled.set_top(rect.bottom() - rect.height() * level as f32 / max.max(1) as f32);
+                    painter.rect_filled(filled, Rounding::same(7.0), theme.accent);
+                    ui.add_space(8.0);
+                    ui.label(egui::RichText::new(Self::volume_icon(level, max)).size(26.0).color(theme.text));
+                    ui.label(egui::RichText::new(level.to_string()).size(16.0).color(theme.text_soft));
+                });
+            });
+        ctx.request_repaint();
+    }
+
+    /// Bring up the playback OSD (again) for `event`.
+    pub fn show_osd(&mut self, event: OsdEvent, title: &str, position_us: i64, duration_us: i64) {
+        self.osd = Some(PlaybackOsd { event, title: title.to_string(), position_us, duration_us, since: Instant::now() });
+    }
+
+    fn render_osd(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
+        const OSD_SECS: f32 = 2.0;
+        const FADE_SECS: f32 = 0.5;
+        let Some(osd) = &self.osd else { return };
+        // In quickly, out slowly once its time is nearly up
+        let age = osd.since.elapsed().as_secs_f32();
+        let showing = age < OSD_SECS - transition(FADE_SECS);
+        let dt = ctx.input(|i| i.stable_dt).min(0.1);
+        let fade = self.osd_tween.update(showing, dt, transition(if showing { TRANSITION_SECS } else { FADE_SECS }));
+        if !showing && fade <= 0.0 {
+            self.osd = None;
+            return;
+        }
+        let (icon, action) = match osd.event {
+            OsdEvent::Play => ("▶", String::new()),
+            OsdEvent::Pause => ("⏸", String::new()),
+            OsdEvent::Seek(delta) if delta < 0 => ("⏪", format!("−{}", crate::resume::format_time(-delta))),
+            OsdEvent::Seek(delta) => ("⏩", format!("+{}", crate::resume::format_time(delta))),
+        };
+        egui::Window::new("playback_osd")
+            .anchor(egui::Align2::CENTER_BOTTOM, slide_in(egui::vec2(0.0, -220.0), fade))
+            .resizable(false).collapsible(false).title_bar(false).interactable(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::symmetric(24.0, 14.0))
+                .rounding(Rounding::same(20.0))
+                .stroke(Stroke::NONE)
+                .fill(theme.overlay(220).gamma_multiply(fade)))
+            .show(ctx, |ui| {
+                ui.multiply_opacity(fade);
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(icon).size(40.0).color(theme.text));
+                    ui.add_space(12.0);
+                    ui.vertical(|ui| {
+                        ui.label(egui::RichText::new(&osd.title).size(20.0).strong().color(theme.text));
+                        ui.horizontal(|ui| {
+                            let time = if osd.duration_us > 0 {
+                                format!("{} / {}", crate::resume::format_time(osd.position_us), crate::resume::format_time(osd.duration_us))
+                            } else {
+                                crate::resume::format_time(osd.position_us)
+                            };
+                            ui.label(egui::RichText::new(time).size(16.0).color(theme.text_soft));
+                            if !action.is_empty() {
+                                ui.label(egui::RichText::new(action).size(16.0).color(theme.accent));
+                            }
+                        });
+                    });
+                });
+            });
+        ctx.request_repaint();
+    }
+
+    fn render_seek_preview(ctx: &Context, preview: &SeekPreview) {
+        let theme = crate::theme::current();
+        let sign = if preview.delta_us < 0 { "−" } else { "+" };
+        egui::Window::new("seek_preview")
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .resizable(false).collapsible(false).title_bar(false).interactable(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::symmetric(24.0, 14.0))
+                .rounding(Rounding::same(18.0))
+                .fill(theme.overlay(220)))
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.label(egui::RichText::new(crate::resume::format_time(preview.target_us))
+                        .size(30.0).strong().color(theme.text));
+                    ui.label(egui::RichText::new(format!("{}{}", sign, crate::resume::format_time(preview.delta_us.abs())))
+                        .size(16.0).color(theme.text_soft));
+                });
+            });
+    }
+
+    /// What the controller is driving, checked in the same order as lib.rs.
+    fn input_context(&self) -> InputContext {
+        if self.locked {
+            InputContext::Locked
+        } else if self.tutorial.is_some() {
+            InputContext::Tutorial
+        } else if self.playback_error.is_modal() {
+            InputContext::PlaybackError
+        } else if self.url_dialog.visible {
+            InputContext::UrlDialog
+        } else if self.keyboard.visible && self.renaming.is_some() {
+            InputContext::Rename
+        } else if self.keyboard.visible && self.naming_preset {
+            InputContext::PresetName
+        } else if self.keyboard.visible {
+            InputContext::Search
+        } else if self.file_browser.visible && self.file_browser.entry_menu.is_some() {
+            InputContext::EntryMenu
+        } else if self.file_browser.visible && self.file_browser.multi_select {
+            InputContext::MediaSelect
+        } else if self.file_browser.visible {
+            InputContext::MediaCenter
+        } else if self.resume_prompt.visible {
+            InputContext::ResumePrompt
+        } else if self.queue.visible {
+            InputContext::Queue
+        } else if self.main_menu_visible {
+            match self.menu_state {
+                MenuState::Main | MenuState::WebBrowser => InputContext::Dock,
+                _ => InputContext::Settings,
+            }
+        } else {
+            InputContext::Playback
+        }
+    }
+
+    // ── Button hint bar (what each controller button does right now) ─────────
+    fn render_hint_bar(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
+        // Playback hints show briefly after a menu closes; panels keep theirs
+        const PLAYBACK_HINT_SECS: f32 = 4.0;
+        let context = self.input_context();
+        let since = match self.hint_context {
+            Some((shown, since)) if shown == context => since,
+            _ => {
+                self.hint_context = Some((context, Instant::now()));
+                Instant::now()
+            }
+        };
+        if context == InputContext::Playback {
+            if since.elapsed().as_secs_f32() > PLAYBACK_HINT_SECS {
+                return;
+            }
+            ctx.request_repaint();
+        }
+        egui::Window::new("hint_bar")
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -80.0))
+            .resizable(false).collapsible(false).title_bar(false).interactable(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::symmetric(18.0, 8.0))
+                .rounding(Rounding::same(16.0))
+                .fill(theme.overlay(200)))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    for (control, label) in actions::hints(context) {
+                        ui.label(egui::RichText::new(control).size(14.0).strong().color(theme.text));
+                        ui.label(egui::RichText::new(label).size(14.0).color(theme.text_weak));
+                        ui.add_space(14.0);
+                    }
+                });
+            });
+    }
+
+    /// Battery outline, nub and a fill for `level` (0…1).
+    fn battery_icon(ui: &mut egui::Ui, level: f32, color: Color32) {
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(34.0, 16.0), egui::Sense::hover());
+        let body = rect.shrink2(egui::vec2(2.0, 0.0)).translate(egui::vec2(-2.0, 0.0));
+        ui.painter().rect_stroke(body, 3.0, Stroke::new(1.5, color));
+        let nub = egui::Rect::from_min_size(egui::pos2(body.right() + 1.0, body.center().y - 3.0), egui::vec2(3.0, 6.0));
+        ui.painter().rect_filled(nub, 1.0, color);
+        let inner = body.shrink(3.0);
+        let fill = egui::Rect::from_min_size(inner.min, egui::vec2(inner.width() * level.clamp(0.0, 1.0), inner.height()));
+        ui.painter().rect_filled(fill, 1.0, color);
+    }
+
+    /// Top right: the time, the phone's battery and its Wi-Fi signal.
+    fn render_status_hud(&self, ctx: &Context, status: &crate::status::DeviceStatus) {
+        use crate::status::Wifi;
+        let theme = crate::theme::current();
+        egui::Window::new("status_hud")
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-40.0, 40.0))
+            .resizable(false).collapsible(false).title_bar(false).interactable(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::symmetric(14.0, 8.0))
+                .rounding(Rounding::same(14.0))
+                .stroke(Stroke::NONE)
+                .fill(theme.overlay(200)))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(&status.time).size(20.0).strong().color(theme.text));
+                    ui.add_space(10.0);
+                    // Wi-Fi: three rising bars, lit up to the signal; dimmed and crossed when off
+                    let (rect, _) = ui.allocate_exact_size(egui::vec2(22.0, 18.0), egui::Sense::hover());
+                    let lit = match status.wifi {
+                        Wifi::Off => 0,
+                        Wifi::On(bars) => bars.unwrap_or(3),
+                    };
+                    for i in 0..3u8 {
+                        let height = 6.0 + 6.0 * i as f32;
+                        let bar = egui::Rect::from_min_max(
+                            egui::pos2(rect.left() + 8.0 * i as f32, rect.bottom() - height),
+                            egui::pos2(rect.left() + 8.0 * i as f32 + 5.0, rect.bottom()));
+                        ui.painter().rect_filled(bar, 1.0, if i < lit { theme.text_soft } else { theme.text_weak.gamma_multiply(0.5) });
+                    }
+                    if status.wifi == Wifi::Off {
+                        ui.painter().line_segment([rect.left_top(), rect.right_bottom()], Stroke::new(2.0, theme.text_soft));
+                    }
+                    if let Some(level) = status.battery {
+                        ui.add_space(10.0);
+                        let low = level < crate::gamepad::BATTERY_LOW && !status.charging;
+                        let color = if low { theme.danger } else { theme.text_soft };
+                        Self::battery_icon(ui, level, color);
+                        let text = format!("{}{:.0}%", if status.charging { "⚡" } else { "" }, level * 100.0);
+                        ui.label(egui::RichText::new(text).size(15.0).color(color));
+                    }
+                });
+            });
+    }
+
+    /// Top left while locked, filling up during the unlock hold.
+    fn render_lock_badge(&self, ctx: &Context) {
+        let theme = crate::theme::current();
+        egui::Window::new("lock_badge")
+            .anchor(egui::Align2::LEFT_TOP, egui::vec2(40.0, 40.0))
+            .resizable(false).collapsible(false).title_bar(false).interactable(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::symmetric(14.0, 8.0))
+                .rounding(Rounding::same(14.0))
+                .stroke(Stroke::NONE)
+                .fill(theme.overlay(200)))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("🔒").size(18.0).color(theme.text_soft));
+                    ui.label(egui::RichText::new(tr("Locked")).size(15.0).color(theme.text_soft));
+                });
+                if self.unlock_progress > 0.0 {
+                    ui.add(egui::ProgressBar::new(self.unlock_progress).desired_width(110.0).desired_height(4.0).fill(theme.accent));
+                    ctx.request_repaint();
+                }
+            });
+    }
+
+    fn render_controller_battery(&self, ctx: &Context, level: f32, top: f32) {
+        let theme = crate::theme::current();
+        let low = level < crate::gamepad::BATTERY_LOW;
+        let color = if low { theme.danger } else { theme.text_soft };
+        egui::Window::new("controller_battery")
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-40.0, top))
+            .resizable(false).collapsible(false).title_bar(false).interactable(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::symmetric(12.0, 8.0))
+                .rounding(Rounding::same(14.0))
+                .stroke(if low { Stroke::new(1.0, color) } else { Stroke::NONE })
+                .fill(theme.overlay(200)))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("🎮").size(18.0).color(color));
+                    Self::battery_icon(ui, level, color);
+                    let text = if low { trf("{}% · low", &[&format!("{:.0}", level * 100.0)]) } else { format!("{:.0}%", level * 100.0) };
+                    ui.label(egui::RichText::new(text).size(15.0).color(color));
                 });
             });
     }
 
     // ── Web toolbar ───────────────────────────────────────────────────────────
     fn render_web_toolbar(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
         egui::Window::new("web_toolbar")
             .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -20.0))
             .resizable(false).collapsible(false).title_bar(false)
             .frame(egui::Frame::window(&ctx.style())
                 .inner_margin(Margin::same(10.0))
                 .rounding(Rounding::same(22.0))
-                .fill(Color32::from_rgba_unmultiplied(24, 24, 32, 235)))
+                .fill(theme.panel(235)))
             .show(ctx, |ui| {
                 ui.horizontal(|ui| {
                     if Self::icon_btn(ui, "←").clicked() { self.web_browser.go_back = true; }
                     if Self::icon_btn(ui, "→").clicked() { self.web_browser.go_forward = true; }
                     if Self::icon_btn(ui, "↺").clicked() { self.web_browser.reload = true; }
                     ui.add(egui::TextEdit::singleline(&mut self.web_browser.url_bar)
-                        .desired_width(360.0).hint_text("Enter URL…"));
+                        .desired_width(360.0).hint_text(tr("Enter URL…")));
                     // 3D toggle for VR web content
                     let on = self.params.stereo_mode != 0;
                     let label = match self.params.stereo_mode { 1 => "3D SBS", 2 => "3D OU", _ => "2D" };
                     if ui.add(egui::Button::new(egui::RichText::new(label).size(16.0)
-                            .color(if on { Color32::WHITE } else { Color32::from_gray(200) }))
+                            .color(if on { theme.on_accent } else { theme.text_soft }))
                         .min_size(egui::vec2(72.0, 44.0))
-                        .fill(if on { Color32::from_rgb(60, 120, 220) }
-                              else { Color32::from_rgba_unmultiplied(40, 40, 55, 200) })).clicked() {
+                        .fill(if on { theme.accent }
+                              else { theme.control(200) })).clicked() {
                         self.params.stereo_mode = (self.params.stereo_mode + 1) % STEREO_MODES;
                     }
+                    if Self::icon_btn(ui, "🗗").clicked() { self.web_browser.pop_out = true; }
                     if Self::icon_btn(ui, "🎬").clicked() {
                         self.params.web_mode = false;
                         self.main_menu_visible = false;
@@ -850,17 +4342,74 @@ impl VrUi {
             });
     }
 
+    // ── Stream URL dialog ─────────────────────────────────────────────────────
+    fn render_url_dialog(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
+        egui::Window::new("url_dialog")
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .resizable(false).collapsible(false).title_bar(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::same(20.0))
+                .rounding(Rounding::same(22.0))
+                .stroke(theme.stroke)
+                .fill(theme.overlay(255)))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(tr("Play from URL")).size(24.0).strong().color(theme.text));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if Self::icon_btn(ui, "✕").clicked() { self.close_url_dialog(); }
+                        if Self::icon_btn(ui, "▶").clicked() { self.submit_url_dialog(); }
+                    });
+                });
+                ui.add_space(8.0);
+                ui.add(egui::TextEdit::singleline(&mut self.keyboard.input)
+                    .font(FontId::new(22.0, FontFamily::Proportional))
+                    .hint_text("http://nas.local/movie.mp4")
+                    .desired_width(700.0));
+                if !self.url_dialog.history.is_empty() {
+                    ui.add_space(8.0);
+                    ui.label(egui::RichText::new(tr("Recent")).size(14.0).color(theme.text_weak));
+                    let mut picked = None;
+                    for url in &self.url_dialog.history {
+                        let short: String = if url.chars().count() > 60 {
+                            url.chars().take(59).chain(std::iter::once('…')).collect()
+                        } else { url.clone() };
+                        let selected = self.keyboard.input == *url;
+                        if ui.add(egui::Button::new(egui::RichText::new(short).size(16.0))
+                            .selected(selected).min_size(egui::vec2(700.0, 34.0))).clicked() {
+                            picked = Some(url.clone());
+                        }
+                    }
+                    if let Some(url) = picked {
+                        self.keyboard.input = url;
+                        self.submit_url_dialog();
+                    }
+                }
+                ui.add_space(10.0);
+                self.keyboard.render(ui);
+            });
+    }
+
     fn render_keyboard(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
         egui::Window::new("keyboard")
             .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
             .resizable(false).collapsible(false).title_bar(false)
             .frame(egui::Frame::window(&ctx.style())
                 .inner_margin(Margin::same(14.0))
                 .rounding(Rounding::same(18.0))
-                .fill(Color32::from_rgb(18, 18, 24)))
+                .fill(theme.overlay(255)))
             .show(ctx, |ui| {
-                if !self.keyboard.input.is_empty() {
-                    ui.label(egui::RichText::new(&self.keyboard.input).size(22.0).color(Color32::WHITE));
+                if let Some((_, ext)) = &self.renaming {
+                    ui.label(egui::RichText::new(tr("Rename")).size(15.0).color(theme.text_weak));
+                    ui.label(egui::RichText::new(format!("{}▏{}", self.keyboard.input, ext)).size(22.0).color(theme.text));
+                    ui.separator();
+                } else if self.naming_preset {
+                    ui.label(egui::RichText::new(tr("Save lens preset as")).size(15.0).color(theme.text_weak));
+                    ui.label(egui::RichText::new(format!("{}▏", self.keyboard.input)).size(22.0).color(theme.text));
+                    ui.separator();
+                } else if !self.keyboard.input.is_empty() {
+                    ui.label(egui::RichText::new(&self.keyboard.input).size(22.0).color(theme.text));
                     ui.separator();
                 }
                 self.keyboard.render(ui);
@@ -868,9 +4417,90 @@ impl VrUi {
     }
 
     fn icon_btn(ui: &mut egui::Ui, icon: &str) -> egui::Response {
+        let theme = crate::theme::current();
         ui.add(egui::Button::new(egui::RichText::new(icon).size(22.0))
             .min_size(egui::vec2(48.0, 44.0))
-            .fill(Color32::from_rgba_unmultiplied(40, 40, 55, 200)))
+            .fill(theme.control(200)))
+    }
+}
+
+// ── Toasts ────────────────────────────────────────────────────────────────────
+
+/// Toasts from other threads (a saved snapshot, a file that failed to open
+/// beside), shown from the next frame.
+static POSTED_TOASTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Queue a toast from anywhere; `VrUi::show_toast` when the UI is at hand.
+pub fn post_toast(text: impl Into<String>) {
+    if let Ok(mut posted) = POSTED_TOASTS.lock() {
+        posted.push(text.into());
+    }
+}
+
+impl VrUi {
+    /// Show `text` briefly at the bottom of the view, after the toasts already
+    /// waiting. The same text again only restarts it if it's showing.
+    pub fn show_toast(&mut self, text: impl Into<String>) {
+        const MAX_WAITING: usize = 4;
+        let text = text.into();
+        if self.toasts.front() == Some(&text) {
+            self.toast_since = Some(Instant::now());
+            return;
+        }
+        if self.toasts.contains(&text) {
+            return;
+        }
+        if self.toasts.len() > MAX_WAITING {
+            // Drop the oldest waiting one, not the one on screen
+            self.toasts.remove(1);
+        }
+        self.toasts.push_back(text);
+    }
+
+    pub fn toast_visible(&self) -> bool { self.toast_since.is_some() }
+
+    /// Take down the toast showing (○ or a click); the next one follows.
+    pub fn dismiss_toast(&mut self) {
+        self.toasts.pop_front();
+        self.toast_since = None;
+    }
+
+    fn render_toast(&mut self, ctx: &Context) {
+        let theme = crate::theme::current();
+        const TOAST_SECS: f32 = 3.0;
+        let Some(text) = self.toasts.front() else { return };
+        let since = *self.toast_since.get_or_insert_with(Instant::now);
+        if since.elapsed().as_secs_f32() > TOAST_SECS {
+            self.dismiss_toast();
+            ctx.request_repaint();
+            return;
+        }
+        let waiting = self.toasts.len() - 1;
+        let mut dismissed = false;
+        egui::Window::new("toast")
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -140.0))
+            .resizable(false).collapsible(false).title_bar(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .inner_margin(Margin::symmetric(20.0, 12.0))
+                .rounding(Rounding::same(18.0))
+                .fill(theme.overlay(220)))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    dismissed = ui.add(egui::Label::new(egui::RichText::new(text.as_str()).size(18.0).color(theme.text))
+                        .sense(egui::Sense::click())).clicked();
+                    if waiting > 0 {
+                        ui.label(egui::RichText::new(format!("+{}", waiting)).size(14.0).color(theme.text_weak));
+                    }
+                    if !crate::gamepad::controllers().is_empty() {
+                        ui.label(egui::RichText::new(crate::gamepad::glyph(crate::gamepad::Button::Circle))
+                            .size(14.0).color(theme.text_weak));
+                    }
+                });
+            });
+        if dismissed {
+            self.dismiss_toast();
+        }
+        ctx.request_repaint();
     }
 }
 
@@ -2,6 +2,8 @@ use log::{info, error};
 use jni::objects::{JObject, JValue};
 use jni::sys::jobject;
 use android_activity::AndroidApp;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 /// Video frame data received from Java
 pub struct VideoFrame {
@@ -98,10 +100,11 @@ use std::sync::atomic::{AtomicI32, Ordering};
 /// Pending video file descriptor from Java (set by onVideoFdReady)
 pub static PENDING_VIDEO_FD: AtomicI32 = AtomicI32::new(-1);
 
-/// Check if there's a pending video fd
-pub fn get_pending_fd() -> Option<i32> {
+/// Take the pending video fd (Java detached it, so it's ours to close)
+pub fn get_pending_fd() -> Option<std::os::fd::OwnedFd> {
+    use std::os::fd::FromRawFd;
     let fd = PENDING_VIDEO_FD.swap(-1, Ordering::SeqCst);
-    if fd >= 0 { Some(fd) } else { None }
+    if fd >= 0 { Some(unsafe { std::os::fd::OwnedFd::from_raw_fd(fd) }) } else { None }
 }
 
 // JNI Export to receive file descriptor for NDK decoder
@@ -112,20 +115,23 @@ pub unsafe extern "C" fn Java_com_vrapp_core_MainActivity_onVideoFdReady(
     fd: jni::sys::jint,
 ) {
     info!("JNI Native: Got video fd = {}", fd);
-    PENDING_VIDEO_FD.store(fd, Ordering::SeqCst);
+    // A second pick before the first was taken: close the one that's replaced
+    let replaced = PENDING_VIDEO_FD.swap(fd, Ordering::SeqCst);
+    if replaced >= 0 {
+        libc::close(replaced);
+    }
 }
 
-// ── Recovery baseline stub ──────────────────────────────────────────────────────
-// The decompiled Java declares onDisplayRotation as native; the head-tracking-flip
-// feature (orientation.rs) isn't rebuilt yet, so this is a no-op for now to keep the
-// JNI link resolved. (onWebFrame/onVoiceResult/onVoiceError are provided by webview.rs.)
-
+/// JNI callback: display rotation (`Surface.ROTATION_*`) on focus and on every
+/// configuration change, for the head-tracking axis remap.
 #[no_mangle]
 pub unsafe extern "C" fn Java_com_vrapp_core_MainActivity_onDisplayRotation(
     _env: jni::JNIEnv,
     _class: JObject,
-    _rotation: jni::sys::jint,
-) {}
+    rotation: jni::sys::jint,
+) {
+    crate::sensors::set_display_rotation(rotation);
+}
 
 /// Start audio from file path (for file browser selections)
 pub fn start_audio_from_path(app: &AndroidApp, path: &str) {
@@ -177,27 +183,75 @@ pub fn seek_audio(app: &AndroidApp, position_ms: i32) {
     }
 }
 
-/// Increase system media volume
-pub fn volume_up(app: &AndroidApp) {
+/// How often the media volume is read back (the phone's own keys change it too).
+const VOLUME_POLL: Duration = Duration::from_millis(250);
+
+/// The media volume as last read back, for the volume OSD.
+struct VolumeOsd {
+    read_at: Option<Instant>,
+    /// (level, max) steps then
+    steps: Option<(i32, i32)>,
+}
+
+static VOLUME: Mutex<VolumeOsd> = Mutex::new(VolumeOsd { read_at: None, steps: None });
+
+/// Media volume as (level, max) steps; read from Java at most every `VOLUME_POLL`.
+pub fn volume(app: &AndroidApp) -> Option<(i32, i32)> {
+    let mut volume = VOLUME.lock().unwrap();
+    if volume.read_at.is_some_and(|at| at.elapsed() < VOLUME_POLL) {
+        return volume.steps;
+    }
+    volume.read_at = Some(Instant::now());
+    let vm = unsafe { jni::JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM).unwrap() };
+    let Ok(mut env) = vm.attach_current_thread() else { return volume.steps };
+    let activity = unsafe { JObject::from_raw(app.activity_as_ptr() as jobject) };
+    let level = env.call_method(&activity, "getVolume", "()I", &[]).and_then(|v| v.i());
+    let max = env.call_method(&activity, "getMaxVolume", "()I", &[]).and_then(|v| v.i());
+    volume.steps = match (level, max) {
+        (Ok(level), Ok(max)) if max > 0 => Some((level, max)),
+        (Err(e), _) | (_, Err(e)) => {
+            error!("Failed to read volume: {:?}", e);
+            None
+        }
+        _ => None,
+    };
+    volume.steps
+}
+
+/// Set the media volume to `level` steps (0…max).
+pub fn set_volume(app: &AndroidApp, level: i32) {
     let vm = unsafe { jni::JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM).unwrap() };
     let mut env = vm.attach_current_thread().unwrap();
     let activity = unsafe { JObject::from_raw(app.activity_as_ptr() as jobject) };
-    
-    match env.call_method(&activity, "volumeUp", "()V", &[]) {
-        Ok(_) => info!("Volume up"),
-        Err(e) => error!("Failed to increase volume: {:?}", e),
+
+    match env.call_method(&activity, "setVolume", "(I)V", &[JValue::Int(level)]) {
+        Ok(_) => {
+            // Read back on the next call
+            VOLUME.lock().unwrap().read_at = None;
+            info!("Volume set to {}", level);
+        }
+        Err(e) => error!("Failed to set volume: {:?}", e),
     }
 }
 
-/// Decrease system media volume
-pub fn volume_down(app: &AndroidApp) {
+/// Brightness last sent to the window, so it only goes to Java on a change.
+static SCREEN_BRIGHTNESS: Mutex<Option<f32>> = Mutex::new(None);
+
+/// Override the window's screen brightness (0…1); negative follows the system
+/// setting again.
+pub fn set_screen_brightness(app: &AndroidApp, brightness: f32) {
+    let mut current = SCREEN_BRIGHTNESS.lock().unwrap();
+    if *current == Some(brightness) {
+        return;
+    }
+    *current = Some(brightness);
     let vm = unsafe { jni::JavaVM::from_raw(app.vm_as_ptr() as *mut jni::sys::JavaVM).unwrap() };
-    let mut env = vm.attach_current_thread().unwrap();
+    let Ok(mut env) = vm.attach_current_thread() else { return };
     let activity = unsafe { JObject::from_raw(app.activity_as_ptr() as jobject) };
-    
-    match env.call_method(&activity, "volumeDown", "()V", &[]) {
-        Ok(_) => info!("Volume down"),
-        Err(e) => error!("Failed to decrease volume: {:?}", e),
+
+    match env.call_method(&activity, "setScreenBrightness", "(F)V", &[JValue::Float(brightness)]) {
+        Ok(_) => info!("Screen brightness {}", if brightness < 0.0 { "system".to_string() } else { format!("{:.0}%", brightness * 100.0) }),
+        Err(e) => error!("Failed to set screen brightness: {:?}", e),
     }
 }
 
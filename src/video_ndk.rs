@@ -3,14 +3,16 @@
 //! Pure NDK video decoding using AMediaCodec and AMediaExtractor.
 //! No Java, no JNI - just Rust + NDK.
 
-use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, AtomicUsize, Ordering}};
 use std::thread::{self, JoinHandle};
 use std::fs::File;
-use std::os::unix::io::AsRawFd;
+use std::os::fd::{AsRawFd, BorrowedFd, OwnedFd};
 use std::ffi::CString;
 use std::ptr;
 use log::{info, warn, error};
 
+use crate::media_source::{MediaSource, NdkDataSource, SourceOpener};
+
 /// Shared frame buffer for passing decoded frames to renderer
 pub struct FrameBuffer {
     pub y_data: Vec<u8>,
@@ -29,6 +31,87 @@ pub struct PlaybackState {
     pub seek_request: Option<i64>,
     pub pause_time_ms: u128,   // When pause started (elapsed ms)
     pub pause_offset_ms: u128, // Cumulative paused time
+    pub loop_at_eos: bool,     // Seek back to 0 at end-of-stream instead of finishing
+    pub finished: bool,        // Set by the decoder thread once the last frame is out
+    pub error: Option<DecodeError>, // Why the decode thread gave up (taken by the UI)
+    pub warning: Option<String>,    // Capability warning for the opened format (taken by the UI)
+    pub rotation_degrees: u32,      // Clockwise display rotation from the container (0/90/180/270)
+    pub primed: bool,               // Pre-buffering: decode the first frame, then hold until resumed
+    pub status: DecoderStatus,      // What the decoder is doing, for the UI
+    pub buffered_until_us: Option<i64>, // End of the downloaded range (streams only)
+    pub seekable: bool,             // False for sources that can't seek (HLS); seeks are ignored
+    pub last_frame_at: Option<std::time::Instant>, // When the last frame came out (stall detection)
+}
+
+/// Playing with no new frame for this long means the stream is starved.
+const STALL_MS: u128 = 1500;
+
+/// What the decoder is doing, as shown to the user.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecoderStatus {
+    /// Opening the source and setting up the codec.
+    Opening,
+    /// Waiting for data when the network can't keep up.
+    Buffering,
+    /// Decoding up to the new position after a seek.
+    Seeking,
+    Playing,
+    Paused,
+    /// The last frame has been shown.
+    Ended,
+    Error(String),
+}
+
+/// Why playback couldn't start or had to stop.
+#[derive(Debug, Clone)]
+pub enum DecodeError {
+    /// The file / URL / custom source couldn't be opened or read.
+    Source(String),
+    /// The container has no video track.
+    NoVideoTrack,
+    /// No decoder for this format could be created, configured and started.
+    CodecUnavailable { mime: String, tried: Vec<String> },
+    /// The codec kept failing mid-stream and restarting it didn't help.
+    CodecFailed { codec: String, status: i32 },
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::Source(e) => write!(f, "Can't open media: {}", e),
+            DecodeError::NoVideoTrack => write!(f, "No video track found"),
+            DecodeError::CodecUnavailable { mime, tried } =>
+                write!(f, "No working decoder for {} (tried {})", mime, tried.join(", ")),
+            DecodeError::CodecFailed { codec, status } =>
+                write!(f, "Decoder {} failed (error {})", codec, status),
+        }
+    }
+}
+
+/// Input fds currently held by decoders (logged, so leaks show up).
+static OPEN_INPUT_FDS: AtomicUsize = AtomicUsize::new(0);
+
+/// A decoder's own duplicate of an input fd. Shared by the decoder handle and its
+/// decode thread; closed once both have let go.
+struct InputFd(OwnedFd);
+
+impl InputFd {
+    fn dup(fd: BorrowedFd<'_>) -> Result<Arc<Self>, String> {
+        let owned = fd.try_clone_to_owned().map_err(|e| format!("dup of fd {} failed: {}", fd.as_raw_fd(), e))?;
+        OPEN_INPUT_FDS.fetch_add(1, Ordering::SeqCst);
+        Ok(Arc::new(Self(owned)))
+    }
+}
+
+impl Drop for InputFd {
+    fn drop(&mut self) {
+        OPEN_INPUT_FDS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Number of input fds decoders are holding open.
+pub fn open_input_fds() -> usize {
+    OPEN_INPUT_FDS.load(Ordering::SeqCst)
 }
 
 /// NDK-based video decoder using AMediaCodec
@@ -37,6 +120,8 @@ pub struct NdkVideoDecoder {
     playback_state: Arc<Mutex<PlaybackState>>,
     running: Arc<AtomicBool>,
     decoder_thread: Option<JoinHandle<()>>,
+    /// The input fd, if decoding from one; released on `stop()`.
+    input_fd: Option<Arc<InputFd>>,
 }
 
 impl NdkVideoDecoder {
@@ -57,9 +142,20 @@ impl NdkVideoDecoder {
                 seek_request: None,
                 pause_time_ms: 0,
                 pause_offset_ms: 0,
+                loop_at_eos: false,
+                finished: false,
+                error: None,
+                warning: None,
+                rotation_degrees: 0,
+                primed: false,
+                status: DecoderStatus::Opening,
+                buffered_until_us: None,
+                seekable: true,
+                last_frame_at: None,
             })),
             running: Arc::new(AtomicBool::new(false)),
             decoder_thread: None,
+            input_fd: None,
         }
     }
 
@@ -79,16 +175,19 @@ impl NdkVideoDecoder {
 
         if let Ok(mut state) = playback_state.lock() {
             state.is_playing = true;
+            state.status = DecoderStatus::Opening;
+            state.buffered_until_us = None;
+            state.seekable = true;
         }
 
         self.decoder_thread = Some(thread::spawn(move || {
             if path.starts_with("test://") {
                 run_test_pattern(frame_buffer, playback_state, running);
-            } else {
-                if let Err(e) = run_mediacodec_decode(&path, frame_buffer.clone(), playback_state.clone(), running.clone()) {
-                    error!("MediaCodec decode error: {}", e);
-                    // Fall back to test pattern
-                    run_test_pattern(frame_buffer, playback_state, running);
+            } else if let Err(e) = run_mediacodec_decode(&path, frame_buffer, playback_state.clone(), running) {
+                error!("MediaCodec decode error: {}", e);
+                if let Ok(mut state) = playback_state.lock() {
+                    state.status = DecoderStatus::Error(e.clone());
+                    state.error = Some(DecodeError::Source(e));
                 }
             }
         }));
@@ -96,14 +195,53 @@ impl NdkVideoDecoder {
         Ok(())
     }
 
-    /// Start decoding from a file descriptor (for content:// URIs)
-    pub fn start_from_fd(&mut self, fd: i32) -> Result<(), String> {
+    /// Start decoding from a file descriptor (an opened file, or a content:// URI's
+    /// fd). The decoder keeps its own duplicate, so the caller can close theirs.
+    pub fn start_from_fd(&mut self, fd: BorrowedFd<'_>) -> Result<(), String> {
+        let input = InputFd::dup(fd)?;
+        info!("NdkVideoDecoder: Starting decode from fd {} ({} input fds open)",
+            input.0.as_raw_fd(), open_input_fds());
+        self.start_source(Source::Fd(Some(Arc::clone(&input))))?;
+        self.input_fd = Some(input);
+        Ok(())
+    }
+
+    /// Start streaming from an http(s) URL. Progressive files are fetched by
+    /// AMediaExtractor itself; `.m3u8` playlists go through the HLS client.
+    pub fn start_from_url(&mut self, url: &str) -> Result<(), String> {
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(format!("Unsupported URL: {}", url));
+        }
+        info!("NdkVideoDecoder: Starting stream from {}", url);
+        if crate::hls::is_hls_url(url) {
+            let url = url.to_string();
+            self.start_from_source(Box::new(move |running| {
+                Ok(Box::new(crate::hls::HlsStream::open(&url, running.clone())?) as Box<dyn MediaSource>)
+            }))
+        } else {
+            self.start_source(Source::Url(url.to_string()))
+        }
+    }
+
+    /// Start decoding from any Rust-implemented `MediaSource`, opened on the
+    /// decode thread.
+    pub fn start_from_source(&mut self, open: SourceOpener) -> Result<(), String> {
+        self.start_source(Source::Custom { open: Some(open), data_source: None })
+    }
+
+    /// Pre-buffer instead of playing: the next start opens the source, sets up the
+    /// codec and decodes the first frame, then holds until `resume()`.
+    pub fn prime(&self) {
+        if let Ok(mut state) = self.playback_state.lock() {
+            state.primed = true;
+        }
+    }
+
+    fn start_source(&mut self, source: Source) -> Result<(), String> {
         if self.running.load(Ordering::SeqCst) {
             self.stop();
         }
 
-        info!("NdkVideoDecoder: Starting decode from fd {}", fd);
-
         let frame_buffer = Arc::clone(&self.frame_buffer);
         let playback_state = Arc::clone(&self.playback_state);
         let running = Arc::clone(&self.running);
@@ -111,14 +249,19 @@ impl NdkVideoDecoder {
         running.store(true, Ordering::SeqCst);
 
         if let Ok(mut state) = playback_state.lock() {
-            state.is_playing = true;
+            state.is_playing = !state.primed;
+            state.status = DecoderStatus::Opening;
+            state.buffered_until_us = None;
+            state.seekable = true;
         }
 
         self.decoder_thread = Some(thread::spawn(move || {
-            if let Err(e) = run_mediacodec_decode_fd(fd, frame_buffer.clone(), playback_state.clone(), running.clone()) {
-                error!("MediaCodec decode fd error: {}", e);
-                // Fall back to test pattern
-                run_test_pattern(frame_buffer, playback_state, running);
+            if let Err(e) = run_mediacodec_decode_source(source, frame_buffer, playback_state.clone(), running) {
+                error!("MediaCodec decode error: {}", e);
+                if let Ok(mut state) = playback_state.lock() {
+                    state.status = DecoderStatus::Error(e.to_string());
+                    state.error = Some(e);
+                }
             }
         }));
 
@@ -135,6 +278,15 @@ impl NdkVideoDecoder {
         None
     }
 
+    /// The most recent frame, whether or not `get_frame` has taken it already.
+    pub fn latest_frame(&self) -> Option<(Vec<u8>, Vec<u8>, u32, u32)> {
+        let buffer = self.frame_buffer.lock().ok()?;
+        if buffer.y_data.is_empty() {
+            return None;
+        }
+        Some((buffer.y_data.clone(), buffer.uv_data.clone(), buffer.width, buffer.height))
+    }
+
     pub fn is_running(&self) -> bool {
         self.running.load(Ordering::SeqCst)
     }
@@ -142,20 +294,36 @@ impl NdkVideoDecoder {
     pub fn pause(&self) {
         if let Ok(mut state) = self.playback_state.lock() {
             state.is_playing = false;
+            // A seek can't finish while paused, so don't leave the spinner up
+            if matches!(state.status, DecoderStatus::Playing | DecoderStatus::Buffering | DecoderStatus::Seeking) {
+                state.status = DecoderStatus::Paused;
+            }
         }
     }
 
     pub fn resume(&self) {
         if let Ok(mut state) = self.playback_state.lock() {
             state.is_playing = true;
+            if state.status == DecoderStatus::Paused {
+                state.status = DecoderStatus::Playing;
+                state.last_frame_at = Some(std::time::Instant::now());
+            }
         }
     }
 
+    /// Seek to `position_us`; nothing happens if the source can't seek.
     pub fn seek(&self, position_us: i64) {
         if let Ok(mut state) = self.playback_state.lock() {
+            if !state.seekable {
+                return;
+            }
             state.seek_request = Some(position_us);
             // Update position immediately so slider reflects seek even when paused
             state.position_us = position_us;
+            state.finished = false;
+            if matches!(state.status, DecoderStatus::Playing | DecoderStatus::Buffering | DecoderStatus::Seeking | DecoderStatus::Ended) {
+                state.status = if state.is_playing { DecoderStatus::Seeking } else { DecoderStatus::Paused };
+            }
         }
     }
 
@@ -167,15 +335,71 @@ impl NdkVideoDecoder {
         self.playback_state.lock().map(|s| s.duration_us).unwrap_or(0)
     }
 
+    /// Whether `seek` works (false for live-window sources like HLS).
+    pub fn is_seekable(&self) -> bool {
+        self.playback_state.lock().map(|s| s.seekable).unwrap_or(true)
+    }
+
+    /// Clockwise rotation the frames need for display (phone clips recorded in portrait).
+    pub fn rotation_degrees(&self) -> u32 {
+        self.playback_state.lock().map(|s| s.rotation_degrees).unwrap_or(0)
+    }
+
+    /// Current decoder status. A playing stream that has stopped producing frames
+    /// reports `Buffering` (the decode thread itself may be blocked on the network).
+    pub fn status(&self) -> DecoderStatus {
+        let Ok(state) = self.playback_state.lock() else { return DecoderStatus::Paused };
+        let stalled = state.is_playing
+            && state.last_frame_at.is_some_and(|t| t.elapsed().as_millis() > STALL_MS);
+        match &state.status {
+            DecoderStatus::Playing if stalled => DecoderStatus::Buffering,
+            status => status.clone(),
+        }
+    }
+
+    /// How far past the current position the stream is downloaded, for network
+    /// sources that report it (local files: `None`).
+    pub fn buffered_ahead_us(&self) -> Option<i64> {
+        self.playback_state.lock().ok()
+            .and_then(|s| s.buffered_until_us.map(|end| (end - s.position_us).max(0)))
+    }
+
     pub fn is_paused(&self) -> bool {
         !self.playback_state.lock().map(|s| s.is_playing).unwrap_or(true)
     }
 
+    /// Loop back to the start at end-of-stream, or (default) stop and report
+    /// `take_finished()` so the caller can decide what plays next.
+    pub fn set_loop_at_eos(&self, enabled: bool) {
+        if let Ok(mut state) = self.playback_state.lock() {
+            state.loop_at_eos = enabled;
+        }
+    }
+
+    /// Why the decode thread stopped, if it failed (reported once).
+    pub fn take_error(&self) -> Option<DecodeError> {
+        self.playback_state.lock().ok().and_then(|mut s| s.error.take())
+    }
+
+    /// Capability warning for the file being played, if any (reported once).
+    pub fn take_warning(&self) -> Option<String> {
+        self.playback_state.lock().ok().and_then(|mut s| s.warning.take())
+    }
+
+    /// True once after the decoder has output its last frame (non-looping only).
+    pub fn take_finished(&self) -> bool {
+        self.playback_state.lock()
+            .map(|mut s| std::mem::replace(&mut s.finished, false))
+            .unwrap_or(false)
+    }
+
     pub fn stop(&mut self) {
         self.running.store(false, Ordering::SeqCst);
         if let Some(handle) = self.decoder_thread.take() {
             let _ = handle.join();
         }
+        // The decode thread has dropped its reference, so this closes the fd
+        self.input_fd = None;
         if let Ok(mut buffer) = self.frame_buffer.lock() {
             buffer.y_data.clear();
             buffer.uv_data.clear();
@@ -190,6 +414,148 @@ impl Drop for NdkVideoDecoder {
     }
 }
 
+// MediaCodecInfo.CodecCapabilities color formats (plus the gralloc YV12 code some
+// vendor decoders report) that decide how the chroma planes are laid out.
+const COLOR_FORMAT_YUV420_PLANAR: i32 = 19;
+const COLOR_FORMAT_YUV420_SEMI_PLANAR: i32 = 21;
+const COLOR_FORMAT_YUV420_FLEXIBLE: i32 = 0x7F42_0888;
+const COLOR_FORMAT_YV12: i32 = 0x3231_5659;
+
+/// How the chroma of a 4:2:0 output buffer is stored.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Chroma {
+    /// NV12: one interleaved UV plane, same stride as Y.
+    SemiPlanar,
+    /// I420: U plane then V plane, each at half the Y stride.
+    I420,
+    /// YV12: V plane then U plane, chroma stride aligned to 16 bytes.
+    Yv12,
+}
+
+impl Chroma {
+    /// Map a codec's `color-format`. "Flexible" means I420 from the platform
+    /// software decoders and NV12 from hardware ones when read as a byte buffer.
+    fn from_color_format(format: Option<i32>, software_codec: bool) -> Self {
+        match format {
+            Some(COLOR_FORMAT_YUV420_PLANAR) => Chroma::I420,
+            Some(COLOR_FORMAT_YV12) => Chroma::Yv12,
+            Some(COLOR_FORMAT_YUV420_FLEXIBLE) if software_codec => Chroma::I420,
+            Some(COLOR_FORMAT_YUV420_SEMI_PLANAR) | Some(_) | None => Chroma::SemiPlanar,
+        }
+    }
+}
+
+/// Platform software decoders (they emit planar output).
+fn is_software_codec(name: &str) -> bool {
+    name.starts_with("c2.android.") || name.starts_with("OMX.google.")
+}
+
+/// Where the picture sits in a MediaCodec output buffer. Many SoCs pad rows to
+/// 64/128-pixel strides and planes to an aligned slice height, and the visible
+/// picture is a crop rectangle inside that.
+#[derive(Clone, Copy, Debug)]
+struct FrameLayout {
+    chroma: Chroma,
+    stride: usize,       // bytes per Y row (UV rows too, for semi-planar NV12)
+    slice_height: usize, // rows in the Y plane, including padding
+    crop_left: usize,
+    crop_top: usize,
+    width: usize,        // visible size (even, so chroma lines up)
+    height: usize,
+}
+
+impl FrameLayout {
+    /// Tightly packed `width`×`height` (what the track format promises).
+    fn packed(width: i32, height: i32, chroma: Chroma) -> Self {
+        let (w, h) = (width.max(2) as usize & !1, height.max(2) as usize & !1);
+        Self { chroma, stride: w, slice_height: h, crop_left: 0, crop_top: 0, width: w, height: h }
+    }
+
+    /// Read `color-format`, `stride`, `slice-height` and the crop rect from a codec
+    /// output format. Missing keys fall back to the format's width/height.
+    unsafe fn from_format(format: *mut ndk_sys::AMediaFormat, codec_name: &str) -> Option<Self> {
+        let get = |key: &str| -> Option<i32> {
+            let key = CString::new(key).ok()?;
+            let mut v: i32 = 0;
+            ndk_sys::AMediaFormat_getInt32(format, key.as_ptr(), &mut v).then_some(v)
+        };
+        let width = get("width")?;
+        let height = get("height")?;
+        let (left, top, right, bottom) = match (get("crop-left"), get("crop-top"), get("crop-right"), get("crop-bottom")) {
+            (Some(l), Some(t), Some(r), Some(b)) if r >= l && b >= t => (l, t, r, b),
+            _ => (0, 0, width - 1, height - 1),
+        };
+        let chroma = Chroma::from_color_format(get("color-format"), is_software_codec(codec_name));
+        let mut layout = Self::packed(right - left + 1, bottom - top + 1, chroma);
+        layout.crop_left = left.max(0) as usize & !1;
+        layout.crop_top = top.max(0) as usize & !1;
+        layout.stride = get("stride").filter(|&s| s >= width).unwrap_or(width) as usize;
+        layout.slice_height = get("slice-height").filter(|&h| h >= height).unwrap_or(height) as usize;
+        Some(layout)
+    }
+
+    /// Copy the visible picture into tightly packed Y and interleaved UV (NV12)
+    /// planes, the renderer's format; planar chroma is interleaved on the way.
+    /// False if the buffer is too small for this layout.
+    fn copy_to_nv12(&self, src: &[u8], y_out: &mut Vec<u8>, uv_out: &mut Vec<u8>) -> bool {
+        let (w, h) = (self.width, self.height);
+        let y_plane = self.stride * self.slice_height;
+        let last_y = (self.crop_top + h - 1) * self.stride + self.crop_left + w;
+        let chroma_row = self.crop_top / 2 + h / 2 - 1;
+        let last_uv = match self.chroma {
+            Chroma::SemiPlanar => y_plane + chroma_row * self.stride + self.crop_left + w,
+            Chroma::I420 | Chroma::Yv12 => {
+                let (c_stride, c_plane) = self.chroma_plane();
+                y_plane + c_plane + chroma_row * c_stride + self.crop_left / 2 + w / 2
+            }
+        };
+        if src.len() < last_y.max(last_uv) {
+            return false;
+        }
+        y_out.resize(w * h, 0);
+        uv_out.resize(w * h / 2, 0);
+        for (row, out) in y_out.chunks_exact_mut(w).enumerate() {
+            let start = (self.crop_top + row) * self.stride + self.crop_left;
+            out.copy_from_slice(&src[start..start + w]);
+        }
+        match self.chroma {
+            Chroma::SemiPlanar => {
+                for (row, out) in uv_out.chunks_exact_mut(w).enumerate() {
+                    let start = y_plane + (self.crop_top / 2 + row) * self.stride + self.crop_left;
+                    out.copy_from_slice(&src[start..start + w]);
+                }
+            }
+            Chroma::I420 | Chroma::Yv12 => {
+                let (c_stride, c_plane) = self.chroma_plane();
+                let (u_base, v_base) = if self.chroma == Chroma::I420 {
+                    (y_plane, y_plane + c_plane)
+                } else {
+                    (y_plane + c_plane, y_plane)
+                };
+                for (row, out) in uv_out.chunks_exact_mut(w).enumerate() {
+                    let offset = (self.crop_top / 2 + row) * c_stride + self.crop_left / 2;
+                    let u = &src[u_base + offset..u_base + offset + w / 2];
+                    let v = &src[v_base + offset..v_base + offset + w / 2];
+                    for (pair, (&u, &v)) in out.chunks_exact_mut(2).zip(u.iter().zip(v)) {
+                        pair[0] = u;
+                        pair[1] = v;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Stride and size of one chroma plane of a planar layout.
+    fn chroma_plane(&self) -> (usize, usize) {
+        let c_stride = match self.chroma {
+            Chroma::Yv12 => (self.stride / 2 + 15) & !15,
+            _ => self.stride / 2,
+        };
+        (c_stride, c_stride * (self.slice_height / 2))
+    }
+}
+
 /// Test pattern generator (fallback)
 fn run_test_pattern(
     frame_buffer: Arc<Mutex<FrameBuffer>>,
@@ -202,6 +568,7 @@ fn run_test_pattern(
 
     if let Ok(mut state) = playback_state.lock() {
         state.duration_us = 60_000_000;
+        state.status = DecoderStatus::Playing;
     }
 
     let start_time = std::time::Instant::now();
@@ -419,9 +786,16 @@ fn run_mediacodec_decode(
                                 flags as u32
                             );
                             AMediaExtractor_advance(extractor);
-                        } else {
-                            // EOS - loop video
+                        } else if playback_state.lock().is_ok_and(|s| s.loop_at_eos && s.seekable) {
+                            // EOS - loop video (loop-one mode)
                             AMediaExtractor_seekTo(extractor, 0, SeekMode::AMEDIAEXTRACTOR_SEEK_PREVIOUS_SYNC);
+                        } else {
+                            // EOS - let the codec drain, the output side reports finished
+                            AMediaCodec_queueInputBuffer(
+                                codec, input_idx as usize, 0, 0, 0,
+                                AMEDIACODEC_BUFFER_FLAG_END_OF_STREAM
+                            );
+                            eos_input = true;
                         }
                     }
                 }
@@ -465,6 +839,13 @@ fn run_mediacodec_decode(
                 }
 
                 AMediaCodec_releaseOutputBuffer(codec, output_idx as usize, false);
+
+                if buffer_info.flags & AMEDIACODEC_BUFFER_FLAG_END_OF_STREAM != 0 {
+                    info!("MediaCodec: End of stream");
+                    if let Ok(mut state) = playback_state.lock() {
+                        state.finished = true;
+                    }
+                }
                 
                 // Simple frame pacing: ~16ms for 60fps, ~33ms for 30fps
                 // This avoids wall-clock drift issues with pause/resume
@@ -489,69 +870,191 @@ fn run_mediacodec_decode(
     Ok(())
 }
 
-/// Real MediaCodec decoding via NDK from file descriptor
-fn run_mediacodec_decode_fd(
-    fd: i32,
-    frame_buffer: Arc<Mutex<FrameBuffer>>,
-    playback_state: Arc<Mutex<PlaybackState>>,
-    running: Arc<AtomicBool>,
-) -> Result<(), String> {
+/// Where the decode thread's extractor reads from.
+enum Source {
+    /// A decoder-owned fd, shared with the `NdkVideoDecoder` handle.
+    Fd(Option<Arc<InputFd>>),
+    /// An http(s) URL, fetched by AMediaExtractor itself.
+    Url(String),
+    /// A `MediaSource` behind an `AMediaDataSource`; `data_source` once opened.
+    Custom { open: Option<SourceOpener>, data_source: Option<NdkDataSource> },
+}
+
+impl Source {
+    /// Release whatever the source holds (closes an owned fd, drops a custom
+    /// source). Call after the extractor reading from it is deleted.
+    fn close(&mut self) {
+        match self {
+            Source::Fd(fd) => { fd.take(); }
+            Source::Custom { data_source, .. } => { data_source.take(); }
+            Source::Url(_) => {}
+        }
+    }
+}
+
+/// Platform software decoders to try when the default decoder for `mime` can't
+/// be created or configured (Codec2 names first, then the older OMX ones).
+fn software_decoders(mime: &str) -> &'static [&'static str] {
+    match mime {
+        "video/avc" => &["c2.android.avc.decoder", "OMX.google.h264.decoder"],
+        "video/hevc" => &["c2.android.hevc.decoder", "OMX.google.hevc.decoder"],
+        "video/x-vnd.on2.vp9" => &["c2.android.vp9.decoder", "OMX.google.vp9.decoder"],
+        "video/x-vnd.on2.vp8" => &["c2.android.vp8.decoder", "OMX.google.vp8.decoder"],
+        "video/av01" => &["c2.android.av1.decoder"],
+        "video/mp4v-es" => &["c2.android.mpeg4.decoder", "OMX.google.mpeg4.decoder"],
+        "video/3gpp" => &["c2.android.h263.decoder", "OMX.google.h263.decoder"],
+        _ => &[],
+    }
+}
+
+/// The first video track of `extractor`: index, format (owned) and mime type.
+unsafe fn find_video_track(extractor: *mut ndk_sys::AMediaExtractor) -> Option<(usize, *mut ndk_sys::AMediaFormat, String)> {
     use ndk_sys::*;
-    
-    info!("MediaCodec: Opening from fd {}", fd);
 
-    // We pass i64::MAX for file length since we don't know the size from fd alone
-    // AMediaExtractor will figure it out
-    let file_len = i64::MAX;
+    let track_count = AMediaExtractor_getTrackCount(extractor);
+    info!("MediaCodec: Found {} tracks", track_count);
+    let key = CString::new("mime").unwrap();
+    for i in 0..track_count as usize {
+        let format = AMediaExtractor_getTrackFormat(extractor, i);
+        if format.is_null() { continue; }
+
+        let mut mime_ptr: *const std::os::raw::c_char = ptr::null();
+        if AMediaFormat_getString(format, key.as_ptr(), &mut mime_ptr) && !mime_ptr.is_null() {
+            let mime = std::ffi::CStr::from_ptr(mime_ptr).to_string_lossy().into_owned();
+            info!("Track {}: {}", i, mime);
+            if mime.starts_with("video/") {
+                return Some((i, format, mime));
+            }
+        }
+        AMediaFormat_delete(format);
+    }
+    None
+}
 
-    unsafe {
-        let extractor = AMediaExtractor_new();
-        if extractor.is_null() {
-            return Err("Failed to create AMediaExtractor".into());
+/// The component name of a codec (e.g. "c2.qti.avc.decoder").
+unsafe fn codec_name(codec: *mut ndk_sys::AMediaCodec) -> Option<String> {
+    let mut name: *mut std::os::raw::c_char = ptr::null_mut();
+    if ndk_sys::AMediaCodec_getName(codec, &mut name).0 != 0 || name.is_null() {
+        return None;
+    }
+    let s = std::ffi::CStr::from_ptr(name).to_string_lossy().into_owned();
+    ndk_sys::AMediaCodec_releaseName(codec, name);
+    Some(s)
+}
+
+/// Create, configure and start a decoder for `mime`: the one the user forced (if
+/// any), the platform default, then the software fallbacks. Returns the running
+/// codec and its name.
+unsafe fn create_codec(
+    mime: &str,
+    format: *mut ndk_sys::AMediaFormat,
+) -> Result<(*mut ndk_sys::AMediaCodec, String), DecodeError> {
+    use ndk_sys::*;
+
+    let mut tried: Vec<String> = Vec::new();
+    let forced = crate::codecs::preferred(mime);
+    let candidates = forced.as_deref().map(Some).into_iter()
+        .chain(std::iter::once(None))
+        .chain(software_decoders(mime).iter().map(|n| Some(*n)));
+    for candidate in candidates {
+        let codec = match candidate {
+            None => {
+                let mime_c = CString::new(mime).unwrap_or_default();
+                AMediaCodec_createDecoderByType(mime_c.as_ptr())
+            }
+            Some(name) => {
+                if tried.iter().any(|t| t == name) { continue; }
+                let name_c = CString::new(name).unwrap_or_default();
+                AMediaCodec_createCodecByName(name_c.as_ptr())
+            }
+        };
+        if codec.is_null() {
+            tried.push(candidate.map(str::to_string).unwrap_or_else(|| format!("default {}", mime)));
+            continue;
         }
+        let name = codec_name(codec).or(candidate.map(str::to_string)).unwrap_or_else(|| mime.to_string());
 
-        let status = AMediaExtractor_setDataSourceFd(extractor, fd, 0, file_len);
+        let status = AMediaCodec_configure(codec, format, ptr::null_mut(), ptr::null_mut(), 0);
+        let status = if status.0 == 0 { AMediaCodec_start(codec) } else { status };
         if status.0 != 0 {
-            AMediaExtractor_delete(extractor);
-            // Close the fd since we own it
-            libc::close(fd);
-            return Err(format!("Failed to set data source fd: {:?}", status.0));
+            warn!("MediaCodec: {} failed to start ({})", name, status.0);
+            AMediaCodec_delete(codec);
+            tried.push(name);
+            continue;
         }
+        if candidate.is_some() && candidate == forced.as_deref() {
+            info!("MediaCodec: using forced decoder {}", name);
+        } else if candidate.is_some() {
+            warn!("MediaCodec: using fallback decoder {}", name);
+        }
+        return Ok((codec, name));
+    }
+    Err(DecodeError::CodecUnavailable { mime: mime.to_string(), tried })
+}
 
-        let track_count = AMediaExtractor_getTrackCount(extractor);
-        info!("MediaCodec: Found {} tracks from fd", track_count);
+/// Mid-stream codec errors: flush this many times in a row, then restart the codec
+/// (at most `MAX_CODEC_RESTARTS` times) before giving up.
+const MAX_CODEC_FLUSHES: u32 = 3;
+const MAX_CODEC_RESTARTS: u32 = 2;
 
-        let mut video_track: Option<usize> = None;
-        let mut video_format: *mut AMediaFormat = ptr::null_mut();
-        let mut mime_type = String::new();
+/// Real MediaCodec decoding via NDK from a file descriptor or URL
+fn run_mediacodec_decode_source(
+    mut source: Source,
+    frame_buffer: Arc<Mutex<FrameBuffer>>,
+    playback_state: Arc<Mutex<PlaybackState>>,
+    running: Arc<AtomicBool>,
+) -> Result<(), DecodeError> {
+    use ndk_sys::*;
 
-        for i in 0..track_count as usize {
-            let format = AMediaExtractor_getTrackFormat(extractor, i);
-            if format.is_null() { continue; }
+    unsafe {
+        let mut extractor = AMediaExtractor_new();
+        if extractor.is_null() {
+            source.close();
+            return Err(DecodeError::Source("Failed to create AMediaExtractor".into()));
+        }
 
-            let mut mime_ptr: *const std::os::raw::c_char = ptr::null();
-            let key = CString::new("mime").unwrap();
-            if AMediaFormat_getString(format, key.as_ptr(), &mut mime_ptr) {
-                if !mime_ptr.is_null() {
-                    let mime = std::ffi::CStr::from_ptr(mime_ptr).to_string_lossy();
-                    info!("Track {}: {}", i, mime);
-                    if mime.starts_with("video/") {
-                        video_track = Some(i);
-                        video_format = format;
-                        mime_type = mime.to_string();
-                        break;
+        let status = match &mut source {
+            Source::Fd(fd) => {
+                let fd = fd.as_ref().map_or(-1, |f| f.0.as_raw_fd());
+                info!("MediaCodec: Opening from fd {}", fd);
+                // We pass i64::MAX for file length since we don't know the size from fd alone
+                // AMediaExtractor will figure it out
+                AMediaExtractor_setDataSourceFd(extractor, fd, 0, i64::MAX)
+            }
+            Source::Url(url) => {
+                info!("MediaCodec: Opening {}", url);
+                // Blocks while the connection is made and the header is read
+                let Ok(c_url) = CString::new(url.as_str()) else {
+                    AMediaExtractor_delete(extractor);
+                    return Err(DecodeError::Source("URL contains NUL".into()));
+                };
+                AMediaExtractor_setDataSource(extractor, c_url.as_ptr())
+            }
+            Source::Custom { open, data_source } => {
+                info!("MediaCodec: Opening custom source");
+                let opened = open.take().ok_or("source already opened".to_string())
+                    .and_then(|open| open(&running))
+                    .and_then(NdkDataSource::new);
+                match opened {
+                    Ok(ds) => AMediaExtractor_setDataSourceCustom(extractor, data_source.insert(ds).as_ptr()),
+                    Err(e) => {
+                        AMediaExtractor_delete(extractor);
+                        return Err(DecodeError::Source(e));
                     }
                 }
             }
-            AMediaFormat_delete(format);
+        };
+        if status.0 != 0 {
+            AMediaExtractor_delete(extractor);
+            source.close();
+            return Err(DecodeError::Source(format!("Failed to set data source: {:?}", status.0)));
         }
 
-        let track_idx = video_track.ok_or("No video track found")?;
-        if video_format.is_null() {
+        let Some((track_idx, mut video_format, mut mime_type)) = find_video_track(extractor) else {
             AMediaExtractor_delete(extractor);
-            libc::close(fd);
-            return Err("No video format".into());
-        }
+            source.close();
+            return Err(DecodeError::NoVideoTrack);
+        };
 
         let mut width: i32 = 1280;
         let mut height: i32 = 720;
@@ -564,55 +1067,76 @@ fn run_mediacodec_decode_fd(
         AMediaFormat_getInt32(video_format, key_width.as_ptr(), &mut width);
         AMediaFormat_getInt32(video_format, key_height.as_ptr(), &mut height);
         AMediaFormat_getInt64(video_format, key_duration.as_ptr(), &mut duration);
+        let mut profile_value: i32 = -1;
+        let key_profile = CString::new("profile").unwrap();
+        let profile = AMediaFormat_getInt32(video_format, key_profile.as_ptr(), &mut profile_value)
+            .then_some(profile_value);
+        // Phones store portrait clips as landscape frames plus a rotation hint
+        let mut rotation: i32 = 0;
+        let key_rotation = CString::new("rotation-degrees").unwrap();
+        AMediaFormat_getInt32(video_format, key_rotation.as_ptr(), &mut rotation);
+        let rotation = ((rotation.rem_euclid(360) + 45) / 90 % 4 * 90) as u32;
+
+        // Some containers (MPEG-TS) carry no duration; ask the source instead
+        if duration <= 0 {
+            if let Source::Custom { data_source: Some(ds), .. } = &source {
+                duration = ds.duration_us().unwrap_or(0);
+            }
+        }
+        let seekable = match &source {
+            Source::Custom { data_source: Some(ds), .. } => ds.seekable(),
+            _ => true,
+        };
 
-        info!("MediaCodec: Video {}x{}, duration {}us, mime {}", width, height, duration, mime_type);
+        info!("MediaCodec: Video {}x{}, duration {}us, mime {}, rotation {}", width, height, duration, mime_type, rotation);
 
         if let Ok(mut state) = playback_state.lock() {
             state.duration_us = duration;
+            state.seekable = seekable;
+            state.rotation_degrees = rotation;
+            state.warning = crate::codecs::warning(&mime_type, profile, width, height);
         }
 
         let status = AMediaExtractor_selectTrack(extractor, track_idx);
         if status.0 != 0 {
             AMediaFormat_delete(video_format);
             AMediaExtractor_delete(extractor);
-            libc::close(fd);
-            return Err(format!("Failed to select track: {:?}", status.0));
+            source.close();
+            return Err(DecodeError::Source(format!("Failed to select track: {:?}", status.0)));
         }
 
-        let mime_cstr = CString::new(mime_type.clone()).unwrap();
-        let codec = AMediaCodec_createDecoderByType(mime_cstr.as_ptr());
-        if codec.is_null() {
-            AMediaFormat_delete(video_format);
-            AMediaExtractor_delete(extractor);
-            libc::close(fd);
-            return Err("Failed to create MediaCodec".into());
-        }
-
-        let status = AMediaCodec_configure(codec, video_format, ptr::null_mut(), ptr::null_mut(), 0);
-        if status.0 != 0 {
-            AMediaCodec_delete(codec);
-            AMediaFormat_delete(video_format);
-            AMediaExtractor_delete(extractor);
-            libc::close(fd);
-            return Err(format!("Failed to configure codec: {:?}", status.0));
+        // Live network streams (no duration): ask for low-latency decoding and pace
+        // frames by their own timestamps instead of locking to a measured rate
+        let low_latency = !matches!(source, Source::Fd(_)) && duration <= 0;
+        if low_latency && crate::codecs::decoders_for(&mime_type).any(|c| c.low_latency) {
+            let key = CString::new("low-latency").unwrap();
+            AMediaFormat_setInt32(video_format, key.as_ptr(), 1);
+            info!("MediaCodec: live stream, low-latency decoding requested");
         }
 
-        let status = AMediaCodec_start(codec);
-        if status.0 != 0 {
-            AMediaCodec_delete(codec);
-            AMediaFormat_delete(video_format);
-            AMediaExtractor_delete(extractor);
-            libc::close(fd);
-            return Err(format!("Failed to start codec: {:?}", status.0));
-        }
+        let (mut codec, mut codec_label) = match create_codec(&mime_type, video_format) {
+            Ok(c) => c,
+            Err(e) => {
+                AMediaFormat_delete(video_format);
+                AMediaExtractor_delete(extractor);
+                source.close();
+                return Err(e);
+            }
+        };
 
-        info!("MediaCodec: Decoder started successfully from fd");
+        info!("MediaCodec: Decoder {} started successfully", codec_label);
+        // Until the codec reports its real output layout, assume tight packing
+        let mut layout = FrameLayout::packed(width, height, Chroma::SemiPlanar);
+        let mut codec_flushes = 0u32;
+        let mut codec_restarts = 0u32;
+        let mut fatal: Option<DecodeError> = None;
 
         let mut start_time = std::time::Instant::now();
         let mut total_paused_duration = std::time::Duration::from_millis(0);
         let mut last_pause_check = std::time::Instant::now();
         let mut frame_count: u64 = 0;
         let mut first_frame = true;
+        let mut eos_input = false;
 
         // Measure-and-Lock Variables
         let mut frames_for_estimation = 0;
@@ -621,11 +1145,16 @@ fn run_mediacodec_decode_fd(
         let mut samples_count: i64 = 0;
         let mut target_interval_ms: u64 = 33; // Start with 30fps assumption
         let mut next_frame_target = std::time::Instant::now();
+        // The primed first frame is on screen from the moment playback resumes
+        let mut hold_first_frame = false;
 
         while running.load(Ordering::SeqCst) {
-            let is_playing = playback_state.lock().map(|s| s.is_playing).unwrap_or(false);
+            // A primed (pre-buffering) decoder keeps going until its first frame is out
+            let (is_playing, priming) = playback_state.lock()
+                .map(|s| (s.is_playing, s.primed && frame_count == 0))
+                .unwrap_or((false, false));
             
-            if !is_playing {
+            if !is_playing && !priming {
                 thread::sleep(std::time::Duration::from_millis(10));
                 // Accumulate paused duration
                 total_paused_duration += last_pause_check.elapsed();
@@ -633,11 +1162,16 @@ fn run_mediacodec_decode_fd(
                 continue;
             }
             last_pause_check = std::time::Instant::now();
+            if is_playing && std::mem::take(&mut hold_first_frame) {
+                thread::sleep(std::time::Duration::from_millis(target_interval_ms));
+                next_frame_target = std::time::Instant::now();
+            }
 
             if let Ok(mut state) = playback_state.lock() {
                 if let Some(seek_pos) = state.seek_request.take() {
                     AMediaExtractor_seekTo(extractor, seek_pos, SeekMode::AMEDIAEXTRACTOR_SEEK_CLOSEST_SYNC);
                     AMediaCodec_flush(codec);
+                    eos_input = false;
                     
                     // Reset timing after seek
                     start_time = std::time::Instant::now();
@@ -650,8 +1184,13 @@ fn run_mediacodec_decode_fd(
                 }
             }
 
-            let input_idx = AMediaCodec_dequeueInputBuffer(codec, 5000);
-            if input_idx >= 0 {
+            // Set when the codec reports an error; handled after the output side
+            let mut codec_error: Option<i32> = None;
+
+            let input_idx = if eos_input { -1 } else { AMediaCodec_dequeueInputBuffer(codec, 5000) };
+            if input_idx < AMEDIACODEC_INFO_TRY_AGAIN_LATER as isize {
+                codec_error = Some(input_idx as i32);
+            } else if input_idx >= 0 {
                 let mut buf_size: usize = 0;
                 let input_buf = AMediaCodec_getInputBuffer(codec, input_idx as usize, &mut buf_size);
                 
@@ -662,13 +1201,63 @@ fn run_mediacodec_decode_fd(
                         let pts = AMediaExtractor_getSampleTime(extractor);
                         let flags = AMediaExtractor_getSampleFlags(extractor);
                         
-                        AMediaCodec_queueInputBuffer(
+                        let status = AMediaCodec_queueInputBuffer(
                             codec, input_idx as usize, 0, 
                             sample_size as usize, pts as u64, flags as u32
                         );
+                        if status.0 != 0 {
+                            codec_error = Some(status.0);
+                        }
                         AMediaExtractor_advance(extractor);
-                    } else {
+                    } else if matches!(&source, Source::Custom { data_source: Some(ds), .. } if ds.next_part()) {
+                        // The source went on to a stream this extractor can't follow (an
+                        // HLS variant switch): read it with a fresh extractor and a codec
+                        // set up for its format. The dequeued input buffer goes with the old codec.
+                        let Source::Custom { data_source: Some(ds), .. } = &source else { unreachable!() };
+                        AMediaExtractor_delete(extractor);
+                        extractor = AMediaExtractor_new();
+                        let track = if extractor.is_null()
+                            || AMediaExtractor_setDataSourceCustom(extractor, ds.as_ptr()).0 != 0 {
+                            None
+                        } else {
+                            find_video_track(extractor)
+                        };
+                        let Some((track, format, mime)) = track else {
+                            fatal = Some(DecodeError::Source("stream switch: no video track in the new part".into()));
+                            break;
+                        };
+                        AMediaExtractor_selectTrack(extractor, track);
+                        if low_latency && crate::codecs::decoders_for(&mime).any(|c| c.low_latency) {
+                            let key = CString::new("low-latency").unwrap();
+                            AMediaFormat_setInt32(format, key.as_ptr(), 1);
+                        }
+                        AMediaCodec_stop(codec);
+                        AMediaCodec_delete(codec);
+                        AMediaFormat_delete(video_format);
+                        video_format = format;
+                        mime_type = mime;
+                        match create_codec(&mime_type, video_format) {
+                            Ok((c, name)) => {
+                                codec = c;
+                                codec_label = name;
+                            }
+                            Err(e) => {
+                                codec = ptr::null_mut();
+                                fatal = Some(e);
+                                break;
+                            }
+                        }
+                        info!("MediaCodec: source switched streams, now {} on {}", mime_type, codec_label);
+                        continue;
+                    } else if playback_state.lock().is_ok_and(|s| s.loop_at_eos && s.seekable) {
                         AMediaExtractor_seekTo(extractor, 0, SeekMode::AMEDIAEXTRACTOR_SEEK_PREVIOUS_SYNC);
+                    } else {
+                        // Signal EOS so the codec drains its last frames, then report finished.
+                        AMediaCodec_queueInputBuffer(
+                            codec, input_idx as usize, 0, 0, 0,
+                            AMEDIACODEC_BUFFER_FLAG_END_OF_STREAM
+                        );
+                        eos_input = true;
                     }
                 }
             }
@@ -681,89 +1270,194 @@ fn run_mediacodec_decode_fd(
             
             if output_idx >= 0 {
                 let pts = buffer_info.presentationTimeUs;
-                
-                if let Ok(mut state) = playback_state.lock() {
-                    state.position_us = pts;
+                codec_flushes = 0;
+                // End of stream may be flagged on the last frame or on an empty buffer after it
+                let end_of_stream = buffer_info.flags & AMEDIACODEC_BUFFER_FLAG_END_OF_STREAM != 0;
+                let has_frame = buffer_info.size > 0;
+
+                // How far the stream is downloaded: the extractor's own cache for
+                // progressive URLs, the source's estimate for custom sources
+                let buffered_until = match &source {
+                    Source::Url(_) => {
+                        let cached = AMediaExtractor_getCachedDuration(extractor);
+                        let read_at = AMediaExtractor_getSampleTime(extractor);
+                        (cached >= 0 && read_at >= 0).then_some(read_at + cached)
+                    }
+                    Source::Custom { data_source: Some(ds), .. } => ds.buffered_until_us(),
+                    _ => None,
+                };
+
+                if has_frame {
+                    if let Ok(mut state) = playback_state.lock() {
+                        state.position_us = pts;
+                        state.buffered_until_us = buffered_until;
+                        state.last_frame_at = Some(std::time::Instant::now());
+                        // First frame after opening / seeking / a stall
+                        if matches!(state.status, DecoderStatus::Opening | DecoderStatus::Buffering | DecoderStatus::Seeking) {
+                            state.status = if state.is_playing { DecoderStatus::Playing } else { DecoderStatus::Paused };
+                        }
+                    }
                 }
 
                 let mut out_size: usize = 0;
                 let out_buf = AMediaCodec_getOutputBuffer(codec, output_idx as usize, &mut out_size);
                 
-                if !out_buf.is_null() && out_size > 0 {
-                    let src_slice = std::slice::from_raw_parts(out_buf, out_size);
-                    let y_size = (width * height) as usize;
-                    let uv_size = y_size / 2;
+                if has_frame && !out_buf.is_null() && out_size > 0 {
+                    let src_slice = std::slice::from_raw_parts(
+                        out_buf.add(buffer_info.offset as usize),
+                        out_size.saturating_sub(buffer_info.offset as usize),
+                    );
                     
                     if let Ok(mut buffer) = frame_buffer.lock() {
-                        if buffer.y_data.len() != y_size { buffer.y_data.resize(y_size, 0); }
-                        if buffer.uv_data.len() != uv_size { buffer.uv_data.resize(uv_size, 0); }
-                        
-                        // Safety check for buffer size
-                        if src_slice.len() >= y_size + uv_size {
-                            buffer.y_data.copy_from_slice(&src_slice[0..y_size]);
-                            buffer.uv_data.copy_from_slice(&src_slice[y_size..y_size+uv_size]);
-                            buffer.width = width as u32;
-                            buffer.height = height as u32;
+                        let buffer = &mut *buffer;
+                        // De-stride and crop into the tightly packed planes the renderer expects
+                        if layout.copy_to_nv12(src_slice, &mut buffer.y_data, &mut buffer.uv_data) {
+                            buffer.width = layout.width as u32;
+                            buffer.height = layout.height as u32;
                             buffer.timestamp_us = pts;
                             buffer.has_new_frame = true;
+                        } else if frame_count == 0 {
+                            warn!("MediaCodec: output buffer ({} bytes) too small for {:?}", src_slice.len(), layout);
                         }
                     }
                 }
                 
                 AMediaCodec_releaseOutputBuffer(codec, output_idx as usize, false);
-                
-                // Measure-and-Lock Pacing Strategy
-                // 1. Measure actual frame rate from first 15 frames
-                if frames_for_estimation < 15 {
-                    if previous_pts >= 0 {
-                        let delta = (pts - previous_pts) / 1000;
-                        if delta > 0 {
-                           accumulated_delta += delta;
-                           samples_count += 1;
-                        }
-                    }
+
+                if has_frame && !is_playing {
+                    // A primed decoder's first frame: it gets its display time after resume()
+                    hold_first_frame = true;
+                    frame_count += 1;
+                } else if has_frame && low_latency {
+                    // Live: show each frame as soon as it's out, spaced by its own pts
+                    // delta, and never let a backlog build (catch up once a frame behind)
+                    let delta_ms = if previous_pts >= 0 { ((pts - previous_pts) / 1000).clamp(0, 100) as u64 } else { 0 };
                     previous_pts = pts;
-                    frames_for_estimation += 1;
-                    
-                    // Default to 30fps (33ms) during estimation to avoid super fast playback
-                    thread::sleep(std::time::Duration::from_millis(33));
-                    
-                    if frames_for_estimation == 15 && samples_count > 0 {
-                        let avg_delta = accumulated_delta as f64 / samples_count as f64;
-                        target_interval_ms = avg_delta.round() as u64;
-                        info!("MediaCodec: Detected Fixed Frame Rate. Avg Delta: {:.2}ms. Locking to {}ms", avg_delta, target_interval_ms);
-                        next_frame_target = std::time::Instant::now();
-                    }
-                } else {
-                    // 2. Locked Constant Timing Loop
-                    // Advance target time by fixed interval
-                    next_frame_target += std::time::Duration::from_millis(target_interval_ms);
-                    
+                    next_frame_target += std::time::Duration::from_millis(delta_ms);
                     let now = std::time::Instant::now();
                     if next_frame_target > now {
                         thread::sleep(next_frame_target - now);
+                    } else if now.duration_since(next_frame_target).as_millis() as u64 > delta_ms {
+                        next_frame_target = now;
+                    }
+                    frame_count += 1;
+                } else if has_frame {
+                    // Measure-and-Lock Pacing Strategy
+                    // 1. Measure actual frame rate from first 15 frames
+                    if frames_for_estimation < 15 {
+                        if previous_pts >= 0 {
+                            let delta = (pts - previous_pts) / 1000;
+                            if delta > 0 {
+                               accumulated_delta += delta;
+                               samples_count += 1;
+                            }
+                        }
+                        previous_pts = pts;
+                        frames_for_estimation += 1;
+
+                        // Default to 30fps (33ms) during estimation to avoid super fast playback
+                        thread::sleep(std::time::Duration::from_millis(33));
+
+                        if frames_for_estimation == 15 && samples_count > 0 {
+                            let avg_delta = accumulated_delta as f64 / samples_count as f64;
+                            target_interval_ms = avg_delta.round() as u64;
+                            info!("MediaCodec: Detected Fixed Frame Rate. Avg Delta: {:.2}ms. Locking to {}ms", avg_delta, target_interval_ms);
+                            next_frame_target = std::time::Instant::now();
+                        }
                     } else {
-                        // We are behind. If we are WAY behind (>100ms), reset the clock to avoid seeking frenzy
-                        if now.duration_since(next_frame_target).as_millis() > 100 {
-                             next_frame_target = now;
+                        // 2. Locked Constant Timing Loop
+                        // Advance target time by fixed interval
+                        next_frame_target += std::time::Duration::from_millis(target_interval_ms);
+
+                        let now = std::time::Instant::now();
+                        if next_frame_target > now {
+                            thread::sleep(next_frame_target - now);
+                        } else {
+                            // We are behind. If we are WAY behind (>100ms), reset the clock to avoid seeking frenzy
+                            if now.duration_since(next_frame_target).as_millis() > 100 {
+                                 next_frame_target = now;
+                            }
                         }
                     }
+
+                    frame_count += 1;
+                    if frame_count % 60 == 0 {
+                        // info!("MediaCodec: Decoded {} frames (Locked: {}ms)", frame_count, target_interval_ms);
+                    }
                 }
 
-                frame_count += 1;
-                if frame_count % 60 == 0 {
-                    // info!("MediaCodec: Decoded {} frames (Locked: {}ms)", frame_count, target_interval_ms);
+                // Report the end only once the last frame has had its display time, so the
+                // next playlist entry (pre-buffered) takes over on the frame boundary
+                if end_of_stream {
+                    info!("MediaCodec: End of stream");
+                    if let Ok(mut state) = playback_state.lock() {
+                        state.finished = true;
+                        state.status = DecoderStatus::Ended;
+                    }
                 }
+            } else if output_idx == AMEDIACODEC_INFO_OUTPUT_FORMAT_CHANGED as isize {
+                // The real buffer layout (stride, slice height, crop) arrives here; the
+                // resolution can also change mid-stream (HLS variant switches)
+                let out_format = AMediaCodec_getOutputFormat(codec);
+                if !out_format.is_null() {
+                    if let Some(l) = FrameLayout::from_format(out_format, &codec_label) {
+                        layout = l;
+                    }
+                    AMediaFormat_delete(out_format);
+                    info!("MediaCodec: Output format changed to {:?}", layout);
+                }
+            } else if output_idx < AMEDIACODEC_INFO_OUTPUT_BUFFERS_CHANGED as isize {
+                codec_error = Some(output_idx as i32);
+            }
+
+            // Recover from codec errors: flush first, restart the codec if flushing
+            // keeps failing, and only give up after that. Either way decoding picks
+            // up again from the keyframe before the last shown frame.
+            if let Some(status) = codec_error {
+                codec_flushes += 1;
+                if codec_flushes <= MAX_CODEC_FLUSHES {
+                    warn!("MediaCodec: {} error {}, flushing ({}/{})", codec_label, status, codec_flushes, MAX_CODEC_FLUSHES);
+                    AMediaCodec_flush(codec);
+                } else if codec_restarts < MAX_CODEC_RESTARTS {
+                    codec_restarts += 1;
+                    codec_flushes = 0;
+                    warn!("MediaCodec: {} error {}, restarting codec ({}/{})", codec_label, status, codec_restarts, MAX_CODEC_RESTARTS);
+                    AMediaCodec_stop(codec);
+                    AMediaCodec_delete(codec);
+                    match create_codec(&mime_type, video_format) {
+                        Ok((c, name)) => {
+                            codec = c;
+                            codec_label = name;
+                        }
+                        Err(e) => {
+                            codec = ptr::null_mut();
+                            fatal = Some(e);
+                            break;
+                        }
+                    }
+                } else {
+                    error!("MediaCodec: {} keeps failing ({}), giving up", codec_label, status);
+                    fatal = Some(DecodeError::CodecFailed { codec: codec_label.clone(), status });
+                    break;
+                }
+                let resume_at = playback_state.lock().map(|s| s.position_us).unwrap_or(0);
+                AMediaExtractor_seekTo(extractor, resume_at, SeekMode::AMEDIAEXTRACTOR_SEEK_PREVIOUS_SYNC);
+                eos_input = false;
             }
         }
 
-        AMediaCodec_stop(codec);
-        AMediaCodec_delete(codec);
+        if !codec.is_null() {
+            AMediaCodec_stop(codec);
+            AMediaCodec_delete(codec);
+        }
         AMediaFormat_delete(video_format);
         AMediaExtractor_delete(extractor);
-        libc::close(fd);
+        source.close();
 
-        info!("MediaCodec fd: Stopped after {} frames", frame_count);
+        info!("MediaCodec: Stopped after {} frames", frame_count);
+        if let Some(e) = fatal {
+            return Err(e);
+        }
     }
 
     Ok(())
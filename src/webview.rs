@@ -4,19 +4,65 @@
 //! engine switch, voice search, …) which forward to the active browser engine
 //! (Chromium WebView or Firefox/Gecko).
 //!
-//! Java → Rust: `onWebFrame` pushes captured RGBA page frames; `onVoiceResult` /
-//! `onVoiceError` deliver PS5-mic voice-search results.
+//! Java → Rust: `onWebHardwareBuffer` lends the buffer a page was rendered into
+//! (hardware_buffer.rs) until `release_frames` gives it back, or `onWebFrame`
+//! sends an RGBA copy of it where the GPU can't take the buffer; `onVoiceResult`
+//! / `onVoiceError` deliver PS5-mic voice-search results.
+//!
+//! Every page belongs to a window: `SCREEN` is the browser on the main screen,
+//! and each floating browser window (window_manager.rs) has a tab of its own,
+//! numbered by `pop_out` / `open_window`, with its own frames and input.
 
 use android_activity::AndroidApp;
 use jni::objects::{JObject, JValue};
 use jni::sys::jobject;
 use log::{error, info};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 
+use crate::hardware_buffer::HardwareBuffer;
+
+/// The browser on the main screen; floating windows are numbered from 1.
+pub const SCREEN: i32 = 0;
+
+/// A captured page.
+pub enum WebFrame {
+    /// A CPU copy: width, height, RGBA bytes (`recycle` them once uploaded)
+    Pixels(u32, u32, Vec<u8>),
+    /// The buffer the page was rendered into, for the GPU to copy from
+    Buffer(LentBuffer),
+}
+
+/// A buffer the browser's reader lent with a frame. Gecko can't draw into it
+/// again until it's given back, which dropping this does (with the next
+/// `release_frames`): keep it until the GPU copy from it has completed.
+pub struct LentBuffer {
+    pub buffer: HardwareBuffer,
+    token: i64,
+}
+
+impl Drop for LentBuffer {
+    fn drop(&mut self) {
+        if let Ok(mut returned) = RETURNED.lock() {
+            returned.push(self.token);
+        }
+    }
+}
+
 // ── Java → Rust shared state ────────────────────────────────────────────────────
 
-/// Latest captured browser frame: (width, height, RGBA bytes).
-static WEB_FRAME: Mutex<Option<(u32, u32, Vec<u8>)>> = Mutex::new(None);
+/// Latest captured frame of each window.
+static WEB_FRAMES: Mutex<Vec<(i32, WebFrame)>> = Mutex::new(Vec::new());
+
+/// Whether the renderer takes hardware buffers (a device from
+/// `hardware_buffer::open_device` that imported them fine so far).
+static HARDWARE_FRAMES: AtomicBool = AtomicBool::new(false);
+
+/// Lent buffers (by Java's token) that are done with, to give back.
+static RETURNED: Mutex<Vec<i64>> = Mutex::new(Vec::new());
+
+/// Pages floating windows moved to, for the saved layout: (window, url).
+static WINDOW_URLS: Mutex<Vec<(i32, String)>> = Mutex::new(Vec::new());
 
 /// Latest voice-search transcript (consumed by lib.rs).
 static VOICE_RESULT: Mutex<Option<String>> = Mutex::new(None);
@@ -116,24 +162,24 @@ pub fn resize(app: &AndroidApp, width: i32, height: i32) {
     });
 }
 
-/// Tap at a normalized (0..1) position on the page.
-pub fn tap(app: &AndroidApp, x: f32, y: f32) {
+/// Tap at a normalized (0..1) position on `window`'s page.
+pub fn tap(app: &AndroidApp, window: i32, x: f32, y: f32) {
     with_activity(app, |env, activity| {
         if let Err(e) = env.call_method(
-            activity, "webViewTap", "(FF)V",
-            &[JValue::Float(x), JValue::Float(y)],
+            activity, "webViewTap", "(IFF)V",
+            &[JValue::Int(window), JValue::Float(x), JValue::Float(y)],
         ) {
             error!("webview: webViewTap failed: {:?}", e);
         }
     });
 }
 
-/// Scroll the page. `dx`/`dy` are deltas; `x`/`y` the normalized (0..1) focus point.
-pub fn inject_scroll(app: &AndroidApp, dx: f32, dy: f32, x: f32, y: f32) {
+/// Scroll `window`'s page. `dx`/`dy` are deltas; `x`/`y` the normalized (0..1) focus point.
+pub fn inject_scroll(app: &AndroidApp, window: i32, dx: f32, dy: f32, x: f32, y: f32) {
     with_activity(app, |env, activity| {
         if let Err(e) = env.call_method(
-            activity, "webViewScroll", "(FFFF)V",
-            &[JValue::Float(dx), JValue::Float(dy), JValue::Float(x), JValue::Float(y)],
+            activity, "webViewScroll", "(IFFFF)V",
+            &[JValue::Int(window), JValue::Float(dx), JValue::Float(dy), JValue::Float(x), JValue::Float(y)],
         ) {
             error!("webview: webViewScroll failed: {:?}", e);
         }
@@ -183,6 +229,69 @@ pub fn switch_tab(app: &AndroidApp, delta: i32) {
 /// Close the active tab (keeps at least one).
 pub fn close_tab(app: &AndroidApp) { call_void(app, "webViewCloseTab"); }
 
+// ── Floating windows ────────────────────────────────────────────────────────────
+
+/// Move the screen's active tab into a new floating window (the screen goes on
+/// with its next tab); the window's number.
+pub fn pop_out(app: &AndroidApp) -> Option<i32> {
+    let mut window = None;
+    with_activity(app, |env, activity| {
+        match env.call_method(activity, "webViewPopOut", "()I", &[]).and_then(|v| v.i()) {
+            Ok(w) => window = Some(w),
+            Err(e) => error!("webview: webViewPopOut failed: {:?}", e),
+        }
+    });
+    window
+}
+
+/// Open `url` in a new floating window's own tab; the window's number.
+pub fn open_window(app: &AndroidApp, url: &str) -> Option<i32> {
+    let mut window = None;
+    with_activity(app, |env, activity| {
+        let j = match env.new_string(url) { Ok(s) => s, Err(_) => return };
+        match env.call_method(
+            activity, "webViewOpenWindow", "(Ljava/lang/String;)I",
+            &[JValue::Object(&JObject::from(j))],
+        ).and_then(|v| v.i()) {
+            Ok(w) => window = Some(w),
+            Err(e) => error!("webview: webViewOpenWindow failed: {:?}", e),
+        }
+    });
+    window
+}
+
+/// Close a floating window's tab.
+pub fn close_window(app: &AndroidApp, window: i32) {
+    with_activity(app, |env, activity| {
+        if let Err(e) = env.call_method(
+            activity, "webViewCloseWindow", "(I)V", &[JValue::Int(window)],
+        ) {
+            error!("webview: webViewCloseWindow failed: {:?}", e);
+        }
+    });
+}
+
+/// Let the browser send hardware buffers (the renderer can import them), or go
+/// back to RGBA copies.
+pub fn set_hardware_frames(on: bool) {
+    HARDWARE_FRAMES.store(on, Ordering::Relaxed);
+}
+
+/// Give the browser back the buffers dropped since last time, to draw into again.
+pub fn release_frames(app: &AndroidApp) {
+    let returned = RETURNED.lock().map(|mut r| std::mem::take(&mut *r)).unwrap_or_default();
+    if returned.is_empty() {
+        return;
+    }
+    with_activity(app, |env, activity| {
+        for token in returned {
+            if let Err(e) = env.call_method(activity, "webViewReleaseFrame", "(J)V", &[JValue::Long(token)]) {
+                error!("webview: webViewReleaseFrame failed: {:?}", e);
+            }
+        }
+    });
+}
+
 // ── Voice search (PS5 mic) ──────────────────────────────────────────────────────
 
 /// Start PS5-mic voice search (Java SpeechRecognizer → `onVoiceResult`).
@@ -190,9 +299,26 @@ pub fn start_voice_search(app: &AndroidApp) { call_void(app, "startVoiceSearch")
 
 // ── Consumers (called from the render loop) ─────────────────────────────────────
 
-/// Take the latest captured browser frame, if any: (width, height, RGBA).
-pub fn get_frame() -> Option<(u32, u32, Vec<u8>)> {
-    WEB_FRAME.lock().ok().and_then(|mut f| f.take())
+/// Take the latest captured frame of every window that sent one.
+pub fn take_frames() -> Vec<(i32, WebFrame)> {
+    WEB_FRAMES.lock().map(|mut f| std::mem::take(&mut *f)).unwrap_or_default()
+}
+
+/// Take the pages floating windows moved to since last time: (window, url).
+pub fn take_window_urls() -> Vec<(i32, String)> {
+    WINDOW_URLS.lock().map(|mut u| std::mem::take(&mut *u)).unwrap_or_default()
+}
+
+/// Keep `frame` as `window`'s latest, in place of one the render loop never took.
+fn push_frame(window: i32, frame: WebFrame) {
+    if let Ok(mut frames) = WEB_FRAMES.lock() {
+        if let Some(i) = frames.iter().position(|(w, _)| *w == window) {
+            if let (_, WebFrame::Pixels(_, _, old)) = frames.swap_remove(i) {
+                recycle(old);
+            }
+        }
+        frames.push((window, frame));
+    }
 }
 
 /// Take the latest voice-search transcript, if any.
@@ -202,11 +328,12 @@ pub fn take_voice_result() -> Option<String> {
 
 // ── Java → Rust JNI callbacks ───────────────────────────────────────────────────
 
-/// Java pushes a captured RGBA page frame here.
+/// Java pushes a captured RGBA frame of `window`'s page here.
 #[no_mangle]
 pub unsafe extern "C" fn Java_com_vrapp_core_MainActivity_onWebFrame(
     mut env: jni::JNIEnv,
     _class: JObject,
+    window: jni::sys::jint,
     width: jni::sys::jint,
     height: jni::sys::jint,
     pixels: jni::objects::JByteArray,
@@ -225,14 +352,45 @@ pub unsafe extern "C" fn Java_com_vrapp_core_MainActivity_onWebFrame(
         return;
     }
 
-    if let Ok(mut frame) = WEB_FRAME.lock() {
-        // Recycle any frame the render loop never consumed.
-        if let Some((_, _, old)) = frame.take() {
-            recycle(old);
+    push_frame(window, WebFrame::Pixels(width as u32, height as u32, buf));
+}
+
+/// Java offers the `HardwareBuffer` `window`'s page was rendered into, lent
+/// under `token`. Returns whether it was taken; if not, Java sends an RGBA copy
+/// instead. A taken buffer's image stays open until `release_frames` hands the
+/// token back.
+#[no_mangle]
+pub unsafe extern "C" fn Java_com_vrapp_core_MainActivity_onWebHardwareBuffer(
+    env: jni::JNIEnv,
+    _class: JObject,
+    window: jni::sys::jint,
+    buffer: JObject,
+    token: jni::sys::jlong,
+) -> jni::sys::jboolean {
+    if !HARDWARE_FRAMES.load(Ordering::Relaxed) {
+        return jni::sys::JNI_FALSE;
+    }
+    match HardwareBuffer::from_java(env.get_raw(), buffer.as_raw()) {
+        Some(buffer) => {
+            push_frame(window, WebFrame::Buffer(LentBuffer { buffer, token }));
+            jni::sys::JNI_TRUE
+        }
+        None => jni::sys::JNI_FALSE,
+    }
+}
+
+/// Java reports the page a floating window moved to.
+#[no_mangle]
+pub unsafe extern "C" fn Java_com_vrapp_core_MainActivity_onWebWindowUrl(
+    mut env: jni::JNIEnv,
+    _class: JObject,
+    window: jni::sys::jint,
+    url: jni::objects::JString,
+) {
+    if let Ok(s) = env.get_string(&url) {
+        if let Ok(mut urls) = WINDOW_URLS.lock() {
+            urls.push((window, s.into()));
         }
-        *frame = Some((width as u32, height as u32, buf));
-    } else {
-        recycle(buf);
     }
 }
 
@@ -1,8 +1,85 @@
 //! Window Manager module
 //!
-//! Manages floating 3D panels/windows in the VR space.
+//! Manages floating 3D panels/windows in the VR space. Video panels place the
+//! dome screens; the others are drawn as flat quads by the renderer
+//! (`scene_panels`).
+//!
+//! A panel can have an egui context of its own (`PanelUi`): `run_uis` lays it
+//! out at the panel's shape every frame and the renderer paints it into the
+//! panel's own texture, so a Dock or Settings panel is a real window in the
+//! scene rather than part of the one UI canvas. Pointer input isn't routed to
+//! them yet.
+//!
+//! A browser panel shows a page of its own: a browser tab rendered for that
+//! window alone (webview.rs, numbered by `PanelContent::Browser::window`) into a
+//! texture of its own, beside whatever the main screen's browser shows. `hit`
+//! finds where the head points on it, for taps and scrolling.
+//!
+//! With auto layout on, browser and settings panels stand side by side on a
+//! cylinder around the viewer, centred straight ahead, and close up whenever one
+//! is opened or closed. A panel grabbed elsewhere stays there until the next one.
+//!
+//! A panel being moved (`place_snapped`) snaps into line within a small reach:
+//! its distance to a preset, its edges to a neighbour's, or else its centre to
+//! an angular grid. A thin guide line in the accent colour shows briefly where.
+
+use std::time::{Duration, Instant};
+
+use egui::{Context, FullOutput, RawInput};
+use glam::{Vec2, Vec3, Quat, Mat4};
+
+use crate::renderer::{PanelSource, ScreenPlacement};
 
-use glam::{Vec3, Quat, Mat4};
+/// Height of a panel's own UI texture, px; the width follows the panel's shape.
+const PANEL_UI_HEIGHT: f32 = 1024.0;
+
+/// Radius of the cylinder auto layout stands panels on, m.
+const LAYOUT_RADIUS: f32 = 2.2;
+/// Space between neighbouring panels on it, radians.
+const LAYOUT_GAP: f32 = 0.06;
+
+/// Smallest and largest width / height a panel can be resized to, m.
+const PANEL_MIN_SIZE: f32 = 0.3;
+const PANEL_MAX_SIZE: f32 = 3.0;
+
+/// The grid a moved panel's centre snaps to, radians of yaw and pitch, and how
+/// near it (or a neighbour's edge) it has to come.
+const SNAP_GRID: f32 = std::f32::consts::PI / 12.0;
+const SNAP_ANGLE: f32 = 0.035;
+/// Distances a moved panel settles at when within `SNAP_DISTANCE` of one, m.
+const SNAP_DISTANCES: [f32; 4] = [1.0, 1.5, 2.2, 3.0];
+const SNAP_DISTANCE: f32 = 0.1;
+/// How long a guide line shows once a snap takes hold.
+const GUIDE_TIME: Duration = Duration::from_millis(700);
+/// Guide line width, m, and how far it runs past the panel (of its size).
+const GUIDE_WIDTH: f32 = 0.006;
+const GUIDE_OVERHANG: f32 = 0.15;
+
+/// Turned about the vertical so a panel at `position` faces the viewer.
+fn facing(position: Vec3) -> Quat {
+    Quat::from_rotation_y((-position.x).atan2(-position.z))
+}
+
+/// Yaw (+ = right), pitch (+ = up) and distance of `position` from the viewer.
+fn spherical(position: Vec3) -> (f32, f32, f32) {
+    let distance = position.length().max(1e-4);
+    (position.x.atan2(-position.z), (position.y / distance).clamp(-1.0, 1.0).asin(), distance)
+}
+
+fn from_spherical(yaw: f32, pitch: f32, distance: f32) -> Vec3 {
+    Vec3::new(yaw.sin() * pitch.cos(), pitch.sin(), -yaw.cos() * pitch.cos()) * distance
+}
+
+/// What snapping set a moved panel's yaw, pitch and distance to, if anything.
+#[derive(Clone, Copy, Default, PartialEq)]
+struct Snap {
+    yaw: Option<f32>,
+    pitch: Option<f32>,
+    distance: Option<f32>,
+}
+
+/// Radius of the dome video screens are drawn on (matches main.wgsl).
+const DOME_RADIUS: f32 = 5.3;
 
 /// A floating window/panel in 3D space
 pub struct Panel {
@@ -12,16 +89,46 @@ pub struct Panel {
     pub scale: Vec3,
     pub title: String,
     pub content_type: PanelContent,
+    /// Its own egui context, for panels that draw UI
+    pub ui: Option<PanelUi>,
+}
+
+/// An egui context laid out at a panel's shape.
+pub struct PanelUi {
+    pub ctx: Context,
+    /// Texture size, px
+    pub size: [u32; 2],
+}
+
+impl PanelUi {
+    /// A context the shape of a `scale`-sized panel, in the current theme and fonts.
+    fn new(scale: Vec3) -> Self {
+        let ctx = Context::default();
+        crate::theme::apply(&ctx);
+        crate::fonts::install(&ctx);
+        let width = (PANEL_UI_HEIGHT * scale.x / scale.y).round().clamp(64.0, 4096.0) as u32;
+        PanelUi { ctx, size: [width, PANEL_UI_HEIGHT as u32] }
+    }
 }
 
 /// What type of content the panel displays
 pub enum PanelContent {
-    /// Embedded web browser
-    Browser { url: String },
+    /// Embedded web browser: its page and the browser window showing it
+    /// (`webview::pop_out` / `open_window`; 0 until `open_browsers` opens one)
+    Browser { url: String, window: i32 },
     /// App launcher dock
     Dock,
     /// Settings menu
     Settings,
+    /// A video screen showing renderer video slot `slot`
+    Video { slot: usize },
+}
+
+impl Panel {
+    /// Angle of the panel from straight ahead (radians, + = right).
+    pub fn yaw(&self) -> f32 {
+        self.position.x.atan2(-self.position.z)
+    }
 }
 
 /// Manages all panels in the scene
@@ -29,6 +136,11 @@ pub struct WindowManager {
     panels: Vec<Panel>,
     next_id: u32,
     focused_panel: Option<u32>,
+    auto_layout: bool,
+    /// The moved panel's current snap and when it took hold
+    snap: Option<(Snap, Instant)>,
+    /// Its guide lines, as thin quads
+    guides: Vec<Mat4>,
 }
 
 impl WindowManager {
@@ -37,25 +149,66 @@ impl WindowManager {
             panels: Vec::new(),
             next_id: 0,
             focused_panel: None,
+            auto_layout: false,
+            snap: None,
+            guides: Vec::new(),
+        }
+    }
+
+    /// Arrange panels on the cylinder from now on (re-flowing them now), or
+    /// leave them where they're put.
+    pub fn set_auto_layout(&mut self, on: bool) {
+        if on && !self.auto_layout {
+            self.auto_layout = true;
+            self.reflow();
+        }
+        self.auto_layout = on;
+    }
+
+    /// With auto layout, stand the browser and settings panels side by side on
+    /// the cylinder, centred straight ahead, each facing the viewer. The dock
+    /// keeps its place below, video screens theirs on the dome.
+    fn reflow(&mut self) {
+        if !self.auto_layout {
+            return;
+        }
+        let arranged = |p: &Panel| matches!(p.content_type, PanelContent::Browser { .. } | PanelContent::Settings);
+        // The angle a flat panel takes standing against the cylinder
+        let arc = |p: &Panel| 2.0 * (p.scale.x / 2.0 / LAYOUT_RADIUS).atan();
+        let (count, arcs) = self.panels.iter().filter(|p| arranged(p))
+            .fold((0, 0.0), |(n, sum), p| (n + 1, sum + arc(p)));
+        if count == 0 {
+            return;
+        }
+        let mut yaw = -(arcs + LAYOUT_GAP * (count - 1) as f32) / 2.0;
+        for panel in self.panels.iter_mut().filter(|p| arranged(p)) {
+            let width = arc(panel);
+            let centre = yaw + width / 2.0;
+            panel.position = Vec3::new(centre.sin() * LAYOUT_RADIUS, 0.0, -centre.cos() * LAYOUT_RADIUS);
+            panel.rotation = facing(panel.position);
+            yaw += width + LAYOUT_GAP;
         }
     }
     
-    /// Spawn a new browser panel
-    pub fn spawn_browser(&mut self, url: &str, position: Vec3) -> u32 {
+    /// Spawn a new browser panel for browser window `window` at `position` (or in
+    /// the auto layout), turned to face the viewer
+    pub fn spawn_browser(&mut self, url: &str, window: i32, position: Vec3) -> u32 {
         let id = self.next_id;
         self.next_id += 1;
         
         let panel = Panel {
             id,
             position,
-            rotation: Quat::IDENTITY,
+            rotation: facing(position),
             scale: Vec3::new(1.6, 0.9, 0.01), // 16:9 aspect ratio
             title: format!("Browser {}", id),
-            content_type: PanelContent::Browser { url: url.to_string() },
+            content_type: PanelContent::Browser { url: url.to_string(), window },
+            ui: None,
         };
         
         self.panels.push(panel);
         self.focused_panel = Some(id);
+        self.reflow();
         id
     }
     
@@ -64,19 +217,98 @@ impl WindowManager {
         let id = self.next_id;
         self.next_id += 1;
         
+        let scale = Vec3::new(2.0, 0.3, 0.01);
         let panel = Panel {
             id,
             position: Vec3::new(0.0, -0.8, -2.0),
             rotation: Quat::from_rotation_x(-0.2), // Slightly tilted up
-            scale: Vec3::new(2.0, 0.3, 0.01),
+            scale,
             title: "Dock".to_string(),
             content_type: PanelContent::Dock,
+            ui: Some(PanelUi::new(scale)),
         };
         
         self.panels.push(panel);
         id
     }
     
+    /// Spawn a video screen for renderer slot `slot` on the dome, `yaw` radians
+    /// from straight ahead, `size` relative to the full main screen.
+    pub fn spawn_video(&mut self, slot: usize, yaw: f32, size: f32) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let panel = Panel {
+            id,
+            position: Vec3::new(yaw.sin() * DOME_RADIUS, 0.0, -yaw.cos() * DOME_RADIUS),
+            rotation: Quat::from_rotation_y(-yaw),
+            scale: Vec3::splat(size),
+            title: format!("Video {}", slot),
+            content_type: PanelContent::Video { slot },
+            ui: None,
+        };
+
+        self.panels.push(panel);
+        id
+    }
+
+    /// Open a panel saved with the layout (config.rs) where it was, its size
+    /// kept within the limits.
+    pub fn restore(&mut self, content_type: PanelContent, position: Vec3, rotation: Quat, scale: Vec3) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let scale = Vec3::new(
+            scale.x.clamp(PANEL_MIN_SIZE, PANEL_MAX_SIZE),
+            scale.y.clamp(PANEL_MIN_SIZE, PANEL_MAX_SIZE),
+            0.01,
+        );
+        let (title, ui) = match content_type {
+            PanelContent::Browser { .. } => (format!("Browser {}", id), None),
+            PanelContent::Dock => ("Dock".to_string(), Some(PanelUi::new(scale))),
+            PanelContent::Settings => ("Settings".to_string(), None),
+            PanelContent::Video { slot } => (format!("Video {}", slot), None),
+        };
+        self.panels.push(Panel { id, position, rotation, scale, title, content_type, ui });
+        id
+    }
+
+    /// Keep browser window `window`'s page up to date, for the saved layout.
+    pub fn set_browser_url(&mut self, window: i32, url: &str) {
+        for panel in &mut self.panels {
+            match &mut panel.content_type {
+                PanelContent::Browser { url: shown, window: w } if *w == window => *shown = url.to_string(),
+                _ => {}
+            }
+        }
+    }
+
+    /// Give every restored browser panel a browser window, `open` loading its page
+    /// in a new one; a panel whose window doesn't open is closed.
+    pub fn open_browsers(&mut self, mut open: impl FnMut(&str) -> Option<i32>) {
+        self.panels.retain_mut(|panel| match &mut panel.content_type {
+            PanelContent::Browser { url, window } if *window == 0 => match open(url) {
+                Some(opened) => {
+                    *window = opened;
+                    true
+                }
+                None => false,
+            },
+            _ => true,
+        });
+        self.reflow();
+    }
+
+    /// The panel showing video slot `slot`, if any.
+    pub fn video_panel(&self, slot: usize) -> Option<&Panel> {
+        self.panels.iter().find(|p| matches!(p.content_type, PanelContent::Video { slot: s } if s == slot))
+    }
+
+    /// Where video slot `slot`'s screen goes; `None` if it has no panel.
+    pub fn video_placement(&self, slot: usize) -> Option<ScreenPlacement> {
+        self.video_panel(slot).map(|p| ScreenPlacement { yaw: p.yaw(), scale: p.scale.x })
+    }
+
     /// Move a panel in 3D space
     pub fn move_panel(&mut self, id: u32, delta: Vec3) {
         if let Some(panel) = self.panels.iter_mut().find(|p| p.id == id) {
@@ -84,12 +316,113 @@ impl WindowManager {
         }
     }
     
-    /// Scale a panel
+    /// Turn a panel to face the viewer from where it is
+    pub fn face_viewer(&mut self, id: u32) {
+        if let Some(panel) = self.panels.iter_mut().find(|p| p.id == id) {
+            panel.rotation = facing(panel.position);
+        }
+    }
+
+    /// Put panel `id` at `target`, facing the viewer, snapped into line: its
+    /// distance to a preset, then its sides, centre or top / bottom to a
+    /// neighbour's (or beside it with the layout gap), or else its centre to
+    /// the angular grid, whichever is in reach.
+    pub fn place_snapped(&mut self, id: u32, target: Vec3) {
+        let Some(scale) = self.panel(id).map(|p| p.scale) else { return };
+        let (yaw, pitch, distance) = spherical(target);
+        let mut snap = Snap {
+            distance: SNAP_DISTANCES.iter().copied().find(|d| (d - distance).abs() < SNAP_DISTANCE),
+            ..Default::default()
+        };
+        let distance = snap.distance.unwrap_or(distance);
+
+        // Where the panel's centre would go for each alignment, with the edge (in
+        // its own -0.5…0.5 coordinates) the guide marks
+        let half_width = (scale.x / 2.0 / distance).atan();
+        let half_height = (scale.y / 2.0 / distance).atan();
+        let mut yaws = Vec::new();
+        let mut pitches = Vec::new();
+        for other in self.panels.iter().filter(|p| p.id != id && !matches!(p.content_type, PanelContent::Video { .. })) {
+            let (at_yaw, at_pitch, at_distance) = spherical(other.position);
+            let width = (other.scale.x / 2.0 / at_distance).atan();
+            let height = (other.scale.y / 2.0 / at_distance).atan();
+            yaws.extend([
+                (at_yaw - width - LAYOUT_GAP - half_width, 0.5),
+                (at_yaw + width + LAYOUT_GAP + half_width, -0.5),
+                (at_yaw - width + half_width, -0.5),
+                (at_yaw + width - half_width, 0.5),
+                (at_yaw, 0.0),
+            ]);
+            pitches.extend([
+                (at_pitch + height - half_height, 0.5),
+                (at_pitch - height + half_height, -0.5),
+                (at_pitch, 0.0),
+            ]);
+        }
+        let nearest = |marks: &[(f32, f32)], at: f32| {
+            let grid = (at / SNAP_GRID).round() * SNAP_GRID;
+            marks.iter().copied()
+                .filter(|(centre, _)| (centre - at).abs() < SNAP_ANGLE)
+                .min_by(|a, b| (a.0 - at).abs().total_cmp(&(b.0 - at).abs()))
+                .or(((grid - at).abs() < SNAP_ANGLE).then_some((grid, 0.0)))
+        };
+        let yaw_mark = nearest(&yaws, yaw);
+        let pitch_mark = nearest(&pitches, pitch);
+        snap.yaw = yaw_mark.map(|(centre, _)| centre);
+        snap.pitch = pitch_mark.map(|(centre, _)| centre);
+
+        let position = from_spherical(snap.yaw.unwrap_or(yaw), snap.pitch.unwrap_or(pitch), distance);
+        let rotation = facing(position);
+        if let Some(panel) = self.panels.iter_mut().find(|p| p.id == id) {
+            panel.position = position;
+            panel.rotation = rotation;
+        }
+
+        // Guides along the lined-up edges (the bottom one for a preset distance),
+        // a centimetre in front of the panel
+        let model = Mat4::from_scale_rotation_translation(scale, rotation, position);
+        let reach = 0.5 + GUIDE_OVERHANG;
+        let line = |from: Vec3, to: Vec3| {
+            let (from, to) = (model.transform_point3(from), model.transform_point3(to));
+            let along = rotation.inverse() * (to - from);
+            Mat4::from_scale_rotation_translation(
+                Vec3::new(along.length(), GUIDE_WIDTH, 1.0),
+                rotation * Quat::from_rotation_z(along.y.atan2(along.x)),
+                (from + to) / 2.0,
+            )
+        };
+        self.guides.clear();
+        if let Some((_, x)) = yaw_mark {
+            self.guides.push(line(Vec3::new(x, -reach, 1.0), Vec3::new(x, reach, 1.0)));
+        }
+        if let Some((_, y)) = pitch_mark {
+            self.guides.push(line(Vec3::new(-reach, y, 1.0), Vec3::new(reach, y, 1.0)));
+        }
+        if snap.distance.is_some() && pitch_mark.is_none_or(|(_, y)| y != -0.5) {
+            self.guides.push(line(Vec3::new(-reach, -0.5, 1.0), Vec3::new(reach, -0.5, 1.0)));
+        }
+
+        // A new snap shows its guides again
+        if snap == Snap::default() {
+            self.snap = None;
+        } else if self.snap.is_none_or(|(was, _)| was != snap) {
+            self.snap = Some((snap, Instant::now()));
+        }
+    }
+
+    /// The move is over: hide its guide lines.
+    pub fn end_snap(&mut self) {
+        self.snap = None;
+        self.guides.clear();
+    }
+
+    /// Scale a panel's width and height, keeping its shape
     pub fn scale_panel(&mut self, id: u32, scale_factor: f32) {
         if let Some(panel) = self.panels.iter_mut().find(|p| p.id == id) {
-            panel.scale *= scale_factor;
-            // Clamp scale
-            panel.scale = panel.scale.clamp(Vec3::splat(0.3), Vec3::splat(3.0));
+            // Stop at the size limits rather than squash one side against them
+            let (small, large) = (panel.scale.x.min(panel.scale.y), panel.scale.x.max(panel.scale.y));
+            let factor = scale_factor.min(PANEL_MAX_SIZE / large).max(PANEL_MIN_SIZE / small);
+            panel.scale *= Vec3::new(factor, factor, 1.0);
         }
     }
     
@@ -99,6 +432,7 @@ impl WindowManager {
         if self.focused_panel == Some(id) {
             self.focused_panel = self.panels.first().map(|p| p.id);
         }
+        self.reflow();
     }
     
     /// Get model matrix for a panel
@@ -112,6 +446,82 @@ impl WindowManager {
         })
     }
     
+    /// Panel `id`, if it's open.
+    pub fn panel(&self, id: u32) -> Option<&Panel> {
+        self.panels.iter().find(|p| p.id == id)
+    }
+
+    /// The browser window panel `id` shows, if it's a browser panel.
+    pub fn browser_window(&self, id: u32) -> Option<i32> {
+        match self.panel(id)?.content_type {
+            PanelContent::Browser { window, .. } => Some(window),
+            _ => None,
+        }
+    }
+
+    /// The nearest floating panel the ray from `origin` along `dir` hits, and
+    /// where on it (0…1 from the top left). Video panels are on the dome, not quads.
+    pub fn hit(&self, origin: Vec3, dir: Vec3) -> Option<(u32, Vec2)> {
+        self.panels.iter()
+            .filter(|p| !matches!(p.content_type, PanelContent::Video { .. }))
+            .filter_map(|panel| {
+                // In the panel's own space it's the unit square at z = 0
+                let to_local = self.get_transform(panel.id)?.inverse();
+                let o = to_local.transform_point3(origin);
+                let d = to_local.transform_vector3(dir);
+                if d.z.abs() < 1e-6 {
+                    return None;
+                }
+                let t = -o.z / d.z;
+                let at = o + d * t;
+                (t > 0.0 && at.x.abs() <= 0.5 && at.y.abs() <= 0.5)
+                    .then(|| (t, panel.id, Vec2::new(at.x + 0.5, 0.5 - at.y)))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, id, at)| (id, at))
+    }
+
+    /// Every panel drawn as a floating quad, with its transform and texture, and
+    /// `pointer` on the panel it's over, then any snap guide lines showing.
+    /// Video panels are left out: their screens are on the dome.
+    pub fn scene_panels(&self, pointer: Option<(u32, Vec2)>) -> Vec<(Mat4, PanelSource, Option<Vec2>)> {
+        let guides = match self.snap {
+            Some((_, since)) if since.elapsed() < GUIDE_TIME => &self.guides[..],
+            _ => &[],
+        };
+        self.panels.iter().filter_map(|panel| {
+            let source = match panel.content_type {
+                PanelContent::Video { .. } => return None,
+                _ if panel.ui.is_some() => PanelSource::Own(panel.id),
+                PanelContent::Browser { window, .. } => PanelSource::Web(window),
+                PanelContent::Dock | PanelContent::Settings => PanelSource::Ui,
+            };
+            let cursor = pointer.filter(|(id, _)| *id == panel.id).map(|(_, at)| at);
+            Some((self.get_transform(panel.id)?, source, cursor))
+        })
+        .chain(guides.iter().map(|&line| (line, PanelSource::Guide, None)))
+        .collect()
+    }
+
+    /// Lay out every panel's own UI for this frame, `content` drawing into its
+    /// context; each panel's id, texture size, context and output, for
+    /// `Renderer::render_panel_ui`.
+    pub fn run_uis(&self, mut content: impl FnMut(&Context, &PanelContent)) -> Vec<(u32, [u32; 2], Context, FullOutput)> {
+        self.panels.iter().filter_map(|panel| {
+            let ui = panel.ui.as_ref()?;
+            // Follow theme changes made in the main UI
+            crate::theme::apply(&ui.ctx);
+            let input = RawInput {
+                screen_rect: Some(egui::Rect::from_min_size(
+                    egui::Pos2::ZERO, egui::vec2(ui.size[0] as f32, ui.size[1] as f32))),
+                ..Default::default()
+            };
+            ui.ctx.set_pixels_per_point(1.0);
+            let output = ui.ctx.run(input, |ctx| content(ctx, &panel.content_type));
+            Some((panel.id, ui.size, ui.ctx.clone(), output))
+        }).collect()
+    }
+
     /// Iterate over all panels
     pub fn panels(&self) -> &[Panel] {
         &self.panels